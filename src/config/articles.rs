@@ -0,0 +1,129 @@
+//! # Articles
+//!
+//! Articles configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::Source;
+use crate::feed::FeedLimits;
+
+use chrono::Duration;
+use serde::Deserialize;
+
+/// ## ArticlesConfig
+///
+/// Describes which articles are displayed in the article list
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ArticlesConfig {
+    /// Hide the articles which have already been read
+    pub hide_read: bool,
+    /// Max amount of articles to keep for each source
+    pub max_articles: Option<usize>,
+    /// Max age of the articles to keep for each source
+    pub max_age_days: Option<u32>,
+    /// Sort articles by the score computed by the scoring rules, instead of by feed order
+    pub sort_by_score: bool,
+    /// Insert a header before the articles of each day, when articles are sorted by date
+    pub group_by_day: bool,
+}
+
+impl ArticlesConfig {
+    /// ### limits
+    ///
+    /// Get the limits to apply to the feed of `source`; source options override global ones
+    pub fn limits(&self, source: &Source) -> FeedLimits {
+        FeedLimits {
+            max_articles: source.max_articles.or(self.max_articles),
+            max_age: source
+                .max_age_days
+                .or(self.max_age_days)
+                .map(|x| Duration::days(x as i64)),
+            max_size: source.max_size_kb.map(|x| x * 1024),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_articles_config() {
+        let articles: ArticlesConfig = toml::de::from_str("hide_read = true").unwrap();
+        assert!(articles.hide_read);
+        assert!(!ArticlesConfig::default().hide_read);
+        assert!(!articles.group_by_day);
+        let articles: ArticlesConfig = toml::de::from_str("group_by_day = true").unwrap();
+        assert!(articles.group_by_day);
+    }
+
+    #[test]
+    fn should_get_source_limits() {
+        let articles: ArticlesConfig =
+            toml::de::from_str("max_articles = 50\nmax_age_days = 30").unwrap();
+        assert_eq!(
+            articles.limits(&Source::from("https://example.com/rss")),
+            FeedLimits {
+                max_articles: Some(50),
+                max_age: Some(Duration::days(30)),
+                max_size: None,
+            }
+        );
+        let source = Source {
+            url: String::from("https://example.com/rss"),
+            max_articles: Some(10),
+            max_age_days: None,
+            max_size_kb: Some(512),
+            refresh: None,
+            cookies: None,
+            client_cert: None,
+            client_key: None,
+            insecure: false,
+            category: None,
+            order: None,
+            pinned: false,
+            scrape: None,
+        };
+        assert_eq!(
+            articles.limits(&source),
+            FeedLimits {
+                max_articles: Some(10),
+                max_age: Some(Duration::days(30)),
+                max_size: Some(512 * 1024),
+            }
+        );
+        assert_eq!(
+            ArticlesConfig::default().limits(&source),
+            FeedLimits {
+                max_articles: Some(10),
+                max_age: None,
+                max_size: Some(512 * 1024),
+            }
+        );
+    }
+}
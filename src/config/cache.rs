@@ -0,0 +1,125 @@
+//! # Cache
+//!
+//! Local article store configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// ## CacheConfig
+///
+/// Describes how the articles are kept on the disk
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Whether to encrypt the article store
+    pub encrypt: bool,
+    /// Key the article store is encrypted with, e.g. read from a password manager with `key_cmd`
+    pub key: Option<String>,
+    /// File containing the key, e.g. an age identity generated by `age-keygen`; lines starting with `#` are ignored
+    pub keyfile: Option<PathBuf>,
+}
+
+impl CacheConfig {
+    /// ### key
+    ///
+    /// Get the key to encrypt the article store with, if encryption is enabled.
+    /// `key` has precedence over `keyfile`
+    pub fn key(&self) -> Result<Option<String>, String> {
+        if !self.encrypt {
+            return Ok(None);
+        }
+        if let Some(key) = self.key.as_deref().filter(|x| !x.is_empty()) {
+            return Ok(Some(key.to_string()));
+        }
+        let keyfile = self.keyfile.as_deref().ok_or_else(|| {
+            String::from("cache encryption requires either `key`, `key_cmd` or `keyfile`")
+        })?;
+        let data = std::fs::read_to_string(keyfile)
+            .map_err(|e| format!("could not read keyfile {}: {}", keyfile.display(), e))?;
+        data.lines()
+            .map(str::trim)
+            .find(|x| !x.is_empty() && !x.starts_with('#'))
+            .map(|x| Some(x.to_string()))
+            .ok_or_else(|| format!("keyfile {} contains no key", keyfile.display()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    #[test]
+    fn should_deserialize_cache_config() {
+        let cache: CacheConfig = toml::de::from_str(
+            r#"
+encrypt = true
+keyfile = "/home/omar/.config/tuifeed/cache.key"
+"#,
+        )
+        .unwrap();
+        assert_eq!(cache.encrypt, true);
+        assert!(cache.key.is_none());
+        assert_eq!(
+            cache.keyfile,
+            Some(PathBuf::from("/home/omar/.config/tuifeed/cache.key"))
+        );
+        assert_eq!(CacheConfig::default().encrypt, false);
+    }
+
+    #[test]
+    fn should_get_cache_key() {
+        assert_eq!(CacheConfig::default().key().unwrap(), None);
+        let cache = CacheConfig {
+            encrypt: true,
+            key: Some(String::from("s3cr3t")),
+            keyfile: None,
+        };
+        assert_eq!(cache.key().unwrap().as_deref(), Some("s3cr3t"));
+        let mut keyfile = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(
+            keyfile,
+            "# created: 2021-12-01T10:00:00+01:00\n# public key: age1xyz\nAGE-SECRET-KEY-1ABC"
+        )
+        .is_ok());
+        let cache = CacheConfig {
+            encrypt: true,
+            key: None,
+            keyfile: Some(keyfile.path().to_path_buf()),
+        };
+        assert_eq!(cache.key().unwrap().as_deref(), Some("AGE-SECRET-KEY-1ABC"));
+        // Encryption without key
+        let cache = CacheConfig {
+            encrypt: true,
+            key: None,
+            keyfile: None,
+        };
+        assert!(cache.key().is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! # Daemon
+//!
+//! Background daemon configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::ArticlePattern;
+
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// ## DaemonConfig
+///
+/// Describes how `tuifeed daemon` fetches sources and notifies new articles
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DaemonConfig {
+    /// Minutes between two fetches of all the sources
+    pub interval: u64,
+    /// Whether to show desktop notifications
+    pub desktop: bool,
+    /// Url of the ntfy topic notifications are published to
+    pub ntfy: Option<String>,
+    /// Rules selecting the new articles to notify
+    pub notify: Vec<NotifyRule>,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            interval: 30,
+            desktop: true,
+            ntfy: None,
+            notify: Vec::new(),
+        }
+    }
+}
+
+impl DaemonConfig {
+    /// ### interval
+    ///
+    /// Get the interval between two fetches as a duration; it's never less than a minute
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval.max(1) * 60)
+    }
+}
+
+/// ## NotifyRule
+///
+/// New articles matching the pattern of the rule are notified
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawNotifyRule")]
+pub struct NotifyRule {
+    pub pattern: ArticlePattern,
+}
+
+/// ## RawNotifyRule
+///
+/// Notify rule as written in the configuration, before the patterns are compiled
+#[derive(Deserialize)]
+struct RawNotifyRule {
+    title: Option<String>,
+    author: Option<String>,
+    feed: Option<String>,
+}
+
+impl TryFrom<RawNotifyRule> for NotifyRule {
+    type Error = String;
+
+    fn try_from(rule: RawNotifyRule) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pattern: ArticlePattern::new(rule.title, rule.author, rule.feed)
+                .map_err(|e| format!("invalid notify rule: {}", e))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::feed::Article;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_daemon_config() {
+        let daemon: DaemonConfig = toml::de::from_str(
+            r#"
+interval = 15
+ntfy = "https://ntfy.sh/my-feeds"
+
+[[notify]]
+title = "(?i)rust"
+
+[[notify]]
+feed = "^releases$"
+"#,
+        )
+        .unwrap();
+        assert_eq!(daemon.interval(), Duration::from_secs(900));
+        assert_eq!(daemon.desktop, true);
+        assert_eq!(daemon.ntfy.as_deref(), Some("https://ntfy.sh/my-feeds"));
+        assert_eq!(daemon.notify.len(), 2);
+        let article = Article {
+            title: Some(String::from("Rust 1.56 is out")),
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::from("https://example.com/a"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        assert!(daemon.notify[0].pattern.matches("blog", &article));
+        assert!(!daemon.notify[1].pattern.matches("blog", &article));
+        let daemon: DaemonConfig = toml::de::from_str("interval = 0\ndesktop = false").unwrap();
+        assert_eq!(daemon.interval(), Duration::from_secs(60));
+        assert_eq!(daemon.desktop, false);
+        assert!(daemon.notify.is_empty());
+    }
+
+    #[test]
+    fn should_not_deserialize_empty_notify_rules() {
+        assert!(toml::de::from_str::<DaemonConfig>("[[notify]]\n").is_err());
+    }
+}
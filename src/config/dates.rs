@@ -0,0 +1,96 @@
+//! # Dates
+//!
+//! Dates configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use chrono::format::{Item, StrftimeItems};
+use serde::{de::Error as DeError, Deserialize, Deserializer};
+
+/// ## DatesConfig
+///
+/// Describes how dates are rendered in the UI
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct DatesConfig {
+    /// Render dates relative to now (e.g. "3h ago")
+    pub relative: bool,
+    /// Amount of hours after which relative dates fall back to absolute dates
+    pub relative_threshold: i64,
+    /// strftime-like format for the article date
+    #[serde(deserialize_with = "deserialize_format")]
+    pub date_format: String,
+    /// strftime-like format for the dates in the article list
+    #[serde(deserialize_with = "deserialize_format")]
+    pub list_date_format: String,
+}
+
+impl Default for DatesConfig {
+    fn default() -> Self {
+        Self {
+            relative: false,
+            relative_threshold: 24 * 7,
+            date_format: String::from("%A %d %B %Y, %H:%M"),
+            list_date_format: String::from("%d %b %H:%M"),
+        }
+    }
+}
+
+/// ### deserialize_format
+///
+/// Deserialize a strftime-like format, making sure it is valid
+fn deserialize_format<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let format = String::deserialize(deserializer)?;
+    if StrftimeItems::new(format.as_str()).any(|x| x == Item::Error) {
+        Err(DeError::custom(format!("invalid date format `{}`", format)))
+    } else {
+        Ok(format)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_dates_config() {
+        let dates: DatesConfig = toml::de::from_str("relative = true").unwrap();
+        assert!(dates.relative);
+        assert_eq!(dates.relative_threshold, 168);
+        let dates: DatesConfig = toml::de::from_str(r#"date_format = "%Y-%m-%d""#).unwrap();
+        assert_eq!(dates.date_format.as_str(), "%Y-%m-%d");
+        assert_eq!(dates.list_date_format.as_str(), "%d %b %H:%M");
+    }
+
+    #[test]
+    fn should_fail_deserializing_dates_config() {
+        assert!(toml::de::from_str::<DatesConfig>(r#"date_format = "%Y-%Q""#).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! # Email
+//!
+//! Describes how digests are sent by email
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+
+/// ## EmailConfig
+///
+/// Describes how `tuifeed digest` sends emails: through the SMTP server, if set, otherwise with sendmail
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    /// Sender address (e.g. `tuifeed <tuifeed@example.com>`)
+    pub from: Option<String>,
+    /// Command reading the message on its standard input, with the recipients in its headers
+    pub sendmail: String,
+    pub smtp: Option<SmtpConfig>,
+}
+
+/// ## SmtpConfig
+///
+/// SMTP server emails are sent through
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SmtpConfig {
+    pub server: String,
+    /// Port of the server; by default 587, or 465 with `tls`
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    /// Connect with TLS from the start, instead of upgrading the connection with STARTTLS
+    #[serde(default)]
+    pub tls: bool,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            from: None,
+            sendmail: String::from("sendmail -t"),
+            smtp: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_email_config() {
+        let email: EmailConfig = toml::de::from_str(
+            r#"
+from = "tuifeed <tuifeed@example.com>"
+smtp = { server = "smtp.example.com", user = "me", password = "s3cr3t" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(email.from.as_deref(), Some("tuifeed <tuifeed@example.com>"));
+        assert_eq!(email.sendmail.as_str(), "sendmail -t");
+        assert_eq!(
+            email.smtp.unwrap(),
+            SmtpConfig {
+                server: String::from("smtp.example.com"),
+                port: None,
+                user: Some(String::from("me")),
+                password: Some(String::from("s3cr3t")),
+                tls: false,
+            }
+        );
+    }
+}
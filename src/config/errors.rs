@@ -0,0 +1,74 @@
+//! # Errors
+//!
+//! Errors reporting configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+
+/// ## ErrorsConfig
+///
+/// Describes how errors are reported
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ErrorsConfig {
+    /// Where errors occurred while fetching feeds are reported
+    pub fetch_errors: ErrorOutput,
+}
+
+/// ## ErrorOutput
+///
+/// Describes where an error is displayed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorOutput {
+    /// Queued in the error popup
+    Popup,
+    /// Displayed in the status bar for a few seconds
+    Status,
+}
+
+impl Default for ErrorsConfig {
+    fn default() -> Self {
+        Self {
+            fetch_errors: ErrorOutput::Popup,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_errors_config() {
+        let errors: ErrorsConfig = toml::de::from_str(r#"fetch_errors = "status""#).unwrap();
+        assert_eq!(errors.fetch_errors, ErrorOutput::Status);
+        assert_eq!(ErrorsConfig::default().fetch_errors, ErrorOutput::Popup);
+        assert!(toml::de::from_str::<ErrorsConfig>(r#"fetch_errors = "stderr""#).is_err());
+    }
+}
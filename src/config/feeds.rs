@@ -0,0 +1,71 @@
+//! # Feeds
+//!
+//! Feed list configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+
+/// ## FeedsConfig
+///
+/// Describes how the sources are listed in the feed list
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct FeedsConfig {
+    /// Order of the sources in the feed list
+    pub sort: FeedSort,
+}
+
+/// ## FeedSort
+///
+/// Describes the order of the sources in the feed list
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeedSort {
+    /// By name
+    #[default]
+    Alphabetical,
+    /// In the order of the configuration: first the sources with an `order`, then the others as they're written
+    Config,
+    /// Sources with unread articles first, then by name
+    UnreadFirst,
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_feeds_config() {
+        let feeds: FeedsConfig = toml::de::from_str(r#"sort = "unread-first""#).unwrap();
+        assert_eq!(feeds.sort, FeedSort::UnreadFirst);
+        let feeds: FeedsConfig = toml::de::from_str(r#"sort = "config""#).unwrap();
+        assert_eq!(feeds.sort, FeedSort::Config);
+        assert_eq!(FeedsConfig::default().sort, FeedSort::Alphabetical);
+        assert!(toml::de::from_str::<FeedsConfig>(r#"sort = "random""#).is_err());
+    }
+}
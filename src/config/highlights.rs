@@ -0,0 +1,159 @@
+//! # Highlights
+//!
+//! Keywords highlighted in article titles and summaries
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use regex::Regex;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// ## Highlights
+///
+/// Words which are highlighted in article titles and summaries.
+/// Words are matched as whole words, ignoring case
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct Highlights {
+    words: Vec<String>,
+    regex: Option<Regex>,
+}
+
+impl TryFrom<Vec<String>> for Highlights {
+    type Error = String;
+
+    fn try_from(words: Vec<String>) -> Result<Self, Self::Error> {
+        let words: Vec<String> = words
+            .into_iter()
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect();
+        let regex = match words.is_empty() {
+            true => None,
+            false => {
+                let words: Vec<String> = words.iter().map(|x| Self::word_pattern(x)).collect();
+                Some(
+                    Regex::new(format!("(?i){}", words.join("|")).as_str())
+                        .map_err(|e| format!("invalid highlights: {}", e))?,
+                )
+            }
+        };
+        Ok(Self { words, regex })
+    }
+}
+
+impl PartialEq for Highlights {
+    fn eq(&self, other: &Self) -> bool {
+        self.words == other.words
+    }
+}
+
+impl Eq for Highlights {}
+
+impl Highlights {
+    /// ### split
+    ///
+    /// Split `s` into consecutive parts, each one with whether it is a highlighted word
+    pub fn split<'a>(&self, s: &'a str) -> Vec<(&'a str, bool)> {
+        let regex = match &self.regex {
+            Some(regex) => regex,
+            None => return vec![(s, false)],
+        };
+        let mut parts: Vec<(&str, bool)> = Vec::new();
+        let mut last = 0;
+        for m in regex.find_iter(s) {
+            if m.start() > last {
+                parts.push((&s[last..m.start()], false));
+            }
+            parts.push((m.as_str(), true));
+            last = m.end();
+        }
+        if last < s.len() || parts.is_empty() {
+            parts.push((&s[last..], false));
+        }
+        parts
+    }
+
+    /// ### word_pattern
+    ///
+    /// Make the pattern matching `word` as a whole word.
+    /// Word boundaries are only required next to word characters, so that words such as `c++` can be matched
+    fn word_pattern(word: &str) -> String {
+        let word_char = |x: char| x.is_alphanumeric() || x == '_';
+        let boundary = |is_word: bool| if is_word { r"\b" } else { "" };
+        format!(
+            "{}{}{}",
+            boundary(word.starts_with(word_char)),
+            regex::escape(word),
+            boundary(word.ends_with(word_char)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_split_highlighted_words() {
+        let highlights =
+            Highlights::try_from(vec![String::from("rust"), String::from("c++")]).unwrap();
+        assert_eq!(
+            highlights.split("Rust and C++: rusty trust in rust"),
+            vec![
+                ("Rust", true),
+                (" and ", false),
+                ("C++", true),
+                (": rusty trust in ", false),
+                ("rust", true),
+            ]
+        );
+        assert_eq!(highlights.split("golang"), vec![("golang", false)]);
+        assert_eq!(highlights.split(""), vec![("", false)]);
+    }
+
+    #[test]
+    fn should_not_split_without_highlights() {
+        let highlights = Highlights::default();
+        assert_eq!(highlights.split("Rust"), vec![("Rust", false)]);
+        let highlights = Highlights::try_from(vec![String::from(" ")]).unwrap();
+        assert_eq!(highlights.split("Rust"), vec![("Rust", false)]);
+    }
+
+    #[test]
+    fn should_deserialize_highlights() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            highlights: Highlights,
+        }
+        let wrapper: Wrapper = toml::de::from_str(r#"highlights = ["rust", "postgres"]"#).unwrap();
+        assert_eq!(
+            wrapper.highlights,
+            Highlights::try_from(vec![String::from("rust"), String::from("postgres")]).unwrap()
+        );
+    }
+}
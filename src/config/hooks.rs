@@ -0,0 +1,114 @@
+//! # Hooks
+//!
+//! Commands run when something happens in tuifeed
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+
+/// ## HooksConfig
+///
+/// Commands run on lifecycle events. Each command is split into arguments on whitespace,
+/// and it receives the event metadata in `TUIFEED_*` environment variables
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Run for each new article of a source, by the daemon or by tuifeed
+    pub on_new_article: Option<String>,
+    /// Run when an article is opened
+    pub on_article_open: Option<String>,
+    /// Run when the user marks an article as read
+    pub on_mark_read: Option<String>,
+    /// Run once all the sources have been fetched
+    pub on_refresh_complete: Option<String>,
+}
+
+/// ## HookEvent
+///
+/// An event which runs a hook
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HookEvent {
+    NewArticle,
+    ArticleOpen,
+    MarkRead,
+    RefreshComplete,
+}
+
+impl HooksConfig {
+    /// ### command
+    ///
+    /// Get the command to run on `event`, if any
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        match event {
+            HookEvent::NewArticle => self.on_new_article.as_deref(),
+            HookEvent::ArticleOpen => self.on_article_open.as_deref(),
+            HookEvent::MarkRead => self.on_mark_read.as_deref(),
+            HookEvent::RefreshComplete => self.on_refresh_complete.as_deref(),
+        }
+        .filter(|x| !x.trim().is_empty())
+    }
+}
+
+impl HookEvent {
+    /// ### name
+    ///
+    /// Get the name of the event, passed to the hooks in `TUIFEED_EVENT`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NewArticle => "new_article",
+            Self::ArticleOpen => "article_open",
+            Self::MarkRead => "mark_read",
+            Self::RefreshComplete => "refresh_complete",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_hooks_config() {
+        let hooks: HooksConfig = toml::de::from_str(
+            r#"
+on_new_article = "/home/omar/bin/new-article.sh"
+on_mark_read = " "
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            hooks.command(HookEvent::NewArticle),
+            Some("/home/omar/bin/new-article.sh")
+        );
+        assert!(hooks.command(HookEvent::MarkRead).is_none());
+        assert!(hooks.command(HookEvent::ArticleOpen).is_none());
+        assert!(HooksConfig::default()
+            .command(HookEvent::RefreshComplete)
+            .is_none());
+        assert_eq!(HookEvent::RefreshComplete.name(), "refresh_complete");
+    }
+}
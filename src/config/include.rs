@@ -0,0 +1,266 @@
+//! # Include
+//!
+//! Configuration files included by the configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::serializer::{SerializerError, SerializerErrorKind};
+
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// Key of the files to include
+const INCLUDE_KEY: &str = "include";
+/// Max depth of nested includes
+const MAX_DEPTH: usize = 8;
+
+/// ### merge_includes
+///
+/// Merge into `value`, read from the file at `p`, the files listed in its `include` key.
+/// Paths are relative to the directory of `p` and may contain `*` and `?` wildcards in the file name.
+/// Included files are merged in order, then `value` is merged on top of them: tables are merged key by key,
+/// arrays of tables (e.g. `[[mute]]` rules) are appended, while other values are replaced.
+/// Files are read with `read`
+pub fn merge_includes<F>(value: &mut Value, p: &Path, read: &mut F) -> Result<(), SerializerError>
+where
+    F: FnMut(&Path) -> Result<Value, SerializerError>,
+{
+    merge_includes_at(value, p, read, 0)
+}
+
+// -- private
+
+/// ### merge_includes_at
+///
+/// Merge includes of `value`, which is included at `depth`
+fn merge_includes_at<F>(
+    value: &mut Value,
+    p: &Path,
+    read: &mut F,
+    depth: usize,
+) -> Result<(), SerializerError>
+where
+    F: FnMut(&Path) -> Result<Value, SerializerError>,
+{
+    let patterns = match value.as_table_mut().and_then(|x| x.remove(INCLUDE_KEY)) {
+        None => return Ok(()),
+        Some(Value::Array(patterns)) => patterns,
+        Some(Value::String(pattern)) => vec![Value::String(pattern)],
+        Some(_) => return Err(include_error(p, "`include` must be a list of paths")),
+    };
+    if depth >= MAX_DEPTH {
+        return Err(include_error(
+            p,
+            "too many nested includes; does a file include itself?",
+        ));
+    }
+    let dir = p.parent().unwrap_or_else(|| Path::new(""));
+    let mut merged = Value::Table(Default::default());
+    for pattern in patterns.iter() {
+        let pattern = pattern
+            .as_str()
+            .ok_or_else(|| include_error(p, "`include` must be a list of paths"))?;
+        for file in expand_pattern(dir, pattern).map_err(|e| include_error(p, e.as_str()))? {
+            let mut included = read(file.as_path())?;
+            merge_includes_at(&mut included, file.as_path(), read, depth + 1)?;
+            merge(&mut merged, included);
+        }
+    }
+    merge(&mut merged, std::mem::replace(value, Value::Boolean(false)));
+    *value = merged;
+    Ok(())
+}
+
+/// ### expand_pattern
+///
+/// Get the files matching `pattern`, relative to `dir`, sorted by name.
+/// A pattern without wildcards must match an existing file
+fn expand_pattern(dir: &Path, pattern: &str) -> Result<Vec<PathBuf>, String> {
+    let path = dir.join(pattern);
+    let name = path
+        .file_name()
+        .and_then(|x| x.to_str())
+        .ok_or_else(|| format!("invalid path \"{}\"", pattern))?;
+    if !name.contains(['*', '?']) {
+        return match path.is_file() {
+            true => Ok(vec![path]),
+            false => Err(format!("no such file \"{}\"", path.display())),
+        };
+    }
+    let regex = wildcard_regex(name);
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut files: Vec<PathBuf> = std::fs::read_dir(parent)
+        .map_err(|e| format!("could not read \"{}\": {}", parent.display(), e))?
+        .flatten()
+        .map(|x| x.path())
+        .filter(|x| x.is_file())
+        .filter(|x| {
+            x.file_name()
+                .and_then(|x| x.to_str())
+                .map(|x| regex.is_match(x))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// ### wildcard_regex
+///
+/// Make the regex matching the file names matched by `pattern`
+fn wildcard_regex(pattern: &str) -> Regex {
+    let regex = regex::escape(pattern)
+        .replace(r"\*", ".*")
+        .replace(r"\?", ".");
+    Regex::new(format!("^{}$", regex).as_str()).unwrap()
+}
+
+/// ### merge
+///
+/// Merge `overlay` on top of `base`
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base), Value::Table(overlay)) => {
+            for (key, value) in overlay.into_iter() {
+                match base.get_mut(&key) {
+                    Some(base) => merge(base, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(overlay))
+            if !overlay.is_empty() && overlay.iter().all(Value::is_table) =>
+        {
+            base.extend(overlay);
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// ### include_error
+///
+/// Make the error for the includes of the file at `p`
+fn include_error(p: &Path, msg: &str) -> SerializerError {
+    SerializerError::new(
+        SerializerErrorKind::Syntax,
+        format!("{}: {}", p.display(), msg),
+    )
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_merge_includes() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let sources_dir = tempdir.path().join("sources.d");
+        std::fs::create_dir(sources_dir.as_path()).unwrap();
+        let files: HashMap<PathBuf, &str> = vec![
+            (
+                tempdir.path().join("work.toml"),
+                "include = [\"sources.d/rust.toml\"]\n[articles]\nhide_read = true\nmax_articles = 10\n[[mute]]\ntitle = \"work\"\n",
+            ),
+            (
+                sources_dir.join("news.toml"),
+                "[sources]\nnytimes = \"https://rss.nytimes.com/services/xml/rss/nyt/World.xml\"\n",
+            ),
+            (
+                sources_dir.join("rust.toml"),
+                "[sources]\ntwir = \"https://this-week-in-rust.org/rss.xml\"\n",
+            ),
+        ]
+        .into_iter()
+        .collect();
+        for (path, content) in files.iter() {
+            std::fs::write(path, content).unwrap();
+        }
+        std::fs::write(sources_dir.join("notes.txt"), "not included").unwrap();
+        let mut value: Value = toml::de::from_str(
+            "include = [\"work.toml\", \"sources.d/*.toml\"]\nhighlights = [\"rust\"]\n[sources]\nhn = \"https://hnrss.org/newest\"\n[articles]\nmax_articles = 20\n[[mute]]\ntitle = \"sponsored\"\n",
+        )
+        .unwrap();
+        let mut read = |p: &Path| -> Result<Value, SerializerError> {
+            Ok(toml::de::from_str(files.get(p).unwrap()).unwrap())
+        };
+        assert!(merge_includes(&mut value, &tempdir.path().join("config.toml"), &mut read).is_ok());
+        let expected: Value = toml::de::from_str(
+            "highlights = [\"rust\"]\n[sources]\nhn = \"https://hnrss.org/newest\"\nnytimes = \"https://rss.nytimes.com/services/xml/rss/nyt/World.xml\"\ntwir = \"https://this-week-in-rust.org/rss.xml\"\n[articles]\nhide_read = true\nmax_articles = 20\n[[mute]]\ntitle = \"work\"\n[[mute]]\ntitle = \"sponsored\"\n",
+        )
+        .unwrap();
+        assert_eq!(value, expected);
+        // Nothing to include
+        let mut value: Value = toml::de::from_str("[sources]\n").unwrap();
+        assert!(merge_includes(&mut value, &tempdir.path().join("config.toml"), &mut read).is_ok());
+        assert_eq!(value, toml::de::from_str("[sources]\n").unwrap());
+    }
+
+    #[test]
+    fn should_fail_merging_includes() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let config = tempdir.path().join("config.toml");
+        let mut read = |_: &Path| -> Result<Value, SerializerError> {
+            Ok(toml::de::from_str("include = [\"config.toml\"]\n").unwrap())
+        };
+        // Missing file
+        let mut value: Value = toml::de::from_str("include = [\"missing.toml\"]\n").unwrap();
+        assert!(merge_includes(&mut value, config.as_path(), &mut read).is_err());
+        // Not a list of paths
+        let mut value: Value = toml::de::from_str("include = 42\n").unwrap();
+        assert!(merge_includes(&mut value, config.as_path(), &mut read).is_err());
+        // Including itself
+        std::fs::write(config.as_path(), "").unwrap();
+        let mut value: Value = toml::de::from_str("include = [\"config.toml\"]\n").unwrap();
+        assert!(merge_includes(&mut value, config.as_path(), &mut read).is_err());
+    }
+
+    #[test]
+    fn should_expand_patterns() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        for name in ["b.toml", "a.toml", "a.json"] {
+            std::fs::write(tempdir.path().join(name), "").unwrap();
+        }
+        assert_eq!(
+            expand_pattern(tempdir.path(), "*.toml").unwrap(),
+            vec![tempdir.path().join("a.toml"), tempdir.path().join("b.toml")]
+        );
+        assert_eq!(
+            expand_pattern(tempdir.path(), "a.*").unwrap(),
+            vec![tempdir.path().join("a.json"), tempdir.path().join("a.toml")]
+        );
+        assert_eq!(
+            expand_pattern(tempdir.path(), "?.json").unwrap(),
+            vec![tempdir.path().join("a.json")]
+        );
+        assert!(expand_pattern(tempdir.path(), "c.*").unwrap().is_empty());
+        assert!(expand_pattern(tempdir.path(), "c.toml").is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! # Interpolate
+//!
+//! Expansion of environment variables and commands in configuration values
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use regex::{Captures, Regex};
+use std::process::Command;
+use toml::value::Table;
+use toml::Value;
+
+/// Suffix of the keys whose value is the command to run to get the value
+const COMMAND_SUFFIX: &str = "_cmd";
+
+lazy_static! {
+    /**
+     * Matches environment variables in string
+     *
+     * - group 1: variable name
+     */
+    static ref ENV_VAR_REGEX: Regex = Regex::new(r"\$\$\{|\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+}
+
+/// ### interpolate
+///
+/// Interpolate all the strings in `value`:
+///
+/// - `${VAR}` is replaced with the value of the environment variable `VAR` (`$${` escapes `${`)
+/// - `key_cmd = "command"` is replaced with `key = "<command output>"`
+pub fn interpolate(value: &mut Value) -> Result<(), String> {
+    interpolate_with(value, &|name| std::env::var(name).ok())
+}
+
+/// ### interpolate_with
+///
+/// Interpolate value, resolving environment variables with `lookup`
+fn interpolate_with<F>(value: &mut Value, lookup: &F) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    match value {
+        Value::String(s) => {
+            *s = expand_vars(s.as_str(), lookup)?;
+            Ok(())
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .try_for_each(|v| interpolate_with(v, lookup)),
+        Value::Table(table) => interpolate_table(table, lookup),
+        _ => Ok(()),
+    }
+}
+
+/// ### interpolate_table
+///
+/// Interpolate table values, running the commands of the keys ending with `_cmd`
+fn interpolate_table<F>(table: &mut Table, lookup: &F) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    table
+        .iter_mut()
+        .try_for_each(|(_, v)| interpolate_with(v, lookup))?;
+    let commands: Vec<String> = table
+        .iter()
+        .filter(|(key, value)| key.ends_with(COMMAND_SUFFIX) && value.is_str())
+        .map(|(key, _)| key.to_string())
+        .collect();
+    for cmd_key in commands.into_iter() {
+        let key = cmd_key.trim_end_matches(COMMAND_SUFFIX).to_string();
+        if table.contains_key(&key) {
+            return Err(format!(
+                "both \"{}\" and \"{}\" are set; only one of them is allowed",
+                key, cmd_key
+            ));
+        }
+        let output = match table.remove(&cmd_key) {
+            Some(Value::String(cmd)) => run_command(cmd.as_str())
+                .map_err(|e| format!("could not get \"{}\": {}", key, e))?,
+            _ => continue,
+        };
+        table.insert(key, Value::String(output));
+    }
+    Ok(())
+}
+
+/// ### expand_vars
+///
+/// Replace environment variables in `s`, resolving them with `lookup`
+fn expand_vars<F>(s: &str, lookup: &F) -> Result<String, String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut error: Option<String> = None;
+    let expanded = ENV_VAR_REGEX.replace_all(s, |caps: &Captures| match caps.get(1) {
+        Some(name) => match lookup(name.as_str()) {
+            Some(value) => value,
+            None => {
+                error = Some(format!(
+                    "environment variable \"{}\" is not set",
+                    name.as_str()
+                ));
+                String::new()
+            }
+        },
+        None => String::from("${"),
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(expanded.to_string()),
+    }
+}
+
+/// ### run_command
+///
+/// Run `cmd` in the shell and return its output, without the trailing newline
+fn run_command(cmd: &str) -> Result<String, String> {
+    #[cfg(not(windows))]
+    let output = Command::new("sh").arg("-c").arg(cmd).output();
+    #[cfg(windows)]
+    let output = Command::new("cmd").arg("/C").arg(cmd).output();
+    let output = output.map_err(|e| format!("could not run \"{}\": {}", cmd, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "\"{}\" exited with {}: {}",
+            cmd,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout)
+        .map(|s| s.trim_end_matches(&['\r', '\n'][..]).to_string())
+        .map_err(|_| format!("output of \"{}\" is not valid UTF-8", cmd))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "TOKEN" => Some(String::from("s3cr3t")),
+            "HOST" => Some(String::from("miniflux.example.com")),
+            _ => None,
+        }
+    }
+
+    fn parse(s: &str) -> Value {
+        toml::de::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn should_expand_env_vars() {
+        assert_eq!(
+            expand_vars("https://${HOST}/feed?token=${TOKEN}", &lookup)
+                .ok()
+                .unwrap()
+                .as_str(),
+            "https://miniflux.example.com/feed?token=s3cr3t"
+        );
+        assert_eq!(
+            expand_vars("no variables $HOST", &lookup)
+                .ok()
+                .unwrap()
+                .as_str(),
+            "no variables $HOST"
+        );
+        assert_eq!(
+            expand_vars("escaped $${TOKEN}", &lookup)
+                .ok()
+                .unwrap()
+                .as_str(),
+            "escaped ${TOKEN}"
+        );
+    }
+
+    #[test]
+    fn should_fail_expanding_unset_env_vars() {
+        assert!(expand_vars("${TOKEN} ${NOT_SET}", &lookup).is_err());
+    }
+
+    #[test]
+    fn should_interpolate_nested_values() {
+        let mut value = parse(
+            r#"
+            [sources]
+            news = "https://${HOST}/news"
+            private = { url = "https://${HOST}/private?token=${TOKEN}" }
+            list = [ "${TOKEN}", 5 ]
+            "#,
+        );
+        assert!(interpolate_with(&mut value, &lookup).is_ok());
+        let sources = value.get("sources").unwrap();
+        assert_eq!(
+            sources.get("news").unwrap().as_str().unwrap(),
+            "https://miniflux.example.com/news"
+        );
+        assert_eq!(
+            sources
+                .get("private")
+                .unwrap()
+                .get("url")
+                .unwrap()
+                .as_str()
+                .unwrap(),
+            "https://miniflux.example.com/private?token=s3cr3t"
+        );
+        assert_eq!(
+            sources
+                .get("list")
+                .unwrap()
+                .get(0)
+                .unwrap()
+                .as_str()
+                .unwrap(),
+            "s3cr3t"
+        );
+    }
+
+    #[test]
+    fn should_interpolate_commands() {
+        let mut value = parse(
+            r#"
+            [auth]
+            password_cmd = "echo ${TOKEN}"
+            "#,
+        );
+        assert!(interpolate_with(&mut value, &lookup).is_ok());
+        let auth = value.get("auth").unwrap();
+        assert!(auth.get("password_cmd").is_none());
+        assert_eq!(auth.get("password").unwrap().as_str().unwrap(), "s3cr3t");
+    }
+
+    #[test]
+    fn should_fail_interpolating_commands() {
+        // Both keys set
+        let mut value = parse(
+            r#"
+            [auth]
+            password = "plain"
+            password_cmd = "echo s3cr3t"
+            "#,
+        );
+        assert!(interpolate_with(&mut value, &lookup).is_err());
+        // Command fails
+        let mut value = parse(
+            r#"
+            [auth]
+            password_cmd = "exit 1"
+            "#,
+        );
+        assert!(interpolate_with(&mut value, &lookup).is_err());
+    }
+}
@@ -0,0 +1,755 @@
+//! # Keys
+//!
+//! Key bindings configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+/// ## KeyBindings
+///
+/// Associates each action in tuifeed to the keys which trigger it.
+/// Each binding can be overridden in the `[keys]` section of the configuration
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    // -- global
+    pub quit: Keys,
+    pub help: Keys,
+    pub fetch_source: Keys,
+    pub fetch_all_sources: Keys,
+    pub fetch_category: Keys,
+    pub force_fetch_all_sources: Keys,
+    pub add_feed: Keys,
+    pub search_podcasts: Keys,
+    pub add_from_clipboard: Keys,
+    pub edit_config: Keys,
+    pub next_unread: Keys,
+    pub previous_unread: Keys,
+    pub undo: Keys,
+    pub switch_feed: Keys,
+    pub command: Keys,
+    pub stop_speaking: Keys,
+    pub toggle_offline: Keys,
+    // -- navigation
+    pub move_down: Keys,
+    pub move_up: Keys,
+    pub scroll_down: Keys,
+    pub scroll_up: Keys,
+    pub go_to_begin: Keys,
+    pub go_to_end: Keys,
+    pub next_pane: Keys,
+    pub previous_pane: Keys,
+    pub switch_list: Keys,
+    // -- feed list
+    pub delete_feed: Keys,
+    pub rename_feed: Keys,
+    pub feed_info: Keys,
+    // -- article
+    pub open_article: Keys,
+    pub open_comments: Keys,
+    pub open_with: Keys,
+    pub share: Keys,
+    pub filter_articles: Keys,
+    pub filter_tag: Keys,
+    pub tag_article: Keys,
+    pub edit_note: Keys,
+    pub star_article: Keys,
+    pub hide_read: Keys,
+    pub mark_all_read: Keys,
+    pub sort_by_score: Keys,
+    pub sort_by_unread: Keys,
+    pub reading_mode: Keys,
+    pub yank_link: Keys,
+    pub yank_article: Keys,
+    pub archive_article: Keys,
+    pub speak_article: Keys,
+    // -- reader
+    pub next_match: Keys,
+    pub previous_match: Keys,
+    pub page_down: Keys,
+    pub page_up: Keys,
+    pub next_article: Keys,
+    pub previous_article: Keys,
+}
+
+/// ## KeyContext
+///
+/// Describes where a key binding is active
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyContext {
+    Global,
+    Navigation,
+    FeedList,
+    ArticleList,
+    Reader,
+}
+
+/// ## CheatsheetFormat
+///
+/// Document format of the key bindings cheatsheet
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheatsheetFormat {
+    Markdown,
+    Text,
+}
+
+/// ## Keys
+///
+/// A list of keys bound to an action.
+/// In the configuration it can be either a single key (`"ctrl+r"`) or a list of keys (`["down", "j"]`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "KeysRepr")]
+pub struct Keys(Vec<KeyEvent>);
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeysRepr {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            quit: Keys::from(["esc"]),
+            help: Keys::from(["?"]),
+            fetch_source: Keys::from(["r"]),
+            fetch_all_sources: Keys::from(["ctrl+r"]),
+            fetch_category: Keys::from(["R"]),
+            force_fetch_all_sources: Keys::from(["alt+r"]),
+            add_feed: Keys::from(["a"]),
+            search_podcasts: Keys::from(["alt+p"]),
+            add_from_clipboard: Keys::from(["alt+a"]),
+            edit_config: Keys::from(["e"]),
+            next_unread: Keys::from(["N"]),
+            previous_unread: Keys::from(["P"]),
+            undo: Keys::from(["u"]),
+            switch_feed: Keys::from(["ctrl+p"]),
+            command: Keys::from([":"]),
+            stop_speaking: Keys::from(["alt+s"]),
+            toggle_offline: Keys::from(["alt+o"]),
+            move_down: Keys::from(["down", "j"]),
+            move_up: Keys::from(["up", "k"]),
+            scroll_down: Keys::from(["pagedown", "ctrl+d"]),
+            scroll_up: Keys::from(["pageup", "ctrl+u"]),
+            go_to_begin: Keys::from(["home", "g"]),
+            go_to_end: Keys::from(["end", "G"]),
+            next_pane: Keys::from(["right", "l"]),
+            previous_pane: Keys::from(["left", "h"]),
+            switch_list: Keys::from(["tab"]),
+            delete_feed: Keys::from(["d", "delete"]),
+            rename_feed: Keys::from(["n"]),
+            feed_info: Keys::from(["i"]),
+            open_article: Keys::from(["enter"]),
+            open_comments: Keys::from(["c"]),
+            open_with: Keys::from(["o"]),
+            share: Keys::from(["ctrl+s"]),
+            filter_articles: Keys::from(["/"]),
+            filter_tag: Keys::from(["t"]),
+            tag_article: Keys::from(["T"]),
+            edit_note: Keys::from(["ctrl+n"]),
+            star_article: Keys::from(["*"]),
+            hide_read: Keys::from(["H"]),
+            mark_all_read: Keys::from(["M"]),
+            sort_by_score: Keys::from(["s"]),
+            sort_by_unread: Keys::from(["U"]),
+            reading_mode: Keys::from(["f"]),
+            yank_link: Keys::from(["y"]),
+            yank_article: Keys::from(["Y"]),
+            archive_article: Keys::from(["W"]),
+            speak_article: Keys::from(["S"]),
+            next_match: Keys::from(["n"]),
+            previous_match: Keys::from(["N"]),
+            page_down: Keys::from(["ctrl+f"]),
+            page_up: Keys::from(["ctrl+b"]),
+            next_article: Keys::from(["n"]),
+            previous_article: Keys::from(["p"]),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// ### describe
+    ///
+    /// Returns all the key bindings grouped by the context where they're active,
+    /// each one with the description of its action
+    pub fn describe(&self) -> Vec<(KeyContext, Vec<(&Keys, &'static str)>)> {
+        vec![
+            (
+                KeyContext::Global,
+                vec![
+                    (&self.quit, "Quit tuifeed"),
+                    (&self.help, "Show key bindings"),
+                    (&self.fetch_source, "Reload selected source"),
+                    (&self.fetch_all_sources, "Reload all sources"),
+                    (
+                        &self.fetch_category,
+                        "Reload the sources in the category of the selected source",
+                    ),
+                    (
+                        &self.force_fetch_all_sources,
+                        "Reload all sources, even if fetched recently",
+                    ),
+                    (&self.add_feed, "Subscribe to a new feed"),
+                    (&self.search_podcasts, "Search podcasts to subscribe to"),
+                    (
+                        &self.add_from_clipboard,
+                        "Subscribe to the feed of the url in the clipboard",
+                    ),
+                    (&self.edit_config, "Edit configuration in $EDITOR"),
+                    (&self.next_unread, "Go to next unread article"),
+                    (&self.previous_unread, "Go to previous unread article"),
+                    (
+                        &self.undo,
+                        "Undo the last change of read articles (or mute)",
+                    ),
+                    (&self.switch_feed, "Go to a feed by name"),
+                    (&self.command, "Type a command"),
+                    (&self.stop_speaking, "Stop reading the article aloud"),
+                    (
+                        &self.toggle_offline,
+                        "Go offline or online; offline, sources are loaded from the store",
+                    ),
+                ],
+            ),
+            (
+                KeyContext::Navigation,
+                vec![
+                    (&self.move_down, "Move down"),
+                    (&self.move_up, "Move up"),
+                    (&self.scroll_down, "Scroll down"),
+                    (&self.scroll_up, "Scroll up"),
+                    (&self.go_to_begin, "Go to the beginning"),
+                    (&self.go_to_end, "Go to the end"),
+                    (&self.next_pane, "Move to the next pane"),
+                    (&self.previous_pane, "Move to the previous pane"),
+                    (
+                        &self.switch_list,
+                        "Switch between feed list and article list",
+                    ),
+                ],
+            ),
+            (
+                KeyContext::FeedList,
+                vec![
+                    (&self.delete_feed, "Unsubscribe from selected source"),
+                    (&self.rename_feed, "Rename selected source"),
+                    (&self.feed_info, "Show the details of selected source"),
+                    (&self.open_article, "Open the website of selected source"),
+                ],
+            ),
+            (
+                KeyContext::ArticleList,
+                vec![
+                    (&self.open_article, "Open article in your browser"),
+                    (&self.open_comments, "Open article comments in your browser"),
+                    (&self.open_with, "Choose the command to open a link with"),
+                    (&self.share, "Share the article with a command"),
+                    (&self.filter_articles, "Filter articles by title"),
+                    (&self.filter_tag, "Show only the articles with a tag"),
+                    (&self.tag_article, "Edit your tags of the selected article"),
+                    (&self.edit_note, "Edit your note on the selected article"),
+                    (&self.star_article, "Star or unstar the selected article"),
+                    (&self.hide_read, "Hide or show read articles"),
+                    (&self.mark_all_read, "Mark all the articles as read"),
+                    (
+                        &self.sort_by_score,
+                        "Sort articles by score or by feed order",
+                    ),
+                    (
+                        &self.sort_by_unread,
+                        "Show unread articles first in the selected feed",
+                    ),
+                    (&self.reading_mode, "Toggle distraction-free reading mode"),
+                    (&self.yank_link, "Copy article link to clipboard"),
+                    (
+                        &self.yank_article,
+                        "Copy article title and link to clipboard",
+                    ),
+                    (
+                        &self.archive_article,
+                        "Save the article page in the archive",
+                    ),
+                    (&self.speak_article, "Read the article aloud"),
+                ],
+            ),
+            (
+                KeyContext::Reader,
+                vec![
+                    (&self.open_article, "Open article in your browser"),
+                    (&self.open_comments, "Open article comments in your browser"),
+                    (&self.open_with, "Choose the command to open a link with"),
+                    (&self.share, "Share the article with a command"),
+                    (&self.tag_article, "Edit your tags of the selected article"),
+                    (&self.edit_note, "Edit your note on the selected article"),
+                    (&self.star_article, "Star or unstar the selected article"),
+                    (&self.reading_mode, "Toggle distraction-free reading mode"),
+                    (&self.yank_link, "Copy article link to clipboard"),
+                    (
+                        &self.yank_article,
+                        "Copy article title and link to clipboard",
+                    ),
+                    (
+                        &self.archive_article,
+                        "Save the article page in the archive",
+                    ),
+                    (&self.speak_article, "Read the article aloud"),
+                    (&self.filter_articles, "Search in the article"),
+                    (
+                        &self.next_match,
+                        "Go to the next match of the search, if any",
+                    ),
+                    (
+                        &self.previous_match,
+                        "Go to the previous match of the search, if any",
+                    ),
+                    (&self.page_down, "Scroll down by a page"),
+                    (&self.page_up, "Scroll up by a page"),
+                    (&self.next_article, "Go to next article"),
+                    (&self.previous_article, "Go to previous article"),
+                ],
+            ),
+        ]
+    }
+
+    /// ### cheatsheet
+    ///
+    /// Render all the key bindings, grouped by context, into a cheatsheet
+    pub fn cheatsheet(&self, format: CheatsheetFormat) -> String {
+        let description = self.describe();
+        match format {
+            CheatsheetFormat::Markdown => {
+                let mut doc = String::from("# tuifeed key bindings\n");
+                for (context, bindings) in description.iter() {
+                    doc.push_str(format!("\n## {}\n\n", context).as_str());
+                    doc.push_str("| Key | Description |\n|-----|-------------|\n");
+                    for (keys, action) in bindings.iter() {
+                        let keys: Vec<String> = keys
+                            .0
+                            .iter()
+                            .map(|x| format!("`{}`", fmt_key(x).replace('|', "\\|")))
+                            .collect();
+                        doc.push_str(format!("| {} | {} |\n", keys.join(", "), action).as_str());
+                    }
+                }
+                doc
+            }
+            CheatsheetFormat::Text => {
+                let width = description
+                    .iter()
+                    .flat_map(|(_, bindings)| bindings.iter())
+                    .map(|(keys, _)| keys.to_string().chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let sections: Vec<String> = description
+                    .iter()
+                    .map(|(context, bindings)| {
+                        let mut section = format!("{}\n", context);
+                        for (keys, action) in bindings.iter() {
+                            section.push_str(
+                                format!(
+                                    "  {:width$}  {}\n",
+                                    keys.to_string(),
+                                    action,
+                                    width = width
+                                )
+                                .as_str(),
+                            );
+                        }
+                        section
+                    })
+                    .collect();
+                sections.join("\n")
+            }
+        }
+    }
+}
+
+impl FromStr for CheatsheetFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "text" | "txt" => Ok(Self::Text),
+            _ => Err(format!(
+                "unknown cheatsheet format \"{}\"; use md or txt",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for KeyContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let context = match self {
+            Self::Global => "Global",
+            Self::Navigation => "Navigation",
+            Self::FeedList => "Feed list",
+            Self::ArticleList => "Article list",
+            Self::Reader => "Reader",
+        };
+        write!(f, "{}", context)
+    }
+}
+
+impl Keys {
+    /// ### matches
+    ///
+    /// Returns whether `key` is one of the bound keys
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let key = normalize(*key);
+        self.0.contains(&key)
+    }
+
+    /// ### overlaps
+    ///
+    /// Returns whether any key is bound to both `self` and `other`
+    pub fn overlaps(&self, other: &Keys) -> bool {
+        self.0.iter().any(|x| other.matches(x))
+    }
+}
+
+impl fmt::Display for Keys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keys: Vec<String> = self.0.iter().map(fmt_key).collect();
+        write!(f, "{}", keys.join(", "))
+    }
+}
+
+impl<const N: usize> From<[&str; N]> for Keys {
+    fn from(keys: [&str; N]) -> Self {
+        Self(
+            keys.iter()
+                .map(|x| parse_key(x).expect("invalid default key binding"))
+                .collect(),
+        )
+    }
+}
+
+impl TryFrom<KeysRepr> for Keys {
+    type Error = String;
+
+    fn try_from(repr: KeysRepr) -> Result<Self, Self::Error> {
+        let keys = match repr {
+            KeysRepr::One(key) => vec![key],
+            KeysRepr::Many(keys) => keys,
+        };
+        keys.iter()
+            .map(|x| parse_key(x))
+            .collect::<Result<Vec<KeyEvent>, String>>()
+            .map(Self)
+    }
+}
+
+impl FromStr for Keys {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(KeysRepr::One(s.to_string()))
+    }
+}
+
+/// ### parse_key
+///
+/// Parse a key binding from its string representation (e.g. `ctrl+r`, `esc`, `G`)
+fn parse_key(s: &str) -> Result<KeyEvent, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    // NOTE: `+` is a valid key, so don't split the last char
+    let (mods, key) = match s.len() > 1 {
+        true => match s[..s.len() - 1].rfind('+') {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => ("", s),
+        },
+        false => ("", s),
+    };
+    for modifier in mods.split('+').filter(|x| !x.is_empty()) {
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return Err(format!("invalid key modifier `{}` in `{}`", modifier, s)),
+        };
+    }
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(ch), None) => Key::Char(ch),
+        _ => match key.to_lowercase().as_str() {
+            "backspace" => Key::Backspace,
+            "enter" | "return" => Key::Enter,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" => Key::PageUp,
+            "pagedown" => Key::PageDown,
+            "tab" => Key::Tab,
+            "backtab" => Key::BackTab,
+            "delete" | "del" => Key::Delete,
+            "insert" | "ins" => Key::Insert,
+            "esc" | "escape" => Key::Esc,
+            "space" => Key::Char(' '),
+            fkey if fkey.starts_with('f') && fkey.len() > 1 => Key::Function(
+                fkey[1..]
+                    .parse::<u8>()
+                    .map_err(|_| format!("invalid key `{}` in `{}`", key, s))?,
+            ),
+            _ => return Err(format!("invalid key `{}` in `{}`", key, s)),
+        },
+    };
+    Ok(normalize(KeyEvent::new(code, modifiers)))
+}
+
+/// ### normalize
+///
+/// Normalize key event: shift is part of the character for chars (e.g. `shift+g` is `G`),
+/// while it's implied for back tab
+fn normalize(mut key: KeyEvent) -> KeyEvent {
+    match key.code {
+        Key::Char(ch) => {
+            if key.modifiers.contains(KeyModifiers::SHIFT) {
+                key.code = Key::Char(ch.to_ascii_uppercase());
+            }
+            key.modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Key::BackTab => key.modifiers.remove(KeyModifiers::SHIFT),
+        _ => {}
+    }
+    key
+}
+
+/// ### fmt_key
+///
+/// Format key event as a human readable string
+fn fmt_key(key: &KeyEvent) -> String {
+    let mut repr = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        repr.push_str("Ctrl+");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        repr.push_str("Alt+");
+    }
+    if key.modifiers.contains(KeyModifiers::SHIFT) {
+        repr.push_str("Shift+");
+    }
+    let code = match key.code {
+        Key::Backspace => String::from("Backspace"),
+        Key::Enter => String::from("Enter"),
+        Key::Left => String::from("Left"),
+        Key::Right => String::from("Right"),
+        Key::Up => String::from("Up"),
+        Key::Down => String::from("Down"),
+        Key::Home => String::from("Home"),
+        Key::End => String::from("End"),
+        Key::PageUp => String::from("PageUp"),
+        Key::PageDown => String::from("PageDown"),
+        Key::Tab => String::from("Tab"),
+        Key::BackTab => String::from("BackTab"),
+        Key::Delete => String::from("Delete"),
+        Key::Insert => String::from("Insert"),
+        Key::Function(n) => format!("F{}", n),
+        Key::Char(' ') => String::from("Space"),
+        // Show letters as uppercase if combined with ctrl/alt, since they're case insensitive
+        Key::Char(ch) if !key.modifiers.is_empty() => ch.to_uppercase().to_string(),
+        Key::Char(ch) => ch.to_string(),
+        Key::Null => String::from("Null"),
+        Key::Esc => String::from("Esc"),
+    };
+    repr.push_str(code.as_str());
+    repr
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_parse_keys() {
+        assert_eq!(
+            parse_key("ctrl+r").unwrap(),
+            KeyEvent::new(Key::Char('r'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            parse_key("Esc").unwrap(),
+            KeyEvent::new(Key::Esc, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("shift+g").unwrap(),
+            KeyEvent::new(Key::Char('G'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("+").unwrap(),
+            KeyEvent::new(Key::Char('+'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("alt++").unwrap(),
+            KeyEvent::new(Key::Char('+'), KeyModifiers::ALT)
+        );
+        assert_eq!(
+            parse_key("f5").unwrap(),
+            KeyEvent::new(Key::Function(5), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            parse_key("ctrl+alt+pagedown").unwrap(),
+            KeyEvent::new(Key::PageDown, KeyModifiers::CONTROL | KeyModifiers::ALT)
+        );
+    }
+
+    #[test]
+    fn should_fail_parsing_keys() {
+        assert!(parse_key("hyper+r").is_err());
+        assert!(parse_key("foobar").is_err());
+        assert!(parse_key("fx").is_err());
+        assert!(Keys::from_str("ctrl+").is_err());
+    }
+
+    #[test]
+    fn should_match_keys() {
+        let keys = Keys::from(["down", "j", "G"]);
+        assert!(keys.matches(&KeyEvent::new(Key::Down, KeyModifiers::NONE)));
+        assert!(keys.matches(&KeyEvent::new(Key::Char('j'), KeyModifiers::NONE)));
+        assert!(keys.matches(&KeyEvent::new(Key::Char('G'), KeyModifiers::SHIFT)));
+        assert!(!keys.matches(&KeyEvent::new(Key::Char('j'), KeyModifiers::CONTROL)));
+        assert!(!keys.matches(&KeyEvent::new(Key::Up, KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn should_tell_overlapping_keys() {
+        let bindings = KeyBindings::default();
+        assert!(bindings.next_unread.overlaps(&bindings.previous_match));
+        assert!(!bindings.next_unread.overlaps(&bindings.next_match));
+        assert!(Keys::from(["G", "end"]).overlaps(&bindings.go_to_end));
+    }
+
+    #[test]
+    fn should_support_vim_navigation_by_default() {
+        let bindings = KeyBindings::default();
+        assert!(bindings
+            .move_down
+            .matches(&KeyEvent::new(Key::Char('j'), KeyModifiers::NONE)));
+        assert!(bindings
+            .move_up
+            .matches(&KeyEvent::new(Key::Char('k'), KeyModifiers::NONE)));
+        assert!(bindings
+            .scroll_down
+            .matches(&KeyEvent::new(Key::Char('d'), KeyModifiers::CONTROL)));
+        assert!(bindings
+            .scroll_up
+            .matches(&KeyEvent::new(Key::Char('u'), KeyModifiers::CONTROL)));
+        assert!(bindings
+            .page_down
+            .matches(&KeyEvent::new(Key::Char('f'), KeyModifiers::CONTROL)));
+        assert!(bindings
+            .page_up
+            .matches(&KeyEvent::new(Key::Char('b'), KeyModifiers::CONTROL)));
+        assert!(bindings
+            .go_to_begin
+            .matches(&KeyEvent::new(Key::Char('g'), KeyModifiers::NONE)));
+        assert!(bindings
+            .go_to_end
+            .matches(&KeyEvent::new(Key::Char('G'), KeyModifiers::SHIFT)));
+        assert!(bindings
+            .previous_pane
+            .matches(&KeyEvent::new(Key::Char('h'), KeyModifiers::NONE)));
+        assert!(bindings
+            .next_pane
+            .matches(&KeyEvent::new(Key::Char('l'), KeyModifiers::NONE)));
+        // Ctrl+d must not collide with delete feed
+        assert!(!bindings
+            .delete_feed
+            .matches(&KeyEvent::new(Key::Char('d'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn should_format_keys() {
+        assert_eq!(
+            Keys::from(["ctrl+r", "esc", "?", "space"]).to_string(),
+            "Ctrl+R, Esc, ?, Space"
+        );
+    }
+
+    #[test]
+    fn should_deserialize_key_bindings() {
+        let bindings: KeyBindings = toml::de::from_str(
+            r#"
+            quit = "q"
+            move_down = ["down", "j"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(bindings.quit, Keys::from(["q"]));
+        assert_eq!(bindings.move_down, Keys::from(["down", "j"]));
+        // Others are default
+        assert_eq!(bindings.fetch_source, KeyBindings::default().fetch_source);
+    }
+
+    #[test]
+    fn should_fail_deserializing_key_bindings() {
+        assert!(toml::de::from_str::<KeyBindings>(r#"quit = "ctrl+foo""#).is_err());
+    }
+
+    #[test]
+    fn should_render_cheatsheet() {
+        let bindings: KeyBindings = toml::de::from_str(r#"quit = ["q", "|"]"#).unwrap();
+        let markdown = bindings.cheatsheet(CheatsheetFormat::Markdown);
+        assert!(markdown.starts_with("# tuifeed key bindings\n\n## Global\n"));
+        assert!(markdown.contains("| `q`, `\\|` | Quit tuifeed |\n"));
+        assert!(markdown.contains("\n## Reader\n"));
+        let text = bindings.cheatsheet(CheatsheetFormat::Text);
+        assert!(text.starts_with("Global\n  q, |"));
+        assert!(text.contains("\nFeed list\n"));
+        assert!(text.contains(" Reload all sources\n"));
+    }
+
+    #[test]
+    fn should_parse_cheatsheet_format() {
+        assert_eq!(
+            CheatsheetFormat::from_str("md").unwrap(),
+            CheatsheetFormat::Markdown
+        );
+        assert_eq!(
+            CheatsheetFormat::from_str("txt").unwrap(),
+            CheatsheetFormat::Text
+        );
+        assert!(CheatsheetFormat::from_str("pdf").is_err());
+    }
+
+    #[test]
+    fn should_describe_key_bindings() {
+        let bindings = KeyBindings::default();
+        let description = bindings.describe();
+        assert_eq!(description[0].0, KeyContext::Global);
+        assert_eq!(description[0].1[0], (&bindings.quit, "Quit tuifeed"));
+    }
+}
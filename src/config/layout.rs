@@ -0,0 +1,143 @@
+//! # Layout
+//!
+//! Layout configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::{de::Error as DeError, Deserialize, Deserializer};
+
+/// Minimum percentage a pane can take
+const MIN_PERCENTAGE: u16 = 10;
+/// Maximum percentage a pane can take
+const MAX_PERCENTAGE: u16 = 90;
+
+/// ## LayoutConfig
+///
+/// Describes the proportions of the panes in the UI
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    /// Width percentage of the pane containing the feed list and the article list
+    #[serde(deserialize_with = "deserialize_percentage")]
+    pub feeds_pane: u16,
+    /// Height percentage of the feed list in the lists pane
+    #[serde(deserialize_with = "deserialize_percentage")]
+    pub list_ratio: u16,
+    /// Panes orientation
+    pub orientation: Orientation,
+    /// When orientation is `auto`, the vertical layout is used if the terminal is narrower than this
+    pub narrow_width: u16,
+}
+
+/// ## Orientation
+///
+/// Describes how the lists pane and the article pane are placed
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    /// Lists on the left, article on the right
+    Horizontal,
+    /// Lists on top, article below
+    Vertical,
+    /// Horizontal, unless the terminal is narrower than `narrow_width`
+    Auto,
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            feeds_pane: 50,
+            list_ratio: 30,
+            orientation: Orientation::Auto,
+            narrow_width: 80,
+        }
+    }
+}
+
+impl LayoutConfig {
+    /// ### is_vertical
+    ///
+    /// Returns whether the vertical layout should be used for a terminal `width` columns wide
+    pub fn is_vertical(&self, width: u16) -> bool {
+        match self.orientation {
+            Orientation::Horizontal => false,
+            Orientation::Vertical => true,
+            Orientation::Auto => width < self.narrow_width,
+        }
+    }
+}
+
+/// ### deserialize_percentage
+///
+/// Deserialize a pane percentage, which must be in range [10, 90]
+fn deserialize_percentage<'de, D>(deserializer: D) -> Result<u16, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let percentage = u16::deserialize(deserializer)?;
+    if (MIN_PERCENTAGE..=MAX_PERCENTAGE).contains(&percentage) {
+        Ok(percentage)
+    } else {
+        Err(DeError::custom(format!(
+            "percentage must be between {} and {}; got {}",
+            MIN_PERCENTAGE, MAX_PERCENTAGE, percentage
+        )))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_layout_config() {
+        let layout: LayoutConfig = toml::de::from_str("feeds_pane = 40").unwrap();
+        assert_eq!(layout.feeds_pane, 40);
+        assert_eq!(layout.list_ratio, 30);
+        assert_eq!(layout.orientation, Orientation::Auto);
+        let layout: LayoutConfig = toml::de::from_str(r#"orientation = "vertical""#).unwrap();
+        assert_eq!(layout.orientation, Orientation::Vertical);
+    }
+
+    #[test]
+    fn should_tell_whether_layout_is_vertical() {
+        let mut layout = LayoutConfig::default();
+        assert!(layout.is_vertical(60));
+        assert!(!layout.is_vertical(120));
+        layout.orientation = Orientation::Horizontal;
+        assert!(!layout.is_vertical(60));
+        layout.orientation = Orientation::Vertical;
+        assert!(layout.is_vertical(120));
+    }
+
+    #[test]
+    fn should_fail_deserializing_layout_config() {
+        assert!(toml::de::from_str::<LayoutConfig>("feeds_pane = 100").is_err());
+        assert!(toml::de::from_str::<LayoutConfig>("list_ratio = 5").is_err());
+        assert!(toml::de::from_str::<LayoutConfig>(r#"orientation = "diagonal""#).is_err());
+    }
+}
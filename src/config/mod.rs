@@ -25,16 +25,417 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+pub mod archive;
+pub mod articles;
+pub mod cache;
+pub mod daemon;
+pub mod dates;
+pub mod email;
+pub mod errors;
+pub mod feeds;
+pub mod highlights;
+pub mod hooks;
+pub mod include;
+pub mod interpolate;
+pub mod keys;
+pub mod layout;
+pub mod maildir;
+pub mod mute;
+pub mod openers;
+pub mod patterns;
+pub mod push;
+pub mod reddit;
+pub mod retry;
+pub mod scoring;
+pub mod scripting;
 pub mod serializer;
+pub mod share;
+pub mod sources;
+pub mod styles;
+pub mod sync;
+pub mod tabs;
+pub mod terminal;
+pub mod theme;
+pub mod tls;
+pub mod tts;
+pub mod urls;
+pub mod video;
+pub mod webhooks;
+pub mod writer;
 
+use crate::helpers::file as file_helpers;
+
+pub use archive::ArchiveConfig;
+pub use articles::ArticlesConfig;
+pub use cache::CacheConfig;
+pub use daemon::DaemonConfig;
+pub use dates::DatesConfig;
+pub use email::{EmailConfig, SmtpConfig};
+pub use errors::{ErrorOutput, ErrorsConfig};
+pub use feeds::{FeedSort, FeedsConfig};
+pub use highlights::Highlights;
+pub use hooks::{HookEvent, HooksConfig};
+pub use keys::{CheatsheetFormat, KeyBindings, Keys};
+pub use layout::LayoutConfig;
+pub use maildir::MaildirConfig;
+pub use mute::{mute_feed, MuteRule};
+pub use openers::Opener;
+pub use patterns::ArticlePattern;
+pub use push::{PushService, PushTarget};
+pub use reddit::RedditConfig;
+pub use retry::RetryConfig;
+pub use scoring::{score_feed, ScoringRule};
+pub use scripting::{ScriptAction, ScriptingConfig};
+pub use serializer::{Json, SerializerError, SerializerErrorKind, Toml, Yaml};
+pub use share::ShareTarget;
+pub use sources::Source;
+pub use styles::FeedStyle;
+pub use sync::SyncConfig;
+pub use tabs::Tab;
+pub use terminal::TerminalConfig;
+pub use theme::{AgeStyles, Theme, ThemeConfig, ThemePreset};
+pub use tls::TlsConfig;
+pub use tts::TtsConfig;
+pub use urls::UrlsConfig;
+pub use video::VideoConfig;
+pub use webhooks::Webhook;
+
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Example configuration, written by `tuifeed init`
+pub const EXAMPLE_CONFIG: &str = include_str!("example.toml");
 
 /// ## Config
 ///
 /// tuifeed configuration
 #[derive(Deserialize, Debug, Default)]
 pub struct Config {
-    /// Association between source name and url
-    pub sources: HashMap<String, String>,
+    /// Association between source name and source
+    pub sources: HashMap<String, Source>,
+    /// Archived articles
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    /// Articles displayed in the article list
+    #[serde(default)]
+    pub articles: ArticlesConfig,
+    /// Local article store
+    #[serde(default)]
+    pub cache: CacheConfig,
+    /// Background daemon
+    #[serde(default)]
+    pub daemon: DaemonConfig,
+    /// Dates rendering
+    #[serde(default)]
+    pub dates: DatesConfig,
+    /// Digests sent by email
+    #[serde(default)]
+    pub email: EmailConfig,
+    /// Errors reporting
+    #[serde(default)]
+    pub errors: ErrorsConfig,
+    /// Feed list
+    #[serde(default)]
+    pub feeds: FeedsConfig,
+    /// Words highlighted in article titles and summaries
+    #[serde(default)]
+    pub highlights: Highlights,
+    /// Commands run on lifecycle events
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Key bindings
+    #[serde(default)]
+    pub keys: KeyBindings,
+    /// Layout proportions
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    /// Maildir new articles are delivered into
+    #[serde(default)]
+    pub maildir: MaildirConfig,
+    /// Rules to mute articles
+    #[serde(default)]
+    pub mute: Vec<MuteRule>,
+    /// Commands listed in the "open with" menu
+    #[serde(default)]
+    pub openers: Vec<Opener>,
+    /// Push notification services the new articles are sent to
+    #[serde(default)]
+    pub push: Vec<PushTarget>,
+    /// Reddit sources
+    #[serde(default)]
+    pub reddit: RedditConfig,
+    /// Automatic retry of failed sources
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Rules to score articles
+    #[serde(default)]
+    pub scoring: Vec<ScoringRule>,
+    /// Script filtering and scoring articles
+    #[serde(default)]
+    pub scripting: ScriptingConfig,
+    /// Commands listed in the share menu
+    #[serde(default)]
+    pub share: Vec<ShareTarget>,
+    /// Styles of the sources in the feed list, by source or category name
+    #[serde(default)]
+    pub styles: HashMap<String, FeedStyle>,
+    /// Read state synchronization
+    #[serde(default)]
+    pub sync: SyncConfig,
+    /// Workspaces displayed in the tab bar
+    #[serde(default)]
+    pub tabs: Vec<Tab>,
+    /// Terminal capabilities
+    #[serde(default)]
+    pub terminal: TerminalConfig,
+    /// Colors
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Certificates verification
+    #[serde(default)]
+    pub tls: TlsConfig,
+    /// Text-to-speech
+    #[serde(default)]
+    pub tts: TtsConfig,
+    /// Article urls cleaning
+    #[serde(default)]
+    pub urls: UrlsConfig,
+    /// Video player
+    #[serde(default)]
+    pub video: VideoConfig,
+    /// Urls the new articles are posted to
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    /// Errors of the sources which couldn't be loaded
+    #[serde(skip)]
+    pub source_errors: Vec<String>,
+    /// Names of the sources, in the order they're written in the configuration
+    #[serde(skip)]
+    pub source_order: Vec<String>,
+}
+
+impl Config {
+    /// ### load
+    ///
+    /// Load and parse the configuration file located at `p`, merging the files it includes;
+    /// files with the `.json` extension are read as JSON, those with the `.yaml` or `.yml` one as YAML,
+    /// the others as TOML.
+    /// Invalid sources are left out of the configuration and reported in `source_errors`
+    pub fn load(p: &Path) -> Result<Self, SerializerError> {
+        let mut source_errors = Vec::new();
+        let mut source_order = Vec::new();
+        let mut config: Self = Self::read(p, |value, data| {
+            source_errors = sources::remove_invalid_sources(value, data);
+            include::merge_includes(value, p, &mut |included| {
+                Self::read(included, |value, data| {
+                    source_errors.extend(
+                        sources::remove_invalid_sources(value, data)
+                            .into_iter()
+                            .map(|err| format!("{}: {}", included.display(), err)),
+                    );
+                    Ok(())
+                })
+            })?;
+            source_order = sources::source_order(value);
+            Ok(())
+        })?;
+        config.source_errors = source_errors;
+        config.source_order = source_order;
+        Ok(config)
+    }
+
+    /// ### source_ranks
+    ///
+    /// Get the position of each source in the configuration order: sources with an `order` come first,
+    /// by ascending order, followed by the others in the order they're written.
+    /// Sources which aren't written in the configuration loaded (e.g. subscribed to later) go last, by name
+    pub fn source_ranks(&self) -> HashMap<String, usize> {
+        let mut names: Vec<&String> = self
+            .source_order
+            .iter()
+            .filter(|x| self.sources.contains_key(*x))
+            .collect();
+        let mut unlisted: Vec<&String> = self
+            .sources
+            .keys()
+            .filter(|x| !self.source_order.contains(x))
+            .collect();
+        unlisted.sort();
+        names.extend(unlisted);
+        names.sort_by_key(|x| self.sources[*x].order.unwrap_or(i64::MAX));
+        names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect()
+    }
+
+    /// ### pinned_sources
+    ///
+    /// Get the names of the sources pinned to the top of the feed list
+    pub fn pinned_sources(&self) -> HashSet<String> {
+        self.sources
+            .iter()
+            .filter(|(_, source)| source.pinned)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// ### read
+    ///
+    /// Read the file at `p`, in the format given by its extension, calling `prepare` before deserializing it
+    fn read<S, F>(p: &Path, prepare: F) -> Result<S, SerializerError>
+    where
+        S: DeserializeOwned + std::fmt::Debug,
+        F: FnOnce(&mut toml::Value, &str) -> Result<(), SerializerError>,
+    {
+        let file = file_helpers::open_file_read(p).map_err(|e| {
+            SerializerError::new(SerializerErrorKind::Io, format!("{}: {}", p.display(), e))
+        })?;
+        if Json::matches(p) {
+            serializer::deserialize(&Json, file, prepare)
+        } else if Yaml::matches(p) {
+            serializer::deserialize(&Yaml, file, prepare)
+        } else {
+            serializer::deserialize(&Toml, file, prepare)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    #[test]
+    fn should_load_config_from_file() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(
+            tmpfile,
+            "[sources]\nnytimes = \"https://rss.nytimes.com/services/xml/rss/nyt/World.xml\""
+        )
+        .is_ok());
+        let config = Config::load(tmpfile.path()).ok().unwrap();
+        assert_eq!(config.sources.len(), 1);
+    }
+
+    #[test]
+    fn should_rank_sources_in_config_order() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(
+            tmpfile,
+            "[sources]\nnytimes = \"https://rss.nytimes.com/services/xml/rss/nyt/World.xml\"\nlobsters = \"https://lobste.rs/rss\"\nhackernews = {{ url = \"https://news.ycombinator.com/rss\", order = 1 }}"
+        )
+        .is_ok());
+        let mut config = Config::load(tmpfile.path()).ok().unwrap();
+        assert_eq!(
+            config.source_order,
+            vec![
+                String::from("nytimes"),
+                String::from("lobsters"),
+                String::from("hackernews")
+            ]
+        );
+        config.sources.insert(
+            String::from("arstechnica"),
+            Source::from("https://feeds.arstechnica.com/arstechnica/index"),
+        );
+        let ranks = config.source_ranks();
+        assert_eq!(ranks["hackernews"], 0);
+        assert_eq!(ranks["nytimes"], 1);
+        assert_eq!(ranks["lobsters"], 2);
+        assert_eq!(ranks["arstechnica"], 3);
+    }
+
+    #[test]
+    fn should_load_config_with_invalid_sources() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(
+            tmpfile,
+            "[sources]\nnytimes = \"https://rss.nytimes.com/services/xml/rss/nyt/World.xml\"\nbroken = {{ link = \"https://example.com\" }}"
+        )
+        .is_ok());
+        let config = Config::load(tmpfile.path()).ok().unwrap();
+        assert_eq!(config.sources.len(), 1);
+        assert_eq!(config.source_errors.len(), 1);
+        assert!(config.source_errors[0].starts_with(r#"Invalid source "broken" at line 3"#));
+    }
+
+    #[test]
+    fn should_load_json_config_from_file() {
+        let mut tmpfile = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        assert!(writeln!(
+            tmpfile,
+            r#"{{"sources": {{"nytimes": "https://rss.nytimes.com/services/xml/rss/nyt/World.xml", "broken": 42}}}}"#
+        )
+        .is_ok());
+        let config = Config::load(tmpfile.path()).ok().unwrap();
+        assert_eq!(config.sources.len(), 1);
+        assert_eq!(config.source_errors.len(), 1);
+    }
+
+    #[test]
+    fn should_load_config_with_includes() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let config_file = tempdir.path().join("config.toml");
+        std::fs::write(
+            config_file.as_path(),
+            "include = [\"news.json\"]\n[sources]\nhn = \"https://hnrss.org/newest\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tempdir.path().join("news.json"),
+            r#"{"sources": {"nytimes": "https://rss.nytimes.com/services/xml/rss/nyt/World.xml", "broken": 42}}"#,
+        )
+        .unwrap();
+        let config = Config::load(config_file.as_path()).ok().unwrap();
+        assert_eq!(config.sources.len(), 2);
+        assert!(config.sources.contains_key("nytimes"));
+        assert_eq!(config.source_errors.len(), 1);
+        assert!(config.source_errors[0].contains("news.json: Invalid source \"broken\""));
+    }
+
+    #[test]
+    fn should_fail_loading_config_from_file() {
+        assert!(Config::load(Path::new("/this/file/does/not/exist.toml")).is_err());
+    }
+
+    #[test]
+    fn should_parse_example_config() {
+        let config: Config =
+            serializer::deserialize(&Toml, EXAMPLE_CONFIG.as_bytes(), |_, _| Ok(()))
+                .ok()
+                .unwrap();
+        assert_eq!(config.sources.len(), 2);
+        // Uncomment all the settings
+        let example: String = EXAMPLE_CONFIG
+            .lines()
+            .map(|line| match line.strip_prefix('#') {
+                Some(setting) if !setting.is_empty() && !setting.starts_with([' ', '#']) => setting,
+                _ => line,
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let config: Config = serializer::deserialize(&Toml, example.as_bytes(), |_, _| Ok(()))
+            .ok()
+            .unwrap();
+        assert_eq!(config.sources.len(), 6);
+        assert_eq!(
+            config.highlights.split("rust").as_slice(),
+            &[("rust", true)]
+        );
+        assert_eq!(config.mute.len(), 1);
+        assert_eq!(config.scoring.len(), 1);
+        assert_eq!(config.openers.len(), 1);
+        assert_eq!(config.share.len(), 1);
+        assert_eq!(config.daemon.notify.len(), 1);
+        assert_eq!(config.urls.rewrite.len(), 1);
+        assert!(config.archive.dir.is_some());
+        assert!(config.tls.ca_file.is_some());
+    }
 }
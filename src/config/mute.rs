@@ -0,0 +1,197 @@
+//! # Mute
+//!
+//! Rules to mute articles, like killfiles
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::ArticlePattern;
+use crate::feed::{Article, Feed};
+
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// ## MuteRule
+///
+/// Mutes the articles matching the pattern of the rule
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawMuteRule")]
+pub struct MuteRule {
+    pub pattern: ArticlePattern,
+    pub action: MuteAction,
+}
+
+/// ## MuteAction
+///
+/// Describes what happens to muted articles
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MuteAction {
+    /// Article is not displayed at all
+    Hide,
+    /// Article is marked as read
+    Read,
+}
+
+/// ## RawMuteRule
+///
+/// Mute rule as written in the configuration, before the patterns are compiled
+#[derive(Deserialize)]
+struct RawMuteRule {
+    title: Option<String>,
+    author: Option<String>,
+    feed: Option<String>,
+    action: Option<MuteAction>,
+}
+
+impl TryFrom<RawMuteRule> for MuteRule {
+    type Error = String;
+
+    fn try_from(rule: RawMuteRule) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pattern: ArticlePattern::new(rule.title, rule.author, rule.feed)
+                .map_err(|e| format!("invalid mute rule: {}", e))?,
+            action: rule.action.unwrap_or(MuteAction::Hide),
+        })
+    }
+}
+
+/// ### mute_action
+///
+/// Get the action of the first rule in `rules` which matches the `article` of `source`
+pub fn mute_action(rules: &[MuteRule], source: &str, article: &Article) -> Option<MuteAction> {
+    rules
+        .iter()
+        .find(|x| x.pattern.matches(source, article))
+        .map(|x| x.action)
+}
+
+/// ### mute_feed
+///
+/// Remove the articles of `feed` hidden by `rules`.
+/// Returns the urls of the articles which must be marked as read
+pub fn mute_feed(rules: &[MuteRule], source: &str, feed: &mut Feed) -> Vec<String> {
+    let mut read: Vec<String> = Vec::new();
+    feed.articles
+        .retain(|x| match mute_action(rules, source, x) {
+            Some(MuteAction::Hide) => false,
+            Some(MuteAction::Read) => {
+                read.push(x.url.clone());
+                true
+            }
+            None => true,
+        });
+    read
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        mute: Vec<MuteRule>,
+    }
+
+    #[test]
+    fn should_deserialize_mute_rules() {
+        let config: Wrapper = toml::de::from_str(
+            r#"
+[[mute]]
+title = "(?i)sponsored"
+
+[[mute]]
+author = "^Bot$"
+feed = "Hacker News"
+action = "read"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.mute.len(), 2);
+        assert_eq!(
+            config.mute[0].pattern.title.as_ref().unwrap().as_str(),
+            "(?i)sponsored"
+        );
+        assert!(config.mute[0].pattern.author.is_none());
+        assert_eq!(config.mute[0].action, MuteAction::Hide);
+        assert_eq!(config.mute[1].action, MuteAction::Read);
+    }
+
+    #[test]
+    fn should_not_deserialize_bad_mute_rules() {
+        assert!(toml::de::from_str::<Wrapper>("[[mute]]\naction = \"hide\"").is_err());
+        assert!(toml::de::from_str::<Wrapper>("[[mute]]\ntitle = \"(unclosed\"").is_err());
+        assert!(
+            toml::de::from_str::<Wrapper>("[[mute]]\ntitle = \"a\"\naction = \"delete\"").is_err()
+        );
+    }
+
+    #[test]
+    fn should_match_articles() {
+        let config: Wrapper = toml::de::from_str(
+            r#"
+[[mute]]
+title = "(?i)sponsored"
+
+[[mute]]
+author = "^Bot$"
+feed = "^hn$"
+action = "read"
+"#,
+        )
+        .unwrap();
+        let mut article = Article {
+            title: Some(String::from("[Sponsored] Buy our stuff")),
+            authors: vec![String::from("Alice"), String::from("Bot")],
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::from("https://example.com/a"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        assert_eq!(
+            mute_action(config.mute.as_slice(), "hn", &article),
+            Some(MuteAction::Hide)
+        );
+        article.title = Some(String::from("Show HN: tuifeed"));
+        assert_eq!(
+            mute_action(config.mute.as_slice(), "hn", &article),
+            Some(MuteAction::Read)
+        );
+        assert_eq!(
+            mute_action(config.mute.as_slice(), "lobsters", &article),
+            None
+        );
+        article.authors = vec![String::from("Robot")];
+        assert_eq!(mute_action(config.mute.as_slice(), "hn", &article), None);
+    }
+}
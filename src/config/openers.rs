@@ -0,0 +1,195 @@
+//! # Openers
+//!
+//! Commands listed in the "open with" menu
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use regex::Regex;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// ## Opener
+///
+/// A command listed in the "open with" menu for the links it matches
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawOpener")]
+pub struct Opener {
+    /// Name displayed in the menu
+    pub name: String,
+    /// Command to run (e.g. `mpv --fs`); the link is passed as last argument
+    pub command: String,
+    /// Links the opener is listed for; all if not set
+    pub url: Option<Regex>,
+    /// Content types the opener is listed for (e.g. `image/*`); all if empty
+    pub types: Vec<String>,
+}
+
+/// ## RawOpener
+///
+/// Opener as written in the configuration, before the url pattern is compiled
+#[derive(Deserialize)]
+struct RawOpener {
+    name: Option<String>,
+    command: String,
+    url: Option<String>,
+    #[serde(default)]
+    types: Vec<String>,
+}
+
+impl TryFrom<RawOpener> for Opener {
+    type Error = String;
+
+    fn try_from(opener: RawOpener) -> Result<Self, Self::Error> {
+        if opener.command.trim().is_empty() {
+            return Err(String::from("invalid opener: command can't be empty"));
+        }
+        let url = opener
+            .url
+            .map(|x| {
+                Regex::new(x.as_str())
+                    .map_err(|e| format!("invalid opener: invalid pattern \"{}\": {}", x, e))
+            })
+            .transpose()?;
+        // Name defaults to the program
+        let name = opener.name.unwrap_or_else(|| {
+            opener
+                .command
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        });
+        Ok(Self {
+            name,
+            command: opener.command,
+            url,
+            types: opener.types.into_iter().map(|x| x.to_lowercase()).collect(),
+        })
+    }
+}
+
+impl Opener {
+    /// ### matches
+    ///
+    /// Returns whether the opener is listed for `url`, whose content type is `content_type`, if known.
+    /// A type ending with `/*` matches all the subtypes (e.g. `image/*` matches `image/png`)
+    pub fn matches(&self, url: &str, content_type: Option<&str>) -> bool {
+        let url_matches = self.url.as_ref().map(|x| x.is_match(url)).unwrap_or(true);
+        let type_matches = self.types.is_empty()
+            || content_type
+                .map(|content_type| {
+                    let content_type = content_type.to_lowercase();
+                    self.types.iter().any(|x| match x.strip_suffix("/*") {
+                        Some(kind) => content_type.split('/').next() == Some(kind),
+                        None => *x == content_type,
+                    })
+                })
+                .unwrap_or(false);
+        url_matches && type_matches
+    }
+}
+
+impl PartialEq for Opener {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.command == other.command
+            && self.url.as_ref().map(|x| x.as_str()) == other.url.as_ref().map(|x| x.as_str())
+            && self.types == other.types
+    }
+}
+
+impl Eq for Opener {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        openers: Vec<Opener>,
+    }
+
+    #[test]
+    fn should_deserialize_openers() {
+        let wrapper: Wrapper = toml::de::from_str(
+            r#"
+[[openers]]
+name = "Video player"
+command = "mpv --fs"
+url = "youtube\\.com|youtu\\.be"
+
+[[openers]]
+command = "imv"
+types = ["image/*"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(wrapper.openers.len(), 2);
+        assert_eq!(wrapper.openers[0].name.as_str(), "Video player");
+        assert_eq!(wrapper.openers[1].name.as_str(), "imv");
+        assert_eq!(wrapper.openers[1].types, vec![String::from("image/*")]);
+    }
+
+    #[test]
+    fn should_reject_invalid_openers() {
+        assert!(toml::de::from_str::<Wrapper>("[[openers]]\ncommand = \"\"").is_err());
+        assert!(
+            toml::de::from_str::<Wrapper>("[[openers]]\ncommand = \"mpv\"\nurl = \"(\"").is_err()
+        );
+    }
+
+    #[test]
+    fn should_match_links() {
+        let wrapper: Wrapper = toml::de::from_str(
+            r#"
+[[openers]]
+command = "mpv"
+url = "youtube\\.com"
+
+[[openers]]
+command = "imv"
+types = ["image/*", "application/pdf"]
+
+[[openers]]
+command = "wget"
+"#,
+        )
+        .unwrap();
+        let (mpv, imv, wget) = (
+            &wrapper.openers[0],
+            &wrapper.openers[1],
+            &wrapper.openers[2],
+        );
+        assert!(mpv.matches("https://www.youtube.com/watch?v=1", None));
+        assert!(!mpv.matches("https://example.com/a.png", Some("image/png")));
+        assert!(imv.matches("https://example.com/a.png", Some("image/png")));
+        assert!(imv.matches("https://example.com/a.pdf", Some("application/PDF")));
+        assert!(!imv.matches("https://example.com/a", None));
+        assert!(!imv.matches("https://example.com/a.mp3", Some("audio/mpeg")));
+        assert!(wget.matches("https://example.com/a", None));
+    }
+}
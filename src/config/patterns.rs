@@ -0,0 +1,146 @@
+//! # Patterns
+//!
+//! Patterns matched against articles by mute and score rules
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::feed::Article;
+
+use regex::Regex;
+
+/// ## ArticlePattern
+///
+/// Regular expressions matched against the title, the authors and the source name of an article.
+/// An article matches if it matches all the expressions set
+#[derive(Debug, Clone)]
+pub struct ArticlePattern {
+    pub title: Option<Regex>,
+    pub author: Option<Regex>,
+    pub feed: Option<Regex>,
+}
+
+impl ArticlePattern {
+    /// ### new
+    ///
+    /// Compile the expressions of the pattern; at least one expression must be set
+    pub fn new(
+        title: Option<String>,
+        author: Option<String>,
+        feed: Option<String>,
+    ) -> Result<Self, String> {
+        if title.is_none() && author.is_none() && feed.is_none() {
+            return Err(String::from(
+                "rule must have at least one of title, author and feed",
+            ));
+        }
+        Ok(Self {
+            title: Self::compile(title)?,
+            author: Self::compile(author)?,
+            feed: Self::compile(feed)?,
+        })
+    }
+
+    /// ### matches
+    ///
+    /// Returns whether the `article` of `source` matches the pattern.
+    /// The author expression matches if any of the article authors matches
+    pub fn matches(&self, source: &str, article: &Article) -> bool {
+        self.title
+            .as_ref()
+            .map(|x| x.is_match(article.title.as_deref().unwrap_or("")))
+            .unwrap_or(true)
+            && self
+                .author
+                .as_ref()
+                .map(|x| article.authors.iter().any(|author| x.is_match(author)))
+                .unwrap_or(true)
+            && self
+                .feed
+                .as_ref()
+                .map(|x| x.is_match(source))
+                .unwrap_or(true)
+    }
+
+    fn compile(pattern: Option<String>) -> Result<Option<Regex>, String> {
+        pattern
+            .map(|x| {
+                Regex::new(x.as_str()).map_err(|e| format!("invalid pattern \"{}\": {}", x, e))
+            })
+            .transpose()
+    }
+}
+
+impl PartialEq for ArticlePattern {
+    fn eq(&self, other: &Self) -> bool {
+        let pattern = |x: &Option<Regex>| x.as_ref().map(|x| x.as_str().to_string());
+        pattern(&self.title) == pattern(&other.title)
+            && pattern(&self.author) == pattern(&other.author)
+            && pattern(&self.feed) == pattern(&other.feed)
+    }
+}
+
+impl Eq for ArticlePattern {}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_match_articles() {
+        let pattern = ArticlePattern::new(
+            Some(String::from("(?i)sponsored")),
+            Some(String::from("^Bot$")),
+            None,
+        )
+        .unwrap();
+        let mut article = Article {
+            title: Some(String::from("[Sponsored] Buy our stuff")),
+            authors: vec![String::from("Alice"), String::from("Bot")],
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::from("https://example.com/a"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        assert!(pattern.matches("hn", &article));
+        article.authors = vec![String::from("Robot")];
+        assert!(!pattern.matches("hn", &article));
+        let pattern = ArticlePattern::new(None, None, Some(String::from("^hn$"))).unwrap();
+        assert!(pattern.matches("hn", &article));
+        assert!(!pattern.matches("lobsters", &article));
+    }
+
+    #[test]
+    fn should_not_make_bad_patterns() {
+        assert!(ArticlePattern::new(None, None, None).is_err());
+        assert!(ArticlePattern::new(Some(String::from("(unclosed")), None, None).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! # Push
+//!
+//! Push notification services the new articles are sent to
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::ArticlePattern;
+use crate::feed::Article;
+
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// Server used by ntfy targets without `server`
+const NTFY_SERVER: &str = "https://ntfy.sh";
+
+/// ## PushTarget
+///
+/// A push notification service which receives the new articles matching the pattern
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawPushTarget")]
+pub struct PushTarget {
+    pub service: PushService,
+    /// Priority of the notifications, in the range of the service
+    pub priority: Option<i8>,
+    /// New articles pushed to the service; without expressions all of them are
+    pub pattern: ArticlePattern,
+}
+
+/// ## PushService
+///
+/// Push notification service and its credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushService {
+    /// ntfy topic, at its full url
+    Ntfy { url: String, token: Option<String> },
+    /// Pushover application token and user key
+    Pushover { token: String, user: String },
+    /// Gotify server and application token
+    Gotify { server: String, token: String },
+}
+
+/// ## RawPushTarget
+///
+/// Push target as written in the configuration, before the patterns are compiled
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPushTarget {
+    service: RawPushService,
+    server: Option<String>,
+    topic: Option<String>,
+    token: Option<String>,
+    user: Option<String>,
+    priority: Option<i8>,
+    title: Option<String>,
+    author: Option<String>,
+    feed: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RawPushService {
+    Ntfy,
+    Pushover,
+    Gotify,
+}
+
+impl TryFrom<RawPushTarget> for PushTarget {
+    type Error = String;
+
+    fn try_from(target: RawPushTarget) -> Result<Self, Self::Error> {
+        let required = |value: Option<String>, name: &str| {
+            value
+                .filter(|x| !x.trim().is_empty())
+                .ok_or_else(|| format!("invalid push target: {} is required", name))
+        };
+        let service = match target.service {
+            RawPushService::Ntfy => PushService::Ntfy {
+                url: format!(
+                    "{}/{}",
+                    target
+                        .server
+                        .as_deref()
+                        .unwrap_or(NTFY_SERVER)
+                        .trim_end_matches('/'),
+                    required(target.topic, "topic")?
+                ),
+                token: target.token,
+            },
+            RawPushService::Pushover => PushService::Pushover {
+                token: required(target.token, "token")?,
+                user: required(target.user, "user")?,
+            },
+            RawPushService::Gotify => PushService::Gotify {
+                server: required(target.server, "server")?
+                    .trim_end_matches('/')
+                    .to_string(),
+                token: required(target.token, "token")?,
+            },
+        };
+        let pattern = match (&target.title, &target.author, &target.feed) {
+            (None, None, None) => ArticlePattern {
+                title: None,
+                author: None,
+                feed: None,
+            },
+            _ => ArticlePattern::new(target.title, target.author, target.feed)
+                .map_err(|e| format!("invalid push target: {}", e))?,
+        };
+        Ok(Self {
+            service,
+            priority: target.priority,
+            pattern,
+        })
+    }
+}
+
+impl PushTarget {
+    /// ### matches
+    ///
+    /// Returns whether the new `article` of `source` must be pushed to the service
+    pub fn matches(&self, source: &str, article: &Article) -> bool {
+        self.pattern.matches(source, article)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[derive(Deserialize)]
+    struct Targets {
+        push: Vec<PushTarget>,
+    }
+
+    #[test]
+    fn should_deserialize_push_targets() {
+        let targets: Targets = toml::de::from_str(
+            r#"
+[[push]]
+service = "ntfy"
+topic = "security"
+priority = 5
+title = "(?i)advisory"
+
+[[push]]
+service = "pushover"
+token = "app-token"
+user = "user-key"
+
+[[push]]
+service = "gotify"
+server = "https://gotify.example.com/"
+token = "app-token"
+feed = "^releases$"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            targets.push[0].service,
+            PushService::Ntfy {
+                url: String::from("https://ntfy.sh/security"),
+                token: None,
+            }
+        );
+        assert_eq!(targets.push[0].priority, Some(5));
+        assert_eq!(
+            targets.push[1].service,
+            PushService::Pushover {
+                token: String::from("app-token"),
+                user: String::from("user-key"),
+            }
+        );
+        assert_eq!(
+            targets.push[2].service,
+            PushService::Gotify {
+                server: String::from("https://gotify.example.com"),
+                token: String::from("app-token"),
+            }
+        );
+        let article = Article {
+            title: Some(String::from("Security advisory for Cargo")),
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::from("https://example.com/a"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        assert!(targets.push[0].matches("blog", &article));
+        assert!(targets.push[1].matches("blog", &article));
+        assert!(!targets.push[2].matches("blog", &article));
+    }
+
+    #[test]
+    fn should_not_deserialize_push_targets_without_credentials() {
+        assert!(toml::de::from_str::<Targets>("[[push]]\nservice = \"ntfy\"").is_err());
+        assert!(
+            toml::de::from_str::<Targets>("[[push]]\nservice = \"pushover\"\ntoken = \"abc\"")
+                .is_err()
+        );
+        assert!(
+            toml::de::from_str::<Targets>("[[push]]\nservice = \"gotify\"\ntoken = \"abc\"")
+                .is_err()
+        );
+        assert!(toml::de::from_str::<Targets>("[[push]]\nservice = \"slack\"").is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! # Retry
+//!
+//! Automatic retry of failed feeds configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+
+/// ## RetryConfig
+///
+/// Describes how sources which couldn't be fetched are retried
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// Whether failed sources are retried automatically
+    pub enabled: bool,
+    /// Seconds to wait before the first retry; the delay is doubled after each attempt
+    pub initial_delay: u64,
+    /// Maximum seconds to wait between two retries
+    pub max_delay: u64,
+    /// Maximum amount of retries for a source
+    pub max_attempts: u32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_delay: 30,
+            max_delay: 900,
+            max_attempts: 5,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_retry_config() {
+        let retry: RetryConfig =
+            toml::de::from_str("initial_delay = 10\nmax_attempts = 3").unwrap();
+        assert_eq!(retry.enabled, true);
+        assert_eq!(retry.initial_delay, 10);
+        assert_eq!(retry.max_delay, 900);
+        assert_eq!(retry.max_attempts, 3);
+        let retry: RetryConfig = toml::de::from_str("enabled = false").unwrap();
+        assert_eq!(retry.enabled, false);
+    }
+}
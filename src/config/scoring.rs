@@ -0,0 +1,145 @@
+//! # Scoring
+//!
+//! Rules to score articles by interest
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::ArticlePattern;
+use crate::feed::{Article, Feed};
+
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// ## ScoringRule
+///
+/// Adds `delta` to the score of the articles matching the pattern of the rule
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawScoringRule")]
+pub struct ScoringRule {
+    pub pattern: ArticlePattern,
+    pub delta: i64,
+}
+
+/// ## RawScoringRule
+///
+/// Scoring rule as written in the configuration, before the patterns are compiled
+#[derive(Deserialize)]
+struct RawScoringRule {
+    title: Option<String>,
+    author: Option<String>,
+    feed: Option<String>,
+    delta: i64,
+}
+
+impl TryFrom<RawScoringRule> for ScoringRule {
+    type Error = String;
+
+    fn try_from(rule: RawScoringRule) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pattern: ArticlePattern::new(rule.title, rule.author, rule.feed)
+                .map_err(|e| format!("invalid scoring rule: {}", e))?,
+            delta: rule.delta,
+        })
+    }
+}
+
+/// ### article_score
+///
+/// Get the score of the `article` of `source`, which is the sum of the deltas of the matching rules.
+/// Returns `None` if no rule matches the article
+pub fn article_score(rules: &[ScoringRule], source: &str, article: &Article) -> Option<i64> {
+    rules
+        .iter()
+        .filter(|x| x.pattern.matches(source, article))
+        .map(|x| x.delta)
+        .fold(None, |score, delta| Some(score.unwrap_or(0) + delta))
+}
+
+/// ### score_feed
+///
+/// Set the interest of each article of `feed` according to `rules`
+pub fn score_feed(rules: &[ScoringRule], source: &str, feed: &mut Feed) {
+    for article in feed.articles.iter_mut() {
+        article.interest = article_score(rules, source, article);
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        scoring: Vec<ScoringRule>,
+    }
+
+    #[test]
+    fn should_score_articles() {
+        let config: Wrapper = toml::de::from_str(
+            r#"
+[[scoring]]
+title = "(?i)\\brust\\b"
+delta = 10
+
+[[scoring]]
+title = "(?i)postgres"
+delta = 5
+
+[[scoring]]
+feed = "^hn$"
+delta = -3
+"#,
+        )
+        .unwrap();
+        let mut article = Article {
+            title: Some(String::from("Rust and Postgres")),
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::from("https://example.com/a"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        let rules = config.scoring.as_slice();
+        assert_eq!(article_score(rules, "lobsters", &article), Some(15));
+        assert_eq!(article_score(rules, "hn", &article), Some(12));
+        article.title = Some(String::from("Golang"));
+        assert_eq!(article_score(rules, "hn", &article), Some(-3));
+        assert_eq!(article_score(rules, "lobsters", &article), None);
+    }
+
+    #[test]
+    fn should_not_deserialize_scoring_rules_without_delta() {
+        assert!(toml::de::from_str::<Wrapper>("[[scoring]]\ntitle = \"rust\"").is_err());
+    }
+}
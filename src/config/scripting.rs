@@ -0,0 +1,89 @@
+//! # Scripting
+//!
+//! Scripting configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::keys::Keys;
+
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// ## ScriptingConfig
+///
+/// Describes the script which filters and scores articles, and the actions it provides
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct ScriptingConfig {
+    /// Path of the rhai script
+    pub file: Option<PathBuf>,
+    pub actions: Vec<ScriptAction>,
+}
+
+/// ## ScriptAction
+///
+/// A function of the script, called on the selected article when one of `key` is pressed
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ScriptAction {
+    pub key: Keys,
+    pub function: String,
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn should_deserialize_scripting_config() {
+        let scripting: ScriptingConfig = toml::de::from_str(
+            r#"
+file = "/home/omar/.config/tuifeed/script.rhai"
+
+[[actions]]
+key = "ctrl+w"
+function = "save_to_wallabag"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            scripting.file,
+            Some(PathBuf::from("/home/omar/.config/tuifeed/script.rhai"))
+        );
+        assert_eq!(scripting.actions.len(), 1);
+        assert_eq!(scripting.actions[0].function, "save_to_wallabag");
+        assert!(scripting.actions[0]
+            .key
+            .matches(&KeyEvent::new(Key::Char('w'), KeyModifiers::CONTROL)));
+        assert_eq!(ScriptingConfig::default().file, None);
+    }
+
+    #[test]
+    fn should_not_deserialize_action_without_function() {
+        assert!(toml::de::from_str::<ScriptAction>("key = \"X\"").is_err());
+    }
+}
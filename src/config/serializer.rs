@@ -25,8 +25,11 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+use super::interpolate;
+
 use serde::de::DeserializeOwned;
 use std::io::Read;
+use std::path::Path;
 use thiserror::Error;
 
 /// ## SerializerError
@@ -36,6 +39,7 @@ use thiserror::Error;
 pub struct SerializerError {
     kind: SerializerErrorKind,
     msg: String,
+    span: Option<Span>,
 }
 
 /// ## SerializerErrorKind
@@ -47,6 +51,23 @@ pub enum SerializerErrorKind {
     Io,
     #[error("Syntax error")]
     Syntax,
+    #[error("Interpolation error")]
+    Interpolation,
+    #[error("Unsupported format")]
+    Unsupported,
+}
+
+/// ## Span
+///
+/// Position of an error in the deserialized document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Line of the error, starting from 0
+    pub line: usize,
+    /// Byte offset of the error in the line
+    pub column: usize,
+    /// Content of the line
+    pub text: String,
 }
 
 impl SerializerError {
@@ -54,7 +75,26 @@ impl SerializerError {
     ///
     /// Instantiates a new `SerializerError` with description message
     pub fn new(kind: SerializerErrorKind, msg: String) -> SerializerError {
-        SerializerError { kind, msg }
+        SerializerError {
+            kind,
+            msg,
+            span: None,
+        }
+    }
+
+    /// ### with_span
+    ///
+    /// Set the position of the error in the document
+    pub fn with_span(mut self, span: Option<Span>) -> SerializerError {
+        self.span = span;
+        self
+    }
+
+    /// ### excerpt
+    ///
+    /// Returns the excerpt of the document line where the error is, if known
+    pub fn excerpt(&self) -> Option<String> {
+        self.span.as_ref().map(|x| x.excerpt())
     }
 }
 
@@ -64,13 +104,188 @@ impl std::fmt::Display for SerializerError {
     }
 }
 
+impl Span {
+    /// ### at
+    ///
+    /// Returns the span at `line` and `column` (a byte offset) of `data`.
+    /// Positions past the end of the document, such as at the end of a truncated document,
+    /// are moved to the end of the last line
+    pub fn at(data: &str, line: usize, column: usize) -> Option<Span> {
+        match data.lines().nth(line) {
+            Some(text) => Some(Span {
+                line,
+                column: column.min(text.len()),
+                text: text.to_string(),
+            }),
+            None => data.lines().enumerate().last().map(|(line, text)| Span {
+                line,
+                column: text.len(),
+                text: text.to_string(),
+            }),
+        }
+    }
+
+    /// ### at_offset
+    ///
+    /// Returns the span at the byte `offset` of `data`
+    pub fn at_offset(data: &str, offset: usize) -> Option<Span> {
+        let before = data.get(..offset)?;
+        let line_start = before.rfind('\n').map(|x| x + 1).unwrap_or(0);
+        Self::at(data, before.matches('\n').count(), offset - line_start)
+    }
+
+    /// ### excerpt
+    ///
+    /// Render the line of the span with a caret below the error, such as
+    ///
+    /// ```text
+    ///  --> line 4, column 9
+    ///   |
+    /// 4 | lefigaro
+    ///   |         ^
+    /// ```
+    pub fn excerpt(&self) -> String {
+        let number = (self.line + 1).to_string();
+        let gutter = " ".repeat(number.len());
+        let prefix = self.text.get(..self.column).unwrap_or(self.text.as_str());
+        // Keep tabs, so that the caret is aligned to the line
+        let padding: String = prefix
+            .chars()
+            .map(|x| if x == '\t' { '\t' } else { ' ' })
+            .collect();
+        format!(
+            "{gutter}--> line {}, column {}\n{gutter} |\n{} | {}\n{gutter} | {}^",
+            number,
+            prefix.chars().count() + 1,
+            number,
+            self.text,
+            padding,
+            gutter = gutter
+        )
+    }
+}
+
+/// ## Format
+///
+/// A document format the configuration can be written in
+pub trait Format {
+    /// ### deserialize_str
+    ///
+    /// Deserialize `data`, written in this format, into `S`.
+    /// The error has a span whenever the position of the error is known
+    fn deserialize_str<S>(&self, data: &str) -> Result<S, SerializerError>
+    where
+        S: DeserializeOwned;
+}
+
+/// ## Toml
+///
+/// TOML format; the default one
+pub struct Toml;
+
+impl Toml {
+    /// ### matches
+    ///
+    /// Returns whether the file at `p` is written in TOML, which is any file not written in another format
+    pub fn matches(p: &Path) -> bool {
+        !Json::matches(p) && !Yaml::matches(p)
+    }
+}
+
+impl Format for Toml {
+    fn deserialize_str<S>(&self, data: &str) -> Result<S, SerializerError>
+    where
+        S: DeserializeOwned,
+    {
+        toml::de::from_str(data).map_err(|err| {
+            SerializerError::new(SerializerErrorKind::Syntax, err.to_string())
+                .with_span(err.line_col().and_then(|(l, c)| Span::at(data, l, c)))
+        })
+    }
+}
+
+/// ## Json
+///
+/// JSON format, for configurations generated by other programs
+pub struct Json;
+
+impl Json {
+    /// ### matches
+    ///
+    /// Returns whether the file at `p` is written in JSON, according to its extension
+    pub fn matches(p: &Path) -> bool {
+        p.extension()
+            .map(|x| x.eq_ignore_ascii_case("json"))
+            .unwrap_or(false)
+    }
+}
+
+impl Format for Json {
+    fn deserialize_str<S>(&self, data: &str) -> Result<S, SerializerError>
+    where
+        S: DeserializeOwned,
+    {
+        serde_json::from_str(data).map_err(|err| {
+            // Lines and columns start from 1; they're 0 if the position is unknown
+            let span = match (err.line(), err.column()) {
+                (0, _) => None,
+                (l, c) => Span::at(data, l - 1, c.saturating_sub(1)),
+            };
+            SerializerError::new(SerializerErrorKind::Syntax, err.to_string()).with_span(span)
+        })
+    }
+}
+
+/// ## Yaml
+///
+/// YAML format, for configurations generated by other programs
+pub struct Yaml;
+
+impl Yaml {
+    /// ### matches
+    ///
+    /// Returns whether the file at `p` is written in YAML, according to its extension
+    pub fn matches(p: &Path) -> bool {
+        p.extension()
+            .map(|x| x.eq_ignore_ascii_case("yaml") || x.eq_ignore_ascii_case("yml"))
+            .unwrap_or(false)
+    }
+}
+
+impl Format for Yaml {
+    fn deserialize_str<S>(&self, data: &str) -> Result<S, SerializerError>
+    where
+        S: DeserializeOwned,
+    {
+        serde_yaml::from_str(data).map_err(|err| {
+            // Lines and columns start from 1
+            let span = err.location().and_then(|x| {
+                Span::at(
+                    data,
+                    x.line().saturating_sub(1),
+                    x.column().saturating_sub(1),
+                )
+            });
+            SerializerError::new(SerializerErrorKind::Syntax, err.to_string()).with_span(span)
+        })
+    }
+}
+
 /// ### deserialize
 ///
-/// Read data from readable and deserialize its content as TOML
-pub fn deserialize<R, S>(mut readable: R) -> Result<S, SerializerError>
+/// Read data from readable and deserialize its content, written in `format`.
+/// Environment variables and commands in values are interpolated before deserializing;
+/// then `prepare` is called with the interpolated document and its source text
+pub fn deserialize<T, R, S, F>(
+    format: &T,
+    mut readable: R,
+    prepare: F,
+) -> Result<S, SerializerError>
 where
+    T: Format,
     R: Read,
     S: DeserializeOwned + Sized + std::fmt::Debug,
+    F: FnOnce(&mut toml::Value, &str) -> Result<(), SerializerError>,
 {
     // Read file content
     let mut data: String = String::new();
@@ -80,13 +295,41 @@ where
             err.to_string(),
         ));
     }
+    // Parse
+    let mut value: toml::Value = format.deserialize_str(data.as_str())?;
+    // Interpolate
+    if let Err(err) = interpolate::interpolate(&mut value) {
+        return Err(SerializerError::new(
+            SerializerErrorKind::Interpolation,
+            err,
+        ));
+    }
+    prepare(&mut value, data.as_str())?;
     // Deserialize
-    match toml::de::from_str(data.as_str()) {
+    match value.try_into() {
         Ok(deserialized) => Ok(deserialized),
-        Err(err) => Err(SerializerError::new(
-            SerializerErrorKind::Syntax,
-            err.to_string(),
-        )),
+        Err(err) => Err(locate_error::<T, S>(format, err.to_string(), data.as_str())),
+    }
+}
+
+/// ### locate_error
+///
+/// Make the error for the document which couldn't be deserialized with `msg`.
+/// Values have no position, so the text is deserialized again to find where the error is:
+/// the position is used only if the error is the same, since values have been interpolated
+fn locate_error<T, S>(format: &T, msg: String, data: &str) -> SerializerError
+where
+    T: Format,
+    S: DeserializeOwned,
+{
+    // The key of the error is not reported by all formats
+    let cause = msg.split(" for key `").next().unwrap_or_default();
+    match format.deserialize_str::<S>(data) {
+        Err(err) if err.msg.starts_with(msg.as_str()) => err,
+        Err(err) if err.msg.starts_with(cause) => {
+            SerializerError::new(SerializerErrorKind::Syntax, msg).with_span(err.span)
+        }
+        _ => SerializerError::new(SerializerErrorKind::Syntax, msg),
     }
 }
 
@@ -117,23 +360,169 @@ mod test {
     fn should_deserialize_config() {
         let config = create_good_toml_config();
         let reader = File::open(config.path()).expect("Could not open TOML file");
-        let config: Config = deserialize(Box::new(reader)).ok().unwrap();
+        let config: Config = deserialize(&Toml, Box::new(reader), |_, _| Ok(()))
+            .ok()
+            .unwrap();
         assert_eq!(config.sources.len(), 2);
         assert_eq!(
-            config.sources.get("nytimes").unwrap().as_str(),
+            config.sources.get("nytimes").unwrap().url.as_str(),
             "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
         );
         assert_eq!(
-            config.sources.get("lefigaro").unwrap().as_str(),
+            config.sources.get("lefigaro").unwrap().url.as_str(),
             "https://www.lefigaro.fr/rss/figaro_actualites.xml"
         );
     }
 
+    #[test]
+    fn should_deserialize_config_with_commands() {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        tmpfile
+            .write_all(b"[sources]\nnews = { url_cmd = \"echo https://example.com/feed.xml\" }\n")
+            .unwrap();
+        let reader = File::open(tmpfile.path()).expect("Could not open TOML file");
+        let config: Config = deserialize(&Toml, reader, |_, _| Ok(())).ok().unwrap();
+        assert_eq!(
+            config.sources.get("news").unwrap().url.as_str(),
+            "https://example.com/feed.xml"
+        );
+    }
+
     #[test]
     fn should_fail_config_deserialization() {
         let config = create_bad_toml_config();
         let reader = File::open(config.path()).expect("Could not open TOML file");
-        assert!(deserialize::<File, Config>(reader).is_err());
+        assert!(deserialize::<_, File, Config, _>(&Toml, reader, |_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn should_render_error_excerpt() {
+        let config = create_bad_toml_config();
+        let reader = File::open(config.path()).expect("Could not open TOML file");
+        let error = deserialize::<_, File, Config, _>(&Toml, reader, |_, _| Ok(()))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.excerpt().unwrap(),
+            " --> line 4, column 17\n  |\n4 |         lefigaro\n  |                 ^"
+        );
+        assert!(
+            SerializerError::new(SerializerErrorKind::Io, String::from("aho"))
+                .excerpt()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn should_locate_deserialization_errors() {
+        let data = "[sources]\nnews = \"https://example.com/feed.xml\"\n\n[dates]\nrelative = 1\n";
+        let error = deserialize::<_, &[u8], Config, _>(&Toml, data.as_bytes(), |_, _| Ok(()))
+            .err()
+            .unwrap();
+        assert_eq!(error.span.as_ref().unwrap().line, 4);
+        assert_eq!(error.span.as_ref().unwrap().text.as_str(), "relative = 1");
+    }
+
+    #[test]
+    fn should_deserialize_json_config() {
+        let data = r#"{
+  "sources": {
+    "nytimes": "https://rss.nytimes.com/services/xml/rss/nyt/World.xml",
+    "hackernews": { "url": "https://news.ycombinator.com/rss", "max_articles": 20 }
+  },
+  "dates": { "relative": true }
+}"#;
+        let config: Config = deserialize(&Json, data.as_bytes(), |_, _| Ok(()))
+            .ok()
+            .unwrap();
+        assert_eq!(config.sources.len(), 2);
+        assert_eq!(
+            config.sources.get("hackernews").unwrap().max_articles,
+            Some(20)
+        );
+        assert!(config.dates.relative);
+    }
+
+    #[test]
+    fn should_deserialize_yaml_config() {
+        let data = r#"sources:
+  nytimes: https://rss.nytimes.com/services/xml/rss/nyt/World.xml
+  hackernews:
+    url: https://news.ycombinator.com/rss
+    max_articles: 20
+dates:
+  relative: true
+"#;
+        let config: Config = deserialize(&Yaml, data.as_bytes(), |_, _| Ok(()))
+            .ok()
+            .unwrap();
+        assert_eq!(config.sources.len(), 2);
+        assert_eq!(
+            config.sources.get("hackernews").unwrap().max_articles,
+            Some(20)
+        );
+        assert!(config.dates.relative);
+        let error = deserialize::<_, &[u8], Config, _>(
+            &Yaml,
+            "sources:\n  - [\n".as_bytes(),
+            |_, _| Ok(()),
+        )
+        .err()
+        .unwrap();
+        assert!(error.excerpt().is_some());
+    }
+
+    #[test]
+    fn should_detect_format_by_extension() {
+        assert!(Json::matches(Path::new("config.json")));
+        assert!(Yaml::matches(Path::new("config.yaml")));
+        assert!(Yaml::matches(Path::new("config.work.YML")));
+        assert!(Toml::matches(Path::new("config.toml")));
+        assert!(!Toml::matches(Path::new("config.yml")));
+    }
+
+    #[test]
+    fn should_locate_json_errors() {
+        let data = "{\n  \"sources\": {},\n  \"dates\": { \"relative\": 1 }\n}";
+        let error = deserialize::<_, &[u8], Config, _>(&Json, data.as_bytes(), |_, _| Ok(()))
+            .err()
+            .unwrap();
+        assert_eq!(error.span.as_ref().unwrap().line, 2);
+        assert_eq!(
+            error.to_string().as_str(),
+            "Syntax error (invalid type: integer `1`, expected a boolean for key `dates.relative`)"
+        );
+        let error =
+            deserialize::<_, &[u8], Config, _>(&Json, b"{\n  \"sources\": \n".as_ref(), |_, _| {
+                Ok(())
+            })
+            .err()
+            .unwrap();
+        assert!(error.excerpt().is_some());
+    }
+
+    #[test]
+    fn should_tell_json_files() {
+        assert!(Json::matches(Path::new(
+            "/home/omar/.config/tuifeed/config.json"
+        )));
+        assert!(Json::matches(Path::new("config.JSON")));
+        assert_eq!(Json::matches(Path::new("config.toml")), false);
+        assert_eq!(Json::matches(Path::new("json")), false);
+    }
+
+    #[test]
+    fn should_get_span_at_offset() {
+        let data = "[sources]\n\tnews = 1\n";
+        let span = Span::at_offset(data, 18).unwrap();
+        assert_eq!(span.line, 1);
+        assert_eq!(span.column, 8);
+        assert_eq!(span.text.as_str(), "\tnews = 1");
+        assert_eq!(
+            span.excerpt(),
+            " --> line 2, column 9\n  |\n2 | \tnews = 1\n  | \t       ^"
+        );
+        assert!(Span::at_offset(data, 128).is_none());
     }
 
     fn create_good_toml_config() -> tempfile::NamedTempFile {
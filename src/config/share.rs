@@ -0,0 +1,164 @@
+//! # Share
+//!
+//! Commands listed in the share menu
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// ## ShareTarget
+///
+/// A command listed in the share menu, which receives the selected article
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawShareTarget")]
+pub struct ShareTarget {
+    /// Name displayed in the menu
+    pub name: String,
+    /// Command template (e.g. `kdeconnect-cli --share %u`); `%u` is replaced with the article url,
+    /// `%t` with the article title and `%%` with `%`
+    pub command: String,
+}
+
+/// ## RawShareTarget
+///
+/// Share target as written in the configuration, before it's validated
+#[derive(Deserialize)]
+struct RawShareTarget {
+    name: Option<String>,
+    command: String,
+}
+
+impl TryFrom<RawShareTarget> for ShareTarget {
+    type Error = String;
+
+    fn try_from(target: RawShareTarget) -> Result<Self, Self::Error> {
+        if target.command.trim().is_empty() {
+            return Err(String::from("invalid share target: command can't be empty"));
+        }
+        // Name defaults to the program
+        let name = target.name.unwrap_or_else(|| {
+            target
+                .command
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        });
+        Ok(Self {
+            name,
+            command: target.command,
+        })
+    }
+}
+
+impl ShareTarget {
+    /// ### args
+    ///
+    /// Get the program and the arguments of the command for the article at `url` titled `title`.
+    /// Placeholders are replaced after the command is split into arguments,
+    /// so a title with spaces is passed as a single argument
+    pub fn args(&self, url: &str, title: &str) -> Vec<String> {
+        self.command
+            .split_whitespace()
+            .map(|arg| {
+                let mut expanded = String::with_capacity(arg.len());
+                let mut chars = arg.chars();
+                while let Some(c) = chars.next() {
+                    if c != '%' {
+                        expanded.push(c);
+                        continue;
+                    }
+                    match chars.next() {
+                        Some('u') => expanded.push_str(url),
+                        Some('t') => expanded.push_str(title),
+                        Some('%') => expanded.push('%'),
+                        Some(other) => {
+                            expanded.push('%');
+                            expanded.push(other);
+                        }
+                        None => expanded.push('%'),
+                    }
+                }
+                expanded
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        share: Vec<ShareTarget>,
+    }
+
+    #[test]
+    fn should_deserialize_share_targets() {
+        let wrapper: Wrapper = toml::de::from_str(
+            r#"
+[[share]]
+name = "Phone"
+command = "kdeconnect-cli -d phone --share %u"
+
+[[share]]
+command = "xdg-email --subject %t --body %u"
+"#,
+        )
+        .unwrap();
+        assert_eq!(wrapper.share.len(), 2);
+        assert_eq!(wrapper.share[0].name.as_str(), "Phone");
+        assert_eq!(wrapper.share[1].name.as_str(), "xdg-email");
+    }
+
+    #[test]
+    fn should_reject_invalid_share_targets() {
+        assert!(toml::de::from_str::<Wrapper>("[[share]]\ncommand = \" \"").is_err());
+    }
+
+    #[test]
+    fn should_expand_share_command() {
+        let target = ShareTarget {
+            name: String::from("mail"),
+            command: String::from("mail -s %t --body=<%u> 100%% 50% %x"),
+        };
+        assert_eq!(
+            target.args("https://example.com/a", "Hello, world"),
+            vec![
+                String::from("mail"),
+                String::from("-s"),
+                String::from("Hello, world"),
+                String::from("--body=<https://example.com/a>"),
+                String::from("100%"),
+                String::from("50%"),
+                String::from("%x"),
+            ]
+        );
+    }
+}
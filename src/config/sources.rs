@@ -0,0 +1,378 @@
+//! # Sources
+//!
+//! Feed sources configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::serializer::Span;
+use crate::feed::{FetchOptions, ScrapeSelectors};
+
+use chrono::{DateTime, Duration, Local};
+use serde::Deserialize;
+use std::path::PathBuf;
+use toml::Value;
+use toml_edit::ImDocument;
+
+/// ## Source
+///
+/// A feed source. In the configuration it can be either just the url of the feed
+/// or a table with the url and the options for the source
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "SourceRepr")]
+pub struct Source {
+    pub url: String,
+    /// Max amount of articles to keep for this source; overrides `articles.max_articles`
+    pub max_articles: Option<usize>,
+    /// Max age of the articles to keep for this source; overrides `articles.max_age_days`
+    pub max_age_days: Option<u32>,
+    /// Max size of the feed download, in KB
+    pub max_size_kb: Option<usize>,
+    /// Minutes during which a fetched source is not fetched again when all sources are reloaded
+    pub refresh: Option<u64>,
+    /// Netscape cookie file with the cookies to send, for feeds behind cookie-based authentication
+    pub cookies: Option<PathBuf>,
+    /// PEM file with the client certificate, for sources requiring mutual TLS
+    pub client_cert: Option<PathBuf>,
+    /// PEM file with the key of the client certificate; if unset, it's read from `client_cert`
+    pub client_key: Option<PathBuf>,
+    /// Don't verify the certificate of the source, e.g. for self-signed development servers
+    pub insecure: bool,
+    /// Category the source belongs to; the sources of a category can be reloaded together
+    pub category: Option<String>,
+    /// Position of the source in the feed list, when sources are sorted by configuration
+    pub order: Option<i64>,
+    /// Keep the source at the top of the feed list, whatever the order
+    pub pinned: bool,
+    /// If set, the url is a web page whose articles are selected with these CSS selectors
+    pub scrape: Option<ScrapeSelectors>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SourceRepr {
+    Url(String),
+    Table(Box<SourceTable>),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SourceTable {
+    url: String,
+    max_articles: Option<usize>,
+    max_age_days: Option<u32>,
+    max_size_kb: Option<usize>,
+    refresh: Option<u64>,
+    cookies: Option<PathBuf>,
+    client_cert: Option<PathBuf>,
+    client_key: Option<PathBuf>,
+    #[serde(default)]
+    insecure: bool,
+    category: Option<String>,
+    order: Option<i64>,
+    #[serde(default)]
+    pinned: bool,
+    scrape: Option<ScrapeSelectors>,
+}
+
+impl Source {
+    /// ### is_fresh
+    ///
+    /// Returns whether the source, last fetched successfully at `last_fetch`, has been fetched
+    /// more recently than its refresh interval, at `now`
+    pub fn is_fresh(&self, last_fetch: Option<DateTime<Local>>, now: DateTime<Local>) -> bool {
+        match (self.refresh, last_fetch) {
+            (Some(refresh), Some(last_fetch)) => {
+                let elapsed = now.signed_duration_since(last_fetch);
+                elapsed >= Duration::zero() && elapsed < Duration::minutes(refresh as i64)
+            }
+            _ => false,
+        }
+    }
+
+    /// ### fetch_options
+    ///
+    /// Get the options of the requests made to fetch the source
+    pub fn fetch_options(&self) -> FetchOptions {
+        FetchOptions {
+            cookies: self.cookies.clone(),
+            client_cert: self.client_cert.clone(),
+            client_key: self.client_key.clone(),
+            insecure: self.insecure,
+            scrape: self.scrape.clone(),
+        }
+    }
+}
+
+impl From<SourceRepr> for Source {
+    fn from(repr: SourceRepr) -> Self {
+        match repr {
+            SourceRepr::Url(url) => Self::from(url),
+            SourceRepr::Table(table) => Self {
+                url: table.url,
+                max_articles: table.max_articles,
+                max_age_days: table.max_age_days,
+                max_size_kb: table.max_size_kb,
+                refresh: table.refresh,
+                cookies: table.cookies,
+                client_cert: table.client_cert,
+                client_key: table.client_key,
+                insecure: table.insecure,
+                category: table.category,
+                order: table.order,
+                pinned: table.pinned,
+                scrape: table.scrape,
+            },
+        }
+    }
+}
+
+impl From<String> for Source {
+    fn from(url: String) -> Self {
+        Self {
+            url,
+            max_articles: None,
+            max_age_days: None,
+            max_size_kb: None,
+            refresh: None,
+            cookies: None,
+            client_cert: None,
+            client_key: None,
+            insecure: false,
+            category: None,
+            order: None,
+            pinned: false,
+            scrape: None,
+        }
+    }
+}
+
+impl From<&str> for Source {
+    fn from(url: &str) -> Self {
+        Self::from(url.to_string())
+    }
+}
+
+/// ### remove_invalid_sources
+///
+/// Remove the sources which can't be deserialized from the configuration `value`, parsed from `data`,
+/// so that the other sources can still be used.
+/// Returns the error of each removed source
+pub fn remove_invalid_sources(value: &mut Value, data: &str) -> Vec<String> {
+    let sources = match value.get_mut("sources").and_then(|x| x.as_table_mut()) {
+        Some(sources) => sources,
+        None => return Vec::new(),
+    };
+    let invalid: Vec<(String, String)> = sources
+        .iter()
+        .filter_map(|(name, source)| check_source(source).err().map(|err| (name.clone(), err)))
+        .collect();
+    if invalid.is_empty() {
+        return Vec::new();
+    }
+    // Find where the sources are written
+    let document = ImDocument::parse(data).ok();
+    let sources_table = document
+        .as_ref()
+        .and_then(|x| x.get("sources"))
+        .and_then(|x| x.as_table_like());
+    invalid
+        .into_iter()
+        .map(|(name, err)| {
+            sources.remove(&name);
+            let span = sources_table
+                .and_then(|x| x.get_key_value(name.as_str()))
+                .and_then(|(key, _)| key.span())
+                .and_then(|x| Span::at_offset(data, x.start));
+            match span {
+                Some(span) => format!(
+                    r#"Invalid source "{}" at line {}: {}"#,
+                    name,
+                    span.line + 1,
+                    err
+                ),
+                None => format!(r#"Invalid source "{}": {}"#, name, err),
+            }
+        })
+        .collect()
+}
+
+/// ### source_order
+///
+/// Get the names of the sources in `value`, in the order they're written
+pub fn source_order(value: &Value) -> Vec<String> {
+    value
+        .get("sources")
+        .and_then(|x| x.as_table())
+        .map(|x| x.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// ### check_source
+///
+/// Check whether the source `value` can be deserialized, returning why it can't otherwise
+fn check_source(value: &Value) -> Result<(), String> {
+    match value {
+        Value::String(_) => Ok(()),
+        Value::Table(_) => value
+            .clone()
+            .try_into::<SourceTable>()
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        other => Err(format!(
+            "expected the url of the feed or a table, found {}",
+            other.type_str()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_deserialize_sources() {
+        let sources: HashMap<String, Source> = toml::de::from_str(
+            r#"
+nytimes = "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+hackernews = { url = "https://news.ycombinator.com/rss", max_articles = 20, max_age_days = 2, max_size_kb = 512, refresh = 60, cookies = "/home/omar/cookies.txt" }
+intranet = { url = "https://intranet.example.com/news.xml", client_cert = "/home/omar/client.pem", insecure = true, category = "work", pinned = true }
+changelog = { url = "https://example.com/changelog", scrape = { item = "li.release", title = "h2", date = "time" } }
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sources.get("nytimes").unwrap(),
+            &Source::from("https://rss.nytimes.com/services/xml/rss/nyt/World.xml")
+        );
+        assert_eq!(
+            sources.get("hackernews").unwrap(),
+            &Source {
+                url: String::from("https://news.ycombinator.com/rss"),
+                max_articles: Some(20),
+                max_age_days: Some(2),
+                max_size_kb: Some(512),
+                refresh: Some(60),
+                cookies: Some(PathBuf::from("/home/omar/cookies.txt")),
+                client_cert: None,
+                client_key: None,
+                insecure: false,
+                category: None,
+                order: None,
+                pinned: false,
+                scrape: None,
+            }
+        );
+        let intranet = sources.get("intranet").unwrap();
+        assert_eq!(
+            intranet.client_cert,
+            Some(PathBuf::from("/home/omar/client.pem"))
+        );
+        assert!(intranet.client_key.is_none());
+        assert_eq!(intranet.insecure, true);
+        assert_eq!(intranet.fetch_options().insecure, true);
+        assert_eq!(intranet.category.as_deref(), Some("work"));
+        assert_eq!(intranet.pinned, true);
+        let scrape = sources.get("changelog").unwrap().fetch_options().scrape;
+        assert_eq!(scrape.as_ref().map(|x| x.item.as_str()), Some("li.release"));
+        assert_eq!(
+            scrape.as_ref().and_then(|x| x.date.as_deref()),
+            Some("time")
+        );
+    }
+
+    #[test]
+    fn should_tell_whether_source_is_fresh() {
+        let now = Local::now();
+        let source = Source {
+            refresh: Some(60),
+            ..Source::from("https://news.ycombinator.com/rss")
+        };
+        assert!(source.is_fresh(Some(now - Duration::minutes(59)), now));
+        assert!(!source.is_fresh(Some(now - Duration::minutes(60)), now));
+        assert!(!source.is_fresh(None, now));
+        // Sources without refresh interval are never fresh
+        assert!(!Source::from("https://news.ycombinator.com/rss")
+            .is_fresh(Some(now - Duration::minutes(1)), now));
+    }
+
+    #[test]
+    fn should_remove_invalid_sources() {
+        let data = r#"[sources]
+nytimes = "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+hackernews = { url = "https://news.ycombinator.com/rss", max_article = 20 }
+lobsters = 42
+"#;
+        let mut value: Value = toml::de::from_str(data).unwrap();
+        let mut errors = remove_invalid_sources(&mut value, data);
+        errors.sort();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0]
+            .starts_with(r#"Invalid source "hackernews" at line 3: unknown field `max_article`"#));
+        assert_eq!(
+            errors[1].as_str(),
+            r#"Invalid source "lobsters" at line 4: expected the url of the feed or a table, found integer"#
+        );
+        let sources: HashMap<String, Source> = value["sources"].clone().try_into().unwrap();
+        assert_eq!(sources.len(), 1);
+        assert!(sources.contains_key("nytimes"));
+        // Valid sources are kept
+        assert!(remove_invalid_sources(&mut value, data).is_empty());
+    }
+
+    #[test]
+    fn should_get_source_order() {
+        let value: Value = toml::de::from_str(
+            r#"[sources]
+nytimes = "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+lobsters = { url = "https://lobste.rs/rss", order = 1 }
+hackernews = "https://news.ycombinator.com/rss"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            source_order(&value),
+            vec![
+                String::from("nytimes"),
+                String::from("lobsters"),
+                String::from("hackernews")
+            ]
+        );
+        let sources: HashMap<String, Source> = value["sources"].clone().try_into().unwrap();
+        assert_eq!(sources["lobsters"].order, Some(1));
+        assert_eq!(sources["lobsters"].pinned, false);
+        assert!(source_order(&Value::Table(Default::default())).is_empty());
+    }
+
+    #[test]
+    fn should_not_deserialize_source_with_unknown_options() {
+        assert!(toml::de::from_str::<HashMap<String, Source>>(
+            r#"hackernews = { url = "https://news.ycombinator.com/rss", max_article = 20 }"#
+        )
+        .is_err());
+    }
+}
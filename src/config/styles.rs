@@ -0,0 +1,134 @@
+//! # Styles
+//!
+//! Colors and modifiers of the sources in the feed list
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+use std::convert::TryFrom;
+use tuirealm::props::{Color, Style, TextModifiers};
+use tuirealm::utils::parser::parse_color;
+
+/// ## FeedStyle
+///
+/// Style of the name of a source, set for the source itself or for its category
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "RawFeedStyle")]
+pub struct FeedStyle {
+    pub color: Option<Color>,
+    pub modifiers: TextModifiers,
+}
+
+/// ## RawFeedStyle
+///
+/// Feed style as written in the configuration, before the color is parsed
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFeedStyle {
+    color: Option<String>,
+    #[serde(default)]
+    bold: bool,
+    #[serde(default)]
+    italic: bool,
+    #[serde(default)]
+    underlined: bool,
+}
+
+impl TryFrom<RawFeedStyle> for FeedStyle {
+    type Error = String;
+
+    fn try_from(style: RawFeedStyle) -> Result<Self, Self::Error> {
+        let color = match style.color {
+            Some(color) => Some(
+                parse_color(color.as_str()).ok_or_else(|| format!("invalid color: {}", color))?,
+            ),
+            None => None,
+        };
+        let mut modifiers = TextModifiers::empty();
+        if style.bold {
+            modifiers |= TextModifiers::BOLD;
+        }
+        if style.italic {
+            modifiers |= TextModifiers::ITALIC;
+        }
+        if style.underlined {
+            modifiers |= TextModifiers::UNDERLINED;
+        }
+        Ok(Self { color, modifiers })
+    }
+}
+
+impl FeedStyle {
+    /// ### style
+    ///
+    /// Get the style to render the name of the source with
+    pub fn style(&self) -> Style {
+        let style = Style::default().add_modifier(self.modifiers);
+        match self.color {
+            Some(color) => style.fg(color),
+            None => style,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn should_deserialize_feed_styles() {
+        let styles: HashMap<String, FeedStyle> = toml::de::from_str(
+            r##"
+"Security advisories" = { color = "red", bold = true }
+news = { color = "#5fafd7", italic = true, underlined = true }
+"##,
+        )
+        .unwrap();
+        assert_eq!(
+            styles.get("Security advisories").unwrap(),
+            &FeedStyle {
+                color: Some(Color::Red),
+                modifiers: TextModifiers::BOLD,
+            }
+        );
+        assert_eq!(
+            styles.get("news").unwrap().style(),
+            Style::default()
+                .fg(Color::Rgb(0x5f, 0xaf, 0xd7))
+                .add_modifier(TextModifiers::ITALIC | TextModifiers::UNDERLINED)
+        );
+    }
+
+    #[test]
+    fn should_not_deserialize_invalid_color() {
+        assert!(toml::de::from_str::<HashMap<String, FeedStyle>>(
+            r#"news = { color = "fuchsia-ish" }"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! # Sync
+//!
+//! Read state synchronization configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+use std::time::Duration;
+
+/// ## SyncConfig
+///
+/// Describes the remote file the read state is synchronized through
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// Url of the file (WebDAV or any server accepting PUT), or path of a local file synchronized by
+    /// other means (e.g. rsync or Syncthing); if not set, the read state is not synchronized
+    pub url: Option<String>,
+    /// User for the HTTP basic authentication
+    pub user: Option<String>,
+    /// Password for the HTTP basic authentication
+    pub password: Option<String>,
+    /// Minutes between two synchronizations
+    pub interval: u64,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            user: None,
+            password: None,
+            interval: 15,
+        }
+    }
+}
+
+impl SyncConfig {
+    /// ### interval
+    ///
+    /// Get the interval between two synchronizations as a duration; it's never less than a minute
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval.max(1) * 60)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_sync_config() {
+        let sync: SyncConfig = toml::de::from_str(
+            r#"
+url = "https://dav.example.com/tuifeed/read.json"
+user = "omar"
+password = "secret"
+interval = 0
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            sync.url.as_deref(),
+            Some("https://dav.example.com/tuifeed/read.json")
+        );
+        assert_eq!(sync.user.as_deref(), Some("omar"));
+        assert_eq!(sync.password.as_deref(), Some("secret"));
+        assert_eq!(sync.interval(), Duration::from_secs(60));
+        let sync = SyncConfig::default();
+        assert!(sync.url.is_none());
+        assert_eq!(sync.interval(), Duration::from_secs(900));
+    }
+}
@@ -0,0 +1,100 @@
+//! # Tabs
+//!
+//! Workspaces which show only the sources of some categories in the feed list
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use serde::Deserialize;
+
+/// ## Tab
+///
+/// A named workspace; the feed list shows only the sources in its categories
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Tab {
+    /// Name displayed in the tab bar
+    pub name: String,
+    /// Categories of the sources in the tab; a tab without categories shows all the sources
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+impl Tab {
+    /// ### contains
+    ///
+    /// Returns whether the sources in `category` belong to the tab
+    pub fn contains(&self, category: Option<&str>) -> bool {
+        self.categories.is_empty()
+            || category
+                .map(|category| self.categories.iter().any(|x| x == category))
+                .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_tabs() {
+        #[derive(Deserialize)]
+        struct Tabs {
+            tabs: Vec<Tab>,
+        }
+        let tabs: Tabs = toml::de::from_str(
+            r#"
+[[tabs]]
+name = "All"
+
+[[tabs]]
+name = "Dev"
+categories = ["rust", "linux"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(tabs.tabs.len(), 2);
+        assert!(tabs.tabs[0].categories.is_empty());
+        assert_eq!(tabs.tabs[1].name.as_str(), "Dev");
+        assert!(toml::de::from_str::<Tabs>("[[tabs]]\ncategories = [\"rust\"]").is_err());
+    }
+
+    #[test]
+    fn should_tell_whether_tab_contains_category() {
+        let all = Tab {
+            name: String::from("All"),
+            categories: Vec::new(),
+        };
+        assert!(all.contains(Some("rust")));
+        assert!(all.contains(None));
+        let dev = Tab {
+            name: String::from("Dev"),
+            categories: vec![String::from("rust"), String::from("linux")],
+        };
+        assert!(dev.contains(Some("linux")));
+        assert!(!dev.contains(Some("news")));
+        assert!(!dev.contains(None));
+    }
+}
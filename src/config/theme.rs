@@ -0,0 +1,371 @@
+//! # Theme
+//!
+//! Colors configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::FeedStyle;
+
+use chrono::Duration;
+use serde::Deserialize;
+use tuirealm::props::{Color, Style, TextModifiers};
+
+/// ## ThemeConfig
+///
+/// Describes which colors are used by the interface
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Colors preset
+    pub preset: ThemePreset,
+    /// Symbols marking feed states and selected rows
+    pub icons: IconSet,
+    /// Styles of the article titles by the age of the articles; articles are not styled by age if unset
+    pub age: Option<AgeStyles>,
+}
+
+/// ## AgeStyles
+///
+/// Styles of the titles of the unread articles in the article list, by the age of the articles:
+/// recent articles stand out, while older ones fade
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AgeStyles {
+    /// Articles published in the last hours are styled as today's
+    pub today_hours: i64,
+    /// Articles published in the last days are styled as this week's
+    pub week_days: i64,
+    pub today: FeedStyle,
+    pub week: FeedStyle,
+    pub older: FeedStyle,
+}
+
+/// ## ThemePreset
+///
+/// Built-in set of colors
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    /// Pick light or dark according to the background of the terminal
+    Auto,
+    /// Colors readable on dark backgrounds
+    Dark,
+    /// Colors readable on light backgrounds
+    Light,
+}
+
+/// ## IconSet
+///
+/// Built-in set of symbols
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    /// Unicode symbols
+    Unicode,
+    /// Icons of the [Nerd Fonts](https://www.nerdfonts.com)
+    NerdFont,
+    /// Plain ASCII characters, for fonts which don't have the other ones
+    Ascii,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            preset: ThemePreset::Dark,
+            icons: IconSet::Unicode,
+            age: None,
+        }
+    }
+}
+
+impl Default for AgeStyles {
+    fn default() -> Self {
+        Self {
+            today_hours: 24,
+            week_days: 7,
+            today: FeedStyle {
+                color: None,
+                modifiers: TextModifiers::BOLD,
+            },
+            week: FeedStyle {
+                color: None,
+                modifiers: TextModifiers::empty(),
+            },
+            older: FeedStyle {
+                color: None,
+                modifiers: TextModifiers::DIM,
+            },
+        }
+    }
+}
+
+impl AgeStyles {
+    /// ### style
+    ///
+    /// Get the style of an article published `age` ago; articles dated in the future are today's
+    pub fn style(&self, age: Duration) -> Style {
+        if age < Duration::hours(self.today_hours) {
+            self.today.style()
+        } else if age < Duration::days(self.week_days) {
+            self.week.style()
+        } else {
+            self.older.style()
+        }
+    }
+}
+
+/// ## Icons
+///
+/// Symbols marking feed states and selected rows
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Icons {
+    /// Frames of the animation displayed while a feed is loading
+    pub loading: &'static [char],
+    pub success: char,
+    pub error: char,
+    pub retrying: char,
+    /// Marker of the sources pinned to the top of the feed list
+    pub pinned: char,
+    /// Marker of the articles fetched for the first time since the last run
+    pub new: char,
+    /// Prefix of the selected row in lists
+    pub selected: &'static str,
+}
+
+impl IconSet {
+    /// ### icons
+    ///
+    /// Get the symbols of the set
+    pub fn icons(self) -> Icons {
+        match self {
+            Self::Unicode => Icons {
+                loading: &['⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷'],
+                success: ' ',
+                error: '✘',
+                retrying: '↻',
+                pinned: '★',
+                new: '●',
+                selected: "➤ ",
+            },
+            Self::NerdFont => Icons {
+                loading: &['\u{f250}', '\u{f251}', '\u{f252}'],
+                success: ' ',
+                error: '\u{f00d}',
+                retrying: '\u{f021}',
+                pinned: '\u{f08d}',
+                new: '\u{f111}',
+                selected: "\u{f054} ",
+            },
+            Self::Ascii => Icons {
+                loading: &['|', '/', '-', '\\'],
+                success: ' ',
+                error: '!',
+                retrying: '~',
+                pinned: '^',
+                new: '*',
+                selected: "> ",
+            },
+        }
+    }
+}
+
+/// ## Theme
+///
+/// Colors and symbols of the interface.
+/// Each color is named after the one it stands for on a dark background
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Theme {
+    /// Background of popups
+    pub background: Color,
+    pub red: Color,
+    pub yellow: Color,
+    pub green: Color,
+    pub blue: Color,
+    pub magenta: Color,
+    pub cyan: Color,
+    /// Warnings, such as the quit popup
+    pub warning: Color,
+    /// Read articles and comments in code
+    pub dim: Color,
+    /// Text of code blocks
+    pub code: Color,
+    pub icons: Icons,
+}
+
+impl Theme {
+    /// ### dark
+    ///
+    /// Colors readable on dark backgrounds
+    pub fn dark() -> Self {
+        Self {
+            background: Color::Black,
+            red: Color::Red,
+            yellow: Color::LightYellow,
+            green: Color::LightGreen,
+            blue: Color::LightBlue,
+            magenta: Color::LightMagenta,
+            cyan: Color::LightCyan,
+            warning: Color::Yellow,
+            dim: Color::DarkGray,
+            code: Color::Gray,
+            icons: IconSet::Unicode.icons(),
+        }
+    }
+
+    /// ### light
+    ///
+    /// Colors readable on light backgrounds
+    pub fn light() -> Self {
+        Self {
+            background: Color::Reset,
+            red: Color::Red,
+            yellow: Color::Yellow,
+            green: Color::Green,
+            blue: Color::Blue,
+            magenta: Color::Magenta,
+            cyan: Color::Cyan,
+            warning: Color::Red,
+            dim: Color::Gray,
+            code: Color::DarkGray,
+            icons: IconSet::Unicode.icons(),
+        }
+    }
+
+    /// ### new
+    ///
+    /// Get the theme described by `config`; `light_background` tells which preset `auto` picks
+    pub fn new(config: &ThemeConfig, light_background: bool) -> Self {
+        let theme = match config.preset {
+            ThemePreset::Dark => Self::dark(),
+            ThemePreset::Light => Self::light(),
+            ThemePreset::Auto if light_background => Self::light(),
+            ThemePreset::Auto => Self::dark(),
+        };
+        Self {
+            icons: config.icons.icons(),
+            ..theme
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_theme_config() {
+        let theme: ThemeConfig = toml::de::from_str(r#"preset = "auto""#).unwrap();
+        assert_eq!(theme.preset, ThemePreset::Auto);
+        assert_eq!(theme.icons, IconSet::Unicode);
+        assert_eq!(ThemeConfig::default().preset, ThemePreset::Dark);
+        assert!(toml::de::from_str::<ThemeConfig>(r#"preset = "solarized""#).is_err());
+        let theme: ThemeConfig = toml::de::from_str(r#"icons = "nerdfont""#).unwrap();
+        assert_eq!(theme.icons, IconSet::NerdFont);
+        assert_eq!(theme.preset, ThemePreset::Dark);
+        assert!(theme.age.is_none());
+    }
+
+    #[test]
+    fn should_deserialize_age_styles() {
+        let theme: ThemeConfig = toml::de::from_str(
+            r#"
+[age]
+week_days = 3
+today = { color = "lightgreen", bold = true }
+"#,
+        )
+        .unwrap();
+        let age = theme.age.unwrap();
+        assert_eq!(age.today_hours, 24);
+        assert_eq!(age.week_days, 3);
+        assert_eq!(age.today.color, Some(Color::LightGreen));
+        assert_eq!(age.older, AgeStyles::default().older);
+        assert!(toml::de::from_str::<ThemeConfig>(
+            r#"
+[age]
+older = { color = "nope" }
+"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn should_get_style_by_age() {
+        let age = AgeStyles::default();
+        let bold = Style::default().add_modifier(TextModifiers::BOLD);
+        let normal = Style::default();
+        let dim = Style::default().add_modifier(TextModifiers::DIM);
+        assert_eq!(age.style(Duration::hours(-2)), bold);
+        assert_eq!(age.style(Duration::hours(3)), bold);
+        assert_eq!(age.style(Duration::hours(24)), normal);
+        assert_eq!(age.style(Duration::days(6)), normal);
+        assert_eq!(age.style(Duration::days(7)), dim);
+        assert_eq!(age.style(Duration::days(400)), dim);
+    }
+
+    #[test]
+    fn should_make_theme() {
+        let config = |preset| ThemeConfig {
+            preset,
+            icons: IconSet::Unicode,
+            age: None,
+        };
+        assert_eq!(Theme::new(&config(ThemePreset::Dark), true), Theme::dark());
+        assert_eq!(
+            Theme::new(&config(ThemePreset::Light), false),
+            Theme::light()
+        );
+        assert_eq!(Theme::new(&config(ThemePreset::Auto), true), Theme::light());
+        assert_eq!(Theme::new(&config(ThemePreset::Auto), false), Theme::dark());
+        let theme = Theme::new(
+            &ThemeConfig {
+                preset: ThemePreset::Light,
+                icons: IconSet::Ascii,
+                age: None,
+            },
+            false,
+        );
+        assert_eq!(theme.cyan, Theme::light().cyan);
+        assert_eq!(theme.icons, IconSet::Ascii.icons());
+    }
+
+    #[test]
+    fn should_get_ascii_icons() {
+        let icons = IconSet::Ascii.icons();
+        assert!(icons.loading.iter().all(char::is_ascii));
+        assert!([icons.success, icons.error, icons.retrying, icons.new]
+            .iter()
+            .all(char::is_ascii));
+        assert!(icons.selected.is_ascii());
+    }
+}
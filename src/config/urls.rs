@@ -0,0 +1,193 @@
+//! # Urls
+//!
+//! Article urls configuration
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::helpers::urls as url_helpers;
+
+use regex::Regex;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// ## UrlsConfig
+///
+/// Describes how article urls are cleaned before being displayed, opened or copied
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct UrlsConfig {
+    /// Whether tracking parameters are removed from article urls
+    pub strip_tracking: bool,
+    /// Query parameters to remove; a trailing `*` matches any parameter starting with the prefix
+    pub tracking_params: Vec<String>,
+    /// Rules to rewrite urls, applied in order
+    pub rewrite: Vec<RewriteRule>,
+}
+
+/// ## RewriteRule
+///
+/// Replaces the part of the url matching `pattern` with `replace`.
+/// `replace` may reference the groups captured by the pattern (e.g. `$1`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawRewriteRule")]
+pub struct RewriteRule {
+    pub pattern: Regex,
+    pub replace: String,
+}
+
+/// ## RawRewriteRule
+///
+/// Rewrite rule as written in the configuration, before the pattern is compiled
+#[derive(Deserialize)]
+struct RawRewriteRule {
+    pattern: String,
+    replace: String,
+}
+
+impl TryFrom<RawRewriteRule> for RewriteRule {
+    type Error = String;
+
+    fn try_from(rule: RawRewriteRule) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pattern: Regex::new(rule.pattern.as_str())
+                .map_err(|e| format!("invalid rewrite pattern \"{}\": {}", rule.pattern, e))?,
+            replace: rule.replace,
+        })
+    }
+}
+
+impl PartialEq for RewriteRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str() && self.replace == other.replace
+    }
+}
+
+impl Eq for RewriteRule {}
+
+impl Default for UrlsConfig {
+    fn default() -> Self {
+        Self {
+            strip_tracking: true,
+            tracking_params: [
+                "utm_*", "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid",
+                "mc_eid", "_hsenc", "_hsmi", "mkt_tok",
+            ]
+            .iter()
+            .map(|x| x.to_string())
+            .collect(),
+            rewrite: Vec::new(),
+        }
+    }
+}
+
+impl UrlsConfig {
+    /// ### clean
+    ///
+    /// Clean `url` according to configuration: rewrite rules are applied first, then tracking parameters are removed
+    pub fn clean(&self, url: &str) -> String {
+        let url = self.rewrite.iter().fold(url.to_string(), |url, rule| {
+            rule.pattern
+                .replace(url.as_str(), rule.replace.as_str())
+                .to_string()
+        });
+        match self.strip_tracking {
+            true => url_helpers::strip_query_params(url.as_str(), self.tracking_params.as_slice()),
+            false => url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_urls_config() {
+        let urls: UrlsConfig = toml::de::from_str("tracking_params = [\"ref\"]").unwrap();
+        assert_eq!(urls.strip_tracking, true);
+        assert_eq!(urls.tracking_params, vec![String::from("ref")]);
+        let urls: UrlsConfig = toml::de::from_str("strip_tracking = false").unwrap();
+        assert_eq!(urls.strip_tracking, false);
+        assert!(urls.tracking_params.contains(&String::from("utm_*")));
+    }
+
+    #[test]
+    fn should_deserialize_rewrite_rules() {
+        let urls: UrlsConfig = toml::de::from_str(
+            r#"rewrite = [ { pattern = "^https://(www\\.)?twitter\\.com/", replace = "https://nitter.net/" } ]"#,
+        )
+        .unwrap();
+        assert_eq!(urls.rewrite.len(), 1);
+        assert_eq!(urls.rewrite[0].replace.as_str(), "https://nitter.net/");
+        assert!(toml::de::from_str::<UrlsConfig>(
+            r#"rewrite = [ { pattern = "(unclosed", replace = "" } ]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn should_rewrite_url() {
+        let urls: UrlsConfig = toml::de::from_str(
+            r#"
+rewrite = [
+    { pattern = "^https://(www\\.)?twitter\\.com/", replace = "https://nitter.net/" },
+    { pattern = "^https://(www\\.)?youtube\\.com/watch\\?v=([\\w-]+)", replace = "https://invidious.io/watch?v=$2" },
+]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            urls.clean("https://twitter.com/rustlang/status/1").as_str(),
+            "https://nitter.net/rustlang/status/1"
+        );
+        assert_eq!(
+            urls.clean("https://www.youtube.com/watch?v=dQw4w9WgXcQ&utm_source=rss")
+                .as_str(),
+            "https://invidious.io/watch?v=dQw4w9WgXcQ"
+        );
+        assert_eq!(
+            urls.clean("https://example.com/").as_str(),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn should_clean_url() {
+        let mut urls = UrlsConfig::default();
+        assert_eq!(
+            urls.clean("https://example.com/post?id=1&utm_source=rss&fbclid=abc")
+                .as_str(),
+            "https://example.com/post?id=1"
+        );
+        urls.strip_tracking = false;
+        assert_eq!(
+            urls.clean("https://example.com/post?utm_source=rss")
+                .as_str(),
+            "https://example.com/post?utm_source=rss"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! # Webhooks
+//!
+//! Urls the new articles are posted to as JSON
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::ArticlePattern;
+use crate::feed::Article;
+
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// ## Webhook
+///
+/// A url which receives the new articles matching the pattern, as JSON in the body of a POST request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawWebhook")]
+pub struct Webhook {
+    pub url: String,
+    /// New articles posted to the webhook; without expressions all of them are
+    pub pattern: ArticlePattern,
+}
+
+/// ## RawWebhook
+///
+/// Webhook as written in the configuration, before the patterns are compiled
+#[derive(Deserialize)]
+struct RawWebhook {
+    url: String,
+    title: Option<String>,
+    author: Option<String>,
+    feed: Option<String>,
+}
+
+impl TryFrom<RawWebhook> for Webhook {
+    type Error = String;
+
+    fn try_from(webhook: RawWebhook) -> Result<Self, Self::Error> {
+        if webhook.url.trim().is_empty() {
+            return Err(String::from("invalid webhook: url can't be empty"));
+        }
+        let pattern = match (&webhook.title, &webhook.author, &webhook.feed) {
+            (None, None, None) => ArticlePattern {
+                title: None,
+                author: None,
+                feed: None,
+            },
+            _ => ArticlePattern::new(webhook.title, webhook.author, webhook.feed)
+                .map_err(|e| format!("invalid webhook: {}", e))?,
+        };
+        Ok(Self {
+            url: webhook.url,
+            pattern,
+        })
+    }
+}
+
+impl Webhook {
+    /// ### matches
+    ///
+    /// Returns whether the new `article` of `source` must be posted to the webhook
+    pub fn matches(&self, source: &str, article: &Article) -> bool {
+        self.pattern.matches(source, article)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_deserialize_webhooks() {
+        #[derive(Deserialize)]
+        struct Webhooks {
+            webhooks: Vec<Webhook>,
+        }
+        let webhooks: Webhooks = toml::de::from_str(
+            r#"
+[[webhooks]]
+url = "https://matrix.example.com/hook"
+title = "(?i)rust"
+
+[[webhooks]]
+url = "https://hooks.slack.com/services/T000/B000/XXXX"
+"#,
+        )
+        .unwrap();
+        assert_eq!(webhooks.webhooks.len(), 2);
+        let article = Article {
+            title: Some(String::from("Rust 1.56 is out")),
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::from("https://example.com/a"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        assert!(webhooks.webhooks[0].matches("blog", &article));
+        assert!(webhooks.webhooks[1].matches("blog", &article));
+        let article = Article {
+            title: Some(String::from("Go 1.17 is out")),
+            ..article
+        };
+        assert!(!webhooks.webhooks[0].matches("blog", &article));
+        assert!(toml::de::from_str::<Webhooks>("[[webhooks]]\nurl = \"\"").is_err());
+        assert!(toml::de::from_str::<Webhooks>(
+            "[[webhooks]]\nurl = \"https://example.com\"\ntitle = \"(\""
+        )
+        .is_err());
+    }
+}
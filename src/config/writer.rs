@@ -0,0 +1,378 @@
+//! # Writer
+//!
+//! Configuration writer, which edits the configuration file preserving its format
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::serializer::{SerializerError, SerializerErrorKind, Toml};
+use crate::helpers::file as file_helpers;
+
+use std::io::Read;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Key, TableLike, Value};
+
+const SOURCES_TABLE: &str = "sources";
+
+/// ### add_source
+///
+/// Add a new source to the configuration file located at `p`.
+/// If a source with the same name already exists, its url is replaced
+pub fn add_source(p: &Path, name: &str, url: &str) -> Result<(), SerializerError> {
+    edit_sources(p, |sources| {
+        sources.insert(name, toml_edit::value(url));
+        Ok(())
+    })
+}
+
+/// ### remove_source
+///
+/// Remove the source named `name` from the configuration file located at `p`
+pub fn remove_source(p: &Path, name: &str) -> Result<(), SerializerError> {
+    edit_sources(p, |sources| {
+        sources.remove(name);
+        Ok(())
+    })
+}
+
+/// ### rename_source
+///
+/// Rename the source `name` to `new_name` in the configuration file located at `p`,
+/// keeping its position in the sources table
+pub fn rename_source(p: &Path, name: &str, new_name: &str) -> Result<(), SerializerError> {
+    edit_sources(p, |sources| {
+        if !sources.contains_key(name) {
+            return Err(format!(r#"No such source "{}""#, name));
+        }
+        if sources.contains_key(new_name) {
+            return Err(format!(r#"A source named "{}" already exists"#, new_name));
+        }
+        // Re-insert all the entries, to preserve the sources order
+        let entries: Vec<(Key, Item)> = sources
+            .iter()
+            .filter_map(|(key, _)| sources.get_key_value(key))
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect();
+        sources.clear();
+        for (key, item) in entries.into_iter() {
+            let key = match key.get() == name {
+                true => Key::new(new_name).with_leaf_decor(key.leaf_decor().clone()),
+                false => key,
+            };
+            sources.entry_format(&key).or_insert(item);
+        }
+        Ok(())
+    })
+}
+
+/// ### set_source_url
+///
+/// Replace the url of the source `name` in the configuration file located at `p`,
+/// keeping the other options of the source.
+/// Urls taken from environment variables or commands are never replaced
+pub fn set_source_url(p: &Path, name: &str, url: &str) -> Result<(), SerializerError> {
+    edit_sources(p, |sources| {
+        let item = match sources.get_mut(name) {
+            Some(item) => item,
+            None => return Err(format!(r#"No such source "{}""#, name)),
+        };
+        if let Some(table) = item.as_table_like_mut() {
+            if table.contains_key("url_cmd") {
+                return Err(format!(r#"The url of "{}" is taken from a command"#, name));
+            }
+            check_url_not_interpolated(name, table.get("url").and_then(Item::as_str))?;
+            table.insert("url", toml_edit::value(url));
+            return Ok(());
+        }
+        match item.as_value_mut() {
+            Some(value) if value.is_str() => {
+                check_url_not_interpolated(name, value.as_str())?;
+                let decor = value.decor().clone();
+                *value = Value::from(url);
+                *value.decor_mut() = decor;
+                Ok(())
+            }
+            _ => Err(format!(r#"Source "{}" is neither a url nor a table"#, name)),
+        }
+    })
+}
+
+// -- private
+
+/// ### check_url_not_interpolated
+///
+/// Returns an error if `url` of source `name` references environment variables
+fn check_url_not_interpolated(name: &str, url: Option<&str>) -> Result<(), String> {
+    match url {
+        Some(url) if url.contains("${") => Err(format!(
+            r#"The url of "{}" contains environment variables"#,
+            name
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// ### edit_sources
+///
+/// Read the configuration file at `p`, apply `edit` to the sources table and write the file back
+fn edit_sources<F>(p: &Path, edit: F) -> Result<(), SerializerError>
+where
+    F: FnOnce(&mut dyn TableLike) -> Result<(), String>,
+{
+    let mut document = read_document(p)?;
+    if !document.contains_key(SOURCES_TABLE) {
+        document.insert(SOURCES_TABLE, toml_edit::table());
+    }
+    match document
+        .get_mut(SOURCES_TABLE)
+        .and_then(Item::as_table_like_mut)
+    {
+        Some(sources) => {
+            edit(sources).map_err(|e| SerializerError::new(SerializerErrorKind::Syntax, e))?
+        }
+        None => {
+            return Err(SerializerError::new(
+                SerializerErrorKind::Syntax,
+                format!("`{}` is not a table", SOURCES_TABLE),
+            ))
+        }
+    }
+    file_helpers::write_file(p, document.to_string().as_str())
+        .map_err(|e| SerializerError::new(SerializerErrorKind::Io, e.to_string()))
+}
+
+/// ### read_document
+///
+/// Read and parse the TOML document at `p`
+fn read_document(p: &Path) -> Result<DocumentMut, SerializerError> {
+    if !Toml::matches(p) {
+        return Err(SerializerError::new(
+            SerializerErrorKind::Unsupported,
+            String::from(
+                "JSON and YAML configurations can't be changed by tuifeed; edit the file instead",
+            ),
+        ));
+    }
+    let mut data = String::new();
+    file_helpers::open_file_read(p)
+        .and_then(|mut reader| reader.read_to_string(&mut data))
+        .map_err(|e| SerializerError::new(SerializerErrorKind::Io, e.to_string()))?;
+    data.parse::<DocumentMut>()
+        .map_err(|e| SerializerError::new(SerializerErrorKind::Syntax, e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::config::Config;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+
+    #[test]
+    fn should_add_source_to_config() {
+        let config = create_toml_config();
+        assert!(add_source(
+            config.path(),
+            "lefigaro",
+            "https://www.lefigaro.fr/rss/figaro_actualites.xml"
+        )
+        .is_ok());
+        let config = Config::load(config.path()).ok().unwrap();
+        assert_eq!(config.sources.len(), 2);
+        assert_eq!(
+            config.sources.get("lefigaro").unwrap().url.as_str(),
+            "https://www.lefigaro.fr/rss/figaro_actualites.xml"
+        );
+    }
+
+    #[test]
+    fn should_add_source_preserving_comments() {
+        let config = create_toml_config();
+        assert!(add_source(
+            config.path(),
+            "Il Post",
+            "https://www.ilpost.it/mondo/feed/"
+        )
+        .is_ok());
+        let mut content = String::new();
+        assert!(file_helpers::open_file_read(config.path())
+            .ok()
+            .unwrap()
+            .read_to_string(&mut content)
+            .is_ok());
+        assert!(content.contains("# my favourite sources"));
+        assert!(content.contains(r#""Il Post" = "https://www.ilpost.it/mondo/feed/""#));
+    }
+
+    #[test]
+    fn should_add_sources_table_if_missing() {
+        let mut config = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(config, "# empty").is_ok());
+        assert!(add_source(
+            config.path(),
+            "nytimes",
+            "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+        )
+        .is_ok());
+        let config = Config::load(config.path()).ok().unwrap();
+        assert_eq!(config.sources.len(), 1);
+    }
+
+    #[test]
+    fn should_remove_source_from_config() {
+        let config = create_toml_config();
+        assert!(remove_source(config.path(), "nytimes").is_ok());
+        let config = Config::load(config.path()).ok().unwrap();
+        assert!(config.sources.is_empty());
+    }
+
+    #[test]
+    fn should_rename_source_in_config() {
+        let config = create_toml_config();
+        assert!(add_source(
+            config.path(),
+            "lefigaro",
+            "https://www.lefigaro.fr/rss/figaro_actualites.xml"
+        )
+        .is_ok());
+        assert!(rename_source(config.path(), "nytimes", "New York Times").is_ok());
+        let mut content = String::new();
+        assert!(file_helpers::open_file_read(config.path())
+            .ok()
+            .unwrap()
+            .read_to_string(&mut content)
+            .is_ok());
+        // Order must be preserved
+        assert!(content.find("New York Times").unwrap() < content.find("lefigaro").unwrap());
+        let config = Config::load(config.path()).ok().unwrap();
+        assert_eq!(config.sources.len(), 2);
+        assert_eq!(
+            config.sources.get("New York Times").unwrap().url.as_str(),
+            "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+        );
+        assert!(!config.sources.contains_key("nytimes"));
+    }
+
+    #[test]
+    fn should_fail_renaming_source() {
+        let config = create_toml_config();
+        assert!(rename_source(config.path(), "lefigaro", "Le Figaro").is_err());
+        assert!(add_source(
+            config.path(),
+            "lefigaro",
+            "https://www.lefigaro.fr/rss/figaro_actualites.xml"
+        )
+        .is_ok());
+        assert!(rename_source(config.path(), "lefigaro", "nytimes").is_err());
+    }
+
+    #[test]
+    fn should_set_source_url_in_config() {
+        let config = create_toml_config();
+        assert!(add_source(config.path(), "lefigaro", "https://www.lefigaro.fr/rss/").is_ok());
+        assert!(set_source_url(
+            config.path(),
+            "nytimes",
+            "https://rss.nytimes.com/services/xml/rss/nyt/Europe.xml"
+        )
+        .is_ok());
+        let config = Config::load(config.path()).ok().unwrap();
+        assert_eq!(
+            config.sources.get("nytimes").unwrap().url.as_str(),
+            "https://rss.nytimes.com/services/xml/rss/nyt/Europe.xml"
+        );
+        assert_eq!(
+            config.sources.get("lefigaro").unwrap().url.as_str(),
+            "https://www.lefigaro.fr/rss/"
+        );
+    }
+
+    #[test]
+    fn should_set_source_url_keeping_options() {
+        let mut config = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(
+            config,
+            "[sources]\nhn = {{ url = \"http://hnrss.org/newest\", max_articles = 30 }}"
+        )
+        .is_ok());
+        assert!(set_source_url(config.path(), "hn", "https://hnrss.org/newest").is_ok());
+        let config = Config::load(config.path()).ok().unwrap();
+        let source = config.sources.get("hn").unwrap();
+        assert_eq!(source.url.as_str(), "https://hnrss.org/newest");
+        assert_eq!(source.max_articles, Some(30));
+    }
+
+    #[test]
+    fn should_not_set_interpolated_source_url() {
+        let mut config = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(
+            config,
+            "[sources]\nprivate = \"https://example.com/feed?token=${{TOKEN}}\"\ncmd = {{ url_cmd = \"echo https://example.com\" }}"
+        )
+        .is_ok());
+        assert!(set_source_url(config.path(), "private", "https://example.org/feed").is_err());
+        assert!(set_source_url(config.path(), "cmd", "https://example.org/feed").is_err());
+        assert!(set_source_url(config.path(), "nope", "https://example.org/feed").is_err());
+    }
+
+    #[test]
+    fn should_fail_editing_bad_config() {
+        let mut config = tempfile::NamedTempFile::new().unwrap();
+        assert!(writeln!(config, "[sources]\nlefigaro").is_ok());
+        assert!(add_source(
+            config.path(),
+            "nytimes",
+            "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn should_not_edit_json_and_yaml_configs() {
+        let mut config = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        assert!(writeln!(config, r#"{{"sources": {{}}}}"#).is_ok());
+        assert!(add_source(
+            config.path(),
+            "nytimes",
+            "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+        )
+        .is_err());
+        assert!(Config::load(config.path()).ok().unwrap().sources.is_empty());
+        let mut config = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        assert!(writeln!(config, "sources: {{}}").is_ok());
+        assert!(remove_source(config.path(), "nytimes").is_err());
+    }
+
+    fn create_toml_config() -> tempfile::NamedTempFile {
+        let mut tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let file_content: &str = r##"
+        # my favourite sources
+        [sources]
+        nytimes = "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+        "##;
+        tmpfile.write_all(file_content.as_bytes()).unwrap();
+        tmpfile
+    }
+}
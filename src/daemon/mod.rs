@@ -0,0 +1,300 @@
+//! # Daemon
+//!
+//! Fetches sources in background, keeping the article store up to date and notifying new articles
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// -- deps
+use crate::config::{mute_feed, score_feed, Config, HookEvent};
+use crate::feed::{Article, Client};
+use crate::helpers::hooks as hook_helpers;
+use crate::helpers::maildir as maildir_helpers;
+use crate::helpers::notify as notify_helpers;
+use crate::script::Script;
+use crate::store::{Store, StoreResult};
+use crate::sync;
+
+use chrono::Local;
+use log::{debug, error, info, warn};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::thread;
+
+/// ## Daemon
+///
+/// Fetches all the sources periodically without any UI, saving their articles into the store
+/// the interactive UI reads from
+pub struct Daemon {
+    client: Client,
+    config: Config,
+    config_file: Option<PathBuf>,
+    script: Option<Script>,
+    store: Store,
+}
+
+impl Daemon {
+    /// ### new
+    ///
+    /// Instantiates a new `Daemon`, keeping articles in `store`
+    pub fn new(config: Config, config_file: Option<PathBuf>, store: Store) -> Self {
+        Self {
+            client: Self::feed_client(&config),
+            script: Self::load_script(&config),
+            config,
+            config_file,
+            store,
+        }
+    }
+
+    /// ### run
+    ///
+    /// Fetch all sources every interval, until the process is killed.
+    /// If `once` is set, sources are fetched only once
+    pub fn run(&mut self, once: bool) {
+        loop {
+            self.heartbeat();
+            self.fetch_all_sources();
+            self.sync_read_state();
+            self.heartbeat();
+            if once {
+                break;
+            }
+            let interval = self.config.daemon.interval();
+            debug!("next fetch in {} seconds", interval.as_secs());
+            thread::sleep(interval);
+            self.reload_config();
+        }
+    }
+
+    // -- private
+
+    /// ### feed_client
+    ///
+    /// Make the client to fetch sources with, according to `config`
+    fn feed_client(config: &Config) -> Client {
+        Client::default()
+            .reddit_metadata(config.reddit.metadata)
+            .ca_file(config.tls.ca_file.clone())
+    }
+
+    /// ### load_script
+    ///
+    /// Load the script set in `config`, if any
+    fn load_script(config: &Config) -> Option<Script> {
+        let p = config.scripting.file.as_deref()?;
+        Script::load(p).map_err(|err| error!("{}", err)).ok()
+    }
+
+    /// ### reload_config
+    ///
+    /// Load the configuration file again, so that changes apply from the next fetch.
+    /// If the configuration is invalid, the current one is kept
+    fn reload_config(&mut self) {
+        if let Some(p) = self.config_file.as_deref() {
+            match Config::load(p) {
+                Ok(config) => {
+                    for err in config.source_errors.iter() {
+                        error!("{}", err);
+                    }
+                    self.client = Self::feed_client(&config);
+                    self.script = Self::load_script(&config);
+                    self.config = config;
+                }
+                Err(err) => error!("could not reload configuration: {}", err),
+            }
+        }
+    }
+
+    /// ### heartbeat
+    ///
+    /// Tell the interactive UI that the daemon is running
+    fn heartbeat(&mut self) {
+        if let Err(err) = self.store.set_daemon_heartbeat() {
+            error!("could not update daemon heartbeat: {}", err);
+        }
+    }
+
+    /// ### fetch_all_sources
+    ///
+    /// Fetch all the sources once, except for the ones fetched more recently than their refresh interval
+    fn fetch_all_sources(&mut self) {
+        let mut names: Vec<String> = self.config.sources.keys().cloned().collect();
+        names.sort();
+        info!("fetching {} sources", names.len());
+        for name in names.into_iter() {
+            if self.is_fresh(name.as_str()) {
+                debug!("{} has been fetched recently, skipping it", name);
+                continue;
+            }
+            let articles = match self.fetch_source(name.as_str()) {
+                Ok(articles) => articles,
+                Err(err) => {
+                    error!("could not store articles of {}: {}", name, err);
+                    continue;
+                }
+            };
+            for article in articles.iter() {
+                self.notify(name.as_str(), article);
+            }
+        }
+        if let Some(command) = self.config.hooks.command(HookEvent::RefreshComplete) {
+            let event = HookEvent::RefreshComplete.name();
+            if let Err(err) =
+                hook_helpers::run_refresh_hook(command, event, self.config.sources.len())
+            {
+                warn!("could not run {} hook: {}", event, err);
+            }
+        }
+    }
+
+    /// ### sync_read_state
+    ///
+    /// Synchronize the read state of the articles with the remote file, if set
+    fn sync_read_state(&mut self) {
+        if self.config.sync.url.is_none() {
+            return;
+        }
+        match sync::sync_read_state(&mut self.store, &self.config.sync) {
+            Ok(changes) => info!("read state synchronized; {} articles changed", changes),
+            Err(err) => warn!("could not synchronize read state: {}", err),
+        }
+    }
+
+    /// ### is_fresh
+    ///
+    /// Returns whether source `name` has been fetched more recently than its refresh interval
+    fn is_fresh(&self, name: &str) -> bool {
+        match self.store.last_fetch(name) {
+            Ok(last_fetch) => self.config.sources[name].is_fresh(last_fetch, Local::now()),
+            Err(err) => {
+                error!("could not get last fetch of {}: {}", name, err);
+                false
+            }
+        }
+    }
+
+    /// ### fetch_source
+    ///
+    /// Fetch source `name` and save its articles into the store.
+    /// Returns the new articles, which haven't been muted
+    fn fetch_source(&mut self, name: &str) -> StoreResult<Vec<Article>> {
+        let source = &self.config.sources[name];
+        let limits = self.config.articles.limits(source);
+        let options = source.fetch_options();
+        let mut feed = match self.client.fetch(source.url.as_str(), &limits, &options) {
+            Ok(feed) => feed,
+            Err(err) => {
+                warn!("could not fetch {}: {}", name, err);
+                self.store.save_error(name, err.to_string().as_str())?;
+                return Ok(Vec::new());
+            }
+        };
+        if let Some(url) = feed.moved_to() {
+            warn!(
+                "{} has been moved permanently to {}; run `tuifeed check --fix`",
+                name, url
+            );
+        }
+        let read = mute_feed(self.config.mute.as_slice(), name, &mut feed);
+        score_feed(self.config.scoring.as_slice(), name, &mut feed);
+        if let Some(script) = self.script.as_ref() {
+            script.process_feed(name, &mut feed);
+        }
+        // NOTE: nothing is notified on the first fetch of a source, otherwise all of its articles would be
+        let known = self.store.article_urls(name)?;
+        let read_urls: HashSet<&String> = read.iter().collect();
+        let new: Vec<Article> = feed
+            .articles()
+            .filter(|_| !known.is_empty())
+            .filter(|x| !known.contains(&x.url) && !read_urls.contains(&x.url))
+            .cloned()
+            .collect();
+        self.store.save_feed(name, &feed)?;
+        for url in read.iter() {
+            self.store.mark_read(url)?;
+        }
+        debug!("{}: {} new articles", name, new.len());
+        Ok(new)
+    }
+
+    /// ### notify
+    ///
+    /// Notify the new `article` of `source` through the configured channels, if it matches the notify rules,
+    /// post it to the webhooks and push it to the services it matches. The new article hook is run for every article,
+    /// which is delivered into the Maildir too, if configured
+    fn notify(&self, source: &str, article: &Article) {
+        if let Some(command) = self.config.hooks.command(HookEvent::NewArticle) {
+            let event = HookEvent::NewArticle.name();
+            if let Err(err) = hook_helpers::run_article_hook(command, event, source, article) {
+                warn!("could not run {} hook: {}", event, err);
+            }
+        }
+        if let Some(dir) = self.config.maildir.dir.as_deref() {
+            if let Err(err) = maildir_helpers::deliver_article(dir, source, article) {
+                warn!("could not deliver article into maildir: {}", err);
+            }
+        }
+        for webhook in self
+            .config
+            .webhooks
+            .iter()
+            .filter(|x| x.matches(source, article))
+        {
+            if let Err(err) = notify_helpers::post_webhook(webhook.url.as_str(), source, article) {
+                warn!("could not post to webhook: {}", err);
+            }
+        }
+        let title = article.title.as_deref().unwrap_or(article.url.as_str());
+        let url = Some(article.url.as_str()).filter(|x| !x.is_empty());
+        for target in self
+            .config
+            .push
+            .iter()
+            .filter(|x| x.matches(source, article))
+        {
+            if let Err(err) =
+                notify_helpers::notify_push(&target.service, target.priority, source, title, url)
+            {
+                warn!("could not send push notification: {}", err);
+            }
+        }
+        let rules = self.config.daemon.notify.as_slice();
+        if !rules
+            .iter()
+            .any(|rule| rule.pattern.matches(source, article))
+        {
+            return;
+        }
+        if self.config.daemon.desktop {
+            if let Err(err) = notify_helpers::notify_desktop(source, title) {
+                warn!("could not show desktop notification: {}", err);
+            }
+        }
+        if let Some(topic) = self.config.daemon.ntfy.as_deref() {
+            if let Err(err) = notify_helpers::notify_ntfy(topic, source, title, url) {
+                warn!("could not send ntfy notification: {}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! # Digest
+//!
+//! Render the articles you kept into a single Markdown or HTML document
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// -- modules
+mod planet;
+mod rss;
+
+// -- export
+pub use planet::render_planet;
+pub use rss::render_rss;
+// -- deps
+use crate::feed::Article;
+use crate::helpers::fmt as fmt_helpers;
+
+use chrono::Duration;
+use std::str::FromStr;
+
+/// ## DigestFormat
+///
+/// Document format of the digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+impl FromStr for DigestFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(format!(
+                "unknown digest format \"{}\"; use markdown or html",
+                s
+            )),
+        }
+    }
+}
+
+/// ## DigestEntry
+///
+/// An article of the digest, with the user tags and note attached to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestEntry {
+    pub source: String,
+    pub article: Article,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+impl DigestEntry {
+    /// ### meta
+    ///
+    /// Make the metadata line of the entry: source, date and authors
+    fn meta(&self) -> String {
+        let mut meta = vec![self.source.clone()];
+        if let Some(date) = self.article.date {
+            meta.push(fmt_helpers::format_datetime(date, "%Y-%m-%d"));
+        }
+        if !self.article.authors.is_empty() {
+            meta.push(self.article.authors.join(", "));
+        }
+        meta.join(" · ")
+    }
+
+    /// ### excerpt
+    ///
+    /// Get the first paragraph of the article summary
+    fn excerpt(&self) -> &str {
+        self.article
+            .summary
+            .split("\n\n")
+            .map(|x| x.trim())
+            .find(|x| !x.is_empty())
+            .unwrap_or("")
+    }
+
+    /// ### title
+    ///
+    /// Get the article title, or its url if it has none
+    fn title(&self) -> &str {
+        self.article
+            .title
+            .as_deref()
+            .unwrap_or(self.article.url.as_str())
+    }
+}
+
+/// ### parse_period
+///
+/// Parse a period made up of an amount and of its unit: `m` (minutes), `h` (hours), `d` (days) or `w` (weeks),
+/// e.g. `24h`
+pub fn parse_period(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let invalid = || format!("invalid period \"{}\"; use e.g. 30m, 24h, 7d or 2w", s);
+    let (amount, unit) = s.split_at(s.len() - s.chars().last().map(char::len_utf8).unwrap_or(0));
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    match unit {
+        "m" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        "w" => Ok(Duration::weeks(amount)),
+        _ => Err(invalid()),
+    }
+}
+
+/// ### render
+///
+/// Render `entries` into a document titled `title`, in `format`
+pub fn render(title: &str, entries: &[DigestEntry], format: DigestFormat) -> String {
+    match format {
+        DigestFormat::Markdown => render_markdown(title, entries),
+        DigestFormat::Html => render_html(title, entries),
+    }
+}
+
+/// ### render_markdown
+///
+/// Render `entries` as a Markdown document
+fn render_markdown(title: &str, entries: &[DigestEntry]) -> String {
+    let mut doc = format!("# {}\n", title);
+    for entry in entries {
+        doc.push_str(
+            format!(
+                "\n## [{}]({})\n\n*{}*\n",
+                escape_markdown(entry.title()),
+                entry.article.url,
+                escape_markdown(entry.meta().as_str())
+            )
+            .as_str(),
+        );
+        if !entry.tags.is_empty() {
+            let tags: Vec<String> = entry.tags.iter().map(|x| format!("`#{}`", x)).collect();
+            doc.push_str(format!("\n{}\n", tags.join(" ")).as_str());
+        }
+        if let Some(note) = entry.note.as_deref() {
+            let note: Vec<String> = note
+                .lines()
+                .map(|x| format!("> {}", x).trim_end().to_string())
+                .collect();
+            doc.push_str(format!("\n{}\n", note.join("\n")).as_str());
+        }
+        if !entry.excerpt().is_empty() {
+            doc.push_str(format!("\n{}\n", entry.excerpt()).as_str());
+        }
+    }
+    doc
+}
+
+/// ### render_html
+///
+/// Render `entries` as a standalone HTML document
+fn render_html(title: &str, entries: &[DigestEntry]) -> String {
+    let title = escape_html(title);
+    let mut doc = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, title
+    );
+    for entry in entries {
+        doc.push_str(
+            format!(
+                "<article>\n<h2><a href=\"{}\">{}</a></h2>\n<p><em>{}</em></p>\n",
+                escape_html(entry.article.url.as_str()),
+                escape_html(entry.title()),
+                escape_html(entry.meta().as_str())
+            )
+            .as_str(),
+        );
+        if !entry.tags.is_empty() {
+            let tags: Vec<String> = entry
+                .tags
+                .iter()
+                .map(|x| format!("<code>#{}</code>", escape_html(x)))
+                .collect();
+            doc.push_str(format!("<p>{}</p>\n", tags.join(" ")).as_str());
+        }
+        if let Some(note) = entry.note.as_deref() {
+            doc.push_str(
+                format!(
+                    "<blockquote>{}</blockquote>\n",
+                    escape_html(note).replace('\n', "<br>\n")
+                )
+                .as_str(),
+            );
+        }
+        if !entry.excerpt().is_empty() {
+            doc.push_str(format!("<p>{}</p>\n", escape_html(entry.excerpt())).as_str());
+        }
+        doc.push_str("</article>\n");
+    }
+    doc.push_str("</body>\n</html>\n");
+    doc
+}
+
+/// ### escape_markdown
+///
+/// Escape the characters which would be taken as Markdown markup in a link text or in emphasis
+fn escape_markdown(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut out, c| {
+            if matches!(c, '\\' | '[' | ']' | '*' | '_' | '`') {
+                out.push('\\');
+            }
+            out.push(c);
+            out
+        })
+}
+
+/// ### escape_html
+///
+/// Escape the characters which have a meaning in HTML
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use chrono::{Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    fn make_entry() -> DigestEntry {
+        DigestEntry {
+            source: String::from("This Week in Rust"),
+            article: Article {
+                title: Some(String::from("This Week in Rust [420]")),
+                authors: vec![String::from("TWiR Contributors")],
+                summary: String::from("Hello <Rustaceans>!\n\nMore news"),
+                links: Vec::new(),
+                url: String::from("https://this-week-in-rust.org/blog/2021/12/01/"),
+                comments: None,
+                categories: Vec::new(),
+                date: Local.timestamp_opt(1638360000, 0).single(),
+                duration: None,
+                enclosures: Vec::new(),
+                flair: None,
+                interest: None,
+                score: None,
+            },
+            tags: vec![String::from("rust")],
+            note: Some(String::from("Read the RFCs\n\nlater")),
+        }
+    }
+
+    #[test]
+    fn should_parse_digest_format() {
+        assert_eq!(
+            DigestFormat::from_str("md").unwrap(),
+            DigestFormat::Markdown
+        );
+        assert_eq!(
+            DigestFormat::from_str("markdown").unwrap(),
+            DigestFormat::Markdown
+        );
+        assert_eq!(DigestFormat::from_str("html").unwrap(), DigestFormat::Html);
+        assert!(DigestFormat::from_str("pdf").is_err());
+    }
+
+    #[test]
+    fn should_parse_period() {
+        assert_eq!(parse_period("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_period("24h").unwrap(), Duration::hours(24));
+        assert_eq!(parse_period("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_period("2w").unwrap(), Duration::weeks(2));
+        assert!(parse_period("").is_err());
+        assert!(parse_period("h").is_err());
+        assert!(parse_period("24").is_err());
+        assert!(parse_period("3y").is_err());
+    }
+
+    #[test]
+    fn should_render_markdown_digest() {
+        assert_eq!(
+            render("Digest", &[make_entry()], DigestFormat::Markdown).as_str(),
+            "# Digest\n\n## [This Week in Rust \\[420\\]](https://this-week-in-rust.org/blog/2021/12/01/)\n\n*This Week in Rust · 2021-12-01 · TWiR Contributors*\n\n`#rust`\n\n> Read the RFCs\n>\n> later\n\nHello <Rustaceans>!\n"
+        );
+    }
+
+    #[test]
+    fn should_render_html_digest() {
+        let doc = render("Digest", &[make_entry()], DigestFormat::Html);
+        assert!(doc.starts_with(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Digest</title>"
+        ));
+        assert!(doc.contains("<h2><a href=\"https://this-week-in-rust.org/blog/2021/12/01/\">This Week in Rust [420]</a></h2>\n<p><em>This Week in Rust · 2021-12-01 · TWiR Contributors</em></p>\n<p><code>#rust</code></p>\n<blockquote>Read the RFCs<br>\n<br>\nlater</blockquote>\n<p>Hello &lt;Rustaceans&gt;!</p>\n</article>\n"));
+        assert!(doc.ends_with("</body>\n</html>\n"));
+    }
+}
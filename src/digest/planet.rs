@@ -0,0 +1,152 @@
+//! # Planet
+//!
+//! Render the articles of many sources into a static HTML page, sorted by date
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{escape_html, DigestEntry};
+use crate::config::ThemePreset;
+use crate::helpers::fmt as fmt_helpers;
+
+/// Colors of the page on dark backgrounds
+const DARK_COLORS: &str =
+    "--background: #1c1c1c; --text: #e4e4e4; --dim: #8a8a8a; --link: #87afff; --accent: #87d7af;";
+/// Colors of the page on light backgrounds
+const LIGHT_COLORS: &str =
+    "--background: #ffffff; --text: #262626; --dim: #6c6c6c; --link: #005fd7; --accent: #008700;";
+
+/// Style of the page, after the colors
+const STYLE: &str = "body { background: var(--background); color: var(--text); font-family: sans-serif; max-width: 48em; margin: 0 auto; padding: 1em; line-height: 1.5; }
+a { color: var(--link); text-decoration: none; }
+a:hover { text-decoration: underline; }
+h1 { color: var(--accent); }
+h2 { color: var(--accent); border-bottom: 1px solid var(--dim); font-size: 1.1em; margin-top: 2em; }
+h3 { margin-bottom: 0; }
+.meta { color: var(--dim); font-size: 0.9em; margin-top: 0; }
+footer { color: var(--dim); font-size: 0.8em; margin-top: 3em; }
+";
+
+/// ### render_planet
+///
+/// Render `entries`, sorted by date, into a standalone HTML page titled `title`, with a header for each day.
+/// With the `auto` theme, the colors follow the preference of the browser
+pub fn render_planet(title: &str, entries: &[DigestEntry], theme: ThemePreset) -> String {
+    let title = escape_html(title);
+    let colors = match theme {
+        ThemePreset::Dark => format!(":root {{ {} }}\n", DARK_COLORS),
+        ThemePreset::Light => format!(":root {{ {} }}\n", LIGHT_COLORS),
+        ThemePreset::Auto => format!(
+            ":root {{ {} }}\n@media (prefers-color-scheme: dark) {{ :root {{ {} }} }}\n",
+            LIGHT_COLORS, DARK_COLORS
+        ),
+    };
+    let mut doc = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n{}{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, colors, STYLE, title
+    );
+    let mut day: Option<String> = None;
+    for entry in entries {
+        let entry_day = entry
+            .article
+            .date
+            .map(|x| fmt_helpers::format_datetime(x, "%A %d %B %Y"))
+            .unwrap_or_else(|| String::from("Undated"));
+        if day.as_ref() != Some(&entry_day) {
+            doc.push_str(format!("<h2>{}</h2>\n", escape_html(entry_day.as_str())).as_str());
+            day = Some(entry_day);
+        }
+        doc.push_str(
+            format!(
+                "<article>\n<h3><a href=\"{}\">{}</a></h3>\n<p class=\"meta\">{}</p>\n",
+                escape_html(entry.article.url.as_str()),
+                escape_html(entry.title()),
+                escape_html(entry.meta().as_str())
+            )
+            .as_str(),
+        );
+        if !entry.excerpt().is_empty() {
+            doc.push_str(format!("<p>{}</p>\n", escape_html(entry.excerpt())).as_str());
+        }
+        doc.push_str("</article>\n");
+    }
+    doc.push_str(
+        format!(
+            "<footer>Updated on {} with tuifeed</footer>\n</body>\n</html>\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        )
+        .as_str(),
+    );
+    doc
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::feed::Article;
+
+    use chrono::{Local, TimeZone};
+
+    fn make_entry(title: &str, timestamp: i64) -> DigestEntry {
+        DigestEntry {
+            source: String::from("Rust Blog"),
+            article: Article {
+                title: Some(String::from(title)),
+                authors: Vec::new(),
+                summary: String::from("Release notes"),
+                links: Vec::new(),
+                url: String::from("https://blog.rust-lang.org/"),
+                comments: None,
+                categories: Vec::new(),
+                date: Local.timestamp_opt(timestamp, 0).single(),
+                duration: None,
+                enclosures: Vec::new(),
+                flair: None,
+                interest: None,
+                score: None,
+            },
+            tags: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn should_render_planet() {
+        let entries = vec![
+            make_entry("Rust 1.57 <is out>", 1638360000),
+            make_entry("Rust 2021 survey", 1638360600),
+            make_entry("Rust 1.56", 1634800000),
+        ];
+        let doc = render_planet("Planet Rust", entries.as_slice(), ThemePreset::Dark);
+        assert!(doc.contains("<title>Planet Rust</title>"));
+        assert!(doc.contains(DARK_COLORS));
+        assert!(!doc.contains("prefers-color-scheme"));
+        assert!(doc.contains("<h3><a href=\"https://blog.rust-lang.org/\">Rust 1.57 &lt;is out&gt;</a></h3>\n<p class=\"meta\">Rust Blog · 2021-12-01</p>\n<p>Release notes</p>\n</article>\n"));
+        // A header for each day
+        assert_eq!(doc.matches("<h2>").count(), 2);
+        assert!(doc.ends_with("</body>\n</html>\n"));
+        let doc = render_planet("Planet Rust", entries.as_slice(), ThemePreset::Auto);
+        assert!(doc.contains("@media (prefers-color-scheme: dark)"));
+    }
+}
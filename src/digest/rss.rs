@@ -0,0 +1,123 @@
+//! # Rss
+//!
+//! Render articles into a RSS 2.0 feed
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{escape_html, DigestEntry};
+
+/// ### render_rss
+///
+/// Render `entries` into a RSS 2.0 feed titled `title`. Each item keeps the url of the article as guid,
+/// while the source of the article is its first category
+pub fn render_rss(title: &str, entries: &[DigestEntry]) -> String {
+    let mut doc = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n<title>{}</title>\n<link>https://github.com/veeso/tuifeed</link>\n<description>{}</description>\n<generator>tuifeed</generator>\n<lastBuildDate>{}</lastBuildDate>\n",
+        escape_html(title),
+        escape_html(title),
+        chrono::Local::now().to_rfc2822()
+    );
+    for entry in entries {
+        let article = &entry.article;
+        doc.push_str("<item>\n");
+        doc.push_str(format!("<title>{}</title>\n", escape_html(entry.title())).as_str());
+        if !article.url.is_empty() {
+            doc.push_str(
+                format!(
+                    "<link>{}</link>\n<guid isPermaLink=\"true\">{}</guid>\n",
+                    escape_html(article.url.as_str()),
+                    escape_html(article.url.as_str())
+                )
+                .as_str(),
+            );
+        }
+        if let Some(date) = article.date {
+            doc.push_str(format!("<pubDate>{}</pubDate>\n", date.to_rfc2822()).as_str());
+        }
+        for author in article.authors.iter() {
+            doc.push_str(format!("<dc:creator>{}</dc:creator>\n", escape_html(author)).as_str());
+        }
+        for category in std::iter::once(&entry.source).chain(article.categories.iter()) {
+            doc.push_str(format!("<category>{}</category>\n", escape_html(category)).as_str());
+        }
+        if !article.summary.trim().is_empty() {
+            doc.push_str(
+                format!(
+                    "<description>{}</description>\n",
+                    escape_html(article.summary.trim())
+                )
+                .as_str(),
+            );
+        }
+        doc.push_str("</item>\n");
+    }
+    doc.push_str("</channel>\n</rss>\n");
+    doc
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::feed::{Article, Feed};
+
+    use chrono::{Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_render_rss() {
+        let entry = DigestEntry {
+            source: String::from("Rust Blog"),
+            article: Article {
+                title: Some(String::from("Rust & Cargo 1.56")),
+                authors: vec![String::from("The Rust Team")],
+                summary: String::from("Rust 2021 is <stable>"),
+                links: Vec::new(),
+                url: String::from("https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html"),
+                comments: None,
+                categories: vec![String::from("release")],
+                date: Local.timestamp_opt(1634800000, 0).single(),
+                duration: None,
+                enclosures: Vec::new(),
+                flair: None,
+                interest: None,
+                score: None,
+            },
+            tags: Vec::new(),
+            note: None,
+        };
+        let doc = render_rss("Rust news", &[entry]);
+        assert!(doc.contains("<item>\n<title>Rust &amp; Cargo 1.56</title>\n<link>https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html</link>\n"));
+        assert!(doc.contains("<category>Rust Blog</category>\n<category>release</category>\n<description>Rust 2021 is &lt;stable&gt;</description>\n</item>\n"));
+        // The feed can be read back
+        let feed = Feed::from(feed_rs::parser::parse(doc.as_bytes()).unwrap());
+        let articles: Vec<&Article> = feed.articles().collect();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].title.as_deref(), Some("Rust & Cargo 1.56"));
+        assert_eq!(
+            articles[0].date,
+            Local.timestamp_opt(1634800000, 0).single()
+        );
+    }
+}
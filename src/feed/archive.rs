@@ -0,0 +1,333 @@
+//! # Archive
+//!
+//! Save article pages as self-contained HTML files
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{charset, FeedError, FeedResult};
+use chrono::{DateTime, Local};
+use log::{debug, info, warn};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Max size of the article page
+const MAX_PAGE_SIZE: usize = 10 * 1024 * 1024;
+/// Max size of each image inlined in the page; larger images are linked
+const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024;
+/// Max length of the file name, extension excluded
+const MAX_NAME_LEN: usize = 64;
+
+lazy_static! {
+    /**
+     * Matches the source of an image
+     *
+     * - group 1: tag up to the attribute value
+     * - group 2: double quoted source
+     * - group 3: single quoted source
+     */
+    static ref IMG_SRC_REGEX: Regex =
+        Regex::new(r#"(?i)(<img\b[^>]*?\ssrc\s*=\s*)(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    /**
+     * Matches the `srcset` attributes, which would load the images from remote
+     */
+    static ref SRCSET_REGEX: Regex =
+        Regex::new(r#"(?i)\ssrcset\s*=\s*(?:"[^"]*"|'[^']*')"#).unwrap();
+    /**
+     * Matches the head opening tag
+     */
+    static ref HEAD_REGEX: Regex = Regex::new(r#"(?i)<head\b[^>]*>"#).unwrap();
+    /**
+     * Matches the base element
+     */
+    static ref BASE_REGEX: Regex = Regex::new(r#"(?i)<base\b"#).unwrap();
+}
+
+/// ### archive_article
+///
+/// Download the page at `url`, inline its images and save it as a self-contained HTML file
+/// in the directory of today in `dir`, named after `title`. Returns the path of the file
+pub fn archive_article(url: &str, title: Option<&str>, dir: &Path) -> FeedResult<PathBuf> {
+    info!("archiving {}", url);
+    let (html, base) = get_page(url)?;
+    let mut images: HashMap<String, Option<String>> = HashMap::new();
+    let html = inline_images(html.as_str(), &base, |src| {
+        images
+            .entry(src.to_string())
+            .or_insert_with(|| get_image(src))
+            .clone()
+    });
+    let html = with_base(html.as_str(), base.as_str());
+    let p = archive_path(dir, Local::now(), title, url);
+    if let Some(parent) = p.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(p.as_path(), html)?;
+    info!("archived {} to {}", url, p.display());
+    Ok(p)
+}
+
+/// ### archive_path
+///
+/// Get the path of the file of the article titled `title` at `url`, archived on `date` in `dir`:
+/// articles are grouped by date and named after their title, or after their url if they have none
+pub fn archive_path(dir: &Path, date: DateTime<Local>, title: Option<&str>, url: &str) -> PathBuf {
+    let name = title
+        .map(slug)
+        .filter(|x| !x.is_empty())
+        .unwrap_or_else(|| slug(url));
+    let name = match name.is_empty() {
+        true => String::from("article"),
+        false => name,
+    };
+    dir.join(date.format("%Y-%m-%d").to_string())
+        .join(format!("{}.html", name))
+}
+
+// -- private
+
+/// ### get_page
+///
+/// Get the page at `url`, decoded to UTF-8. Returns the page and its url, after redirects
+fn get_page(url: &str) -> FeedResult<(String, Url)> {
+    let response = ureq::get(url).call().map_err(|err| {
+        warn!("GET {} failed: {}", url, err);
+        FeedError::from(err)
+    })?;
+    let base = Url::parse(response.get_url()).map_err(|e| FeedError::Http(e.to_string()))?;
+    let content_type = response.header("content-type").map(|x| x.to_string());
+    let body = read_body(response.into_reader(), MAX_PAGE_SIZE)?;
+    let body = charset::decode_feed(body.as_slice(), content_type.as_deref());
+    Ok((String::from_utf8_lossy(body.as_ref()).to_string(), base))
+}
+
+/// ### get_image
+///
+/// Get the image at `url` as a data url; returns `None` if it can't be downloaded or if it's too large
+fn get_image(url: &str) -> Option<String> {
+    debug!("inlining image {}", url);
+    let response = match ureq::get(url).call() {
+        Ok(response) => response,
+        Err(err) => {
+            warn!("could not get image {}: {}", url, err);
+            return None;
+        }
+    };
+    let content_type = response
+        .header("content-type")
+        .filter(|x| x.starts_with("image/"))
+        .unwrap_or("image/*")
+        .to_string();
+    match read_body(response.into_reader(), MAX_IMAGE_SIZE) {
+        Ok(body) => Some(data_url(content_type.as_str(), body.as_slice())),
+        Err(err) => {
+            warn!("could not get image {}: {}", url, err);
+            None
+        }
+    }
+}
+
+/// ### read_body
+///
+/// Read the HTTP response body from `reader`, failing as soon as it exceeds `max_size` bytes
+fn read_body(reader: impl Read, max_size: usize) -> FeedResult<Vec<u8>> {
+    let mut body = Vec::new();
+    reader.take(max_size as u64 + 1).read_to_end(&mut body)?;
+    match body.len() > max_size {
+        true => Err(FeedError::TooLarge(max_size)),
+        false => Ok(body),
+    }
+}
+
+/// ### inline_images
+///
+/// Replace the sources of the images in `html` with the data urls returned by `fetch`
+/// for their absolute urls, resolved against `base`. Images which can't be fetched are linked
+/// with their absolute url. `srcset` attributes are removed, so that no image is loaded from remote
+fn inline_images<F>(html: &str, base: &Url, mut fetch: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let html = SRCSET_REGEX.replace_all(html, "");
+    IMG_SRC_REGEX
+        .replace_all(html.as_ref(), |caps: &Captures| {
+            let src = caps
+                .get(2)
+                .or_else(|| caps.get(3))
+                .map(|x| x.as_str())
+                .unwrap_or_default();
+            let src = match base.join(src.trim()) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {
+                    fetch(url.as_str()).unwrap_or_else(|| url.to_string())
+                }
+                // Data urls are already inline
+                _ => src.to_string(),
+            };
+            format!("{}\"{}\"", &caps[1], src.replace('"', "%22"))
+        })
+        .to_string()
+}
+
+/// ### with_base
+///
+/// Declare `url` as the base url of `html`, so that relative links keep working in the archived page.
+/// Pages which already declare their base url are returned as they are
+fn with_base(html: &str, url: &str) -> String {
+    if BASE_REGEX.is_match(html) {
+        return html.to_string();
+    }
+    let base = format!("<base href=\"{}\">", url.replace('"', "%22"));
+    match HEAD_REGEX.find(html) {
+        Some(head) => format!("{}{}{}", &html[..head.end()], base, &html[head.end()..]),
+        None => format!("{}{}", base, html),
+    }
+}
+
+/// ### data_url
+///
+/// Make the data url of `data`, whose type is `content_type`
+fn data_url(content_type: &str, data: &[u8]) -> String {
+    format!("data:{};base64,{}", content_type, base64::encode(data))
+}
+
+/// ### slug
+///
+/// Make a file name out of `s`, keeping only lowercase alphanumeric characters separated by `-`
+fn slug(s: &str) -> String {
+    let words: Vec<String> = s
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .map(|x| x.to_lowercase())
+        .collect();
+    let mut slug = String::new();
+    for word in words {
+        if slug.chars().count() + word.chars().count() + 1 > MAX_NAME_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(word.as_str());
+    }
+    slug
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_inline_images() {
+        let base = Url::parse("https://example.com/blog/post.html").unwrap();
+        let html = r#"<p><img class="a" src="/a.png" srcset="/a@2x.png 2x"><IMG src='b.jpg'><img src="https://cdn.example.com/c.gif"><img src="data:image/png;base64,AAAA"></p>"#;
+        let mut fetched = Vec::new();
+        let html = inline_images(html, &base, |url| {
+            fetched.push(url.to_string());
+            match url.ends_with(".gif") {
+                true => None,
+                false => Some(String::from("data:image/png;base64,AAAA")),
+            }
+        });
+        assert_eq!(
+            html.as_str(),
+            r#"<p><img class="a" src="data:image/png;base64,AAAA"><IMG src="data:image/png;base64,AAAA"><img src="https://cdn.example.com/c.gif"><img src="data:image/png;base64,AAAA"></p>"#
+        );
+        assert_eq!(
+            fetched,
+            vec![
+                String::from("https://example.com/a.png"),
+                String::from("https://example.com/blog/b.jpg"),
+                String::from("https://cdn.example.com/c.gif"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_declare_base_url() {
+        assert_eq!(
+            with_base(
+                "<html><head lang=\"en\"><title>a</title></head></html>",
+                "https://example.com/a"
+            )
+            .as_str(),
+            "<html><head lang=\"en\"><base href=\"https://example.com/a\"><title>a</title></head></html>"
+        );
+        assert_eq!(
+            with_base("<p>a</p>", "https://example.com/a").as_str(),
+            "<base href=\"https://example.com/a\"><p>a</p>"
+        );
+        assert_eq!(
+            with_base("<head><base href=\"/\"></head>", "https://example.com/a").as_str(),
+            "<head><base href=\"/\"></head>"
+        );
+    }
+
+    #[test]
+    fn should_make_data_url() {
+        assert_eq!(
+            data_url("image/png", b"hello").as_str(),
+            "data:image/png;base64,aGVsbG8="
+        );
+    }
+
+    #[test]
+    fn should_make_archive_path() {
+        let date = Local.timestamp_opt(1638360000, 0).single().unwrap();
+        let day = date.format("%Y-%m-%d").to_string();
+        let dir = Path::new("/archive");
+        assert_eq!(
+            archive_path(
+                dir,
+                date,
+                Some("Rust 1.57: what's new?"),
+                "https://example.com/a"
+            ),
+            dir.join(day.as_str()).join("rust-1-57-what-s-new.html")
+        );
+        assert_eq!(
+            archive_path(dir, date, None, "https://example.com/a"),
+            dir.join(day.as_str()).join("https-example-com-a.html")
+        );
+        assert_eq!(
+            archive_path(dir, date, Some("???"), ""),
+            dir.join(day.as_str()).join("article.html")
+        );
+    }
+
+    #[test]
+    fn should_make_slug() {
+        assert_eq!(slug("Hello, World!").as_str(), "hello-world");
+        assert_eq!(slug("Ünïcödé  ŧitle").as_str(), "ünïcödé-ŧitle");
+        assert_eq!(
+            slug(vec!["word"; 30].join(" ").as_str()).chars().count(),
+            64
+        );
+    }
+}
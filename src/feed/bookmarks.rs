@@ -0,0 +1,148 @@
+//! # Bookmarks
+//!
+//! Bookmarks exported by browsers in the Netscape bookmark file format
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::helpers::strings as str_helpers;
+
+use regex::Regex;
+use std::path::Path;
+use url::Url;
+
+lazy_static! {
+    /**
+     * Matches the bookmarks in a Netscape bookmark file
+     *
+     * - group 1: attributes of the anchor
+     * - group 2: title
+     */
+    static ref BOOKMARK_REGEX: Regex = Regex::new(r"(?is)<a\s([^>]*)>(.*?)</a>").unwrap();
+    static ref HREF_REGEX: Regex =
+        Regex::new(r#"(?is)\bhref\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap();
+}
+
+/// ## Bookmark
+///
+/// A website bookmarked in the browser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+}
+
+/// ### load_bookmarks
+///
+/// Load the bookmarks from the Netscape bookmark file at `p`
+pub fn load_bookmarks(p: &Path) -> Result<Vec<Bookmark>, String> {
+    std::fs::read_to_string(p)
+        .map(|data| parse_bookmarks(data.as_str()))
+        .map_err(|e| format!("could not read bookmarks {}: {}", p.display(), e))
+}
+
+/// ### parse_bookmarks
+///
+/// Parse the bookmarks in the content of a Netscape bookmark file, in order.
+/// Only web pages are kept (e.g. `javascript:` bookmarklets and `place:` queries are not),
+/// and a page bookmarked many times is returned once
+pub fn parse_bookmarks(data: &str) -> Vec<Bookmark> {
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    for anchor in BOOKMARK_REGEX.captures_iter(data) {
+        let href = match HREF_REGEX.captures(&anchor[1]) {
+            Some(href) => href
+                .get(1)
+                .or_else(|| href.get(2))
+                .or_else(|| href.get(3))
+                .map(|x| str_helpers::decode_html_entities(x.as_str().trim()))
+                .unwrap_or_default(),
+            None => continue,
+        };
+        let url = match Url::parse(href.as_str()) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url.to_string(),
+            _ => continue,
+        };
+        if bookmarks.iter().any(|x| x.url == url) {
+            continue;
+        }
+        bookmarks.push(Bookmark {
+            title: str_helpers::sanitize_line(&anchor[2]),
+            url,
+        });
+    }
+    bookmarks
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    const BOOKMARKS: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks Menu</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1638000000" LAST_MODIFIED="1638000000">Blogs</H3>
+    <DL><p>
+        <DT><A HREF="https://blog.rust-lang.org/" ADD_DATE="1638000000" ICON="data:image/png;base64,AAAA">Rust Blog</A>
+        <DT><A HREF="https://example.com/?a=1&amp;b=2" ADD_DATE="1638000000">Tom &amp; Jerry</A>
+    </DL><p>
+    <DT><A HREF="javascript:alert(1)">Bookmarklet</A>
+    <DT><A HREF="place:sort=8&maxResults=10">Recent Tags</A>
+    <DT><A HREF="https://blog.rust-lang.org/">Rust Blog again</A>
+    <DT><A HREF="https://lwn.net"></A>
+</DL>
+"#;
+
+    #[test]
+    fn should_parse_bookmarks() {
+        assert_eq!(
+            parse_bookmarks(BOOKMARKS),
+            vec![
+                Bookmark {
+                    title: String::from("Rust Blog"),
+                    url: String::from("https://blog.rust-lang.org/"),
+                },
+                Bookmark {
+                    title: String::from("Tom & Jerry"),
+                    url: String::from("https://example.com/?a=1&b=2"),
+                },
+                Bookmark {
+                    title: String::new(),
+                    url: String::from("https://lwn.net/"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn should_fail_loading_missing_bookmark_file() {
+        assert!(load_bookmarks(Path::new("/this/file/does/not/exist.html")).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//! # Charset
+//!
+//! Decoding of feeds which are not encoded in UTF-8
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use encoding_rs::{Decoder, Encoding, UTF_8, WINDOWS_1252};
+use regex::bytes::Regex as BytesRegex;
+use regex::Regex;
+use std::borrow::Cow;
+use std::io::{self, Read};
+
+/// Bytes read to detect the encoding of streamed feeds; enough for the XML declaration
+const PREFIX_SIZE: usize = 1024;
+/// Bytes decoded at once by streamed feeds
+const CHUNK_SIZE: usize = 8192;
+
+lazy_static! {
+    /**
+     * Matches the encoding in the XML declaration
+     *
+     * - group 1: encoding label
+     */
+    static ref XML_ENCODING_REGEX: BytesRegex =
+        BytesRegex::new(r#"^\s*<\?xml[^>]*?\sencoding\s*=\s*["']([A-Za-z0-9._:\-]+)["']"#).unwrap();
+    /**
+     * Matches the charset parameter in a Content-Type header
+     *
+     * - group 1: charset label
+     */
+    static ref CHARSET_REGEX: Regex =
+        Regex::new(r#"(?i);\s*charset\s*=\s*["']?([A-Za-z0-9._:\-]+)"#).unwrap();
+}
+
+/// ### decode_feed
+///
+/// Decode `body` to UTF-8, according to its byte order mark, the charset declared in the
+/// `content_type` HTTP header or the encoding in the XML declaration, in this order.
+/// Bodies declaring no encoding which are not valid UTF-8 are decoded as Windows-1252.
+/// The XML declaration of the decoded body declares UTF-8, so that the parser won't decode it again
+pub fn decode_feed<'a>(body: &'a [u8], content_type: Option<&str>) -> Cow<'a, [u8]> {
+    let encoding = detect_encoding(body, content_type);
+    // Nothing to do for valid UTF-8
+    if encoding == UTF_8 && std::str::from_utf8(body).is_ok() {
+        return Cow::Borrowed(body);
+    }
+    let (decoded, _, _) = encoding.decode(body);
+    Cow::Owned(declare_utf8(decoded.as_ref()).into_bytes())
+}
+
+/// ### decode_reader
+///
+/// Decode the feed read from `reader` to UTF-8 while it's read, as `decode_feed` does.
+/// The encoding is detected from the first bytes of the feed
+pub fn decode_reader<R: Read>(
+    mut reader: R,
+    content_type: Option<&str>,
+) -> io::Result<DecodeReader<R>> {
+    let mut prefix = Vec::with_capacity(PREFIX_SIZE);
+    (&mut reader)
+        .take(PREFIX_SIZE as u64)
+        .read_to_end(&mut prefix)?;
+    let mut decoder = DecodeReader {
+        reader,
+        decoder: detect_encoding(prefix.as_slice(), content_type).new_decoder(),
+        output: Vec::new(),
+        position: 0,
+        eof: false,
+    };
+    let prefix = decoder.decode(prefix.as_slice());
+    decoder.output = declare_utf8(prefix.as_str()).into_bytes();
+    Ok(decoder)
+}
+
+/// ## DecodeReader
+///
+/// Reads a feed decoding it to UTF-8 one chunk at a time
+pub struct DecodeReader<R: Read> {
+    reader: R,
+    decoder: Decoder,
+    /// Decoded chunk
+    output: Vec<u8>,
+    /// Bytes of `output` already read
+    position: usize,
+    eof: bool,
+}
+
+impl<R: Read> DecodeReader<R> {
+    /// ### decode
+    ///
+    /// Decode `input`; characters split across chunks are kept by the decoder until the next chunk
+    fn decode(&mut self, input: &[u8]) -> String {
+        let capacity = self
+            .decoder
+            .max_utf8_buffer_length(input.len())
+            .unwrap_or(input.len() * 3 + 3);
+        let mut decoded = String::with_capacity(capacity);
+        let _ = self.decoder.decode_to_string(input, &mut decoded, self.eof);
+        decoded
+    }
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.output.len() {
+            if self.eof {
+                return Ok(0);
+            }
+            let mut input = [0; CHUNK_SIZE];
+            let read = self.reader.read(&mut input)?;
+            self.eof = read == 0;
+            self.output = self.decode(&input[..read]).into_bytes();
+            self.position = 0;
+        }
+        let read = buf.len().min(self.output.len() - self.position);
+        buf[..read].copy_from_slice(&self.output[self.position..self.position + read]);
+        self.position += read;
+        Ok(read)
+    }
+}
+
+/// ### detect_encoding
+///
+/// Detect the encoding of `body`, according to its byte order mark, the charset declared in the
+/// `content_type` HTTP header or the encoding in the XML declaration, in this order.
+/// `body` may be truncated, so an incomplete character at its end doesn't make it invalid UTF-8
+fn detect_encoding(body: &[u8], content_type: Option<&str>) -> &'static Encoding {
+    Encoding::for_bom(body)
+        .map(|(encoding, _)| encoding)
+        .or_else(|| content_type.and_then(http_charset))
+        .or_else(|| xml_encoding(body))
+        .unwrap_or_else(|| match std::str::from_utf8(body) {
+            Ok(_) => UTF_8,
+            Err(err) if err.error_len().is_none() => UTF_8,
+            Err(_) => WINDOWS_1252,
+        })
+}
+
+/// ### http_charset
+///
+/// Get the encoding declared with the charset parameter of a Content-Type header
+fn http_charset(content_type: &str) -> Option<&'static Encoding> {
+    CHARSET_REGEX
+        .captures(content_type)
+        .and_then(|x| x.get(1))
+        .and_then(|x| Encoding::for_label(x.as_str().as_bytes()))
+}
+
+/// ### xml_encoding
+///
+/// Get the encoding declared in the XML declaration of `body`
+fn xml_encoding(body: &[u8]) -> Option<&'static Encoding> {
+    XML_ENCODING_REGEX
+        .captures(body)
+        .and_then(|x| x.get(1))
+        .and_then(|x| Encoding::for_label(x.as_bytes()))
+}
+
+/// ### declare_utf8
+///
+/// Replace the encoding in the XML declaration of `xml` with UTF-8
+fn declare_utf8(xml: &str) -> String {
+    match XML_ENCODING_REGEX
+        .captures(xml.as_bytes())
+        .and_then(|x| x.get(1))
+    {
+        Some(label) => format!("{}UTF-8{}", &xml[..label.start()], &xml[label.end()..]),
+        None => xml.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// Decode `body` both at once and streamed, which must give the same result
+    fn decode(body: &[u8], content_type: Option<&str>) -> String {
+        let decoded = String::from_utf8(decode_feed(body, content_type).into_owned()).unwrap();
+        let mut streamed = String::new();
+        decode_reader(body, content_type)
+            .unwrap()
+            .read_to_string(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, decoded);
+        decoded
+    }
+
+    #[test]
+    fn should_keep_utf8_feeds() {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?><rss>caffè</rss>"#.as_bytes();
+        assert!(matches!(decode_feed(body, None), Cow::Borrowed(_)));
+        assert!(matches!(
+            decode_feed(body, Some("application/rss+xml")),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn should_decode_feed_with_xml_encoding() {
+        let mut body = br#"<?xml version="1.0" encoding="ISO-8859-1"?><rss>caff"#.to_vec();
+        body.push(0xe8);
+        body.extend_from_slice(b"</rss>");
+        assert_eq!(
+            decode(body.as_slice(), Some("text/xml")).as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><rss>caffè</rss>"#
+        );
+    }
+
+    #[test]
+    fn should_decode_feed_with_http_charset() {
+        // "Привет" in Windows-1251
+        let mut body = b"<?xml version='1.0'?><rss>".to_vec();
+        body.extend_from_slice(&[0xcf, 0xf0, 0xe8, 0xe2, 0xe5, 0xf2]);
+        body.extend_from_slice(b"</rss>");
+        assert_eq!(
+            decode(
+                body.as_slice(),
+                Some("application/rss+xml; charset=windows-1251")
+            )
+            .as_str(),
+            "<?xml version='1.0'?><rss>Привет</rss>"
+        );
+    }
+
+    #[test]
+    fn should_prefer_http_charset_over_xml_encoding() {
+        let mut body = br#"<?xml version="1.0" encoding="UTF-8"?><rss>"#.to_vec();
+        body.extend_from_slice(&[0xcf, 0xf0, 0xe8]);
+        body.extend_from_slice(b"</rss>");
+        assert_eq!(
+            decode(body.as_slice(), Some(r#"text/xml; charset="Windows-1251""#)).as_str(),
+            r#"<?xml version="1.0" encoding="UTF-8"?><rss>При</rss>"#
+        );
+    }
+
+    #[test]
+    fn should_decode_feed_with_bom() {
+        let mut body = vec![0xff, 0xfe];
+        for c in "<rss>ciao</rss>".encode_utf16() {
+            body.extend_from_slice(&c.to_le_bytes());
+        }
+        assert_eq!(
+            decode(body.as_slice(), Some("text/xml; charset=iso-8859-1")).as_str(),
+            "<rss>ciao</rss>"
+        );
+    }
+
+    #[test]
+    fn should_decode_invalid_utf8_as_windows_1252() {
+        let body = [
+            b'<', b'r', b'>', 0x93, b'h', b'i', 0x94, b'<', b'/', b'r', b'>',
+        ];
+        assert_eq!(decode(&body, None).as_str(), "<r>“hi”</r>");
+    }
+
+    #[test]
+    fn should_decode_reader_across_chunks() {
+        // "caffè" in UTF-8 and Windows-1252, split across the prefix and the chunks
+        let text = "caffè ".repeat(PREFIX_SIZE + CHUNK_SIZE);
+        let body = format!("<rss>{}</rss>", text);
+        assert_eq!(decode(body.as_bytes(), None), body);
+        let mut body = b"<rss>".to_vec();
+        for _ in 0..(PREFIX_SIZE + CHUNK_SIZE) {
+            body.extend_from_slice(b"caff");
+            body.extend_from_slice(&[0xe8, b' ']);
+        }
+        body.extend_from_slice(b"</rss>");
+        assert_eq!(
+            decode(body.as_slice(), Some("text/xml; charset=windows-1252")),
+            format!("<rss>{}</rss>", text)
+        );
+    }
+
+    #[test]
+    fn should_get_http_charset() {
+        assert_eq!(
+            http_charset("text/xml; charset=ISO-8859-1"),
+            Some(WINDOWS_1252)
+        );
+        assert_eq!(http_charset("text/xml;charset=utf-8"), Some(UTF_8));
+        assert!(http_charset("text/xml").is_none());
+        assert!(http_charset("text/xml; charset=klingon").is_none());
+    }
+}
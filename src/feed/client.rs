@@ -25,41 +25,317 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use super::{Feed, FeedError, FeedResult};
+use super::comments::CommentsReader;
+use super::shorthand::{self, Shorthand};
+use super::tls;
+use super::{
+    charset, reddit, releases, scrape, CookieJar, Feed, FeedError, FeedLimits, FeedResult,
+    FetchOptions, ScrapeSelectors,
+};
+use chrono::Utc;
 use feed_rs::parser as feed_parser;
-use std::io::Read;
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+use ureq::{Agent, AgentBuilder, Response};
+use url::Url;
+
+/// Maximum amount of redirects to follow
+const MAX_REDIRECTS: usize = 5;
+/// Max size of the feed download, unless set for the source
+const DEFAULT_MAX_FEED_SIZE: usize = 10 * 1024 * 1024;
 
 /// ## Client
 ///
 /// RSS client. Fetches its sources to retrieve all the required Feeds
-#[derive(Default)]
-pub struct Client;
+#[derive(Debug, Default, Clone)]
+pub struct Client {
+    /// Whether to fetch flair and score of the Reddit posts
+    reddit_metadata: bool,
+    /// PEM file with additional certificate authorities to trust
+    ca_file: Option<PathBuf>,
+}
 
 impl Client {
+    /// ### reddit_metadata
+    ///
+    /// Set whether to fetch flair and score of the posts of Reddit sources
+    pub fn reddit_metadata(mut self, enabled: bool) -> Self {
+        self.reddit_metadata = enabled;
+        self
+    }
+
+    /// ### ca_file
+    ///
+    /// Set the PEM file with the certificate authorities to trust along with the built-in ones
+    pub fn ca_file(mut self, ca_file: Option<PathBuf>) -> Self {
+        self.ca_file = ca_file;
+        self
+    }
+
     /// ### fetch_source
     ///
-    /// Fetch a single source from remote
-    pub fn fetch(&self, source: &str) -> FeedResult<Feed> {
-        let body = self.get_feed(source)?;
-        self.parse_feed(body)
+    /// Fetch a single source from remote, applying `limits` to its articles and `options` to the requests.
+    /// Shorthand sources (e.g. `youtube:@channel`) are expanded into their feed url
+    pub fn fetch(
+        &self,
+        source: &str,
+        limits: &FeedLimits,
+        options: &FetchOptions,
+    ) -> FeedResult<Feed> {
+        let agent = self.agent(options)?;
+        let mut cookies = match options.cookies.as_deref() {
+            Some(p) => CookieJar::load(p)?,
+            None => CookieJar::default(),
+        };
+        let url = self.resolve_source(&agent, source, &mut cookies)?;
+        info!("fetching feed from {}", url);
+        let (response, moved_to) = self.get_feed(&agent, url.as_str(), &mut cookies)?;
+        // Shorthand sources are never moved, since they're not urls
+        let moved_to = moved_to.filter(|_| !shorthand::is_shorthand(source));
+        if let Some(url) = moved_to.as_deref() {
+            info!("{} has been moved permanently to {}", source, url);
+        }
+        let content_type = response.header("content-type").map(|x| x.to_string());
+        let max_size = limits.max_size.unwrap_or(DEFAULT_MAX_FEED_SIZE);
+        // Don't even start downloading feeds which declare to be too large
+        let content_length = response
+            .header("content-length")
+            .and_then(|x| x.parse::<usize>().ok());
+        if content_length.map(|x| x > max_size).unwrap_or(false) {
+            warn!("{} is larger than {} bytes", source, max_size);
+            return Err(FeedError::TooLarge(max_size));
+        }
+        let mut reader = LimitedReader::new(response.into_reader(), max_size);
+        let feed = match options.scrape.as_ref() {
+            Some(selectors) => self.scrape_page(
+                &mut reader,
+                content_type.as_deref(),
+                url.as_str(),
+                selectors,
+            ),
+            None => self.parse_feed(&mut reader, content_type.as_deref()),
+        };
+        // Reading fails once the limit is exceeded, whatever the error reported by the parser is
+        let feed = match reader.exceeded() {
+            true => Err(FeedError::TooLarge(max_size)),
+            false => feed,
+        };
+        let mut feed = feed
+            .map(|feed| Feed {
+                moved_to,
+                ..feed.limit(limits)
+            })
+            .map_err(|err| {
+                warn!("could not parse feed from {}: {}", source, err);
+                err
+            })?;
+        if shorthand::is_release_source(source) {
+            releases::apply_tags(&mut feed);
+        }
+        if let Some(listing) = shorthand::reddit_listing(source).filter(|_| self.reddit_metadata) {
+            // Metadata are optional: the feed is returned anyway
+            match self.get_reddit_metadata(&agent, listing.as_str(), &mut cookies) {
+                Ok(metadata) => reddit::apply_metadata(&mut feed, &metadata),
+                Err(err) => warn!("could not get Reddit metadata from {}: {}", listing, err),
+            }
+        }
+        Ok(feed)
     }
 
     // -- private
 
+    /// ### agent
+    ///
+    /// Make the HTTP agent to fetch a source with `options`.
+    /// Redirects are not followed by the agent, in order to detect the moved sources
+    fn agent(&self, options: &FetchOptions) -> FeedResult<Agent> {
+        let builder = AgentBuilder::new().redirects(0);
+        if self.ca_file.is_none() && options.client_cert.is_none() && !options.insecure {
+            return Ok(builder.build());
+        }
+        let config = tls::client_config(
+            self.ca_file.as_deref(),
+            options.client_cert.as_deref(),
+            options.client_key.as_deref(),
+            options.insecure,
+        )
+        .map_err(|err| {
+            warn!("could not configure TLS: {}", err);
+            err
+        })?;
+        Ok(builder.tls_config(Arc::new(config)).build())
+    }
+
+    /// ### resolve_source
+    ///
+    /// Get the feed url of `source`; YouTube handles are looked up in the channel page
+    fn resolve_source(
+        &self,
+        agent: &Agent,
+        source: &str,
+        cookies: &mut CookieJar,
+    ) -> FeedResult<String> {
+        match shorthand::expand(source) {
+            Shorthand::Url(url) => Ok(url),
+            Shorthand::YoutubeHandle(page_url) => {
+                debug!("looking up YouTube channel id in {}", page_url);
+                let (response, _) = self.get_feed(agent, page_url.as_str(), cookies)?;
+                let page = response.into_string()?;
+                shorthand::find_youtube_channel_id(page.as_str())
+                    .map(shorthand::youtube_channel_feed)
+                    .ok_or_else(|| {
+                        warn!("could not find YouTube channel id in {}", page_url);
+                        FeedError::Http(format!("Could not find YouTube channel {}", page_url))
+                    })
+            }
+        }
+    }
+
+    /// ### get_reddit_metadata
+    ///
+    /// Get the metadata of the posts in the Reddit JSON `listing`
+    fn get_reddit_metadata(
+        &self,
+        agent: &Agent,
+        listing: &str,
+        cookies: &mut CookieJar,
+    ) -> FeedResult<HashMap<String, reddit::PostMetadata>> {
+        let (response, _) = self.get_feed(agent, listing, cookies)?;
+        reddit::parse_listing(response.into_string()?.as_str())
+    }
+
     /// ### get_feed
     ///
-    /// Get feed via HTTP GET request
-    fn get_feed(&self, source: &str) -> FeedResult<impl Read + Send> {
-        Ok(ureq::get(source).call()?.into_reader())
+    /// Get feed via HTTP GET request with `agent`, following redirects.
+    /// Cookies in `cookies` are sent with each request, and the cookies set by responses are kept in it,
+    /// so that sessions started by login redirects are followed.
+    /// Returns the response and, if the source has been moved with permanent redirects,
+    /// the url it has been moved to
+    fn get_feed(
+        &self,
+        agent: &Agent,
+        source: &str,
+        cookies: &mut CookieJar,
+    ) -> FeedResult<(Response, Option<String>)> {
+        let mut url = source.to_string();
+        let mut moved_to = None;
+        let mut permanent = true;
+        for _ in 0..=MAX_REDIRECTS {
+            let parsed = Url::parse(url.as_str())
+                .map_err(|e| FeedError::Http(format!("Bad url {}: {}", url, e)))?;
+            let mut request = agent.get(url.as_str());
+            if let Some(cookie) = cookies.header(&parsed, Utc::now().timestamp()) {
+                request = request.set("Cookie", cookie.as_str());
+            }
+            let response = match request.call() {
+                Ok(response) => response,
+                Err(err) => {
+                    warn!("GET {} failed: {}", url, err);
+                    return Err(err.into());
+                }
+            };
+            debug!(
+                "GET {}: {} {}",
+                url,
+                response.status(),
+                response.status_text()
+            );
+            for set_cookie in response.all("set-cookie") {
+                cookies.store(&parsed, set_cookie, Utc::now().timestamp());
+            }
+            let status = response.status();
+            match response.header("location") {
+                Some(location) if matches!(status, 301 | 302 | 303 | 307 | 308) => {
+                    let next = parsed
+                        .join(location)
+                        .map_err(|e| {
+                            FeedError::Http(format!("Bad redirection to {}: {}", location, e))
+                        })?
+                        .to_string();
+                    // Only the leading permanent redirects tell where the source has been moved
+                    permanent &= matches!(status, 301 | 308);
+                    if permanent {
+                        moved_to = Some(next.clone());
+                    }
+                    url = next;
+                }
+                _ => return Ok((response, moved_to)),
+            }
+        }
+        warn!("GET {} failed: too many redirects", source);
+        Err(FeedError::Http(String::from("Too many redirects")))
+    }
+
+    /// ### scrape_page
+    ///
+    /// Scrape the articles from the HTML page read from `reader`.
+    /// Pages are read entirely, since selectors apply to the whole document
+    fn scrape_page(
+        &self,
+        mut reader: impl Read,
+        content_type: Option<&str>,
+        url: &str,
+        selectors: &ScrapeSelectors,
+    ) -> FeedResult<Feed> {
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+        let body = charset::decode_feed(body.as_slice(), content_type);
+        scrape::scrape_page(&String::from_utf8_lossy(body.as_ref()), url, selectors)
     }
 
     /// ### parse_feed
     ///
-    /// Parse feed from HTTP response
-    fn parse_feed<R: Read>(&self, response: R) -> FeedResult<Feed> {
-        feed_parser::parse(response)
+    /// Parse feed while it's read from the HTTP response body, decoding it according to `content_type`
+    fn parse_feed(&self, reader: impl Read, content_type: Option<&str>) -> FeedResult<Feed> {
+        let mut reader = CommentsReader::new(charset::decode_reader(reader, content_type)?);
+        let mut feed = feed_parser::parse(&mut reader)
             .map(Feed::from)
-            .map_err(FeedError::from)
+            .map_err(FeedError::from)?;
+        reader.apply(&mut feed);
+        Ok(feed)
+    }
+}
+
+/// ## LimitedReader
+///
+/// Reads from `reader`, failing as soon as more than `max_size` bytes have been read,
+/// so that misbehaving feeds are never downloaded entirely
+struct LimitedReader<R: Read> {
+    reader: R,
+    max_size: usize,
+    read: usize,
+}
+
+impl<R: Read> LimitedReader<R> {
+    fn new(reader: R, max_size: usize) -> Self {
+        Self {
+            reader,
+            max_size,
+            read: 0,
+        }
+    }
+
+    /// ### exceeded
+    ///
+    /// Returns whether more than `max_size` bytes have been read
+    fn exceeded(&self) -> bool {
+        self.read > self.max_size
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.read += read;
+        match self.exceeded() {
+            true => Err(io::Error::other(
+                FeedError::TooLarge(self.max_size).to_string(),
+            )),
+            false => Ok(read),
+        }
     }
 }
 
@@ -68,34 +344,227 @@ mod test {
 
     use super::*;
 
+    use pretty_assertions::assert_eq;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Start an HTTP server redirecting the paths in `redirects` to their locations;
+    /// any other path is answered with an empty feed. Returns the server address
+    fn make_redirect_server(redirects: &[(&str, &str, &str)]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let redirects: Vec<(String, String, String)> = redirects
+            .iter()
+            .map(|(path, status, location)| {
+                (path.to_string(), status.to_string(), location.to_string())
+            })
+            .collect();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = String::new();
+                let _ = BufReader::new(&stream).read_line(&mut request);
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let response = match redirects.iter().find(|(x, _, _)| *x == path) {
+                    Some((_, status, location)) => format!(
+                        "HTTP/1.1 {}\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        status, location
+                    ),
+                    None => String::from(
+                        "HTTP/1.1 200 OK\r\nContent-Length: 11\r\nConnection: close\r\n\r\n<rss></rss>",
+                    ),
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        address
+    }
+
     #[test]
     fn should_get_source() {
         let client = Client::default();
+        let agent = client.agent(&FetchOptions::default()).ok().unwrap();
         assert!(client
-            .get_feed(&String::from(
-                "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
-            ))
+            .get_feed(
+                &agent,
+                "https://rss.nytimes.com/services/xml/rss/nyt/World.xml",
+                &mut CookieJar::default()
+            )
             .is_ok());
     }
 
     #[test]
     fn should_fail_getting_source() {
         let client = Client::default();
+        let agent = client.agent(&FetchOptions::default()).ok().unwrap();
+        assert!(client
+            .get_feed(
+                &agent,
+                "https://rss.nytimes.com/services/xml/rss/nyt/pippopippopippo.xml",
+                &mut CookieJar::default()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn should_detect_permanent_redirects() {
+        let server = make_redirect_server(&[
+            ("/moved", "301 Moved Permanently", "/new"),
+            ("/new", "302 Found", "/feed.xml"),
+        ]);
+        let client = Client::default();
+        let agent = client.agent(&FetchOptions::default()).ok().unwrap();
+        let (_, moved_to) = client
+            .get_feed(
+                &agent,
+                format!("{}/moved", server).as_str(),
+                &mut CookieJar::default(),
+            )
+            .ok()
+            .unwrap();
+        assert_eq!(moved_to, Some(format!("{}/new", server)));
+        // Temporary redirects are followed, but not reported
+        let (response, moved_to) = client
+            .get_feed(
+                &agent,
+                format!("{}/new", server).as_str(),
+                &mut CookieJar::default(),
+            )
+            .ok()
+            .unwrap();
+        assert!(moved_to.is_none());
+        let mut content = String::new();
+        assert!(response.into_reader().read_to_string(&mut content).is_ok());
+        assert_eq!(content.as_str(), "<rss></rss>");
+    }
+
+    #[test]
+    fn should_send_cookies() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request = String::new();
+                while reader.read_line(&mut request).unwrap_or(0) > 2 {}
+                // The login page sets the session, then redirects to the feed
+                let response = if request.starts_with("GET /login ") {
+                    "HTTP/1.1 302 Found\r\nLocation: /feed.xml\r\nSet-Cookie: session=abc; Path=/\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                } else if request.contains("token=xyz; session=abc") {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 11\r\nConnection: close\r\n\r\n<rss></rss>"
+                } else {
+                    "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                };
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        let client = Client::default();
+        let agent = client.agent(&FetchOptions::default()).ok().unwrap();
+        let mut cookies = CookieJar::parse("127.0.0.1\tFALSE\t/\tFALSE\t0\ttoken\txyz\n");
         assert!(client
-            .get_feed(&String::from(
-                "https://rss.nytimes.com/services/xml/rss/nyt/pippopippopippo.xml"
-            ))
+            .get_feed(&agent, format!("{}/login", address).as_str(), &mut cookies)
+            .is_ok());
+        assert!(client
+            .get_feed(
+                &agent,
+                format!("{}/feed.xml", address).as_str(),
+                &mut CookieJar::default()
+            )
             .is_err());
     }
 
+    #[test]
+    fn should_fail_fetching_with_invalid_tls_files() {
+        let client =
+            Client::default().ca_file(Some(PathBuf::from("/this/file/does/not/exist.pem")));
+        assert!(matches!(
+            client.fetch(
+                "https://news.ycombinator.com/rss",
+                &FeedLimits::default(),
+                &FetchOptions::default()
+            ),
+            Err(FeedError::Tls(_))
+        ));
+        let options = FetchOptions {
+            client_cert: Some(PathBuf::from("/this/file/does/not/exist.pem")),
+            ..FetchOptions::default()
+        };
+        assert!(matches!(
+            Client::default().fetch(
+                "https://news.ycombinator.com/rss",
+                &FeedLimits::default(),
+                &options
+            ),
+            Err(FeedError::Tls(_))
+        ));
+    }
+
+    #[test]
+    fn should_limit_reader_to_max_size() {
+        let mut body = Vec::new();
+        let mut reader = LimitedReader::new("<rss></rss>".as_bytes(), 11);
+        assert!(reader.read_to_end(&mut body).is_ok());
+        assert_eq!(body.as_slice(), b"<rss></rss>");
+        assert_eq!(reader.exceeded(), false);
+        let mut reader = LimitedReader::new("<rss></rss>".as_bytes(), 10);
+        assert!(reader.read_to_end(&mut Vec::new()).is_err());
+        assert_eq!(reader.exceeded(), true);
+    }
+
+    #[test]
+    fn should_not_fetch_too_large_feed() {
+        let server = make_redirect_server(&[]);
+        let client = Client::default();
+        let limits = FeedLimits {
+            max_size: Some(8),
+            ..FeedLimits::default()
+        };
+        assert_eq!(
+            client
+                .fetch(
+                    format!("{}/feed.xml", server).as_str(),
+                    &limits,
+                    &FetchOptions::default()
+                )
+                .err()
+                .unwrap(),
+            FeedError::TooLarge(8)
+        );
+    }
+
+    #[test]
+    fn should_parse_feed_in_http_charset() {
+        let mut body =
+            b"<rss version=\"2.0\"><channel><title>Notizie</title><item><title>Perch".to_vec();
+        body.push(0xe9);
+        body.extend_from_slice(b"</title><link>https://example.com/</link></item></channel></rss>");
+        let feed = Client::default()
+            .parse_feed(body.as_slice(), Some("text/xml; charset=ISO-8859-1"))
+            .ok()
+            .unwrap();
+        assert_eq!(
+            feed.articles().next().unwrap().title.as_deref(),
+            Some("Perché")
+        );
+    }
+
     #[test]
     fn should_fetch_source() {
         let client = Client::default();
         assert!(client
-            .fetch("https://rss.nytimes.com/services/xml/rss/nyt/World.xml",)
+            .fetch(
+                "https://rss.nytimes.com/services/xml/rss/nyt/World.xml",
+                &FeedLimits::default(),
+                &FetchOptions::default()
+            )
             .is_ok());
         assert!(client
-            .fetch("https://www.lefigaro.fr/rss/figaro_actualites.xml",)
+            .fetch(
+                "https://www.lefigaro.fr/rss/figaro_actualites.xml",
+                &FeedLimits::default(),
+                &FetchOptions::default()
+            )
             .is_ok());
     }
 }
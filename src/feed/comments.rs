@@ -0,0 +1,203 @@
+//! # Comments
+//!
+//! Finds the comments page of articles, for feeds such as Hacker News and Lobste.rs
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::Feed;
+
+use regex::Regex;
+use std::io::{self, Read};
+
+/// Start of a RSS item
+const ITEM_START: &[u8] = b"<item";
+/// End of a RSS item
+const ITEM_END: &[u8] = b"</item>";
+
+lazy_static! {
+    /**
+     * Matches RSS items
+     *
+     * - group 1: item content
+     */
+    static ref RSS_ITEM_REGEX: Regex = Regex::new(r"(?s)<item(?:\s[^>]*)?>(.*?)</item>").unwrap();
+    /**
+     * Matches the comments element in a RSS item
+     *
+     * - group 1: comments url, maybe wrapped in CDATA
+     */
+    static ref RSS_COMMENTS_REGEX: Regex = Regex::new(r"(?s)<comments>(.*?)</comments>").unwrap();
+    /**
+     * Matches "Article URL" and "Comments URL" in a summary (e.g. hnrss.org)
+     *
+     * - group 1: kind of url
+     * - group 2: url
+     */
+    static ref SUMMARY_URL_REGEX: Regex =
+        Regex::new(r#"(?i)(Article|Comments) URL:\s*(?:<a href="([^"]+)")?"#).unwrap();
+}
+
+/// ### find_in_summary
+///
+/// Find the story and the comments urls written in the summary html, such as
+/// `Article URL: <a href="...">...</a> Comments URL: <a href="...">...</a>`
+pub fn find_in_summary(summary: &str) -> (Option<String>, Option<String>) {
+    let mut story = None;
+    let mut comments = None;
+    for group in SUMMARY_URL_REGEX.captures_iter(summary) {
+        let url = match group.get(2) {
+            Some(url) => Some(url.as_str().replace("&amp;", "&")),
+            None => continue,
+        };
+        match group[1].to_ascii_lowercase().as_str() {
+            "article" => story = story.or(url),
+            _ => comments = comments.or(url),
+        }
+    }
+    (story, comments)
+}
+
+/// ## CommentsReader
+///
+/// Reads a RSS feed from `reader`, collecting the `<comments>` element of each item as the items are read.
+/// Only the item being read is kept, so that feeds are never held in memory
+pub struct CommentsReader<R: Read> {
+    reader: R,
+    /// Bytes read since the start of the item being read
+    item: Vec<u8>,
+    /// Comments url of each item read so far
+    comments: Vec<Option<String>>,
+}
+
+impl<R: Read> CommentsReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            item: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    /// ### apply
+    ///
+    /// Set the comments url of the articles in `feed`, parsed from the read RSS feed.
+    /// Items are matched to articles by position, so `feed` must not have been limited yet
+    pub fn apply(self, feed: &mut Feed) {
+        if self.comments.len() != feed.articles.len() {
+            return;
+        }
+        for (article, comments) in feed.articles.iter_mut().zip(self.comments) {
+            if article.comments.is_none() && comments.as_deref() != Some(article.url.as_str()) {
+                article.comments = comments;
+            }
+        }
+    }
+
+    /// ### scan
+    ///
+    /// Collect the comments of the items completed by `bytes`
+    fn scan(&mut self, bytes: &[u8]) {
+        self.item.extend_from_slice(bytes);
+        while let Some(end) = find(self.item.as_slice(), ITEM_END).map(|x| x + ITEM_END.len()) {
+            let item = String::from_utf8_lossy(&self.item[..end]);
+            if let Some(content) = RSS_ITEM_REGEX.captures(item.as_ref()) {
+                self.comments.push(
+                    RSS_COMMENTS_REGEX
+                        .captures(&content[1])
+                        .map(|x| {
+                            x[1].trim()
+                                .trim_start_matches("<![CDATA[")
+                                .trim_end_matches("]]>")
+                                .trim()
+                                .replace("&amp;", "&")
+                        })
+                        .filter(|x| !x.is_empty()),
+                );
+            }
+            self.item.drain(..end);
+        }
+        // Bytes preceding the next item are dropped, but for a tag which may be split across reads
+        let start = find(self.item.as_slice(), ITEM_START)
+            .unwrap_or_else(|| self.item.len().saturating_sub(ITEM_START.len() - 1));
+        self.item.drain(..start);
+    }
+}
+
+impl<R: Read> Read for CommentsReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.scan(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// ### find
+///
+/// Find the position of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_find_urls_in_summary() {
+        assert_eq!(
+            find_in_summary(
+                r#"<p>Article URL: <a href="https://example.com/post">https://example.com/post</a></p>
+<p>Comments URL: <a href="https://news.ycombinator.com/item?id=1&amp;p=2">https://news.ycombinator.com/item?id=1</a></p>"#
+            ),
+            (
+                Some(String::from("https://example.com/post")),
+                Some(String::from("https://news.ycombinator.com/item?id=1&p=2"))
+            )
+        );
+        assert_eq!(find_in_summary("<p>Hello</p>"), (None, None));
+    }
+
+    #[test]
+    fn should_collect_rss_comments_while_reading() {
+        let xml = br#"<rss version="2.0"><channel><title>Lobsters</title>
+<item><title>One</title><link>https://example.com/one</link><comments>https://lobste.rs/s/aaaaaa</comments></item>
+<item><title>Two</title><link>https://example.com/two</link></item>
+<item><title>Three</title><link>https://lobste.rs/s/cccccc</link><comments><![CDATA[https://lobste.rs/s/cccccc]]></comments></item>
+</channel></rss>"#;
+        // Feed is read a few bytes at a time, so that tags are split across reads
+        let mut reader = CommentsReader::new(std::io::BufReader::with_capacity(7, &xml[..]));
+        let mut feed = Feed::from(feed_rs::parser::parse(&mut reader).unwrap());
+        reader.apply(&mut feed);
+        let comments: Vec<Option<&str>> = feed.articles().map(|x| x.comments.as_deref()).collect();
+        assert_eq!(
+            comments,
+            vec![Some("https://lobste.rs/s/aaaaaa"), None, None]
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! # Cookies
+//!
+//! Cookie jar for the sources behind cookie-based authentication
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{FeedError, FeedResult};
+
+use chrono::DateTime;
+use std::path::Path;
+use url::Url;
+
+/// Prefix of the domain of http-only cookies in Netscape cookie files
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// ## CookieJar
+///
+/// Cookies sent with the requests of a source. The jar is loaded from a Netscape `cookies.txt`
+/// file, as exported by browsers and written by curl, and keeps the cookies set by the responses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cookie {
+    /// Domain, without leading dot
+    domain: String,
+    /// Whether the cookie is sent to the subdomains of `domain` too
+    include_subdomains: bool,
+    path: String,
+    /// Whether the cookie is sent only over https
+    secure: bool,
+    /// Unix timestamp of the expiration; session cookies never expire
+    expires: Option<i64>,
+    name: String,
+    value: String,
+}
+
+impl CookieJar {
+    /// ### load
+    ///
+    /// Load the cookie jar from the Netscape cookie file at `p`
+    pub fn load(p: &Path) -> FeedResult<Self> {
+        std::fs::read_to_string(p)
+            .map(|data| Self::parse(data.as_str()))
+            .map_err(|e| FeedError::Io(format!("could not read cookies {}: {}", p.display(), e)))
+    }
+
+    /// ### parse
+    ///
+    /// Parse the content of a Netscape cookie file; malformed lines are ignored
+    pub fn parse(data: &str) -> Self {
+        let cookies = data
+            .lines()
+            .map(|line| line.strip_prefix(HTTP_ONLY_PREFIX).unwrap_or(line))
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split('\t').collect();
+                if fields.len() != 7 {
+                    return None;
+                }
+                let expires = fields[4].trim().parse::<i64>().ok()?;
+                Some(Cookie {
+                    domain: fields[0].trim_start_matches('.').to_lowercase(),
+                    include_subdomains: fields[1].eq_ignore_ascii_case("TRUE"),
+                    path: fields[2].to_string(),
+                    secure: fields[3].eq_ignore_ascii_case("TRUE"),
+                    expires: Some(expires).filter(|x| *x > 0),
+                    name: fields[5].to_string(),
+                    value: fields[6].to_string(),
+                })
+            })
+            .collect();
+        Self { cookies }
+    }
+
+    /// ### header
+    ///
+    /// Get the value of the `Cookie` header to send to `url` at the unix timestamp `now`, if any cookie matches
+    pub fn header(&self, url: &Url, now: i64) -> Option<String> {
+        let cookies: Vec<String> = self
+            .cookies
+            .iter()
+            .filter(|x| x.matches(url, now))
+            .map(|x| format!("{}={}", x.name, x.value))
+            .collect();
+        match cookies.is_empty() {
+            true => None,
+            false => Some(cookies.join("; ")),
+        }
+    }
+
+    /// ### store
+    ///
+    /// Store the cookie of the `Set-Cookie` header received from `url` at the unix timestamp `now`,
+    /// replacing the cookie with the same name, domain and path. Expired cookies are removed
+    pub fn store(&mut self, url: &Url, set_cookie: &str, now: i64) {
+        let mut attributes = set_cookie.split(';').map(str::trim);
+        let (name, value) = match attributes.next().and_then(|x| x.split_once('=')) {
+            Some((name, value)) if !name.trim().is_empty() => (name.trim(), value.trim()),
+            _ => return,
+        };
+        let host = match url.host_str() {
+            Some(host) => host.to_lowercase(),
+            None => return,
+        };
+        let mut cookie = Cookie {
+            domain: host,
+            include_subdomains: false,
+            path: default_path(url),
+            secure: false,
+            expires: None,
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let mut max_age = None;
+        for attribute in attributes {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            match key.trim().to_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    cookie.domain = value.trim().trim_start_matches('.').to_lowercase();
+                    cookie.include_subdomains = true;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "secure" => cookie.secure = true,
+                "max-age" => max_age = value.trim().parse::<i64>().ok(),
+                "expires" => {
+                    // Dashes are used by the legacy format (e.g. `Wed, 21-Oct-2015 07:28:00 GMT`)
+                    let date = value.trim().replace('-', " ");
+                    if let Ok(date) = DateTime::parse_from_rfc2822(date.as_str()) {
+                        cookie.expires = Some(date.timestamp());
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Max-Age has precedence over Expires
+        if let Some(max_age) = max_age {
+            cookie.expires = Some(now + max_age);
+        }
+        self.cookies.retain(|x| {
+            x.name != cookie.name || x.domain != cookie.domain || x.path != cookie.path
+        });
+        if cookie.expires.map(|x| x > now).unwrap_or(true) {
+            self.cookies.push(cookie);
+        }
+    }
+}
+
+impl Cookie {
+    /// ### matches
+    ///
+    /// Returns whether the cookie must be sent to `url` at the unix timestamp `now`
+    fn matches(&self, url: &Url, now: i64) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.to_lowercase(),
+            None => return false,
+        };
+        let domain_matches = host == self.domain
+            || (self.include_subdomains && host.ends_with(format!(".{}", self.domain).as_str()));
+        let path_matches = url.path().starts_with(self.path.as_str());
+        domain_matches
+            && path_matches
+            && (!self.secure || url.scheme() == "https")
+            && self.expires.map(|x| x > now).unwrap_or(true)
+    }
+}
+
+/// ### default_path
+///
+/// Get the path of the cookies set by `url` without the `Path` attribute: the directory of its path
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => String::from("/"),
+        Some(i) => url.path()[..i].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    const COOKIES: &str = "# Netscape HTTP Cookie File
+# This is a generated file! Do not edit.
+
+.example.com\tTRUE\t/\tFALSE\t0\tsession\tabc
+#HttpOnly_intranet.example.com\tFALSE\t/feeds\tTRUE\t2000000000\ttoken\txyz
+old.example.com\tFALSE\t/\tFALSE\t1000\told\tvalue
+malformed line
+";
+
+    #[test]
+    fn should_parse_cookie_file() {
+        let jar = CookieJar::parse(COOKIES);
+        assert_eq!(jar.cookies.len(), 3);
+        assert_eq!(
+            jar.cookies[1],
+            Cookie {
+                domain: String::from("intranet.example.com"),
+                include_subdomains: false,
+                path: String::from("/feeds"),
+                secure: true,
+                expires: Some(2000000000),
+                name: String::from("token"),
+                value: String::from("xyz"),
+            }
+        );
+        assert!(jar.cookies[0].expires.is_none());
+    }
+
+    #[test]
+    fn should_get_cookie_header() {
+        let jar = CookieJar::parse(COOKIES);
+        let now = 1600000000;
+        assert_eq!(
+            jar.header(
+                &Url::parse("https://intranet.example.com/feeds/rss.xml").unwrap(),
+                now
+            )
+            .as_deref(),
+            Some("session=abc; token=xyz")
+        );
+        // Secure cookies are sent only over https
+        assert_eq!(
+            jar.header(
+                &Url::parse("http://intranet.example.com/feeds/rss.xml").unwrap(),
+                now
+            )
+            .as_deref(),
+            Some("session=abc")
+        );
+        // Expired cookies are not sent
+        assert_eq!(
+            jar.header(&Url::parse("http://old.example.com/rss.xml").unwrap(), now)
+                .as_deref(),
+            Some("session=abc")
+        );
+        assert!(jar
+            .header(&Url::parse("https://example.org/rss.xml").unwrap(), now)
+            .is_none());
+    }
+
+    #[test]
+    fn should_store_cookies_set_by_responses() {
+        let mut jar = CookieJar::default();
+        let url = Url::parse("https://example.com/feeds/rss.xml").unwrap();
+        let now = 1600000000;
+        jar.store(&url, "session=abc; Path=/; Secure; HttpOnly", now);
+        jar.store(&url, "lang=it", now);
+        assert_eq!(
+            jar.header(&url, now).as_deref(),
+            Some("session=abc; lang=it")
+        );
+        // Cookies without path are sent to the directory of the url only
+        assert_eq!(
+            jar.header(&Url::parse("https://example.com/rss.xml").unwrap(), now)
+                .as_deref(),
+            Some("session=abc")
+        );
+        // Replace and expire cookies
+        jar.store(&url, "session=def; Path=/; Max-Age=60", now);
+        assert_eq!(
+            jar.header(&url, now).as_deref(),
+            Some("lang=it; session=def")
+        );
+        assert_eq!(jar.header(&url, now + 60).as_deref(), Some("lang=it"));
+        jar.store(&url, "lang=; Expires=Thu, 01 Jan 1970 00:00:00 GMT", now);
+        assert_eq!(jar.header(&url, now).as_deref(), Some("session=def"));
+        // Domain cookies are sent to subdomains
+        jar.store(&url, "id=1; Domain=.example.com; Path=/", now);
+        assert_eq!(
+            jar.header(&Url::parse("https://www.example.com/").unwrap(), now)
+                .as_deref(),
+            Some("id=1")
+        );
+    }
+
+    #[test]
+    fn should_fail_loading_missing_cookie_file() {
+        assert!(CookieJar::load(Path::new("/this/file/does/not/exist.txt")).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+//! # Discovery
+//!
+//! Feed autodiscovery: find the feeds advertised by a web page
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{FeedError, FeedResult};
+
+use feed_rs::parser as feed_parser;
+use log::{info, warn};
+use regex::Regex;
+use std::io::Read;
+use std::time::Duration;
+use url::Url;
+
+/// Timeout of the request to the page
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(15);
+/// Max size of the page to look for feeds in
+const MAX_PAGE_SIZE: u64 = 5 * 1024 * 1024;
+/// Content types of the feeds advertised by pages
+const FEED_TYPES: [&str; 4] = [
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/rdf+xml",
+    "application/feed+json",
+];
+
+lazy_static! {
+    static ref LINK_TAG_REGEX: Regex = Regex::new(r"(?is)<link\s[^>]*>").unwrap();
+    /**
+     * Matches an attribute of a tag
+     *
+     * - group 1: name
+     * - group 2: double-quoted value (maybe)
+     * - group 3: single-quoted value (maybe)
+     * - group 4: unquoted value (maybe)
+     */
+    static ref ATTRIBUTE_REGEX: Regex =
+        Regex::new(r#"(?s)([A-Za-z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap();
+}
+
+/// ### discover_feeds
+///
+/// Find the feeds of the page at `url`. If `url` is a feed itself, it is the only one returned;
+/// otherwise the feeds advertised by the page with `<link rel="alternate">` are returned, in order
+pub fn discover_feeds(url: &str) -> FeedResult<Vec<String>> {
+    info!("looking for feeds in {}", url);
+    let response = ureq::get(url)
+        .timeout(DISCOVERY_TIMEOUT)
+        .call()
+        .map_err(|err| {
+            warn!("GET {} failed: {}", url, err);
+            err
+        })?;
+    // Links are relative to the page url, after redirects
+    let page_url = response.get_url().to_string();
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_PAGE_SIZE)
+        .read_to_end(&mut body)?;
+    if feed_parser::parse(body.as_slice()).is_ok() {
+        return Ok(vec![page_url]);
+    }
+    let feeds = find_feed_links(
+        String::from_utf8_lossy(body.as_slice()).as_ref(),
+        page_url.as_str(),
+    );
+    match feeds.is_empty() {
+        true => Err(FeedError::Parse(format!("No feed found in {}", url))),
+        false => Ok(feeds),
+    }
+}
+
+/// ### find_feed_links
+///
+/// Get the urls of the feeds advertised in `html` by the `<link rel="alternate">` elements,
+/// resolved against `base_url`. Duplicated urls are returned once
+pub fn find_feed_links(html: &str, base_url: &str) -> Vec<String> {
+    let base = Url::parse(base_url).ok();
+    let mut feeds: Vec<String> = Vec::new();
+    for tag in LINK_TAG_REGEX.find_iter(html) {
+        let mut rel = None;
+        let mut content_type = None;
+        let mut href = None;
+        for attribute in ATTRIBUTE_REGEX.captures_iter(tag.as_str()) {
+            let value = attribute
+                .get(2)
+                .or_else(|| attribute.get(3))
+                .or_else(|| attribute.get(4))
+                .map(|x| x.as_str().trim())
+                .unwrap_or("");
+            match attribute[1].to_lowercase().as_str() {
+                "rel" => rel = Some(value.to_lowercase()),
+                "type" => content_type = Some(value.to_lowercase()),
+                "href" => href = Some(value),
+                _ => {}
+            }
+        }
+        let is_feed = rel
+            .map(|x| x.split_whitespace().any(|x| x == "alternate"))
+            .unwrap_or(false)
+            && content_type
+                .map(|x| FEED_TYPES.contains(&x.as_str()))
+                .unwrap_or(false);
+        let url = match href.filter(|x| is_feed && !x.is_empty()) {
+            Some(href) => match base.as_ref() {
+                Some(base) => base.join(href).map(|x| x.to_string()).ok(),
+                None => Url::parse(href).map(|x| x.to_string()).ok(),
+            },
+            None => None,
+        };
+        if let Some(url) = url.filter(|x| !feeds.contains(x)) {
+            feeds.push(url);
+        }
+    }
+    feeds
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_find_feed_links() {
+        let html = r#"<!DOCTYPE html>
+<html>
+<head>
+    <link rel="stylesheet" href="/style.css" type="text/css">
+    <link rel="alternate" type="application/rss+xml" title="Posts" href="/feed.xml" />
+    <link href='https://example.com/atom.xml' type='application/atom+xml' rel='alternate'>
+    <LINK REL="alternate" TYPE="application/feed+json" HREF=feed.json>
+    <link rel="alternate" hreflang="it" href="/it/">
+    <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+</head>
+</html>"#;
+        assert_eq!(
+            find_feed_links(html, "https://example.com/blog/"),
+            vec![
+                String::from("https://example.com/feed.xml"),
+                String::from("https://example.com/atom.xml"),
+                String::from("https://example.com/blog/feed.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_find_feed_links() {
+        assert!(
+            find_feed_links("<html><body>Hello</body></html>", "https://example.com").is_empty()
+        );
+        // Relative links can't be resolved without a valid base
+        assert!(find_feed_links(
+            r#"<link rel="alternate" type="application/rss+xml" href="/feed.xml">"#,
+            "not a url"
+        )
+        .is_empty());
+    }
+}
@@ -27,18 +27,38 @@
  * SOFTWARE.
  */
 // -- modules
+mod archive;
+mod bookmarks;
+mod charset;
 mod client;
+mod comments;
+mod cookies;
+mod discovery;
+mod podcasts;
+mod reddit;
+mod releases;
 mod result;
+mod scrape;
+mod shorthand;
+mod tls;
 
 use crate::helpers::strings as str_helpers;
 
 // -- export
+pub use archive::archive_article;
+pub use bookmarks::load_bookmarks;
 pub use client::Client;
+pub use cookies::CookieJar;
+pub use discovery::discover_feeds;
+pub use podcasts::{search_podcasts, Podcast};
 pub use result::{FeedError, FeedResult};
+pub use scrape::ScrapeSelectors;
 // -- deps
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local};
 use feed_rs::model::{Entry as RssEntry, Feed as RssFeed};
+use std::path::PathBuf;
 use std::slice::Iter;
+use std::time::Duration as StdDuration;
 
 /// ## Feed
 ///
@@ -46,6 +66,53 @@ use std::slice::Iter;
 #[derive(Debug, Clone, PartialEq)]
 pub struct Feed {
     pub(crate) articles: Vec<Article>,
+    /// Url the feed has been permanently moved to, if the source responded with 301 or 308
+    pub(crate) moved_to: Option<String>,
+    pub(crate) channel: Channel,
+}
+
+/// ## Channel
+///
+/// Describes the feed itself, as declared in its channel element
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Channel {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Url of the website the feed belongs to
+    pub link: Option<String>,
+    pub language: Option<String>,
+    /// Software which generated the feed
+    pub generator: Option<String>,
+}
+
+/// ## FeedLimits
+///
+/// Limits applied to a feed and to its articles, to keep huge feeds manageable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeedLimits {
+    /// Max amount of articles to keep
+    pub max_articles: Option<usize>,
+    /// Articles older than this are discarded
+    pub max_age: Option<Duration>,
+    /// Max size of the feed download, in bytes; 10 MB if unset
+    pub max_size: Option<usize>,
+}
+
+/// ## FetchOptions
+///
+/// Options of the HTTP requests made to fetch a source
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchOptions {
+    /// Netscape cookie file with the cookies to send to the source
+    pub cookies: Option<PathBuf>,
+    /// PEM file with the certificate the client authenticates with
+    pub client_cert: Option<PathBuf>,
+    /// PEM file with the key of the client certificate; if unset, it's read from `client_cert`
+    pub client_key: Option<PathBuf>,
+    /// Don't verify the certificate of the source
+    pub insecure: bool,
+    /// Selectors of the articles, if the source is a web page to scrape rather than a feed
+    pub scrape: Option<ScrapeSelectors>,
 }
 
 /// ## Article
@@ -56,8 +123,34 @@ pub struct Article {
     pub title: Option<String>,
     pub authors: Vec<String>,
     pub summary: String,
+    /// Links found in the summary, numbered by their position
+    pub links: Vec<String>,
     pub url: String,
+    /// Url of the comments page, if different from the article url
+    pub comments: Option<String>,
+    /// Categories (or tags) of the article
+    pub categories: Vec<String>,
     pub date: Option<DateTime<Local>>,
+    /// Duration of the attached media (e.g. a video or a podcast episode), if known
+    pub duration: Option<StdDuration>,
+    /// Media attached to the article, such as podcast episodes or images
+    pub enclosures: Vec<Enclosure>,
+    /// Flair of the post, for Reddit sources
+    pub flair: Option<String>,
+    /// Score computed by the scoring rules, if any matches the article
+    pub interest: Option<i64>,
+    /// Score of the post, for Reddit sources
+    pub score: Option<i64>,
+}
+
+/// ## Enclosure
+///
+/// A media file attached to an article
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enclosure {
+    pub url: String,
+    /// Mime type declared by the feed (e.g. `audio/mpeg`)
+    pub content_type: Option<String>,
 }
 
 impl Feed {
@@ -67,35 +160,185 @@ impl Feed {
     pub fn articles(&self) -> Iter<'_, Article> {
         self.articles.iter()
     }
+
+    /// ### article
+    ///
+    /// Get the article at `index`
+    pub fn article(&self, index: usize) -> Option<&Article> {
+        self.articles.get(index)
+    }
+
+    /// ### moved_to
+    ///
+    /// Get the url the feed has been permanently moved to, if any
+    pub fn moved_to(&self) -> Option<&str> {
+        self.moved_to.as_deref()
+    }
+
+    /// ### channel
+    ///
+    /// Get the description of the feed
+    pub fn channel(&self) -> &Channel {
+        &self.channel
+    }
+
+    /// ### limit
+    ///
+    /// Discard articles older than the max age, then keep only the first `max_articles`.
+    /// Articles without a date are never considered too old
+    pub fn limit(mut self, limits: &FeedLimits) -> Self {
+        if let Some(max_age) = limits.max_age {
+            let oldest = Local::now() - max_age;
+            self.articles
+                .retain(|x| x.date.map(|date| date >= oldest).unwrap_or(true));
+        }
+        if let Some(max_articles) = limits.max_articles {
+            self.articles.truncate(max_articles);
+        }
+        self
+    }
 }
 
 // -- converter
 
 impl From<RssFeed> for Feed {
     fn from(feed: RssFeed) -> Self {
+        // The website is the alternate link; `self` links point to the feed itself
+        let link = feed
+            .links
+            .iter()
+            .find(|x| matches!(x.rel.as_deref(), None | Some("alternate")))
+            .map(|x| str_helpers::decode_html_entities(x.href.trim()))
+            .filter(|x| !x.is_empty());
+        let generator = feed.generator.map(|x| match x.version {
+            Some(version) => format!("{} {}", x.content.trim(), version),
+            None => x.content.trim().to_string(),
+        });
+        let channel = Channel {
+            title: feed
+                .title
+                .map(|x| str_helpers::sanitize_line(x.content.as_str())),
+            description: feed
+                .description
+                .map(|x| str_helpers::sanitize_line(x.content.as_str())),
+            link,
+            language: feed.language.map(|x| x.trim().to_string()),
+            generator,
+        };
         Self {
             articles: feed.entries.into_iter().map(Article::from).collect(),
+            moved_to: None,
+            channel: channel.non_empty(),
+        }
+    }
+}
+
+impl Channel {
+    /// ### non_empty
+    ///
+    /// Drop the empty attributes of the channel
+    fn non_empty(self) -> Self {
+        let non_empty = |x: Option<String>| x.filter(|x| !x.is_empty());
+        Self {
+            title: non_empty(self.title),
+            description: non_empty(self.description),
+            link: non_empty(self.link),
+            language: non_empty(self.language),
+            generator: non_empty(self.generator),
         }
     }
 }
 
 impl From<RssEntry> for Article {
     fn from(entry: RssEntry) -> Self {
+        let duration = entry.media.iter().find_map(|x| {
+            x.duration
+                .or_else(|| x.content.iter().find_map(|content| content.duration))
+        });
+        let mut enclosures: Vec<Enclosure> = Vec::new();
+        for content in entry.media.iter().flat_map(|x| x.content.iter()) {
+            let url = match content.url.as_ref() {
+                Some(url) => url.to_string(),
+                None => continue,
+            };
+            if !enclosures.iter().any(|x| x.url == url) {
+                enclosures.push(Enclosure {
+                    url,
+                    content_type: content
+                        .content_type
+                        .as_ref()
+                        .map(|x| x.essence_str().to_string()),
+                });
+            }
+        }
+        // Atom entries may have only the content (e.g. GitHub releases)
+        let summary = entry
+            .summary
+            .map(|x| x.content)
+            .or_else(|| entry.content.and_then(|x| x.body));
+        let (story, summary_comments) = summary
+            .as_deref()
+            .map(comments::find_in_summary)
+            .unwrap_or_default();
+        let (summary, links) = summary
+            .as_deref()
+            .map(str_helpers::number_links)
+            .unwrap_or_default();
+        let url = str_helpers::decode_html_entities(
+            entry
+                .links
+                .iter()
+                .find(|x| x.rel.as_deref() != Some("replies"))
+                .map(|x| x.href.as_str())
+                .unwrap_or(entry.id.as_str())
+                .trim(),
+        );
+        let comments = entry
+            .links
+            .iter()
+            .find(|x| x.rel.as_deref() == Some("replies"))
+            .map(|x| x.href.clone())
+            .or(summary_comments);
+        // Some feeds link the comments page, writing the story url in the summary
+        let url = match story {
+            Some(story) if url.is_empty() || comments.as_deref() == Some(url.as_str()) => story,
+            _ => url,
+        };
+        let comments = comments.filter(|x| *x != url);
+        let mut categories: Vec<String> = Vec::new();
+        for category in entry.categories.iter() {
+            let name =
+                str_helpers::sanitize_line(category.label.as_deref().unwrap_or(&category.term));
+            if !name.is_empty() && !categories.contains(&name) {
+                categories.push(name);
+            }
+        }
         Self {
             title: entry
                 .title
-                .map(|x| str_helpers::strip_html(x.content.as_str())),
-            authors: entry.authors.into_iter().map(|x| x.name).collect(),
-            summary: entry
-                .summary
-                .map(|x| str_helpers::strip_html(x.content.as_str()))
-                .unwrap_or_default(),
-            url: entry
-                .links
-                .get(0)
-                .map(|x| x.href.clone())
-                .unwrap_or(entry.id),
-            date: entry.updated.map(DateTime::<Local>::from),
+                .map(|x| str_helpers::sanitize_line(x.content.as_str()))
+                .filter(|x| !x.is_empty()),
+            authors: entry
+                .authors
+                .into_iter()
+                .map(|x| str_helpers::sanitize_line(x.name.as_str()))
+                .filter(|x| !x.is_empty())
+                .collect(),
+            summary: str_helpers::sanitize_text(summary.as_str()),
+            links,
+            url,
+            comments,
+            categories,
+            // Some parsers copy the date of the channel into the updated date of its items
+            date: entry
+                .published
+                .or(entry.updated)
+                .map(DateTime::<Local>::from),
+            duration,
+            enclosures,
+            flair: None,
+            interest: None,
+            score: None,
         }
     }
 }
@@ -112,8 +355,11 @@ mod test {
     fn should_get_feed_attributes() {
         let feed = Feed {
             articles: Vec::default(),
+            moved_to: None,
+            channel: Channel::default(),
         };
         assert!(feed.articles.is_empty());
+        assert!(feed.article(0).is_none());
     }
 
     #[test]
@@ -121,12 +367,43 @@ mod test {
         let entry = RssEntry::default();
         let article = Article::from(entry);
         assert!(article.authors.is_empty());
+        assert!(article.duration.is_none());
+        assert!(article.enclosures.is_empty());
         assert_eq!(article.date, None);
         assert_eq!(article.summary, String::new());
+        assert!(article.links.is_empty());
         assert_eq!(article.title, None);
         assert_eq!(article.url, String::new());
     }
 
+    #[test]
+    fn should_prefer_publication_date() {
+        let feed = Feed::from(
+            feed_rs::parser::parse(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Rust news</title>
+    <lastBuildDate>Sat, 01 Jan 2022 00:00:00 +0000</lastBuildDate>
+    <item>
+      <title>Rust 1.56</title>
+      <link>https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html</link>
+      <pubDate>Thu, 21 Oct 2021 00:00:00 +0000</pubDate>
+    </item>
+  </channel>
+</rss>"#
+                    .as_bytes(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            feed.articles().next().unwrap().date,
+            Some(DateTime::<Local>::from(
+                DateTime::parse_from_rfc2822("Thu, 21 Oct 2021 00:00:00 +0000").unwrap()
+            ))
+        );
+    }
+
     #[test]
     fn should_convert_rssfeed_into_feed() {
         let feed = RssFeed {
@@ -152,4 +429,188 @@ mod test {
         let feed = Feed::from(feed);
         assert_eq!(feed.articles.len(), 2);
     }
+
+    #[test]
+    fn should_get_feed_channel() {
+        let feed = Feed::from(
+            feed_rs::parser::parse(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>This Week in Rust</title>
+    <atom:link href="https://this-week-in-rust.org/rss.xml" rel="self" type="application/rss+xml"/>
+    <link>https://this-week-in-rust.org/</link>
+    <description>Handpicked Rust updates, &lt;b&gt;delivered&lt;/b&gt; to your inbox.</description>
+    <language>en</language>
+    <generator>Pelican</generator>
+  </channel>
+</rss>"#
+                    .as_bytes(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            feed.channel(),
+            &Channel {
+                title: Some(String::from("This Week in Rust")),
+                description: Some(String::from(
+                    "Handpicked Rust updates, delivered to your inbox."
+                )),
+                link: Some(String::from("https://this-week-in-rust.org/")),
+                language: Some(String::from("en")),
+                generator: Some(String::from("Pelican")),
+            }
+        );
+    }
+
+    #[test]
+    fn should_get_media_duration() {
+        let feed = feed_rs::parser::parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:media="http://search.yahoo.com/mrss/">
+  <title>Rust Videos</title>
+  <entry>
+    <id>yt:video:dQw4w9WgXcQ</id>
+    <title>RustConf keynote</title>
+    <link rel="alternate" href="https://www.youtube.com/watch?v=dQw4w9WgXcQ"/>
+    <media:group>
+      <media:title>RustConf keynote</media:title>
+      <media:content url="https://www.youtube.com/v/dQw4w9WgXcQ" type="application/x-shockwave-flash" duration="754"/>
+    </media:group>
+  </entry>
+</feed>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let feed = Feed::from(feed);
+        let article = feed.articles().next().unwrap();
+        assert_eq!(article.duration, Some(StdDuration::from_secs(754)));
+        assert_eq!(
+            article.url.as_str(),
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ"
+        );
+    }
+
+    #[test]
+    fn should_get_enclosures() {
+        let feed = feed_rs::parser::parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Podcast</title>
+    <item>
+      <title>Episode 1</title>
+      <link>https://example.com/episode-1</link>
+      <enclosure url="https://example.com/episode-1.mp3" type="audio/mpeg" length="1024"/>
+    </item>
+  </channel>
+</rss>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let feed = Feed::from(feed);
+        let article = feed.articles().next().unwrap();
+        assert_eq!(
+            article.enclosures,
+            vec![Enclosure {
+                url: String::from("https://example.com/episode-1.mp3"),
+                content_type: Some(String::from("audio/mpeg")),
+            }]
+        );
+    }
+
+    #[test]
+    fn should_get_story_and_comments_from_summary() {
+        let feed = feed_rs::parser::parse(
+            r#"<rss version="2.0"><channel><title>Hacker News: Front Page</title>
+<item>
+  <title>Show HN: tuifeed</title>
+  <description><![CDATA[<p>Article URL: <a href="https://github.com/veeso/tuifeed">https://github.com/veeso/tuifeed</a></p><p>Comments URL: <a href="https://news.ycombinator.com/item?id=1">https://news.ycombinator.com/item?id=1</a></p>]]></description>
+  <link>https://news.ycombinator.com/item?id=1</link>
+</item>
+</channel></rss>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let feed = Feed::from(feed);
+        let article = feed.articles().next().unwrap();
+        assert_eq!(article.url.as_str(), "https://github.com/veeso/tuifeed");
+        assert_eq!(
+            article.comments.as_deref(),
+            Some("https://news.ycombinator.com/item?id=1")
+        );
+    }
+
+    #[test]
+    fn should_get_article_categories() {
+        let feed = feed_rs::parser::parse(
+            r#"<rss version="2.0"><channel><title>This Week in Rust</title>
+<item>
+  <title>This Week in Rust 420</title>
+  <link>https://this-week-in-rust.org/blog/2021/12/01/this-week-in-rust-420/</link>
+  <category>rust</category>
+  <category> Newsletter </category>
+  <category>rust</category>
+</item>
+</channel></rss>"#
+                .as_bytes(),
+        )
+        .unwrap();
+        let feed = Feed::from(feed);
+        assert_eq!(
+            feed.articles().next().unwrap().categories,
+            vec![String::from("rust"), String::from("Newsletter")]
+        );
+    }
+
+    #[test]
+    fn should_limit_feed_articles() {
+        let article = |title: &str, age: Option<i64>| Article {
+            title: Some(title.to_string()),
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::new(),
+            date: age.map(|x| Local::now() - Duration::days(x)),
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            comments: None,
+            categories: Vec::new(),
+            score: None,
+        };
+        let feed = Feed {
+            articles: vec![
+                article("today", Some(0)),
+                article("undated", None),
+                article("last week", Some(7)),
+                article("yesterday", Some(1)),
+            ],
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        let titles = |feed: &Feed| -> Vec<String> {
+            feed.articles()
+                .map(|x| x.title.clone().unwrap_or_default())
+                .collect()
+        };
+        // No limits
+        let limited = feed.clone().limit(&FeedLimits::default());
+        assert_eq!(limited.articles.len(), 4);
+        // Max age
+        let limited = feed.clone().limit(&FeedLimits {
+            max_articles: None,
+            max_age: Some(Duration::days(2)),
+            max_size: None,
+        });
+        assert_eq!(titles(&limited), vec!["today", "undated", "yesterday"]);
+        // Both
+        let limited = feed.limit(&FeedLimits {
+            max_articles: Some(2),
+            max_age: Some(Duration::days(2)),
+            max_size: None,
+        });
+        assert_eq!(titles(&limited), vec!["today", "undated"]);
+    }
 }
@@ -0,0 +1,143 @@
+//! # Podcasts
+//!
+//! Search of podcasts in the iTunes directory
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{FeedError, FeedResult};
+
+use log::{info, warn};
+use serde::Deserialize;
+use std::time::Duration;
+use url::Url;
+
+/// Endpoint of the iTunes search API
+const ITUNES_SEARCH_URL: &str = "https://itunes.apple.com/search";
+/// Max amount of podcasts returned by a search
+const MAX_RESULTS: usize = 25;
+/// Timeout of the search request
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// ## Podcast
+///
+/// A podcast found in the directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Podcast {
+    pub name: String,
+    pub author: Option<String>,
+    /// Url of the podcast feed
+    pub url: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    results: Vec<SearchResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResult {
+    collection_name: Option<String>,
+    artist_name: Option<String>,
+    feed_url: Option<String>,
+}
+
+/// ### search_podcasts
+///
+/// Search the podcasts matching `query` in the iTunes directory
+pub fn search_podcasts(query: &str) -> FeedResult<Vec<Podcast>> {
+    let url = Url::parse_with_params(
+        ITUNES_SEARCH_URL,
+        &[
+            ("media", "podcast"),
+            ("entity", "podcast"),
+            ("limit", MAX_RESULTS.to_string().as_str()),
+            ("term", query.trim()),
+        ],
+    )
+    .map_err(|e| FeedError::Http(e.to_string()))?;
+    info!("searching podcasts matching \"{}\"", query);
+    let response = ureq::get(url.as_str())
+        .timeout(SEARCH_TIMEOUT)
+        .call()
+        .map_err(|err| {
+            warn!("podcast search failed: {}", err);
+            err
+        })?;
+    parse_results(response.into_string()?.as_str())
+}
+
+/// ### parse_results
+///
+/// Parse the response of the search API; results without a feed are skipped
+fn parse_results(response: &str) -> FeedResult<Vec<Podcast>> {
+    let response: SearchResponse =
+        serde_json::from_str(response).map_err(|e| FeedError::Parse(e.to_string()))?;
+    Ok(response
+        .results
+        .into_iter()
+        .filter_map(|x| {
+            Some(Podcast {
+                name: x.collection_name.map(|x| x.trim().to_string())?,
+                author: x.artist_name.map(|x| x.trim().to_string()),
+                url: x.feed_url.filter(|x| !x.trim().is_empty())?,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_parse_search_results() {
+        let response = r#"{
+ "resultCount":3,
+ "results": [
+{"wrapperType":"track", "kind":"podcast", "collectionName":"Rustacean Station", "artistName":"Rustacean Station", "feedUrl":"https://rustacean-station.org/podcast.rss"},
+{"wrapperType":"track", "kind":"podcast", "collectionName":"No feed", "artistName":"Someone"},
+{"wrapperType":"track", "kind":"podcast", "collectionName":"New Rustacean", "feedUrl":"https://newrustacean.com/feed.xml"}]
+}"#;
+        assert_eq!(
+            parse_results(response).unwrap(),
+            vec![
+                Podcast {
+                    name: String::from("Rustacean Station"),
+                    author: Some(String::from("Rustacean Station")),
+                    url: String::from("https://rustacean-station.org/podcast.rss"),
+                },
+                Podcast {
+                    name: String::from("New Rustacean"),
+                    author: None,
+                    url: String::from("https://newrustacean.com/feed.xml"),
+                },
+            ]
+        );
+        assert!(parse_results("<html></html>").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! # Reddit
+//!
+//! Extracts the metadata of Reddit posts from the JSON listing
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{Feed, FeedError, FeedResult};
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Reddit origin; permalinks in listings are relative to it
+const REDDIT_ORIGIN: &str = "https://www.reddit.com";
+
+/// ## PostMetadata
+///
+/// Metadata of a Reddit post which is not provided in the feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostMetadata {
+    pub flair: Option<String>,
+    pub score: i64,
+}
+
+#[derive(Deserialize)]
+struct Listing {
+    data: ListingData,
+}
+
+#[derive(Deserialize)]
+struct ListingData {
+    children: Vec<Child>,
+}
+
+#[derive(Deserialize)]
+struct Child {
+    data: Post,
+}
+
+#[derive(Deserialize)]
+struct Post {
+    permalink: String,
+    link_flair_text: Option<String>,
+    score: i64,
+}
+
+/// ### parse_listing
+///
+/// Parse a Reddit JSON listing, returning the metadata of each post by its url
+pub fn parse_listing(listing: &str) -> FeedResult<HashMap<String, PostMetadata>> {
+    let listing: Listing =
+        serde_json::from_str(listing).map_err(|e| FeedError::Parse(e.to_string()))?;
+    Ok(listing
+        .data
+        .children
+        .into_iter()
+        .map(|x| {
+            (
+                format!("{}{}", REDDIT_ORIGIN, x.data.permalink),
+                PostMetadata {
+                    flair: x
+                        .data
+                        .link_flair_text
+                        .map(|x| x.trim().to_string())
+                        .filter(|x| !x.is_empty()),
+                    score: x.data.score,
+                },
+            )
+        })
+        .collect())
+}
+
+/// ### apply_metadata
+///
+/// Set flair and score of the articles in `feed` found in `metadata`
+pub fn apply_metadata(feed: &mut Feed, metadata: &HashMap<String, PostMetadata>) {
+    for article in feed.articles.iter_mut() {
+        if let Some(post) = metadata.get(article.url.as_str()) {
+            article.flair = post.flair.clone();
+            article.score = Some(post.score);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::feed::{Article, Channel};
+
+    use pretty_assertions::assert_eq;
+
+    const LISTING: &str = r#"{
+  "kind": "Listing",
+  "data": {
+    "after": null,
+    "children": [
+      {
+        "kind": "t3",
+        "data": {
+          "title": "Announcing Rust 1.57",
+          "permalink": "/r/rust/comments/r5l4vw/announcing_rust_157/",
+          "link_flair_text": "📢 announcement",
+          "score": 640
+        }
+      },
+      {
+        "kind": "t3",
+        "data": {
+          "title": "Hey Rustaceans! Got a question?",
+          "permalink": "/r/rust/comments/r3ecgg/hey_rustaceans/",
+          "link_flair_text": null,
+          "score": 12
+        }
+      }
+    ]
+  }
+}"#;
+
+    #[test]
+    fn should_parse_listing() {
+        let metadata = parse_listing(LISTING).ok().unwrap();
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(
+            metadata
+                .get("https://www.reddit.com/r/rust/comments/r5l4vw/announcing_rust_157/")
+                .unwrap(),
+            &PostMetadata {
+                flair: Some(String::from("📢 announcement")),
+                score: 640,
+            }
+        );
+        assert!(parse_listing("<html></html>").is_err());
+    }
+
+    #[test]
+    fn should_apply_metadata() {
+        let article = |url: &str| Article {
+            title: None,
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: url.to_string(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            comments: None,
+            categories: Vec::new(),
+            score: None,
+        };
+        let mut feed = Feed {
+            articles: vec![
+                article("https://www.reddit.com/r/rust/comments/r3ecgg/hey_rustaceans/"),
+                article("https://www.reddit.com/r/rust/comments/aaaaaa/not_in_listing/"),
+            ],
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        apply_metadata(&mut feed, &parse_listing(LISTING).ok().unwrap());
+        assert_eq!(feed.articles[0].flair, None);
+        assert_eq!(feed.articles[0].score, Some(12));
+        assert_eq!(feed.articles[1].score, None);
+    }
+}
@@ -0,0 +1,128 @@
+//! # Releases
+//!
+//! Releases of GitHub and GitLab repositories
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::Feed;
+
+/// Path segments followed by the tag in the urls of releases and tags
+const TAG_PATHS: [&str; 2] = ["/releases/tag/", "/-/tags/"];
+
+/// ### apply_tags
+///
+/// Put the tag of each release among the categories of its article, and in its title
+/// if the release name doesn't mention it. Releases without a name are titled with the tag
+pub fn apply_tags(feed: &mut Feed) {
+    for article in feed.articles.iter_mut() {
+        let tag = match release_tag(article.url.as_str()) {
+            Some(tag) => tag,
+            None => continue,
+        };
+        article.title = match article.title.take() {
+            Some(title) if title.contains(tag.as_str()) => Some(title),
+            Some(title) => Some(format!("{} ({})", title, tag)),
+            None => Some(tag.clone()),
+        };
+        if !article.categories.contains(&tag) {
+            article.categories.insert(0, tag);
+        }
+    }
+}
+
+/// ### release_tag
+///
+/// Get the tag in the url of a release
+fn release_tag(url: &str) -> Option<String> {
+    TAG_PATHS
+        .iter()
+        .find_map(|x| url.split_once(x))
+        .map(|(_, tag)| tag.trim_end_matches('/'))
+        .filter(|x| !x.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::feed::{Article, Channel};
+
+    use pretty_assertions::assert_eq;
+
+    fn make_article(title: Option<&str>, url: &str) -> Article {
+        Article {
+            title: title.map(String::from),
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: url.to_string(),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn should_apply_release_tags() {
+        let mut feed = Feed {
+            articles: vec![
+                make_article(
+                    Some("Rust 1.56.0"),
+                    "https://github.com/rust-lang/rust/releases/tag/1.56.0",
+                ),
+                make_article(
+                    Some("Stable release"),
+                    "https://github.com/owner/repo/releases/tag/v2.0",
+                ),
+                make_article(
+                    None,
+                    "https://gitlab.com/inkscape/inkscape/-/tags/INKSCAPE_1_1",
+                ),
+                make_article(Some("Not a release"), "https://example.com/a"),
+            ],
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        apply_tags(&mut feed);
+        let articles: Vec<(Option<&str>, &[String])> = feed
+            .articles()
+            .map(|x| (x.title.as_deref(), x.categories.as_slice()))
+            .collect();
+        assert_eq!(
+            articles,
+            vec![
+                (Some("Rust 1.56.0"), &[String::from("1.56.0")][..]),
+                (Some("Stable release (v2.0)"), &[String::from("v2.0")][..]),
+                (Some("INKSCAPE_1_1"), &[String::from("INKSCAPE_1_1")][..]),
+                (Some("Not a release"), &[][..]),
+            ]
+        );
+    }
+}
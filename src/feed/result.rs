@@ -43,8 +43,14 @@ pub enum FeedError {
     Parse(String),
     #[error("HTTP request failed: {0}")]
     Http(String),
+    #[error("TLS error: {0}")]
+    Tls(String),
     #[error("I/O error: {0}")]
     Io(String),
+    #[error("Feed is larger than {} KB", .0 / 1024)]
+    TooLarge(usize),
+    #[error("Not available offline, since it has never been fetched")]
+    Offline,
 }
 
 impl From<RequestError> for FeedError {
@@ -0,0 +1,320 @@
+//! # Scrape
+//!
+//! Builds feeds out of web pages without a feed, selecting their articles with CSS selectors
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::{Article, Channel, Feed, FeedError, FeedResult};
+use crate::helpers::strings as str_helpers;
+
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use std::convert::TryFrom;
+use url::Url;
+
+/// ## ScrapeSelectors
+///
+/// CSS selectors describing where the articles are in a web page.
+/// `title`, `link`, `date` and `summary` are looked up inside each element matching `item`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "RawScrapeSelectors")]
+pub struct ScrapeSelectors {
+    pub item: String,
+    /// If unset, the text of the link is used
+    pub title: Option<String>,
+    /// If unset, the first link of the item, or the item itself if it's a link
+    pub link: Option<String>,
+    /// Element with the date, in its `datetime` attribute or in its text
+    pub date: Option<String>,
+    pub summary: Option<String>,
+}
+
+/// ## RawScrapeSelectors
+///
+/// Selectors as written in the configuration, before they're validated
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawScrapeSelectors {
+    item: String,
+    title: Option<String>,
+    link: Option<String>,
+    date: Option<String>,
+    summary: Option<String>,
+}
+
+impl TryFrom<RawScrapeSelectors> for ScrapeSelectors {
+    type Error = String;
+
+    fn try_from(raw: RawScrapeSelectors) -> Result<Self, Self::Error> {
+        let selectors = Self {
+            item: raw.item,
+            title: raw.title,
+            link: raw.link,
+            date: raw.date,
+            summary: raw.summary,
+        };
+        selectors.compile().map_err(|e| e.to_string())?;
+        Ok(selectors)
+    }
+}
+
+/// ## Compiled
+///
+/// Compiled selectors
+struct Compiled {
+    item: Selector,
+    title: Option<Selector>,
+    link: Selector,
+    date: Option<Selector>,
+    summary: Option<Selector>,
+}
+
+impl ScrapeSelectors {
+    /// ### compile
+    ///
+    /// Parse the selectors
+    fn compile(&self) -> FeedResult<Compiled> {
+        let parse = |s: &str| {
+            Selector::parse(s)
+                .map_err(|e| FeedError::Parse(format!("invalid selector `{}`: {:?}", s, e)))
+        };
+        let optional = |s: Option<&String>| s.map(|x| parse(x.as_str())).transpose();
+        Ok(Compiled {
+            item: parse(self.item.as_str())?,
+            title: optional(self.title.as_ref())?,
+            link: parse(self.link.as_deref().unwrap_or("a[href]"))?,
+            date: optional(self.date.as_ref())?,
+            summary: optional(self.summary.as_ref())?,
+        })
+    }
+}
+
+/// ### scrape_page
+///
+/// Build the feed of the web `page` at `url`, selecting its articles with `selectors`.
+/// Items without a link are skipped. Fails if no item is found, since the page has probably changed
+pub fn scrape_page(page: &str, url: &str, selectors: &ScrapeSelectors) -> FeedResult<Feed> {
+    let compiled = selectors.compile()?;
+    let base = Url::parse(url).ok();
+    let html = Html::parse_document(page);
+    let mut articles: Vec<Article> = Vec::new();
+    let mut items = 0;
+    for item in html.select(&compiled.item) {
+        items += 1;
+        let link = match selectors.link {
+            None if item.value().name() == "a" => Some(item),
+            _ => item.select(&compiled.link).next(),
+        };
+        let url = match link
+            .and_then(|x| x.value().attr("href"))
+            .and_then(|x| resolve(base.as_ref(), x))
+        {
+            Some(url) if !articles.iter().any(|x| x.url == url) => url,
+            _ => continue,
+        };
+        let title = compiled
+            .title
+            .as_ref()
+            .and_then(|x| item.select(x).next())
+            .or(link)
+            .map(|x| str_helpers::sanitize_line(x.inner_html().as_str()))
+            .filter(|x| !x.is_empty());
+        let date = compiled
+            .date
+            .as_ref()
+            .and_then(|x| item.select(x).next())
+            .and_then(|x| {
+                x.value()
+                    .attr("datetime")
+                    .map(str::to_string)
+                    .or_else(|| Some(text(x)))
+            })
+            .and_then(|x| parse_date(x.as_str()));
+        let (summary, links) = compiled
+            .summary
+            .as_ref()
+            .and_then(|x| item.select(x).next())
+            .map(|x| str_helpers::number_links(x.inner_html().as_str()))
+            .unwrap_or_default();
+        articles.push(Article {
+            title,
+            authors: Vec::new(),
+            summary: str_helpers::sanitize_text(summary.as_str()),
+            links,
+            url,
+            comments: None,
+            categories: Vec::new(),
+            date,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        });
+    }
+    if items == 0 {
+        return Err(FeedError::Parse(format!(
+            "no element matches `{}`",
+            selectors.item
+        )));
+    }
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|x| html.select(&x).next())
+        .map(|x| str_helpers::sanitize_line(x.inner_html().as_str()));
+    let channel = Channel {
+        title,
+        link: Some(url.to_string()),
+        ..Channel::default()
+    };
+    Ok(Feed {
+        articles,
+        moved_to: None,
+        channel: channel.non_empty(),
+    })
+}
+
+/// ### text
+///
+/// Get the text of `element`
+fn text(element: ElementRef) -> String {
+    element.text().collect::<Vec<&str>>().join(" ")
+}
+
+/// ### resolve
+///
+/// Resolve `href` against the url of the page
+fn resolve(base: Option<&Url>, href: &str) -> Option<String> {
+    let href = href.trim();
+    match base {
+        Some(base) => base.join(href).ok().map(|x| x.to_string()),
+        None => Url::parse(href).ok().map(|x| x.to_string()),
+    }
+}
+
+/// ### parse_date
+///
+/// Parse a date written as RFC 3339, RFC 2822 or `YYYY-MM-DD`
+fn parse_date(s: &str) -> Option<DateTime<Local>> {
+    let s = s.trim();
+    DateTime::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::parse_from_rfc2822(s))
+        .map(DateTime::<Local>::from)
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|x| Local.from_local_date(&x).single())
+                .map(|x| x.and_hms(0, 0, 0))
+        })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Example &amp; Co. news</title></head>
+<body>
+<ul class="news">
+  <li class="post">
+    <h2>Release 1.0</h2>
+    <a href="/news/release-1.0">Read more</a>
+    <time datetime="2021-11-20T10:00:00+01:00">November 20th</time>
+    <p class="excerpt">The <b>first</b> stable release</p>
+  </li>
+  <li class="post">
+    <h2>Beta</h2>
+    <a href="https://example.org/beta">Read more</a>
+    <time>2021-10-01</time>
+  </li>
+  <li class="post"><h2>No link</h2></li>
+</ul>
+</body>
+</html>"#;
+
+    fn selectors(item: &str) -> ScrapeSelectors {
+        ScrapeSelectors {
+            item: item.to_string(),
+            title: Some(String::from("h2")),
+            link: None,
+            date: Some(String::from("time")),
+            summary: Some(String::from(".excerpt")),
+        }
+    }
+
+    #[test]
+    fn should_scrape_page() {
+        let feed = scrape_page(PAGE, "https://example.com/news/", &selectors("li.post")).unwrap();
+        assert_eq!(feed.channel().title.as_deref(), Some("Example & Co. news"));
+        assert_eq!(
+            feed.channel().link.as_deref(),
+            Some("https://example.com/news/")
+        );
+        let articles: Vec<&Article> = feed.articles().collect();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].title.as_deref(), Some("Release 1.0"));
+        assert_eq!(articles[0].url, "https://example.com/news/release-1.0");
+        assert_eq!(articles[0].summary, "The first stable release");
+        assert_eq!(
+            articles[0].date.unwrap(),
+            DateTime::parse_from_rfc3339("2021-11-20T10:00:00+01:00").unwrap()
+        );
+        assert_eq!(articles[1].url, "https://example.org/beta");
+        assert_eq!(
+            articles[1].date.unwrap().naive_local().date(),
+            NaiveDate::from_ymd(2021, 10, 1)
+        );
+    }
+
+    #[test]
+    fn should_use_item_links() {
+        let selectors = ScrapeSelectors {
+            item: String::from("li.post a"),
+            title: None,
+            link: None,
+            date: None,
+            summary: None,
+        };
+        let feed = scrape_page(PAGE, "https://example.com/news/", &selectors).unwrap();
+        let titles: Vec<Option<&str>> = feed.articles().map(|x| x.title.as_deref()).collect();
+        assert_eq!(titles, vec![Some("Read more"), Some("Read more")]);
+    }
+
+    #[test]
+    fn should_fail_scraping_page_without_items() {
+        assert!(scrape_page(PAGE, "https://example.com/", &selectors("article")).is_err());
+    }
+
+    #[test]
+    fn should_not_deserialize_invalid_selectors() {
+        assert!(toml::de::from_str::<ScrapeSelectors>("item = \"li[\"").is_err());
+        assert!(toml::de::from_str::<ScrapeSelectors>("item = \"li.post\"").is_ok());
+    }
+}
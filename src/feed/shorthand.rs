@@ -0,0 +1,294 @@
+//! # Shorthand
+//!
+//! Expands shorthand sources (e.g. `youtube:@channel`) into feed urls
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use regex::Regex;
+
+/// Prefix of YouTube channel sources
+const YOUTUBE_PREFIX: &str = "youtube:";
+/// Prefix of Reddit sources
+const REDDIT_PREFIX: &str = "reddit:";
+/// Prefix of GitHub repository sources
+const GITHUB_PREFIX: &str = "github:";
+/// Prefix of GitLab project sources
+const GITLAB_PREFIX: &str = "gitlab:";
+
+lazy_static! {
+    /// Matches YouTube channel ids
+    static ref YOUTUBE_CHANNEL_ID_REGEX: Regex = Regex::new(r"^UC[0-9A-Za-z_-]{22}$").unwrap();
+    /**
+     * Matches the channel id in a YouTube channel page
+     *
+     * - group 1: channel id
+     */
+    static ref YOUTUBE_CHANNEL_PAGE_REGEX: Regex = Regex::new(
+        r#"(?:<link rel="canonical" href="https://www\.youtube\.com/channel/|"externalId":"|"channelId":")(UC[0-9A-Za-z_-]{22})"#
+    )
+    .unwrap();
+}
+
+/// ## Shorthand
+///
+/// Describes how to get the feed url of a source
+#[derive(Debug, PartialEq, Eq)]
+pub enum Shorthand {
+    /// The source is already a feed url, or it expands to this url
+    Url(String),
+    /// The source is the handle of a YouTube channel; the id of the channel must be looked up
+    /// in its page, located at the provided url
+    YoutubeHandle(String),
+}
+
+/// ### expand
+///
+/// Expand `source` into the feed url or into the page to look up to find it
+pub fn expand(source: &str) -> Shorthand {
+    if let Some(path) = source.strip_prefix(REDDIT_PREFIX) {
+        return Shorthand::Url(reddit_url(path, "rss"));
+    }
+    if let Some(url) = source
+        .strip_prefix(GITHUB_PREFIX)
+        .and_then(github_url)
+        .or_else(|| source.strip_prefix(GITLAB_PREFIX).and_then(gitlab_url))
+    {
+        return Shorthand::Url(url);
+    }
+    match source.strip_prefix(YOUTUBE_PREFIX).map(str::trim) {
+        Some(channel) if YOUTUBE_CHANNEL_ID_REGEX.is_match(channel) => {
+            Shorthand::Url(youtube_channel_feed(channel))
+        }
+        Some(handle) => Shorthand::YoutubeHandle(format!(
+            "https://www.youtube.com/@{}",
+            handle.trim_start_matches('@')
+        )),
+        None => Shorthand::Url(source.to_string()),
+    }
+}
+
+/// ### reddit_listing
+///
+/// Get the url of the JSON listing of `source`, if it's a Reddit source
+pub fn reddit_listing(source: &str) -> Option<String> {
+    source
+        .strip_prefix(REDDIT_PREFIX)
+        .map(|path| reddit_url(path, "json"))
+}
+
+/// ### reddit_url
+///
+/// Make the url of the listing at `path` (e.g. `r/rust/top?t=week`) in the format `ext` (`rss` or `json`).
+/// The `r/` prefix can be omitted for subreddits
+fn reddit_url(path: &str, ext: &str) -> String {
+    let path = path.trim().trim_start_matches('/');
+    let (path, query) = match path.split_once('?') {
+        Some((path, query)) => (path, format!("?{}", query)),
+        None => (path, String::new()),
+    };
+    let path = path.trim_end_matches('/');
+    let prefix = match path.starts_with("r/") || path.starts_with("u/") || path.starts_with("user/")
+    {
+        true => "",
+        false => "r/",
+    };
+    format!("https://www.reddit.com/{}{}.{}{}", prefix, path, ext, query)
+}
+
+/// ### is_release_source
+///
+/// Returns whether `source` follows the releases or the tags of a GitHub or GitLab repository
+pub fn is_release_source(source: &str) -> bool {
+    (source.starts_with(GITHUB_PREFIX) || source.starts_with(GITLAB_PREFIX)) && is_shorthand(source)
+}
+
+/// ### github_url
+///
+/// Make the feed url of `path`: `owner/repo`, followed by `/releases` (default) or `/tags`
+fn github_url(path: &str) -> Option<String> {
+    let segments: Vec<&str> = path
+        .trim()
+        .trim_matches('/')
+        .split('/')
+        .filter(|x| !x.is_empty())
+        .collect();
+    match segments.as_slice() {
+        [owner, repo] | [owner, repo, "releases"] => Some(format!(
+            "https://github.com/{}/{}/releases.atom",
+            owner, repo
+        )),
+        [owner, repo, "tags"] => Some(format!("https://github.com/{}/{}/tags.atom", owner, repo)),
+        _ => None,
+    }
+}
+
+/// ### gitlab_url
+///
+/// Make the feed url of `path`: the path of the project, optionally on a self-hosted instance
+/// (e.g. `gitlab.gnome.org/GNOME/gtk`), followed by `/releases` (default) or `/tags`.
+/// GitLab publishes only the feed of the tags, which carry the release notes
+fn gitlab_url(path: &str) -> Option<String> {
+    let path = path.trim().trim_matches('/');
+    let path = path
+        .strip_suffix("/releases")
+        .or_else(|| path.strip_suffix("/tags"))
+        .unwrap_or(path);
+    let (host, project) = match path.split_once('/') {
+        Some((host, project)) if host.contains('.') => (host, project),
+        _ => ("gitlab.com", path),
+    };
+    match project.split('/').filter(|x| !x.is_empty()).count() {
+        0 | 1 => None,
+        _ => Some(format!("https://{}/{}/-/tags?format=atom", host, project)),
+    }
+}
+
+/// ### is_shorthand
+///
+/// Returns whether `source` is a shorthand rather than a feed url
+pub fn is_shorthand(source: &str) -> bool {
+    expand(source) != Shorthand::Url(source.to_string())
+}
+
+/// ### youtube_channel_feed
+///
+/// Get the feed url of the YouTube channel with `id`
+pub fn youtube_channel_feed(id: &str) -> String {
+    format!("https://www.youtube.com/feeds/videos.xml?channel_id={}", id)
+}
+
+/// ### find_youtube_channel_id
+///
+/// Find the channel id in the html of a YouTube channel page
+pub fn find_youtube_channel_id(page: &str) -> Option<&str> {
+    YOUTUBE_CHANNEL_PAGE_REGEX
+        .captures(page)
+        .and_then(|x| x.get(1))
+        .map(|x| x.as_str())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_expand_youtube_channel_id() {
+        assert_eq!(
+            expand("youtube:UCaYhcUwRBNscFNUKTjgPFiA"),
+            Shorthand::Url(String::from(
+                "https://www.youtube.com/feeds/videos.xml?channel_id=UCaYhcUwRBNscFNUKTjgPFiA"
+            ))
+        );
+    }
+
+    #[test]
+    fn should_expand_youtube_handle() {
+        assert_eq!(
+            expand("youtube:@rustvideos"),
+            Shorthand::YoutubeHandle(String::from("https://www.youtube.com/@rustvideos"))
+        );
+        assert_eq!(
+            expand("youtube:rustvideos"),
+            Shorthand::YoutubeHandle(String::from("https://www.youtube.com/@rustvideos"))
+        );
+    }
+
+    #[test]
+    fn should_expand_reddit_sources() {
+        assert_eq!(
+            expand("reddit:r/rust"),
+            Shorthand::Url(String::from("https://www.reddit.com/r/rust.rss"))
+        );
+        assert_eq!(
+            expand("reddit:rust/top?t=week"),
+            Shorthand::Url(String::from("https://www.reddit.com/r/rust/top.rss?t=week"))
+        );
+        assert_eq!(
+            expand("reddit:/u/spez/"),
+            Shorthand::Url(String::from("https://www.reddit.com/u/spez.rss"))
+        );
+        assert_eq!(
+            reddit_listing("reddit:r/rust+programming/new").as_deref(),
+            Some("https://www.reddit.com/r/rust+programming/new.json")
+        );
+        assert!(reddit_listing("https://www.reddit.com/r/rust.rss").is_none());
+    }
+
+    #[test]
+    fn should_expand_release_sources() {
+        assert_eq!(
+            expand("github:rust-lang/rust"),
+            Shorthand::Url(String::from(
+                "https://github.com/rust-lang/rust/releases.atom"
+            ))
+        );
+        assert_eq!(
+            expand("github:rust-lang/rust/tags"),
+            Shorthand::Url(String::from("https://github.com/rust-lang/rust/tags.atom"))
+        );
+        assert_eq!(
+            expand("gitlab:inkscape/inkscape/releases"),
+            Shorthand::Url(String::from(
+                "https://gitlab.com/inkscape/inkscape/-/tags?format=atom"
+            ))
+        );
+        assert_eq!(
+            expand("gitlab:gitlab.gnome.org/GNOME/gtk"),
+            Shorthand::Url(String::from(
+                "https://gitlab.gnome.org/GNOME/gtk/-/tags?format=atom"
+            ))
+        );
+        assert!(is_release_source("github:rust-lang/rust/releases"));
+        assert!(!is_release_source("github:rust-lang"));
+        assert!(!is_release_source("reddit:r/rust"));
+    }
+
+    #[test]
+    fn should_not_expand_urls() {
+        assert_eq!(
+            expand("https://blog.rust-lang.org/feed.xml"),
+            Shorthand::Url(String::from("https://blog.rust-lang.org/feed.xml"))
+        );
+        assert!(!is_shorthand("https://blog.rust-lang.org/feed.xml"));
+        assert!(is_shorthand("youtube:@rustvideos"));
+    }
+
+    #[test]
+    fn should_find_youtube_channel_id() {
+        assert_eq!(
+            find_youtube_channel_id(
+                r#"<html><head><link rel="canonical" href="https://www.youtube.com/channel/UCaYhcUwRBNscFNUKTjgPFiA"></head></html>"#
+            ),
+            Some("UCaYhcUwRBNscFNUKTjgPFiA")
+        );
+        assert_eq!(
+            find_youtube_channel_id(r#"{"metadata":{"externalId":"UCaYhcUwRBNscFNUKTjgPFiA"}}"#),
+            Some("UCaYhcUwRBNscFNUKTjgPFiA")
+        );
+        assert_eq!(find_youtube_channel_id("<html></html>"), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! # Clipboard
+//!
+//! Clipboard helpers
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use arboard::Clipboard as SystemClipboard;
+use std::env;
+use std::io::{stdout, Write};
+
+/// ## Clipboard
+///
+/// Copies text to the system clipboard, or to the terminal clipboard through OSC 52
+/// when the system clipboard is not available (e.g. in SSH sessions). Text is pasted from the system clipboard only.
+/// The system clipboard must be kept alive, since on X11 the copied text is lost when it's dropped
+#[derive(Default)]
+pub struct Clipboard {
+    system: Option<SystemClipboard>,
+}
+
+impl Clipboard {
+    /// ### copy
+    ///
+    /// Copy `text` to clipboard
+    pub fn copy(&mut self, text: &str) -> Result<(), String> {
+        if !is_ssh_session() {
+            if self.system.is_none() {
+                self.system = SystemClipboard::new().ok();
+            }
+            if let Some(clipboard) = self.system.as_mut() {
+                if clipboard.set_text(text.to_string()).is_ok() {
+                    return Ok(());
+                }
+            }
+        }
+        // Fallback to OSC 52
+        let mut out = stdout();
+        out.write_all(osc52(text).as_bytes())
+            .and_then(|_| out.flush())
+            .map_err(|e| format!("Could not copy to clipboard: {}", e))
+    }
+
+    /// ### paste
+    ///
+    /// Get the text in the system clipboard. The terminal clipboard can't be read through OSC 52,
+    /// so pasting is not available in SSH sessions
+    pub fn paste(&mut self) -> Result<String, String> {
+        if is_ssh_session() {
+            return Err(String::from("Clipboard is not available in SSH sessions"));
+        }
+        if self.system.is_none() {
+            self.system = Some(
+                SystemClipboard::new().map_err(|e| format!("Clipboard is not available: {}", e))?,
+            );
+        }
+        match self.system.as_mut() {
+            Some(clipboard) => clipboard
+                .get_text()
+                .map_err(|e| format!("Could not paste from clipboard: {}", e)),
+            None => Err(String::from("Clipboard is not available")),
+        }
+    }
+}
+
+/// ### osc52
+///
+/// Make the OSC 52 escape sequence which sets the terminal clipboard to `text`
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64::encode(text))
+}
+
+/// ### is_ssh_session
+///
+/// Returns whether tuifeed is running in a SSH session
+fn is_ssh_session() -> bool {
+    env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_make_osc52_sequence() {
+        assert_eq!(
+            osc52("https://example.com").as_str(),
+            "\x1b]52;c;aHR0cHM6Ly9leGFtcGxlLmNvbQ==\x07"
+        );
+    }
+}
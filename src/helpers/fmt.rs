@@ -25,13 +25,104 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Duration, Local, Locale, NaiveDate};
+use std::convert::TryFrom;
+use std::env;
+use std::time::Duration as StdDuration;
+
+/// Average reading speed used to estimate reading time
+const WORDS_PER_MINUTE: usize = 200;
+
+lazy_static! {
+    /// Locale used to format dates, taken from the environment
+    static ref LOCALE: Locale = locale_from_env();
+}
 
 /// ### format_datetime
 ///
-/// Format datetime according to provided format
+/// Format datetime according to provided format, using the locale set in `LC_TIME`
 pub fn format_datetime(datetime: DateTime<Local>, fmt: &str) -> String {
-    datetime.format(fmt).to_string()
+    datetime.format_localized(fmt, *LOCALE).to_string()
+}
+
+/// ### format_day
+///
+/// Format the day of `datetime` as `Today` or `Yesterday` relative to `today`, or as `%Y-%m-%d`
+pub fn format_day(datetime: DateTime<Local>, today: NaiveDate) -> String {
+    let day = datetime.naive_local().date();
+    if day == today {
+        String::from("Today")
+    } else if today.pred_opt() == Some(day) {
+        String::from("Yesterday")
+    } else {
+        day.format("%Y-%m-%d").to_string()
+    }
+}
+
+/// ### locale_from_env
+///
+/// Get the time locale from the environment (`LC_ALL`, `LC_TIME` or `LANG`).
+/// Falls back to POSIX locale if not set or not supported
+fn locale_from_env() -> Locale {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|x| env::var(x).ok())
+        .find(|x| !x.is_empty())
+        .map(|x| parse_locale(x.as_str()))
+        .unwrap_or(Locale::POSIX)
+}
+
+/// ### parse_locale
+///
+/// Parse a locale in the `language_TERRITORY.codeset@modifier` form (e.g. `it_IT.UTF-8`)
+fn parse_locale(s: &str) -> Locale {
+    let name = s.split(['.', '@']).next().unwrap_or(s);
+    Locale::try_from(name).unwrap_or(Locale::POSIX)
+}
+
+/// ### format_elapsed
+///
+/// Format elapsed time in a compact human readable form, using the largest unit (e.g. `3h`).
+/// Elapsed time shorter than a minute is formatted as `<1m`
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let units = [
+        (Duration::weeks(52), "y"),
+        (Duration::weeks(1), "w"),
+        (Duration::days(1), "d"),
+        (Duration::hours(1), "h"),
+        (Duration::minutes(1), "m"),
+    ];
+    units
+        .iter()
+        .find(|(unit, _)| elapsed >= *unit)
+        .map(|(unit, suffix)| format!("{}{}", elapsed.num_seconds() / unit.num_seconds(), suffix))
+        .unwrap_or_else(|| String::from("<1m"))
+}
+
+/// ### format_duration
+///
+/// Format the duration of a media as `m:ss`, or as `h:mm:ss` if it lasts one hour or more
+pub fn format_duration(duration: StdDuration) -> String {
+    let seconds = duration.as_secs();
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, (seconds % 3600) / 60, seconds % 60),
+    }
+}
+
+/// ### format_reading_time
+///
+/// Format the word count of a text and the time required to read it, at `WORDS_PER_MINUTE`
+/// (e.g. `800 words · 4 min read`). Reading time is always at least one minute
+pub fn format_reading_time(text: &str) -> String {
+    let words = text.split_whitespace().count();
+    let minutes = words.div_ceil(WORDS_PER_MINUTE).max(1);
+    format!(
+        "{} {} · {} min read",
+        words,
+        if words == 1 { "word" } else { "words" },
+        minutes
+    )
 }
 
 #[cfg(test)]
@@ -39,17 +130,65 @@ mod test {
 
     use super::*;
 
+    use chrono::TimeZone;
     use pretty_assertions::assert_eq;
     use std::{
         ops::Add,
-        time::{Duration, SystemTime},
+        time::{Duration as StdDuration, SystemTime},
     };
 
     #[test]
     fn should_format_datetime() {
         let datetime: DateTime<Local> = SystemTime::from(SystemTime::UNIX_EPOCH)
-            .add(Duration::from_secs(36000))
+            .add(StdDuration::from_secs(36000))
             .into();
         assert_eq!(format_datetime(datetime, "%Y-%m-%d"), "1970-01-01");
     }
+
+    #[test]
+    fn should_format_day() {
+        let today = NaiveDate::from_ymd(2021, 11, 22);
+        let at = |d: u32| Local.ymd(2021, 11, d).and_hms(12, 0, 0);
+        assert_eq!(format_day(at(22), today), "Today");
+        assert_eq!(format_day(at(21), today), "Yesterday");
+        assert_eq!(format_day(at(20), today), "2021-11-20");
+    }
+
+    #[test]
+    fn should_parse_locale() {
+        assert_eq!(parse_locale("it_IT.UTF-8"), Locale::it_IT);
+        assert_eq!(parse_locale("de_DE@euro"), Locale::de_DE);
+        assert_eq!(parse_locale("fr_BE"), Locale::fr_BE);
+        assert_eq!(parse_locale("C"), Locale::POSIX);
+        assert_eq!(parse_locale("klingon"), Locale::POSIX);
+    }
+
+    #[test]
+    fn should_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::seconds(30)), "<1m");
+        assert_eq!(format_elapsed(Duration::minutes(5)), "5m");
+        assert_eq!(format_elapsed(Duration::minutes(190)), "3h");
+        assert_eq!(format_elapsed(Duration::hours(50)), "2d");
+        assert_eq!(format_elapsed(Duration::days(15)), "2w");
+        assert_eq!(format_elapsed(Duration::weeks(110)), "2y");
+    }
+
+    #[test]
+    fn should_format_duration() {
+        assert_eq!(format_duration(StdDuration::from_secs(42)), "0:42");
+        assert_eq!(format_duration(StdDuration::from_secs(754)), "12:34");
+        assert_eq!(format_duration(StdDuration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn should_format_reading_time() {
+        assert_eq!(format_reading_time(""), "0 words · 1 min read");
+        assert_eq!(format_reading_time("hello"), "1 word · 1 min read");
+        assert_eq!(
+            format_reading_time(" hello\n  world "),
+            "2 words · 1 min read"
+        );
+        let text = vec!["word"; 840].join(" ");
+        assert_eq!(format_reading_time(text.as_str()), "840 words · 5 min read");
+    }
 }
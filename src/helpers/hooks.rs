@@ -0,0 +1,145 @@
+//! # Hooks
+//!
+//! Hook commands execution
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::notify as notify_helpers;
+use crate::feed::Article;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// ### run_article_hook
+///
+/// Run the hook `command` for `event` on the `article` of `source`.
+/// The article is described in the environment and written to the standard input as JSON
+pub fn run_article_hook(
+    command: &str,
+    event: &str,
+    source: &str,
+    article: &Article,
+) -> Result<(), String> {
+    run_hook(
+        command,
+        event,
+        article_env(source, article).as_slice(),
+        notify_helpers::article_json(source, article),
+    )
+}
+
+/// ### run_refresh_hook
+///
+/// Run the hook `command` for `event` once `sources` sources have been fetched
+pub fn run_refresh_hook(command: &str, event: &str, sources: usize) -> Result<(), String> {
+    run_hook(
+        command,
+        event,
+        &[("TUIFEED_SOURCES", sources.to_string())],
+        String::new(),
+    )
+}
+
+/// ### run_hook
+///
+/// Run the hook `command` for `event` in background, with the variables in `env` and writing `stdin` to its
+/// standard input. `TUIFEED_EVENT` is set to `event`
+pub fn run_hook(
+    command: &str,
+    event: &str,
+    env: &[(&str, String)],
+    stdin: String,
+) -> Result<(), String> {
+    let mut args = command.split_whitespace();
+    let program = args.next().ok_or_else(|| String::from("Empty command"))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .env("TUIFEED_EVENT", event)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Could not start hook `{}`: {}", program, e))?;
+    // Write the input and reap the process once it exits, without waiting for it
+    std::thread::spawn(move || {
+        if let Some(mut input) = child.stdin.take() {
+            let _ = input.write_all(stdin.as_bytes());
+        }
+        child.wait()
+    });
+    Ok(())
+}
+
+/// ### article_env
+///
+/// Get the environment variables describing the `article` of `source`
+fn article_env(source: &str, article: &Article) -> Vec<(&'static str, String)> {
+    vec![
+        ("TUIFEED_SOURCE", source.to_string()),
+        ("TUIFEED_TITLE", article.title.clone().unwrap_or_default()),
+        ("TUIFEED_URL", article.url.clone()),
+        ("TUIFEED_AUTHORS", article.authors.join(", ")),
+        (
+            "TUIFEED_DATE",
+            article.date.map(|x| x.to_rfc3339()).unwrap_or_default(),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_make_article_env() {
+        let article = Article {
+            title: Some(String::from("Rust 1.56 is out")),
+            authors: vec![String::from("alice"), String::from("bob")],
+            summary: String::new(),
+            links: Vec::new(),
+            url: String::from("https://example.com/a"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        let env = article_env("blog", &article);
+        assert_eq!(env[0], ("TUIFEED_SOURCE", String::from("blog")));
+        assert_eq!(env[3], ("TUIFEED_AUTHORS", String::from("alice, bob")));
+        assert_eq!(env[4], ("TUIFEED_DATE", String::new()));
+    }
+
+    #[test]
+    fn should_not_run_empty_hook() {
+        assert!(run_hook("  ", "mark_read", &[], String::new()).is_err());
+    }
+}
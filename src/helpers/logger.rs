@@ -0,0 +1,151 @@
+//! # Logger
+//!
+//! Logging to a rotating log file
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use log::LevelFilter;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Size after which the log file is rotated
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+/// Amount of rotated log files to keep
+const MAX_LOG_FILES: usize = 3;
+
+/// ### init_logger
+///
+/// Write logs with level up to `level` to the file at `p`, rotating it if it's too big.
+/// Panics are logged too
+pub fn init_logger(p: &Path, level: LevelFilter) -> Result<(), String> {
+    if level == LevelFilter::Off {
+        return Ok(());
+    }
+    rotate_log_file(p, MAX_LOG_SIZE, MAX_LOG_FILES)
+        .map_err(|e| format!("could not rotate log file: {}", e))?;
+    let file = fern::log_file(p).map_err(|e| format!("could not open log file: {}", e))?;
+    fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!(
+                "{} [{}] {}: {}",
+                chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%z"),
+                record.level(),
+                record.target(),
+                message
+            ))
+        })
+        // Dependencies are too verbose below warnings
+        .level(level.min(LevelFilter::Warn))
+        .level_for(env!("CARGO_PKG_NAME"), level)
+        .chain(file)
+        .apply()
+        .map_err(|e| e.to_string())?;
+    log_panics();
+    Ok(())
+}
+
+/// ### log_panics
+///
+/// Log panics before running the default panic hook, since the message is lost in the alternate screen
+fn log_panics() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error!("{}", info);
+        default_hook(info);
+    }));
+}
+
+/// ### rotate_log_file
+///
+/// If the log file at `p` is bigger than `max_size`, move it to `<p>.1`, shifting the older
+/// log files, and keeping at most `max_files` of them
+fn rotate_log_file(p: &Path, max_size: u64, max_files: usize) -> io::Result<()> {
+    match fs::metadata(p) {
+        Ok(metadata) if metadata.len() >= max_size => {}
+        _ => return Ok(()),
+    }
+    for i in (1..max_files).rev() {
+        let rotated = rotated_log_file(p, i);
+        if rotated.exists() {
+            fs::rename(rotated, rotated_log_file(p, i + 1))?;
+        }
+    }
+    fs::rename(p, rotated_log_file(p, 1))
+}
+
+/// ### rotated_log_file
+///
+/// Get path of the `i`-th rotated log file
+fn rotated_log_file(p: &Path, i: usize) -> PathBuf {
+    let mut name = p.as_os_str().to_os_string();
+    name.push(format!(".{}", i));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::helpers::file as file_helpers;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_rotate_log_file() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let log_file = tempdir.path().join("tuifeed.log");
+        // Doesn't exist
+        assert!(rotate_log_file(log_file.as_path(), 4, 2).is_ok());
+        // Too small
+        assert!(file_helpers::write_file(log_file.as_path(), "a").is_ok());
+        assert!(rotate_log_file(log_file.as_path(), 4, 2).is_ok());
+        assert!(log_file.exists());
+        // Rotate
+        assert!(file_helpers::write_file(log_file.as_path(), "first").is_ok());
+        assert!(rotate_log_file(log_file.as_path(), 4, 2).is_ok());
+        assert!(!log_file.exists());
+        assert!(file_helpers::write_file(log_file.as_path(), "second").is_ok());
+        assert!(rotate_log_file(log_file.as_path(), 4, 2).is_ok());
+        assert!(file_helpers::write_file(log_file.as_path(), "third").is_ok());
+        assert!(rotate_log_file(log_file.as_path(), 4, 2).is_ok());
+        assert_eq!(
+            fs::read_to_string(rotated_log_file(log_file.as_path(), 1)).unwrap(),
+            "third"
+        );
+        assert_eq!(
+            fs::read_to_string(rotated_log_file(log_file.as_path(), 2)).unwrap(),
+            "second"
+        );
+        assert!(!rotated_log_file(log_file.as_path(), 3).exists());
+    }
+
+    #[test]
+    fn should_get_rotated_log_file() {
+        assert_eq!(
+            rotated_log_file(Path::new("/tmp/tuifeed.log"), 2),
+            PathBuf::from("/tmp/tuifeed.log.2")
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! # Mail
+//!
+//! Send emails through sendmail or an SMTP server
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::config::{EmailConfig, SmtpConfig};
+
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// ### send_html_email
+///
+/// Send the HTML document `html` to `to`, with `subject`, as described by `config`
+pub fn send_html_email(
+    config: &EmailConfig,
+    to: &str,
+    subject: &str,
+    html: String,
+) -> Result<(), String> {
+    let message = make_message(config, to, subject, html)?;
+    match config.smtp.as_ref() {
+        Some(smtp) => send_smtp(smtp, &message),
+        None => send_sendmail(config.sendmail.as_str(), &message),
+    }
+}
+
+/// ### make_message
+///
+/// Make the HTML message from the sender of `config` to `to`
+fn make_message(
+    config: &EmailConfig,
+    to: &str,
+    subject: &str,
+    html: String,
+) -> Result<Message, String> {
+    let from = config
+        .from
+        .as_deref()
+        .ok_or_else(|| String::from("set the sender address in `email.from`"))?;
+    let from: Mailbox = from
+        .parse()
+        .map_err(|e| format!("invalid sender address \"{}\": {}", from, e))?;
+    let to: Mailbox = to
+        .parse()
+        .map_err(|e| format!("invalid recipient address \"{}\": {}", to, e))?;
+    Message::builder()
+        .from(from)
+        .to(to)
+        .subject(subject)
+        .header(ContentType::TEXT_HTML)
+        .body(html)
+        .map_err(|e| format!("could not make message: {}", e))
+}
+
+/// ### send_smtp
+///
+/// Send `message` through the SMTP server
+fn send_smtp(smtp: &SmtpConfig, message: &Message) -> Result<(), String> {
+    let builder = match smtp.tls {
+        true => SmtpTransport::relay(smtp.server.as_str()),
+        false => SmtpTransport::starttls_relay(smtp.server.as_str()),
+    };
+    let mut builder = builder.map_err(|e| format!("{}: {}", smtp.server, e))?;
+    if let Some(port) = smtp.port {
+        builder = builder.port(port);
+    }
+    if let Some(user) = smtp.user.as_ref() {
+        builder = builder.credentials(Credentials::new(
+            user.clone(),
+            smtp.password.clone().unwrap_or_default(),
+        ));
+    }
+    builder
+        .build()
+        .send(message)
+        .map(|_| ())
+        .map_err(|e| format!("could not send email through {}: {}", smtp.server, e))
+}
+
+/// ### send_sendmail
+///
+/// Send `message` writing it to the standard input of the sendmail `command`
+fn send_sendmail(command: &str, message: &Message) -> Result<(), String> {
+    let mut args = command.split_whitespace();
+    let program = args.next().ok_or_else(|| String::from("Empty command"))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| format!("could not run `{}`: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(message.formatted().as_slice())
+            .map_err(|e| format!("could not write to `{}`: {}", program, e))?;
+    }
+    let status = child
+        .wait()
+        .map_err(|e| format!("`{}` failed: {}", program, e))?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(format!("`{}` exited with status {}", program, status)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_make_html_message() {
+        let config = EmailConfig {
+            from: Some(String::from("tuifeed <tuifeed@example.com>")),
+            ..EmailConfig::default()
+        };
+        let message = make_message(
+            &config,
+            "me@example.com",
+            "tuifeed digest",
+            String::from("<h1>Hello</h1>"),
+        )
+        .unwrap();
+        let message = String::from_utf8(message.formatted()).unwrap();
+        assert!(message.contains("To: me@example.com\r\n"));
+        assert!(message.contains("Subject: tuifeed digest\r\n"));
+        assert!(message.contains("Content-Type: text/html; charset=utf-8\r\n"));
+    }
+
+    #[test]
+    fn should_not_make_message_without_sender() {
+        assert!(make_message(
+            &EmailConfig::default(),
+            "me@example.com",
+            "tuifeed digest",
+            String::new()
+        )
+        .is_err());
+        let config = EmailConfig {
+            from: Some(String::from("tuifeed")),
+            ..EmailConfig::default()
+        };
+        assert!(make_message(&config, "me@example.com", "tuifeed digest", String::new()).is_err());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_send_email_with_sendmail() {
+        let config = EmailConfig {
+            from: Some(String::from("tuifeed@example.com")),
+            sendmail: String::from("cat"),
+            smtp: None,
+        };
+        assert!(send_html_email(&config, "me@example.com", "digest", String::new()).is_ok());
+        let config = EmailConfig {
+            sendmail: String::from("false"),
+            ..config
+        };
+        assert!(send_html_email(&config, "me@example.com", "digest", String::new()).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+//! # Maildir
+//!
+//! Deliver articles as messages into a Maildir, for mail clients such as mutt or notmuch
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::feed::Article;
+
+use chrono::Local;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Deliveries made by this process, making the names of the messages unique
+static DELIVERIES: AtomicUsize = AtomicUsize::new(0);
+
+/// ### deliver_article
+///
+/// Write the `article` of `source` as a new message into the Maildir at `dir`, creating it if necessary.
+/// The message is written into `tmp` first, then moved into `new`, as the Maildir format requires
+pub fn deliver_article(dir: &Path, source: &str, article: &Article) -> Result<(), String> {
+    for subdir in ["tmp", "new", "cur"] {
+        std::fs::create_dir_all(dir.join(subdir))
+            .map_err(|e| format!("could not create maildir {}: {}", dir.display(), e))?;
+    }
+    let name = format!(
+        "{}.P{}Q{}.tuifeed",
+        Local::now().timestamp(),
+        std::process::id(),
+        DELIVERIES.fetch_add(1, Ordering::SeqCst)
+    );
+    let tmp = dir.join("tmp").join(name.as_str());
+    std::fs::write(tmp.as_path(), make_message(source, article))
+        .map_err(|e| format!("could not write {}: {}", tmp.display(), e))?;
+    std::fs::rename(tmp.as_path(), dir.join("new").join(name.as_str()))
+        .map_err(|e| format!("could not deliver {}: {}", tmp.display(), e))
+}
+
+/// ### make_message
+///
+/// Make the message of the `article` of `source`: the subject is the title, the body is the summary followed by
+/// the url, while the `X-Feed-*` headers describe the source
+fn make_message(source: &str, article: &Article) -> String {
+    let date = article.date.unwrap_or_else(Local::now);
+    let mut headers = vec![
+        (
+            "From",
+            format!("{} <tuifeed@localhost>", encode_header(source)),
+        ),
+        ("Date", date.to_rfc2822()),
+        (
+            "Subject",
+            encode_header(article.title.as_deref().unwrap_or(article.url.as_str())),
+        ),
+        ("X-Feed-Source", encode_header(source)),
+        ("X-Feed-Url", article.url.clone()),
+    ];
+    if !article.authors.is_empty() {
+        headers.push((
+            "X-Feed-Authors",
+            encode_header(article.authors.join(", ").as_str()),
+        ));
+    }
+    if !article.categories.is_empty() {
+        headers.push((
+            "X-Feed-Categories",
+            encode_header(article.categories.join(", ").as_str()),
+        ));
+    }
+    headers.push(("MIME-Version", String::from("1.0")));
+    headers.push(("Content-Type", String::from("text/plain; charset=utf-8")));
+    headers.push(("Content-Transfer-Encoding", String::from("8bit")));
+    let mut message: String = headers
+        .into_iter()
+        .map(|(name, value)| format!("{}: {}\n", name, value))
+        .collect();
+    message.push('\n');
+    if !article.summary.trim().is_empty() {
+        message.push_str(article.summary.trim());
+        message.push_str("\n\n");
+    }
+    message.push_str(article.url.as_str());
+    message.push('\n');
+    message
+}
+
+/// ### encode_header
+///
+/// Make `value` fit into a header: line breaks are replaced with spaces, then non-ASCII values are encoded as
+/// RFC 2047 words
+fn encode_header(value: &str) -> String {
+    let value = value.replace(&['\r', '\n'][..], " ");
+    match value.is_ascii() {
+        true => value,
+        false => format!("=?UTF-8?B?{}?=", base64::encode(value)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    fn make_article() -> Article {
+        Article {
+            title: Some(String::from("Rust 1.56 is out")),
+            authors: vec![String::from("The Rust Team")],
+            summary: String::from("Rust 2021 is stable\n"),
+            links: Vec::new(),
+            url: String::from("https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html"),
+            comments: None,
+            categories: vec![String::from("release")],
+            date: Local.timestamp_opt(1634800000, 0).single(),
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn should_make_message() {
+        let message = make_message("Rust Blog", &make_article());
+        let (headers, body) = message.split_once("\n\n").unwrap();
+        assert!(headers.starts_with("From: Rust Blog <tuifeed@localhost>\nDate: "));
+        assert!(headers.contains("\nSubject: Rust 1.56 is out\n"));
+        assert!(headers.contains("\nX-Feed-Source: Rust Blog\n"));
+        assert!(headers
+            .contains("\nX-Feed-Url: https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html\n"));
+        assert!(headers.contains("\nX-Feed-Authors: The Rust Team\n"));
+        assert!(headers.contains("\nX-Feed-Categories: release\n"));
+        assert_eq!(
+            body,
+            "Rust 2021 is stable\n\nhttps://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html\n"
+        );
+    }
+
+    #[test]
+    fn should_encode_headers() {
+        assert_eq!(encode_header("Rust\nBlog").as_str(), "Rust Blog");
+        assert_eq!(encode_header("Perché").as_str(), "=?UTF-8?B?UGVyY2jDqQ==?=");
+    }
+
+    #[test]
+    fn should_deliver_articles() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let maildir = tempdir.path().join("feeds");
+        assert!(deliver_article(maildir.as_path(), "Rust Blog", &make_article()).is_ok());
+        assert!(deliver_article(maildir.as_path(), "Rust Blog", &make_article()).is_ok());
+        assert_eq!(std::fs::read_dir(maildir.join("new")).unwrap().count(), 2);
+        assert_eq!(std::fs::read_dir(maildir.join("tmp")).unwrap().count(), 0);
+        assert!(maildir.join("cur").is_dir());
+    }
+}
@@ -26,9 +26,17 @@
  * SOFTWARE.
  */
 // -- helpers
+pub mod clipboard;
 pub mod file;
 pub mod fmt;
+pub mod hooks;
+pub mod logger;
+pub mod mail;
+pub mod maildir;
+pub mod notify;
 pub mod open;
 pub mod path;
+pub mod speech;
 pub mod strings;
 pub mod ui;
+pub mod urls;
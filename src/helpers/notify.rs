@@ -0,0 +1,254 @@
+//! # Notify
+//!
+//! Notification helpers
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::config::PushService;
+use crate::feed::Article;
+
+use serde_json::json;
+#[cfg(unix)]
+use std::process::{Command, Stdio};
+
+/// Endpoint of the Pushover messages API
+const PUSHOVER_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// ### notify_desktop
+///
+/// Show a desktop notification with `title` and `body`
+#[cfg(target_os = "macos")]
+pub fn notify_desktop(title: &str, body: &str) -> Result<(), String> {
+    let script = format!(
+        "display notification {} with title {}",
+        apple_script_string(body),
+        apple_script_string(title)
+    );
+    run_notifier("osascript", &["-e", script.as_str()])
+}
+
+/// ### notify_desktop
+///
+/// Show a desktop notification with `title` and `body`
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn notify_desktop(title: &str, body: &str) -> Result<(), String> {
+    run_notifier("notify-send", &["--app-name=tuifeed", title, body])
+}
+
+/// ### notify_desktop
+///
+/// Show a desktop notification with `title` and `body`
+#[cfg(not(unix))]
+pub fn notify_desktop(_title: &str, _body: &str) -> Result<(), String> {
+    Err(String::from(
+        "desktop notifications are not supported on this system",
+    ))
+}
+
+/// ### notify_ntfy
+///
+/// Publish a notification with `title` and `body` to the ntfy `topic` url.
+/// If `click` is set, the notification opens it when clicked
+pub fn notify_ntfy(
+    topic: &str,
+    title: &str,
+    body: &str,
+    click: Option<&str>,
+) -> Result<(), String> {
+    let mut request = ureq::post(topic).set("Title", title);
+    if let Some(click) = click {
+        request = request.set("Click", click);
+    }
+    request
+        .send_string(body)
+        .map(|_| ())
+        .map_err(|e| format!("could not publish to {}: {}", topic, e))
+}
+
+/// ### notify_push
+///
+/// Push a notification with `title` and `body` to `service`, with `priority` if set.
+/// If `click` is set, the notification opens it when clicked
+pub fn notify_push(
+    service: &PushService,
+    priority: Option<i8>,
+    title: &str,
+    body: &str,
+    click: Option<&str>,
+) -> Result<(), String> {
+    match service {
+        PushService::Ntfy { url, token } => {
+            let mut request = ureq::post(url).set("Title", title);
+            if let Some(click) = click {
+                request = request.set("Click", click);
+            }
+            if let Some(priority) = priority {
+                request = request.set("Priority", priority.to_string().as_str());
+            }
+            if let Some(token) = token {
+                request = request.set("Authorization", format!("Bearer {}", token).as_str());
+            }
+            request
+                .send_string(body)
+                .map(|_| ())
+                .map_err(|e| format!("could not publish to {}: {}", url, e))
+        }
+        PushService::Pushover { token, user } => {
+            let priority = priority.map(|x| x.to_string());
+            let mut form = vec![
+                ("token", token.as_str()),
+                ("user", user.as_str()),
+                ("title", title),
+                ("message", body),
+            ];
+            if let Some(click) = click {
+                form.push(("url", click));
+            }
+            if let Some(priority) = priority.as_deref() {
+                form.push(("priority", priority));
+            }
+            ureq::post(PUSHOVER_URL)
+                .send_form(form.as_slice())
+                .map(|_| ())
+                .map_err(|e| format!("could not push to Pushover: {}", e))
+        }
+        PushService::Gotify { server, token } => {
+            let mut message = json!({
+                "title": title,
+                "message": body,
+                "priority": priority,
+            });
+            if let Some(click) = click {
+                message["extras"] =
+                    json!({ "client::notification": { "click": { "url": click } } });
+            }
+            ureq::post(format!("{}/message", server).as_str())
+                .set("X-Gotify-Key", token.as_str())
+                .set("Content-Type", "application/json")
+                .send_string(message.to_string().as_str())
+                .map(|_| ())
+                .map_err(|e| format!("could not push to {}: {}", server, e))
+        }
+    }
+}
+
+/// ### post_webhook
+///
+/// Post the new `article` of `source` to the webhook at `url`, as JSON
+pub fn post_webhook(url: &str, source: &str, article: &Article) -> Result<(), String> {
+    ureq::post(url)
+        .set("Content-Type", "application/json")
+        .send_string(article_json(source, article).as_str())
+        .map(|_| ())
+        .map_err(|e| format!("could not post to {}: {}", url, e))
+}
+
+/// ### article_json
+///
+/// Make the JSON object describing the `article` of `source`
+pub fn article_json(source: &str, article: &Article) -> String {
+    json!({
+        "source": source,
+        "title": article.title,
+        "url": article.url,
+        "authors": article.authors,
+        "date": article.date.map(|x| x.to_rfc3339()),
+        "categories": article.categories,
+        "summary": article.summary,
+    })
+    .to_string()
+}
+
+/// ### run_notifier
+///
+/// Run the notifier `program` with `args`, waiting for it to exit
+#[cfg(unix)]
+fn run_notifier(program: &str, args: &[&str]) -> Result<(), String> {
+    let status = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("could not run `{}`: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("`{}` exited with status {}", program, status))
+    }
+}
+
+/// ### apple_script_string
+///
+/// Quote `s` as an AppleScript string literal
+#[cfg(any(target_os = "macos", test))]
+fn apple_script_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_make_article_json() {
+        let article = Article {
+            title: Some(String::from("Rust 1.56 is out")),
+            authors: vec![String::from("The Rust Team")],
+            summary: String::from("Rust 2021 is stable"),
+            links: Vec::new(),
+            url: String::from("https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        };
+        let payload: serde_json::Value =
+            serde_json::from_str(article_json("Rust Blog", &article).as_str()).unwrap();
+        assert_eq!(payload["source"], "Rust Blog");
+        assert_eq!(payload["title"], "Rust 1.56 is out");
+        assert_eq!(
+            payload["url"],
+            "https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html"
+        );
+        assert_eq!(payload["authors"][0], "The Rust Team");
+        assert!(payload["date"].is_null());
+    }
+
+    #[test]
+    fn should_quote_apple_script_strings() {
+        assert_eq!(apple_script_string("hello"), "\"hello\"");
+        assert_eq!(
+            apple_script_string("say \"hi\" \\o/"),
+            "\"say \\\"hi\\\" \\\\o/\""
+        );
+    }
+}
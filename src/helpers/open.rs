@@ -26,7 +26,15 @@
  * SOFTWARE.
  */
 use open;
+use std::env;
+use std::ffi::OsStr;
 use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[cfg(unix)]
+const DEFAULT_EDITOR: &str = "vi";
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
 
 /// ### open_text_file
 ///
@@ -38,9 +46,105 @@ pub fn open_text_file(p: &Path) -> Result<(), String> {
     open::that(p).map_err(|e| e.to_string())
 }
 
+/// ### edit_text_file
+///
+/// Edit text file with the editor set in `$VISUAL` or `$EDITOR`, falling back to the system default editor.
+/// This function blocks until the editor exits
+pub fn edit_text_file(p: &Path) -> Result<(), String> {
+    if !p.exists() {
+        return Err(format!("{}: No such file or directory", p.display()));
+    }
+    let editor = get_editor();
+    // Editor may contain arguments (e.g. `code --wait`)
+    let mut args = editor.split_whitespace();
+    let program = args.next().unwrap_or(DEFAULT_EDITOR);
+    let status = Command::new(program)
+        .args(args)
+        .arg(p)
+        .status()
+        .map_err(|e| format!("Could not start editor `{}`: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Editor `{}` exited with status {}",
+            program, status
+        ))
+    }
+}
+
 /// ### open_link
 ///
 /// Open link
 pub fn open_link(link: &str) -> Result<(), String> {
     open::that(link).map_err(|e| e.to_string())
 }
+
+/// ### open_link_with
+///
+/// Open link with `command` (e.g. `mpv --fs`), passing the link as last argument.
+/// The command runs in background, detached from the terminal
+pub fn open_link_with(command: &str, link: &str) -> Result<(), String> {
+    if command.trim().is_empty() {
+        return Err(String::from("Empty command"));
+    }
+    let args: Vec<&str> = command
+        .split_whitespace()
+        .chain(std::iter::once(link))
+        .collect();
+    run_command(args.as_slice())
+}
+
+/// ### run_command
+///
+/// Run the program in `args[0]` with the other arguments.
+/// The command runs in background, detached from the terminal
+pub fn run_command<S: AsRef<OsStr>>(args: &[S]) -> Result<(), String> {
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| String::from("Empty command"))?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            format!(
+                "Could not start `{}`: {}",
+                program.as_ref().to_string_lossy(),
+                e
+            )
+        })?;
+    // Reap the process once it exits
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// ### get_editor
+///
+/// Get the user's preferred editor from environment
+fn get_editor() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|x| !x.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn should_fail_running_empty_command() {
+        assert!(open_link_with(" ", "https://example.com").is_err());
+        assert!(run_command::<&str>(&[]).is_err());
+    }
+
+    #[test]
+    fn should_fail_editing_unexisting_file() {
+        assert!(edit_text_file(Path::new("/this/file/does/not/exist.toml")).is_err());
+    }
+}
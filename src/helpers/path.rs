@@ -26,8 +26,26 @@
  * SOFTWARE.
  */
 use super::file as file_helper;
+use std::env;
 use std::path::{Path, PathBuf};
 
+/// Configuration file written on the first run
+const INITIAL_CONFIG: &str = r##"[sources]
+# Write here the sources you want to get the feed from following the example below
+# New_York_Times = "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
+"##;
+
+/// ### xdg_dir
+///
+/// Get the base directory set in the XDG environment variable `var`, if it's an absolute path,
+/// otherwise use `fallback`
+fn xdg_dir(var: &str, fallback: Option<PathBuf>) -> Option<PathBuf> {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or(fallback)
+}
+
 /// ### get_config_dir
 ///
 /// Get tuifeed configuration directory path.
@@ -36,7 +54,7 @@ pub fn init_config_dir() -> Result<Option<PathBuf>, String> {
     // Get file
     #[cfg(not(test))]
     lazy_static! {
-        static ref CONF_DIR: Option<PathBuf> = dirs::config_dir();
+        static ref CONF_DIR: Option<PathBuf> = xdg_dir("XDG_CONFIG_HOME", dirs::config_dir());
     }
     #[cfg(test)]
     lazy_static! {
@@ -60,33 +78,180 @@ pub fn init_config_dir() -> Result<Option<PathBuf>, String> {
     }
 }
 
+/// ### init_data_dir
+///
+/// Get tuifeed data directory path, where articles are stored.
+/// Returns None, if it's not possible to get it
+pub fn init_data_dir() -> Result<Option<PathBuf>, String> {
+    #[cfg(not(test))]
+    lazy_static! {
+        static ref DATA_DIR: Option<PathBuf> = xdg_dir("XDG_DATA_HOME", dirs::data_dir());
+    }
+    #[cfg(test)]
+    lazy_static! {
+        static ref DATA_DIR: Option<PathBuf> = Some(std::env::temp_dir());
+    }
+    match DATA_DIR.as_ref() {
+        Some(data_dir) => {
+            let mut p: PathBuf = data_dir.clone();
+            // Append tuifeed dir
+            p.push("tuifeed/");
+            // If directory doesn't exist, create it
+            init_dir(p.as_path())?;
+            Ok(Some(p))
+        }
+        None => Ok(None),
+    }
+}
+
+/// ### init_cache_dir
+///
+/// Get tuifeed cache directory path, where the log file is written.
+/// Returns None, if it's not possible to get it
+pub fn init_cache_dir() -> Result<Option<PathBuf>, String> {
+    #[cfg(not(test))]
+    lazy_static! {
+        static ref CACHE_DIR: Option<PathBuf> = xdg_dir("XDG_CACHE_HOME", dirs::cache_dir());
+    }
+    #[cfg(test)]
+    lazy_static! {
+        static ref CACHE_DIR: Option<PathBuf> = Some(std::env::temp_dir().join("cache"));
+    }
+    match CACHE_DIR.as_ref() {
+        Some(cache_dir) => {
+            let mut p: PathBuf = cache_dir.clone();
+            // Append tuifeed dir
+            p.push("tuifeed/");
+            // If directory doesn't exist, create it
+            init_dir(p.as_path())?;
+            Ok(Some(p))
+        }
+        None => Ok(None),
+    }
+}
+
+/// ### get_store_file
+///
+/// Returns path for the article store database
+pub fn get_store_file(data_dir: &Path) -> PathBuf {
+    let mut store_file: PathBuf = PathBuf::from(data_dir);
+    store_file.push("articles.db");
+    store_file
+}
+
+/// ### get_log_file
+///
+/// Returns path for the log file
+pub fn get_log_file(cache_dir: &Path) -> PathBuf {
+    let mut log_file: PathBuf = PathBuf::from(cache_dir);
+    log_file.push("tuifeed.log");
+    log_file
+}
+
+/// ### get_socket_file
+///
+/// Returns path for the control socket of the running instance
+pub fn get_socket_file(data_dir: &Path) -> PathBuf {
+    let mut socket_file: PathBuf = PathBuf::from(data_dir);
+    socket_file.push("tuifeed.sock");
+    socket_file
+}
+
+/// ### get_archive_dir
+///
+/// Returns path for the directory of the archived articles
+pub fn get_archive_dir(data_dir: &Path) -> PathBuf {
+    let mut archive_dir: PathBuf = PathBuf::from(data_dir);
+    archive_dir.push("archive");
+    archive_dir
+}
+
+/// ### get_profile_dir
+///
+/// Returns the data directory of `profile` in `data_dir`.
+/// If the directory doesn't exist, it will be created
+pub fn get_profile_dir(data_dir: &Path, profile: &str) -> Result<PathBuf, String> {
+    let mut p: PathBuf = PathBuf::from(data_dir);
+    p.push("profiles");
+    p.push(profile);
+    init_dir(p.as_path())?;
+    Ok(p)
+}
+
+/// ### is_valid_profile
+///
+/// Returns whether `profile` can be used as profile name.
+/// Profile names are used in paths, so only alphanumeric characters, `-` and `_` are allowed
+pub fn is_valid_profile(profile: &str) -> bool {
+    !profile.is_empty()
+        && profile
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
 /// ### get_config_path
 ///
-/// Returns path for config file.
+/// Returns path for config file; if `profile` is set, the profile config file is returned
+/// (e.g. `config.work.toml`).
+/// The JSON or YAML config file (e.g. `config.json`, `config.yaml`) is returned if it exists and the TOML one doesn't
+pub fn get_config_path(config_dir: &Path, profile: Option<&str>) -> PathBuf {
+    let name = match profile {
+        Some(profile) => format!("config.{}", profile),
+        None => String::from("config"),
+    };
+    let cfg_file = config_dir.join(format!("{}.toml", name));
+    if cfg_file.exists() {
+        return cfg_file;
+    }
+    ["json", "yaml", "yml"]
+        .iter()
+        .map(|x| config_dir.join(format!("{}.{}", name, x)))
+        .find(|x| x.is_file())
+        .unwrap_or(cfg_file)
+}
+
+/// ### get_config_file
+///
+/// Returns path for config file, as `get_config_path` does.
 /// If the file doesn't exist, it will initialize it
-pub fn get_config_file(config_dir: &Path) -> Result<PathBuf, String> {
-    // Prepare paths
-    let mut cfg_file: PathBuf = PathBuf::from(config_dir);
-    cfg_file.push("config.toml");
-    // Check if exists
+pub fn get_config_file(config_dir: &Path, profile: Option<&str>) -> Result<PathBuf, String> {
+    ensure_config_file(get_config_path(config_dir, profile))
+}
+
+/// ### ensure_config_file
+///
+/// Initialize the config file at `cfg_file`, if it doesn't exist yet
+pub fn ensure_config_file(cfg_file: PathBuf) -> Result<PathBuf, String> {
     if !cfg_file.exists() {
         init_config_file(cfg_file.as_path())?
     }
     Ok(cfg_file)
 }
 
+/// ### init_dir
+///
+/// Create directory at `p`, if it doesn't exist yet
+pub fn init_dir(p: &Path) -> Result<(), String> {
+    match p.is_dir() {
+        true => Ok(()),
+        false => std::fs::create_dir_all(p).map_err(|e| e.to_string()),
+    }
+}
+
+/// ### is_initial_config_file
+///
+/// Returns whether the file at `p` is still the configuration file written on the first run
+pub fn is_initial_config_file(p: &Path) -> bool {
+    std::fs::read_to_string(p)
+        .map(|content| content == INITIAL_CONFIG)
+        .unwrap_or(false)
+}
+
 /// ### init_config_file
 ///
 /// Initialize configuration file
 fn init_config_file(p: &Path) -> Result<(), String> {
-    file_helper::write_file(
-        p,
-        r##"[sources]
-# Write here the sources you want to get the feed from following the example below
-# New_York_Times = "https://rss.nytimes.com/services/xml/rss/nyt/World.xml"
-"##,
-    )
-    .map_err(|e| e.to_string())
+    file_helper::write_file(p, INITIAL_CONFIG).map_err(|e| e.to_string())
 }
 
 #[cfg(test)]
@@ -134,11 +299,158 @@ mod tests {
     #[serial]
     fn should_get_config_file() {
         let conf_dir: PathBuf = init_config_dir().ok().unwrap().unwrap();
-        let cfg_file = get_config_file(conf_dir.as_path()).ok().unwrap();
+        let cfg_file = get_config_file(conf_dir.as_path(), None).ok().unwrap();
         assert_eq!(
             format!("{}", cfg_file.display()),
             format!("{}config.toml", conf_dir.display())
         );
+        let cfg_file = get_config_file(conf_dir.as_path(), Some("work"))
+            .ok()
+            .unwrap();
+        assert_eq!(
+            format!("{}", cfg_file.display()),
+            format!("{}config.work.toml", conf_dir.display())
+        );
+        assert!(cfg_file.is_file());
         assert!(std::fs::remove_dir_all(conf_dir.as_path()).is_ok());
     }
+
+    #[test]
+    #[serial]
+    fn should_init_config_file_at_path() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let mut cfg_file = tempdir.path().to_path_buf();
+        cfg_file.push("custom.toml");
+        assert_eq!(ensure_config_file(cfg_file.clone()).ok().unwrap(), cfg_file);
+        assert!(cfg_file.is_file());
+        // Existing file is kept
+        assert!(file_helper::write_file(cfg_file.as_path(), "[sources]\n").is_ok());
+        assert!(ensure_config_file(cfg_file.clone()).is_ok());
+        assert_eq!(
+            std::fs::read_to_string(cfg_file.as_path()).unwrap(),
+            "[sources]\n"
+        );
+    }
+
+    #[test]
+    fn should_get_json_config_path() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let json_file = tempdir.path().join("config.work.json");
+        assert_eq!(
+            get_config_path(tempdir.path(), Some("work")),
+            tempdir.path().join("config.work.toml")
+        );
+        assert!(file_helper::write_file(json_file.as_path(), "{}").is_ok());
+        assert_eq!(get_config_path(tempdir.path(), Some("work")), json_file);
+        assert_eq!(
+            get_config_path(tempdir.path(), None),
+            tempdir.path().join("config.toml")
+        );
+        // TOML file is preferred
+        assert!(ensure_config_file(tempdir.path().join("config.work.toml")).is_ok());
+        assert_eq!(
+            get_config_path(tempdir.path(), Some("work")),
+            tempdir.path().join("config.work.toml")
+        );
+    }
+
+    #[test]
+    fn should_get_yaml_config_path() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let yaml_file = tempdir.path().join("config.yml");
+        assert!(file_helper::write_file(yaml_file.as_path(), "sources: {}\n").is_ok());
+        assert_eq!(get_config_path(tempdir.path(), None), yaml_file);
+    }
+
+    #[test]
+    fn should_tell_initial_config_file() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let cfg_file = tempdir.path().join("config.toml");
+        assert_eq!(is_initial_config_file(cfg_file.as_path()), false);
+        assert!(ensure_config_file(cfg_file.clone()).is_ok());
+        assert!(is_initial_config_file(cfg_file.as_path()));
+        assert!(file_helper::write_file(cfg_file.as_path(), "[sources]\n").is_ok());
+        assert_eq!(is_initial_config_file(cfg_file.as_path()), false);
+    }
+
+    #[test]
+    fn should_get_profile_dir() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let profile_dir = get_profile_dir(tempdir.path(), "work").ok().unwrap();
+        assert_eq!(profile_dir, tempdir.path().join("profiles").join("work"));
+        assert!(profile_dir.is_dir());
+    }
+
+    #[test]
+    fn should_validate_profile() {
+        assert!(is_valid_profile("work"));
+        assert!(is_valid_profile("my_home-2"));
+        assert!(!is_valid_profile(""));
+        assert!(!is_valid_profile("../work"));
+        assert!(!is_valid_profile("work.toml"));
+        assert!(!is_valid_profile("my work"));
+    }
+
+    #[test]
+    fn should_init_dir() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let mut p = tempdir.path().to_path_buf();
+        p.push("foo/bar");
+        assert!(init_dir(p.as_path()).is_ok());
+        assert!(p.is_dir());
+        assert!(init_dir(p.as_path()).is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn should_get_xdg_dir() {
+        let fallback = Some(PathBuf::from("/home/omar/.config"));
+        env::remove_var("TUIFEED_TEST_XDG_HOME");
+        assert_eq!(xdg_dir("TUIFEED_TEST_XDG_HOME", fallback.clone()), fallback);
+        env::set_var("TUIFEED_TEST_XDG_HOME", "/tmp/xdg");
+        assert_eq!(
+            xdg_dir("TUIFEED_TEST_XDG_HOME", fallback.clone()),
+            Some(PathBuf::from("/tmp/xdg"))
+        );
+        // Relative paths are ignored
+        env::set_var("TUIFEED_TEST_XDG_HOME", "xdg");
+        assert_eq!(xdg_dir("TUIFEED_TEST_XDG_HOME", fallback.clone()), fallback);
+        env::remove_var("TUIFEED_TEST_XDG_HOME");
+    }
+
+    #[test]
+    #[serial]
+    fn should_get_cache_dir() {
+        let cache_dir: PathBuf = init_cache_dir().ok().unwrap().unwrap();
+        assert!(cache_dir.is_dir());
+        assert_eq!(
+            cache_dir,
+            std::env::temp_dir().join("cache").join("tuifeed/")
+        );
+        assert!(std::fs::remove_dir_all(cache_dir.as_path()).is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn should_get_store_file() {
+        let data_dir: PathBuf = init_data_dir().ok().unwrap().unwrap();
+        assert!(data_dir.is_dir());
+        assert_eq!(
+            format!("{}", get_store_file(data_dir.as_path()).display()),
+            format!("{}articles.db", data_dir.display())
+        );
+        assert_eq!(
+            format!("{}", get_log_file(data_dir.as_path()).display()),
+            format!("{}tuifeed.log", data_dir.display())
+        );
+        assert_eq!(
+            format!("{}", get_socket_file(data_dir.as_path()).display()),
+            format!("{}tuifeed.sock", data_dir.display())
+        );
+        assert_eq!(
+            format!("{}", get_archive_dir(data_dir.as_path()).display()),
+            format!("{}archive", data_dir.display())
+        );
+        assert!(std::fs::remove_dir_all(data_dir.as_path()).is_ok());
+    }
 }
@@ -0,0 +1,112 @@
+//! # Speech
+//!
+//! Text-to-speech helpers
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+
+/// ## Speaker
+///
+/// Reads text aloud with a text-to-speech command running in background.
+/// Only one text is read at a time; the command is stopped when the speaker is dropped
+#[derive(Default)]
+pub struct Speaker {
+    child: Option<Child>,
+}
+
+impl Speaker {
+    /// ### speak
+    ///
+    /// Read `text` aloud with `command` (e.g. `espeak-ng`), writing it to the command standard input.
+    /// The text being read is stopped first
+    pub fn speak(&mut self, command: &str, text: &str) -> Result<(), String> {
+        self.stop();
+        let mut args = command.split_whitespace();
+        let program = args.next().ok_or_else(|| String::from("Empty command"))?;
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Could not start `{}`: {}", program, e))?;
+        // Write the text in background, since the command may read it while speaking
+        if let Some(mut stdin) = child.stdin.take() {
+            let text = text.to_string();
+            std::thread::spawn(move || stdin.write_all(text.as_bytes()));
+        }
+        self.child = Some(child);
+        Ok(())
+    }
+
+    /// ### stop
+    ///
+    /// Stop the text being read. Returns whether the command was still running
+    pub fn stop(&mut self) -> bool {
+        match self.child.take() {
+            Some(mut child) => {
+                let running = matches!(child.try_wait(), Ok(None));
+                let _ = child.kill();
+                let _ = child.wait();
+                running
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for Speaker {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    #[cfg(unix)]
+    fn should_speak_and_stop() {
+        let mut speaker = Speaker::default();
+        assert!(speaker.speak("cat", "hello world").is_ok());
+        assert!(speaker.speak("sleep 10", "hello world").is_ok());
+        assert_eq!(speaker.stop(), true);
+        assert_eq!(speaker.stop(), false);
+    }
+
+    #[test]
+    fn should_fail_speaking_with_unexisting_command() {
+        let mut speaker = Speaker::default();
+        assert!(speaker.speak("", "hello").is_err());
+        assert!(speaker
+            .speak("this-command-does-not-exist", "hello")
+            .is_err());
+    }
+}
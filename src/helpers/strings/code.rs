@@ -0,0 +1,216 @@
+//! # Code
+//!
+//! Syntax highlighting of code blocks
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+#[cfg(feature = "highlighting")]
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxSet};
+
+/// ## CodeToken
+///
+/// Kind of a token of source code, used to highlight it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(feature = "highlighting"), allow(dead_code))]
+pub enum CodeToken {
+    Plain,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+#[cfg(feature = "highlighting")]
+lazy_static! {
+    /**
+     * Syntaxes bundled with syntect; code is highlighted line by line, without newlines
+     */
+    static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_nonewlines();
+    /**
+     * Scopes of each token, in order of precedence; the innermost scope of a token with a kind wins.
+     * Operators are keywords for syntect, but they're not highlighted
+     */
+    static ref TOKEN_SCOPES: Vec<(Scope, CodeToken)> = [
+        ("comment", CodeToken::Comment),
+        ("string", CodeToken::String),
+        ("constant.numeric", CodeToken::Number),
+        ("keyword.operator", CodeToken::Plain),
+        ("keyword", CodeToken::Keyword),
+        ("storage", CodeToken::Keyword),
+        ("constant.language", CodeToken::Keyword),
+    ]
+    .iter()
+    .map(|(scope, token)| (Scope::new(scope).unwrap(), *token))
+    .collect();
+}
+
+/// ## CodeHighlighter
+///
+/// Splits the lines of a code block written in `language` into tokens to highlight.
+/// Lines must be highlighted in order, so that strings and comments spanning several lines are recognized.
+/// Lines in unknown languages, or when built without the `highlighting` feature, are a single plain token
+pub struct CodeHighlighter {
+    #[cfg(feature = "highlighting")]
+    state: Option<(ParseState, ScopeStack)>,
+}
+
+impl CodeHighlighter {
+    /// ### new
+    ///
+    /// Instantiates a new `CodeHighlighter` for `language`, as declared by the class of a code block
+    /// (e.g. `rust`, `py`)
+    #[cfg(feature = "highlighting")]
+    pub fn new(language: Option<&str>) -> Self {
+        let state = language
+            .map(|x| match x.to_lowercase().as_str() {
+                "c++" => String::from("cpp"),
+                "console" | "shell" | "zsh" => String::from("sh"),
+                "golang" => String::from("go"),
+                "python3" => String::from("py"),
+                language => language.to_string(),
+            })
+            .and_then(|x| SYNTAX_SET.find_syntax_by_token(x.as_str()))
+            .map(|syntax| (ParseState::new(syntax), ScopeStack::new()));
+        Self { state }
+    }
+
+    #[cfg(not(feature = "highlighting"))]
+    pub fn new(_language: Option<&str>) -> Self {
+        Self {}
+    }
+
+    /// ### highlight
+    ///
+    /// Split the next line of the code block into tokens
+    #[cfg(feature = "highlighting")]
+    pub fn highlight(&mut self, line: &str) -> Vec<(String, CodeToken)> {
+        let (parser, scopes) = match self.state.as_mut() {
+            Some(state) => state,
+            None => return vec![(line.to_string(), CodeToken::Plain)],
+        };
+        let mut tokens: Vec<(String, CodeToken)> = Vec::new();
+        let mut push = |text: &str, token: CodeToken| match tokens.last_mut() {
+            _ if text.is_empty() => {}
+            Some((last, last_token)) if *last_token == token => last.push_str(text),
+            _ => tokens.push((text.to_string(), token)),
+        };
+        let mut start = 0;
+        for (end, op) in parser.parse_line(line, &SYNTAX_SET) {
+            push(&line[start..end], Self::token(scopes));
+            scopes.apply(&op);
+            start = end;
+        }
+        push(&line[start..], Self::token(scopes));
+        tokens
+    }
+
+    #[cfg(not(feature = "highlighting"))]
+    pub fn highlight(&mut self, line: &str) -> Vec<(String, CodeToken)> {
+        vec![(line.to_string(), CodeToken::Plain)]
+    }
+
+    /// ### token
+    ///
+    /// Get the kind of the token with `scopes`
+    #[cfg(feature = "highlighting")]
+    fn token(scopes: &ScopeStack) -> CodeToken {
+        scopes
+            .as_slice()
+            .iter()
+            .rev()
+            .find_map(|scope| {
+                TOKEN_SCOPES
+                    .iter()
+                    .find(|(prefix, _)| prefix.is_prefix_of(*scope))
+                    .map(|(_, token)| *token)
+            })
+            .unwrap_or(CodeToken::Plain)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    /// Get the kind of the token containing `text` in `tokens`
+    #[cfg(feature = "highlighting")]
+    fn token_of(tokens: &[(String, CodeToken)], text: &str) -> CodeToken {
+        tokens
+            .iter()
+            .find(|(x, _)| x.contains(text))
+            .map(|(_, token)| *token)
+            .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "highlighting")]
+    fn should_highlight_code() {
+        let line = r#"    let s = "a \"b\""; // 42"#;
+        let tokens = CodeHighlighter::new(Some("rust")).highlight(line);
+        assert_eq!(
+            tokens.iter().map(|(x, _)| x.as_str()).collect::<String>(),
+            line
+        );
+        assert_eq!(token_of(&tokens, "let"), CodeToken::Keyword);
+        assert_eq!(token_of(&tokens, r#"a \"b"#), CodeToken::String);
+        assert_eq!(token_of(&tokens, "// 42"), CodeToken::Comment);
+        let tokens = CodeHighlighter::new(Some("Python")).highlight("x = 3.14 if ok else None");
+        assert_eq!(token_of(&tokens, "3.14"), CodeToken::Number);
+        assert_eq!(token_of(&tokens, "else"), CodeToken::Keyword);
+        assert_eq!(token_of(&tokens, "x"), CodeToken::Plain);
+    }
+
+    #[test]
+    #[cfg(feature = "highlighting")]
+    fn should_highlight_multiline_comments() {
+        let mut highlighter = CodeHighlighter::new(Some("c"));
+        assert_eq!(
+            token_of(&highlighter.highlight("/* a comment"), "comment"),
+            CodeToken::Comment
+        );
+        assert_eq!(
+            highlighter.highlight("   spanning lines */"),
+            vec![(String::from("   spanning lines */"), CodeToken::Comment)]
+        );
+        assert_eq!(
+            token_of(&highlighter.highlight("int x;"), "int"),
+            CodeToken::Keyword
+        );
+    }
+
+    #[test]
+    fn should_not_highlight_unknown_languages() {
+        assert_eq!(
+            CodeHighlighter::new(Some("brainfuck")).highlight("  let x = 1;"),
+            vec![(String::from("  let x = 1;"), CodeToken::Plain)]
+        );
+        assert_eq!(
+            CodeHighlighter::new(None).highlight("  let x = 1;"),
+            vec![(String::from("  let x = 1;"), CodeToken::Plain)]
+        );
+    }
+}
@@ -25,34 +25,138 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+mod code;
 mod lookup;
 
+pub use code::{CodeHighlighter, CodeToken};
+
 use regex::Regex;
-use unicode_truncate::UnicodeTruncateStr;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Prefix of the search queries which are regular expressions
+pub const REGEX_QUERY_PREFIX: &str = "re:";
 
 lazy_static! {
     static ref HTML_TAG_REGEX: Regex = Regex::new(r"<[^>]+>").unwrap();
+    /**
+     * Matches HTML anchors in string
+     *
+     * - group 1: href
+     * - group 2: link text
+     */
+    static ref HTML_LINK_REGEX: Regex =
+        Regex::new(r#"(?is)<a\s[^>]*?href\s*=\s*["']([^"']*)["'][^>]*>(.*?)</a>"#).unwrap();
+    /**
+     * Matches the `[N]` markers of numbered links
+     *
+     * - group 1: link number
+     */
+    static ref LINK_MARKER_REGEX: Regex = Regex::new(r"\[(\d+)\]").unwrap();
     /**
      * Matches HTML entities in string
      *
-     * - group 2: decimal (maybe)
+     * - group 1: decimal (maybe)
+     * - group 2: hexadecimal (maybe)
      * - group 3: literal (e.g. amp, gt, ...) (maybe)
      */
-    static ref HTML_ENTITIES_REGEX: Regex = Regex::new(r"&(#([0-9]+))?([a-z]+)?;").unwrap();
+    static ref HTML_ENTITIES_REGEX: Regex =
+        Regex::new(r"&(?:#([0-9]+)|#[xX]([0-9a-fA-F]+)|([A-Za-z][A-Za-z0-9]*));").unwrap();
+    /**
+     * Matches CDATA sections
+     *
+     * - group 1: content
+     */
+    static ref CDATA_REGEX: Regex = Regex::new(r"(?s)<!\[CDATA\[(.*?)\]\]>").unwrap();
+    /// Matches inline formatting tags, which may appear escaped in titles
+    static ref INLINE_TAG_REGEX: Regex = Regex::new(
+        r"(?i)</?(a|abbr|b|br|cite|code|del|em|i|ins|mark|p|q|s|small|span|strong|sub|sup|u)(\s[^>]*)?/?>"
+    )
+    .unwrap();
+    /// Matches the tags which end a line of text
+    static ref LINE_BREAK_TAG_REGEX: Regex =
+        Regex::new(r"(?i)<br\s*/?>|</(p|div|li|h[1-6]|blockquote|tr)>").unwrap();
+    /**
+     * Matches preformatted blocks
+     *
+     * - group 1: content
+     */
+    static ref PRE_BLOCK_REGEX: Regex = Regex::new(r"(?is)<pre(?:\s[^>]*)?>(.*?)</pre>").unwrap();
+    /**
+     * Matches the language declared by the class of a code block (e.g. `language-rust` or `lang-rust`)
+     *
+     * - group 1: language
+     */
+    static ref CODE_LANGUAGE_REGEX: Regex =
+        Regex::new(r#"(?i)class\s*=\s*["'](?:[^"']*\s)?(?:language|lang)-([a-z0-9_+#-]+)"#).unwrap();
+    /**
+     * Matches tables
+     *
+     * - group 1: content
+     */
+    static ref TABLE_REGEX: Regex = Regex::new(r"(?is)<table(?:\s[^>]*)?>(.*?)</table>").unwrap();
+    /**
+     * Matches table rows
+     *
+     * - group 1: content
+     */
+    static ref TABLE_ROW_REGEX: Regex = Regex::new(r"(?is)<tr(?:\s[^>]*)?>(.*?)</tr>").unwrap();
+    /**
+     * Matches table cells; header cells are `th`
+     *
+     * - group 1: tag name
+     * - group 2: content
+     */
+    static ref TABLE_CELL_REGEX: Regex =
+        Regex::new(r"(?is)<(t[dh])(?:\s[^>]*)?>(.*?)</t[dh]>").unwrap();
+    static ref BR_TAG_REGEX: Regex = Regex::new(r"(?i)<br\s*/?>").unwrap();
+    static ref WHITESPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+    static ref INLINE_WHITESPACES_REGEX: Regex = Regex::new(r"[^\S\n]+").unwrap();
     static ref REPEATED_NEWLINES_REGEX: Regex = Regex::new(r"(\r?\n|\r)\d*(\r?\n|\r)").unwrap();
 }
 
 /// ### elide_string_at
 ///
-/// Elide string at `len` and append `
+/// Elide string at `len` columns and append `…`.
+/// Width is measured in terminal columns and graphemes are never split,
+/// so wide (e.g. CJK) and combined characters are elided correctly
 pub fn elide_string_at(s: &str, len: usize) -> String {
-    if s.len() < len {
+    if s.width() <= len {
         s.to_string()
     } else {
-        format!("{}…", s.unicode_truncate(len - 1).0)
+        format!("{}…", truncate_string_at(s, len.saturating_sub(1)))
     }
 }
 
+/// ### truncate_string_at
+///
+/// Truncate string at `len` columns, without splitting graphemes
+pub fn truncate_string_at(s: &str, len: usize) -> &str {
+    let mut width = 0;
+    for (i, grapheme) in s.grapheme_indices(true) {
+        width += grapheme.width();
+        if width > len {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// ### pad_string_to
+///
+/// Pad string with spaces at the end, until it's `len` columns wide
+pub fn pad_string_to(s: &str, len: usize) -> String {
+    let padding = len.saturating_sub(s.width());
+    format!("{}{}", s, " ".repeat(padding))
+}
+
+/// ### string_width
+///
+/// Returns the amount of terminal columns required to display the string
+pub fn string_width(s: &str) -> usize {
+    s.width()
+}
+
 /// ### replace_multiple_newlines
 ///
 /// Remove repeated newlines in string and replace them with `with`
@@ -60,31 +164,297 @@ pub fn replace_multiple_newlines(s: &str, with: &str) -> String {
     REPEATED_NEWLINES_REGEX.replace_all(s, with).to_string()
 }
 
+/// ### fuzzy_match
+///
+/// Fuzzy match `pattern` against `text`, ignoring case.
+/// Returns `None` if the characters of `pattern` don't appear in order in `text`,
+/// otherwise returns the match score: the higher the score, the better the match.
+/// Consecutive characters and characters at the beginning of a word are preferred
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<i64> {
+    let mut pattern = pattern.chars().flat_map(char::to_lowercase).peekable();
+    let mut score: i64 = 0;
+    let mut prev: Option<char> = None;
+    let mut prev_matched = false;
+    for ch in text.chars() {
+        let expected = match pattern.peek() {
+            Some(expected) => *expected,
+            None => break,
+        };
+        let matched = ch.to_lowercase().eq(std::iter::once(expected));
+        if matched {
+            score += 1;
+            if prev_matched {
+                score += 5;
+            }
+            if prev.map(|x| !x.is_alphanumeric()).unwrap_or(true) {
+                score += 8;
+            }
+            pattern.next();
+        } else {
+            score -= 1;
+        }
+        prev_matched = matched;
+        prev = Some(ch);
+    }
+    match pattern.peek() {
+        None => Some(score),
+        Some(_) => None,
+    }
+}
+
+/// ### search_regex
+///
+/// Make the regex matching the occurrences of `query` in a text, ignoring case.
+/// Queries starting with `REGEX_QUERY_PREFIX` are regular expressions, while the other ones are matched literally
+pub fn search_regex(query: &str) -> Result<Regex, String> {
+    let pattern = match query.strip_prefix(REGEX_QUERY_PREFIX) {
+        Some(pattern) => pattern.to_string(),
+        None => regex::escape(query),
+    };
+    Regex::new(format!("(?i){}", pattern).as_str())
+        .map_err(|e| format!("invalid regex {}: {}", pattern, e))
+}
+
+/// ### is_regex_query
+///
+/// Returns whether the search `query` is a regular expression
+pub fn is_regex_query(query: &str) -> bool {
+    query.starts_with(REGEX_QUERY_PREFIX)
+}
+
 /// strip_html
 ///
 /// Strip html tags and entities from string
 pub fn strip_html(s: &str) -> String {
-    let mut escaped = HTML_TAG_REGEX.replace_all(s, "").to_string();
-    let copy = escaped.clone();
-    for group in HTML_ENTITIES_REGEX.captures_iter(copy.as_str()) {
-        if let Some(mtch) = group.get(2) {
-            // Convert mtch to u32
-            let replace_with = match mtch.as_str().parse::<u32>() {
-                Err(_) => '�',
-                Ok(val) => char::from_u32(val).unwrap_or('�'),
+    decode_html_entities(HTML_TAG_REGEX.replace_all(s, "").as_ref())
+}
+
+/// ### decode_html_entities
+///
+/// Replace html entities (e.g. `&amp;`, `&#8220;` or `&#x2019;`) with the characters they represent.
+/// Unknown entities are kept as they are
+pub fn decode_html_entities(s: &str) -> String {
+    HTML_ENTITIES_REGEX
+        .replace_all(s, |group: &regex::Captures| {
+            let code = match (group.get(1), group.get(2)) {
+                (Some(decimal), _) => Some(decimal.as_str().parse::<u32>().ok()),
+                (None, Some(hex)) => Some(u32::from_str_radix(hex.as_str(), 16).ok()),
+                (None, None) => None,
             };
-            // Get char from decimal
-            escaped = escaped.replace(&group[0], replace_with.to_string().as_str());
-        } else if let Some(mtch) = group.get(3) {
-            let replace_with = lookup::HTML_ENTITIES_TABLE
+            match code {
+                Some(code) => code.and_then(char::from_u32).unwrap_or('�').to_string(),
+                None => lookup::HTML_ENTITIES_TABLE
+                    .iter()
+                    .find(|(repr, _)| *repr == &group[3])
+                    .map(|(_, code)| code.to_string())
+                    .unwrap_or_else(|| group[0].to_string()),
+            }
+        })
+        .to_string()
+}
+
+/// ### sanitize_line
+///
+/// Sanitize a single line of text, such as a title: CDATA wrappers and html tags are removed,
+/// entities are decoded and whitespaces are collapsed into a single space.
+/// Inline formatting tags which were escaped (e.g. `&lt;em&gt;`) are removed too
+pub fn sanitize_line(s: &str) -> String {
+    let text = strip_html(CDATA_REGEX.replace_all(s, "$1").as_ref());
+    let text = INLINE_TAG_REGEX.replace_all(text.as_str(), "");
+    WHITESPACES_REGEX
+        .replace_all(text.as_ref(), " ")
+        .trim()
+        .to_string()
+}
+
+/// Delimits the code blocks in sanitized text; the opening fence is followed by the language, if declared
+pub const CODE_FENCE: &str = "```";
+
+/// Max width of a table column; longer cells are elided
+const TABLE_COLUMN_WIDTH: usize = 32;
+
+/// Marks the position of a code block while the rest of the text is sanitized
+const CODE_BLOCK_PLACEHOLDER: &str = "\u{e000}code-block-";
+
+/// ### sanitize_text
+///
+/// Sanitize html text, such as a summary: line breaking tags are replaced with newlines,
+/// then tags are removed, entities are decoded and whitespaces are normalized,
+/// keeping at most one empty line between paragraphs.
+/// Preformatted blocks keep their whitespaces and are delimited by `CODE_FENCE` lines,
+/// as well as tables, whose columns are aligned
+pub fn sanitize_text(s: &str) -> String {
+    let text = CDATA_REGEX.replace_all(s, "$1");
+    let mut blocks: Vec<String> = Vec::new();
+    let text = PRE_BLOCK_REGEX.replace_all(text.as_ref(), |group: &regex::Captures| {
+        blocks.push(code_block(&group[0], &group[1]));
+        format!("\n{}{}\n", CODE_BLOCK_PLACEHOLDER, blocks.len() - 1)
+    });
+    let text = TABLE_REGEX.replace_all(text.as_ref(), |group: &regex::Captures| {
+        match text_table(&group[1]) {
+            Some(table) => {
+                blocks.push(table);
+                format!("\n{}{}\n", CODE_BLOCK_PLACEHOLDER, blocks.len() - 1)
+            }
+            None => String::from("\n"),
+        }
+    });
+    let text = LINE_BREAK_TAG_REGEX.replace_all(text.as_ref(), "$0\n");
+    let text = strip_html(text.as_ref())
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+    let mut lines: Vec<&str> = Vec::new();
+    let normalized = INLINE_WHITESPACES_REGEX.replace_all(text.as_str(), " ");
+    for line in normalized.split('\n').map(str::trim) {
+        if let Some(block) = line
+            .strip_prefix(CODE_BLOCK_PLACEHOLDER)
+            .and_then(|x| x.parse::<usize>().ok())
+            .and_then(|x| blocks.get(x))
+        {
+            lines.push(block.as_str());
+            continue;
+        }
+        // Keep at most one empty line
+        if line.is_empty() && lines.last().map(|x| x.is_empty()).unwrap_or(true) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim_end().to_string()
+}
+
+/// ### code_block
+///
+/// Convert the preformatted block `html`, whose content is `content`, into a fenced code block.
+/// The language is taken from the class of the block or of its code tag
+fn code_block(html: &str, content: &str) -> String {
+    let language = CODE_LANGUAGE_REGEX
+        .captures(html)
+        .map(|x| x[1].to_lowercase())
+        .unwrap_or_default();
+    let code = strip_html(BR_TAG_REGEX.replace_all(content, "\n").as_ref())
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\t', "    ");
+    let code: Vec<&str> = code.trim_matches('\n').lines().map(str::trim_end).collect();
+    format!(
+        "{}{}\n{}\n{}",
+        CODE_FENCE,
+        language,
+        code.join("\n"),
+        CODE_FENCE
+    )
+}
+
+/// ### text_table
+///
+/// Render the rows of the html table whose content is `html` as a fenced block with aligned columns.
+/// A rule separates the header, if the first row is made of header cells.
+/// Returns `None` if the table has no cells
+fn text_table(html: &str) -> Option<String> {
+    let mut header = false;
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for (i, row) in TABLE_ROW_REGEX.captures_iter(html).enumerate() {
+        let cells: Vec<regex::Captures> = TABLE_CELL_REGEX.captures_iter(&row[1]).collect();
+        if cells.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            header = cells.iter().all(|x| x[1].eq_ignore_ascii_case("th"));
+        }
+        rows.push(
+            cells
                 .iter()
-                .find(|(repr, _)| *repr == mtch.as_str())
-                .map(|(_, code)| code)
-                .unwrap_or(&"�");
-            escaped = escaped.replace(&group[0], replace_with);
+                .map(|x| elide_string_at(sanitize_line(&x[2]).as_str(), TABLE_COLUMN_WIDTH))
+                .collect(),
+        );
+    }
+    let columns = rows.iter().map(Vec::len).max()?;
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter_map(|x| x.get(i))
+                .map(|x| x.width())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut lines: Vec<String> = Vec::with_capacity(rows.len() + 1);
+    for (i, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| pad_string_to(cell.as_str(), *width))
+            .collect();
+        lines.push(cells.join(" │ ").trim_end().to_string());
+        if i == 0 && header {
+            let rule: Vec<String> = widths.iter().map(|x| "─".repeat(*x)).collect();
+            lines.push(rule.join("─┼─"));
+        }
+    }
+    Some(format!(
+        "{}\n{}\n{}",
+        CODE_FENCE,
+        lines.join("\n"),
+        CODE_FENCE
+    ))
+}
+
+/// ### number_links
+///
+/// Replace each html link in `s` with its text followed by a `[N]` marker.
+/// Returns the html with the markers and the numbered urls; the same url always gets the same number.
+/// Anchors to the same page (e.g. `#footnote`) are not numbered
+pub fn number_links(s: &str) -> (String, Vec<String>) {
+    let mut links: Vec<String> = Vec::new();
+    let html = HTML_LINK_REGEX.replace_all(s, |group: &regex::Captures| {
+        let url = strip_html(&group[1]);
+        let text = &group[2];
+        if url.is_empty() || url.starts_with('#') {
+            return text.to_string();
+        }
+        let number = match links.iter().position(|x| *x == url) {
+            Some(index) => index + 1,
+            None => {
+                links.push(url);
+                links.len()
+            }
+        };
+        format!("{} [{}]", text, number)
+    });
+    (html.to_string(), links)
+}
+
+/// ### find_link_markers
+///
+/// Find the `[N]` markers of the numbered links in `line`, as made by `number_links`.
+/// Returns the column each marker starts at, along with the marker and its number
+pub fn find_link_markers(line: &str) -> Vec<(usize, &str, usize)> {
+    LINK_MARKER_REGEX
+        .captures_iter(line)
+        .filter_map(|group| {
+            let marker = group.get(0)?;
+            let number = group[1].parse().ok()?;
+            Some((
+                string_width(&line[..marker.start()]),
+                marker.as_str(),
+                number,
+            ))
+        })
+        .collect()
+}
+
+/// ### split_tags
+///
+/// Split a comma-separated list of tags; tags are trimmed and lowercased, empty and duplicated ones are dropped
+pub fn split_tags(s: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in s.split(',').map(|x| x.trim().to_lowercase()) {
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
         }
     }
-    escaped
+    tags
 }
 
 #[cfg(test)]
@@ -107,6 +477,38 @@ mod test {
         assert_eq!(elide_string_at("Hello world!", 24).as_str(), "Hello world!");
     }
 
+    #[test]
+    fn should_elide_wide_and_combined_chars() {
+        assert_eq!(elide_string_at("日本語のタイトル", 7).as_str(), "日本語…");
+        assert_eq!(elide_string_at("日本語のタイトル", 8).as_str(), "日本語…");
+        assert_eq!(
+            elide_string_at("e\u{301}e\u{301}e\u{301}", 2).as_str(),
+            "e\u{301}…"
+        );
+        assert_eq!(elide_string_at("abc", 3).as_str(), "abc");
+    }
+
+    #[test]
+    fn should_truncate_string() {
+        assert_eq!(truncate_string_at("Hello world!", 5), "Hello");
+        assert_eq!(truncate_string_at("Hello", 24), "Hello");
+        assert_eq!(truncate_string_at("日本語", 5), "日本");
+    }
+
+    #[test]
+    fn should_pad_string() {
+        assert_eq!(pad_string_to("abc", 5).as_str(), "abc  ");
+        assert_eq!(pad_string_to("日本", 5).as_str(), "日本 ");
+        assert_eq!(pad_string_to("abcdef", 5).as_str(), "abcdef");
+    }
+
+    #[test]
+    fn should_get_string_width() {
+        assert_eq!(string_width("abc"), 3);
+        assert_eq!(string_width("日本"), 4);
+        assert_eq!(string_width("e\u{301}"), 1);
+    }
+
     #[test]
     fn should_elide_without_panicking() {
         assert_eq!(
@@ -115,6 +517,77 @@ mod test {
         )
     }
 
+    #[test]
+    fn should_fuzzy_match() {
+        assert!(fuzzy_match("", "Hello world").is_some());
+        assert!(fuzzy_match("hw", "Hello world").is_some());
+        assert!(fuzzy_match("HELLO", "hello world").is_some());
+        assert!(fuzzy_match("wh", "Hello world").is_none());
+        assert!(fuzzy_match("hello", "hell").is_none());
+        // Word start and consecutive chars are preferred
+        assert!(
+            fuzzy_match("rust", "Rust 2021 released").unwrap()
+                > fuzzy_match("rust", "Run unit tests").unwrap()
+        );
+        assert!(
+            fuzzy_match("wor", "Hello world").unwrap()
+                > fuzzy_match("wor", "Where's my order").unwrap()
+        );
+    }
+
+    #[test]
+    fn should_make_search_regex() {
+        let regex = search_regex("C++").unwrap();
+        assert_eq!(
+            regex
+                .find_iter("c++ is not C, but c++ is C++")
+                .map(|x| x.start())
+                .collect::<Vec<usize>>(),
+            vec![0, 18, 25]
+        );
+        assert!(!search_regex("v1.2").unwrap().is_match("v1x2"));
+        // Regex mode
+        let regex = search_regex(r"re:CVE-\d{4}-\d+").unwrap();
+        assert_eq!(
+            regex
+                .find("Fix for cve-2021-44228 released")
+                .map(|x| x.as_str()),
+            Some("cve-2021-44228")
+        );
+        assert!(is_regex_query(r"re:v1\.2"));
+        assert!(!is_regex_query("v1.2"));
+        assert!(search_regex("re:(unclosed").is_err());
+        assert!(search_regex("(unclosed").is_ok());
+    }
+
+    #[test]
+    fn should_number_links() {
+        let (html, links) = number_links(
+            r##"<p>Read <a href="https://example.com/a?x=1&amp;y=2">this</a> and <A class="x" HREF='https://example.com/b'>that</A>, then <a href="https://example.com/a?x=1&amp;y=2">this again</a> or the <a href="#notes">notes</a></p>"##,
+        );
+        assert_eq!(
+            strip_html(html.as_str()).as_str(),
+            "Read this [1] and that [2], then this again [1] or the notes"
+        );
+        assert_eq!(
+            links,
+            vec![
+                String::from("https://example.com/a?x=1&y=2"),
+                String::from("https://example.com/b")
+            ]
+        );
+        assert_eq!(number_links("no links").1.len(), 0);
+    }
+
+    #[test]
+    fn should_find_link_markers() {
+        assert_eq!(
+            find_link_markers("Read this [1] and «that» [12], not [this]"),
+            vec![(10, "[1]", 1), (25, "[12]", 12)]
+        );
+        assert!(find_link_markers("no links").is_empty());
+    }
+
     #[test]
     fn should_strip_html() {
         assert_eq!(
@@ -129,6 +602,85 @@ mod test {
         );
     }
 
+    #[test]
+    fn should_decode_html_entities() {
+        assert_eq!(
+            decode_html_entities("Tom &amp; Jerry &#8220;cartoons&#x201D; &AElig;").as_str(),
+            "Tom & Jerry “cartoons” Æ"
+        );
+        // Entities are decoded once
+        assert_eq!(decode_html_entities("&amp;lt;").as_str(), "&lt;");
+        // Unknown entities are kept
+        assert_eq!(decode_html_entities("AT&T; &foo;").as_str(), "AT&T; &foo;");
+    }
+
+    #[test]
+    fn should_sanitize_line() {
+        assert_eq!(
+            sanitize_line("  <![CDATA[Rust &amp; <em>WebAssembly</em>]]>\n in 2021 ").as_str(),
+            "Rust & WebAssembly in 2021"
+        );
+        assert_eq!(
+            sanitize_line("Why &lt;strong&gt;you&lt;/strong&gt; should use Vec&lt;T&gt;").as_str(),
+            "Why you should use Vec<T>"
+        );
+    }
+
+    #[test]
+    fn should_sanitize_text() {
+        assert_eq!(
+            sanitize_text(
+                "<p>First   paragraph,\r\n on two lines</p>\n\n\n<p>Second<br/>paragraph &amp; more</p>   "
+            )
+            .as_str(),
+            "First paragraph,\non two lines\n\nSecond\nparagraph & more"
+        );
+        assert_eq!(sanitize_text("<![CDATA[<b>Hi</b>]]>").as_str(), "Hi");
+    }
+
+    #[test]
+    fn should_render_tables() {
+        assert_eq!(
+            sanitize_text(
+                "<p>Results:</p><table><thead><tr><th>Crate</th><th>Downloads</th></tr></thead>\n<tbody><tr><td><b>tokio</b></td><td>1&nbsp;000</td></tr><tr><td>ureq</td><td>12</td><td>extra</td></tr></tbody></table>"
+            )
+            .as_str(),
+            "Results:\n\n```\nCrate │ Downloads\n──────┼───────────┼──────\ntokio │ 1 000\nureq  │ 12        │ extra\n```"
+        );
+        assert_eq!(
+            sanitize_text("<table><tr></tr></table>Empty").as_str(),
+            "Empty"
+        );
+    }
+
+    #[test]
+    fn should_keep_code_blocks() {
+        assert_eq!(
+            sanitize_text(
+                "<p>Look:</p><pre><code class=\"hljs language-Rust\">fn main() {\n\tif a &lt; b {\n\n        <span class=\"hljs-keyword\">return</span>;   \n    }\n}\n</code></pre><p>Done</p>"
+            )
+            .as_str(),
+            "Look:\n\n```rust\nfn main() {\n    if a < b {\n\n        return;\n    }\n}\n```\nDone"
+        );
+        assert_eq!(
+            sanitize_text("<pre>a<br>  b</pre>").as_str(),
+            "```\na\n  b\n```"
+        );
+    }
+
+    #[test]
+    fn should_split_tags() {
+        assert_eq!(
+            split_tags(" Rust, later,,rust , read later"),
+            vec![
+                String::from("rust"),
+                String::from("later"),
+                String::from("read later")
+            ]
+        );
+        assert!(split_tags(" , ").is_empty());
+    }
+
     #[test]
     fn should_replace_multiple_newlines() {
         assert_eq!(
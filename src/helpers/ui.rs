@@ -25,6 +25,7 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+use std::time::Duration;
 use tuirealm::tui::layout::{Constraint, Direction, Layout, Rect};
 
 /// ### draw_area_in
@@ -55,6 +56,179 @@ pub fn draw_area_in(parent: Rect, width: u16, height: u16) -> Rect {
         .split(new_area[1])[1]
 }
 
+/// ### draw_area_in_rows
+///
+/// Draw an area (W% x H rows) in the middle of the parent area
+pub fn draw_area_in_rows(parent: Rect, width: u16, height: u16) -> Rect {
+    let height = height.min(parent.height);
+    let new_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length((parent.height - height) / 2),
+                Constraint::Length(height),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
+        .split(parent);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - width) / 2),
+                Constraint::Percentage(width),
+                Constraint::Percentage((100 - width) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(new_area[1])[1]
+}
+
+/// ### hyperlink
+///
+/// Make `text` a hyperlink to `url` using the OSC 8 escape sequence
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// ### term_supports_hyperlinks
+///
+/// Returns whether the terminal described by `term` (the TERM environment variable) supports OSC 8 hyperlinks.
+/// Most terminal emulators just ignore OSC 8 if unsupported, but a few of them print it
+pub fn term_supports_hyperlinks(term: &str) -> bool {
+    !matches!(term, "" | "dumb" | "linux" | "vt100" | "vt220")
+}
+
+/// ### list_bounds
+///
+/// Get the range of the items of a list, whose rows are `heights` tall, displayed in `max_height` rows
+/// when `selected` is selected; lists are scrolled as little as possible to display the selected item,
+/// as tui does when rendering a list
+pub fn list_bounds(heights: &[usize], selected: usize, max_height: usize) -> (usize, usize) {
+    let (mut start, mut end, mut height) = (0, 0, 0);
+    while end < heights.len() && height + heights[end] <= max_height {
+        height += heights[end];
+        end += 1;
+    }
+    let selected = selected.min(heights.len().saturating_sub(1));
+    while selected >= end && end < heights.len() {
+        height += heights[end];
+        end += 1;
+        while height > max_height {
+            height -= heights[start];
+            start += 1;
+        }
+    }
+    (start, end)
+}
+
+/// ### query_background_color
+///
+/// Ask the terminal for its background color with the OSC 11 escape sequence, waiting up to `timeout`.
+/// The query is followed by a device attributes request, which every terminal answers, so that
+/// terminals which don't support OSC 11 don't make us wait.
+/// Must be called before the input listener is started, otherwise the answer is read as keys
+#[cfg(unix)]
+pub fn query_background_color(timeout: Duration) -> Option<(u8, u8, u8)> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::unix::io::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    crossterm::terminal::enable_raw_mode().ok()?;
+    let mut response: Vec<u8> = Vec::new();
+    if tty.write_all(b"\x1b]11;?\x07\x1b[c").is_ok() && tty.flush().is_ok() {
+        let started = Instant::now();
+        let mut buffer = [0; 64];
+        // Read until the answer to the device attributes request, which comes last
+        while !response.ends_with(b"c") || !contains(&response, b"\x1b[?") {
+            let remaining = match timeout.checked_sub(started.elapsed()) {
+                Some(remaining) => remaining.as_millis() as libc::c_int,
+                None => break,
+            };
+            let mut fd = libc::pollfd {
+                fd: tty.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            // SAFETY: `fd` is a valid pollfd and the length is 1
+            if unsafe { libc::poll(&mut fd, 1, remaining) } <= 0 {
+                break;
+            }
+            match tty.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => response.extend_from_slice(&buffer[..read]),
+            }
+        }
+    }
+    let _ = crossterm::terminal::disable_raw_mode();
+    parse_background_color(String::from_utf8_lossy(&response).as_ref())
+}
+
+#[cfg(not(unix))]
+pub fn query_background_color(_timeout: Duration) -> Option<(u8, u8, u8)> {
+    None
+}
+
+#[cfg(unix)]
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|x| x == needle)
+}
+
+/// ### parse_background_color
+///
+/// Parse the color in the terminal answer to OSC 11 (e.g. `\x1b]11;rgb:ffff/ffff/ffff\x07`).
+/// Each component has 1 to 4 hex digits
+pub fn parse_background_color(response: &str) -> Option<(u8, u8, u8)> {
+    let start = response.find("\x1b]11;")? + 5;
+    let color: String = response[start..]
+        .chars()
+        .take_while(|x| *x != '\x07' && *x != '\x1b')
+        .collect();
+    let color = color
+        .strip_prefix("rgb:")
+        .or_else(|| color.strip_prefix("rgba:"))?;
+    let components: Vec<u8> = color
+        .split('/')
+        .take(3)
+        .map(|x| {
+            let max = 16u32.checked_pow(x.len() as u32)?.checked_sub(1)?;
+            let value = u32::from_str_radix(x, 16).ok()?;
+            match x.len() {
+                1..=4 => Some((value * 255 / max) as u8),
+                _ => None,
+            }
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    match components.as_slice() {
+        [r, g, b] => Some((*r, *g, *b)),
+        _ => None,
+    }
+}
+
+/// ### is_light_color
+///
+/// Returns whether a color is light, according to its relative luminance
+pub fn is_light_color((r, g, b): (u8, u8, u8)) -> bool {
+    let luminance = 0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64;
+    luminance > 127.5
+}
+
+/// ### colorfgbg_is_light
+///
+/// Returns whether the background in `value` (the COLORFGBG environment variable, e.g. `15;0`)
+/// is light, if it is a palette color
+pub fn colorfgbg_is_light(value: &str) -> Option<bool> {
+    let background: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(matches!(background, 7 | 9..=15))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -71,4 +245,72 @@ mod tests {
         assert_eq!(child.width, 271);
         assert_eq!(child.height, 54);
     }
+
+    #[test]
+    fn test_utils_ui_hyperlink() {
+        assert_eq!(
+            hyperlink("https://example.com", "example").as_str(),
+            "\x1b]8;;https://example.com\x1b\\example\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_utils_ui_list_bounds() {
+        assert_eq!(list_bounds(&[1, 2, 1], 0, 10), (0, 3));
+        assert_eq!(list_bounds(&[2, 2, 2, 2], 1, 5), (0, 2));
+        assert_eq!(list_bounds(&[2, 2, 2, 2], 3, 5), (2, 4));
+        assert_eq!(list_bounds(&[], 0, 5), (0, 0));
+    }
+
+    #[test]
+    fn test_utils_ui_term_supports_hyperlinks() {
+        assert!(term_supports_hyperlinks("xterm-256color"));
+        assert!(!term_supports_hyperlinks("linux"));
+        assert!(!term_supports_hyperlinks("dumb"));
+    }
+
+    #[test]
+    fn test_utils_ui_parse_background_color() {
+        assert_eq!(
+            parse_background_color("\x1b]11;rgb:ffff/ffff/ffff\x07\x1b[?62;22c"),
+            Some((255, 255, 255))
+        );
+        assert_eq!(
+            parse_background_color("\x1b]11;rgb:1e1e/2020/8080\x1b\\"),
+            Some((30, 32, 128))
+        );
+        assert_eq!(
+            parse_background_color("\x1b]11;rgba:f/0/80/ff\x07"),
+            Some((255, 0, 128))
+        );
+        assert_eq!(parse_background_color("\x1b[?62;22c"), None);
+        assert_eq!(parse_background_color("\x1b]11;rgb:ff/ff\x07"), None);
+        assert_eq!(parse_background_color("\x1b]11;#ffffff\x07"), None);
+    }
+
+    #[test]
+    fn test_utils_ui_is_light_color() {
+        assert!(is_light_color((255, 255, 255)));
+        assert!(is_light_color((253, 246, 227)));
+        assert!(!is_light_color((0, 43, 54)));
+        assert!(!is_light_color((0, 0, 255)));
+    }
+
+    #[test]
+    fn test_utils_ui_colorfgbg_is_light() {
+        assert_eq!(colorfgbg_is_light("15;0"), Some(false));
+        assert_eq!(colorfgbg_is_light("0;default;15"), Some(true));
+        assert_eq!(colorfgbg_is_light("0;7"), Some(true));
+        assert_eq!(colorfgbg_is_light("default"), None);
+    }
+
+    #[test]
+    fn test_utils_ui_draw_area_in_rows() {
+        let area: Rect = Rect::new(0, 0, 100, 50);
+        let child: Rect = draw_area_in_rows(area, 50, 6);
+        assert_eq!(child.x, 25);
+        assert_eq!(child.y, 22);
+        assert_eq!(child.width, 50);
+        assert_eq!(child.height, 6);
+    }
 }
@@ -0,0 +1,215 @@
+//! # Urls
+//!
+//! Urls helpers
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use url::Url;
+
+/// ### strip_query_params
+///
+/// Remove from the query of `url` the parameters matching `params`.
+/// A param ending with `*` matches all the parameters starting with the prefix (e.g. `utm_*`).
+/// If the url can't be parsed or there's nothing to remove, the url is returned unchanged
+pub fn strip_query_params(url: &str, params: &[String]) -> String {
+    let mut parsed = match Url::parse(url) {
+        Ok(parsed) if parsed.query().is_some() => parsed,
+        _ => return url.to_string(),
+    };
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let kept: Vec<&(String, String)> = pairs
+        .iter()
+        .filter(|(k, _)| !params.iter().any(|x| param_matches(x, k)))
+        .collect();
+    if kept.len() == pairs.len() {
+        return url.to_string();
+    }
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(kept);
+    }
+    parsed.to_string()
+}
+
+/// ### is_video_url
+///
+/// Returns whether `url` points to a video on YouTube
+pub fn is_video_url(url: &str) -> bool {
+    let url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    let host = url.host_str().unwrap_or("");
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("m."))
+        .unwrap_or(host);
+    match host {
+        "youtube.com" => url.path() == "/watch" || url.path().starts_with("/shorts/"),
+        "youtu.be" => url.path().len() > 1,
+        _ => false,
+    }
+}
+
+/// ### guess_content_type
+///
+/// Guess the content type of the file `url` points to from its extension, for the most common media
+pub fn guess_content_type(url: &str) -> Option<&'static str> {
+    let url = Url::parse(url).ok()?;
+    let extension = url.path().rsplit_once('.')?.1.to_lowercase();
+    Some(match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp4" | "m4v" => "video/mp4",
+        "webm" => "video/webm",
+        "mkv" => "video/x-matroska",
+        "mov" => "video/quicktime",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "ogg" | "oga" => "audio/ogg",
+        "opus" => "audio/opus",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        "pdf" => "application/pdf",
+        _ => return None,
+    })
+}
+
+/// ### strip_feed_scheme
+///
+/// Convert the `feed:` urls browsers use to subscribe to feeds (e.g. `feed://example.com/rss` or
+/// `feed:https://example.com/rss`) into the url of the feed. Other urls are returned unchanged
+pub fn strip_feed_scheme(url: &str) -> String {
+    match url.strip_prefix("feed:") {
+        Some(rest) if rest.starts_with("//") => format!("https:{}", rest),
+        Some(rest) => rest.to_string(),
+        None => url.to_string(),
+    }
+}
+
+/// ### param_matches
+///
+/// Returns whether query parameter `key` matches `pattern`
+fn param_matches(pattern: &str, key: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => pattern == key,
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn params() -> Vec<String> {
+        vec![String::from("utm_*"), String::from("fbclid")]
+    }
+
+    #[test]
+    fn should_strip_query_params() {
+        assert_eq!(
+            strip_query_params(
+                "https://example.com/a?utm_source=rss&utm_medium=feed&fbclid=x",
+                &params()
+            )
+            .as_str(),
+            "https://example.com/a"
+        );
+        assert_eq!(
+            strip_query_params(
+                "https://example.com/a?p=2&utm_campaign=x&q=rust+lang#comments",
+                &params()
+            )
+            .as_str(),
+            "https://example.com/a?p=2&q=rust+lang#comments"
+        );
+    }
+
+    #[test]
+    fn should_tell_video_urls() {
+        assert!(is_video_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(is_video_url("https://m.youtube.com/shorts/dQw4w9WgXcQ"));
+        assert!(is_video_url("https://youtu.be/dQw4w9WgXcQ"));
+        assert!(!is_video_url("https://www.youtube.com/@rustvideos"));
+        assert!(!is_video_url("https://example.com/watch?v=1"));
+        assert!(!is_video_url("not an url"));
+    }
+
+    #[test]
+    fn should_guess_content_type() {
+        assert_eq!(
+            guess_content_type("https://example.com/photos/cat.JPG?size=large"),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            guess_content_type("https://example.com/episode.mp3"),
+            Some("audio/mpeg")
+        );
+        assert_eq!(guess_content_type("https://example.com/v1.2/article"), None);
+        assert_eq!(guess_content_type("https://example.com/"), None);
+        assert_eq!(guess_content_type("not a url.png"), None);
+    }
+
+    #[test]
+    fn should_strip_feed_scheme() {
+        assert_eq!(
+            strip_feed_scheme("feed://example.com/rss").as_str(),
+            "https://example.com/rss"
+        );
+        assert_eq!(
+            strip_feed_scheme("feed:http://example.com/rss").as_str(),
+            "http://example.com/rss"
+        );
+        assert_eq!(
+            strip_feed_scheme("https://example.com/feed").as_str(),
+            "https://example.com/feed"
+        );
+    }
+
+    #[test]
+    fn should_keep_url_unchanged() {
+        assert_eq!(
+            strip_query_params("https://example.com", &params()).as_str(),
+            "https://example.com"
+        );
+        assert_eq!(
+            strip_query_params("https://example.com?utmost=1&fbclid2=a", &params()).as_str(),
+            "https://example.com?utmost=1&fbclid2=a"
+        );
+        assert_eq!(
+            strip_query_params("not an url?utm_source=x", &params()).as_str(),
+            "not an url?utm_source=x"
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! # Ipc
+//!
+//! Control socket to drive a running instance of tuifeed
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// -- deps
+#[cfg(unix)]
+use log::{debug, warn};
+use std::fmt;
+use std::io;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+#[cfg(unix)]
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::time::Duration;
+
+/// How long to wait for a client to send its request or for the instance to reply
+#[cfg(unix)]
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[cfg(not(unix))]
+const UNSUPPORTED: &str = "control sockets are not supported on this system";
+
+/// ## Request
+///
+/// A request sent to the running instance; each request is a line on the socket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// Fetch all sources again
+    Refresh,
+    /// Subscribe to the feed at url
+    OpenUrl(String),
+}
+
+impl Request {
+    /// ### parse
+    ///
+    /// Parse a request line
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (command, arg) = match line.split_once(' ') {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (line, ""),
+        };
+        match (command, arg) {
+            ("refresh", "") => Ok(Self::Refresh),
+            ("open-url", "") => Err(String::from("open-url requires an url")),
+            ("open-url", url) => Ok(Self::OpenUrl(url.to_string())),
+            _ => Err(format!("unknown request \"{}\"", line)),
+        }
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Refresh => write!(f, "refresh"),
+            Self::OpenUrl(url) => write!(f, "open-url {}", url),
+        }
+    }
+}
+
+/// ## Server
+///
+/// Listens on the control socket for the requests sent with `tuifeed ctl`.
+/// The socket is removed once the server is dropped
+#[cfg(unix)]
+pub struct Server {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+#[cfg(unix)]
+impl Server {
+    /// ### bind
+    ///
+    /// Listen on the socket at `p`. A socket left behind by an instance which is not running
+    /// anymore is replaced, while it fails if another instance is listening on it
+    pub fn bind(p: &Path) -> io::Result<Self> {
+        if p.exists() {
+            if UnixStream::connect(p).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    "another instance of tuifeed is running",
+                ));
+            }
+            std::fs::remove_file(p)?;
+        }
+        let listener = UnixListener::bind(p)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            path: p.to_path_buf(),
+        })
+    }
+
+    /// ### poll
+    ///
+    /// Get the next valid request sent to the socket, if any; it doesn't block.
+    /// Clients are told whether their request has been accepted
+    pub fn poll(&self) -> Option<Request> {
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => return None,
+                Err(err) => {
+                    warn!("could not accept control connection: {}", err);
+                    return None;
+                }
+            };
+            match Self::read_request(stream) {
+                Ok(Some(request)) => return Some(request),
+                Ok(None) => continue,
+                Err(err) => warn!("could not read control request: {}", err),
+            }
+        }
+    }
+
+    /// ### read_request
+    ///
+    /// Read the request sent on `stream` and reply to it.
+    /// Returns `None` if the request is invalid
+    fn read_request(stream: UnixStream) -> io::Result<Option<Request>> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut writer = &stream;
+        match Request::parse(line.as_str()) {
+            Ok(request) => {
+                debug!("received control request: {}", request);
+                writeln!(writer, "ok")?;
+                Ok(Some(request))
+            }
+            Err(err) => {
+                warn!("invalid control request: {}", err);
+                writeln!(writer, "error: {}", err)?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.path.as_path());
+    }
+}
+
+/// ## Server
+///
+/// Control sockets are not supported on this system: the server never receives any request
+#[cfg(not(unix))]
+pub struct Server;
+
+#[cfg(not(unix))]
+impl Server {
+    /// ### bind
+    ///
+    /// Always fails, since control sockets are not supported on this system
+    pub fn bind(_p: &Path) -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, UNSUPPORTED))
+    }
+
+    /// ### poll
+    ///
+    /// There are never requests on this system
+    pub fn poll(&self) -> Option<Request> {
+        None
+    }
+}
+
+/// ### send
+///
+/// Send `request` to the instance listening on the socket at `p`
+#[cfg(unix)]
+pub fn send(p: &Path, request: &Request) -> Result<(), String> {
+    let stream = UnixStream::connect(p).map_err(|err| match err.kind() {
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused => {
+            String::from("tuifeed is not running")
+        }
+        _ => err.to_string(),
+    })?;
+    let mut reply = String::new();
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .and_then(|_| writeln!(&stream, "{}", request))
+        .and_then(|_| BufReader::new(&stream).read_line(&mut reply))
+        .map_err(|err| err.to_string())?;
+    match reply.trim() {
+        "ok" => Ok(()),
+        reply => Err(reply.strip_prefix("error: ").unwrap_or(reply).to_string()),
+    }
+}
+
+/// ### send
+///
+/// Always fails, since control sockets are not supported on this system
+#[cfg(not(unix))]
+pub fn send(_p: &Path, _request: &Request) -> Result<(), String> {
+    Err(String::from(UNSUPPORTED))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_parse_requests() {
+        assert_eq!(Request::parse("refresh\n").unwrap(), Request::Refresh);
+        assert_eq!(
+            Request::parse("open-url  https://example.com/rss ").unwrap(),
+            Request::OpenUrl(String::from("https://example.com/rss"))
+        );
+        assert!(Request::parse("open-url").is_err());
+        assert!(Request::parse("refresh now").is_err());
+        assert!(Request::parse("reboot").is_err());
+        let request = Request::OpenUrl(String::from("feed://example.com/rss"));
+        assert_eq!(
+            Request::parse(request.to_string().as_str()).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_send_requests_to_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tuifeed.sock");
+        assert!(send(path.as_path(), &Request::Refresh).is_err());
+        let server = Server::bind(path.as_path()).unwrap();
+        assert!(server.poll().is_none());
+        // Only one instance can listen on the socket
+        assert!(Server::bind(path.as_path()).is_err());
+        let client = std::thread::spawn({
+            let path = path.clone();
+            move || send(path.as_path(), &Request::Refresh)
+        });
+        let request = loop {
+            if let Some(request) = server.poll() {
+                break request;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(request, Request::Refresh);
+        assert!(client.join().unwrap().is_ok());
+        drop(server);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn should_replace_stale_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tuifeed.sock");
+        // Bind and leak the socket file, as if the instance crashed
+        drop(UnixListener::bind(path.as_path()).unwrap());
+        assert!(path.exists());
+        assert!(Server::bind(path.as_path()).is_ok());
+    }
+}
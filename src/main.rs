@@ -31,22 +31,37 @@ extern crate lazy_static;
 extern crate tuirealm;
 
 use argh::FromArgs;
+use log::{info, warn, LevelFilter};
+use std::collections::HashSet;
 use std::env;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use url::Url;
 
 // includes
 mod config;
+mod daemon;
+mod digest;
 mod feed;
 mod helpers;
+mod ipc;
+mod script;
+mod store;
+mod sync;
 mod ui;
 
 // -- internal
-use config::serializer as config_serializer;
-use config::Config;
+use config::{writer as config_writer, ArticlePattern, CacheConfig, CheatsheetFormat, Config};
+use daemon::Daemon;
+use digest::{parse_period, DigestEntry, DigestFormat};
+use feed::FeedLimits;
 use helpers::file as file_helpers;
+use helpers::logger;
+use helpers::mail as mail_helpers;
 use helpers::open as open_helpers;
 use helpers::path as path_helpers;
+use store::Store;
 use ui::Ui;
 
 #[derive(FromArgs)]
@@ -54,8 +69,40 @@ use ui::Ui;
 Please, report issues to <https://github.com/veeso/tuifeed>
 Please, consider supporting the author <https://www.buymeacoffee.com/veeso>")]
 struct Args {
-    #[argh(switch, short = 'c', description = "open tuifeed configuration")]
-    config: bool,
+    #[argh(subcommand)]
+    command: Option<Command>,
+    #[argh(
+        option,
+        short = 'c',
+        description = "use configuration file at path, instead of the default one"
+    )]
+    config: Option<PathBuf>,
+    #[argh(option, description = "store articles in this directory")]
+    data_dir: Option<PathBuf>,
+    #[argh(
+        option,
+        short = 'p',
+        description = "use profile; profiles have their own configuration and articles"
+    )]
+    profile: Option<String>,
+    #[argh(switch, short = 'e', description = "edit tuifeed configuration")]
+    edit: bool,
+    #[argh(
+        switch,
+        description = "don't fetch sources, but load their articles from the store"
+    )]
+    offline: bool,
+    #[argh(
+        option,
+        description = "write logs to this file; default tuifeed.log in the data directory"
+    )]
+    log_file: Option<PathBuf>,
+    #[argh(
+        option,
+        default = "LevelFilter::Warn",
+        description = "set log level (off, error, warn, info, debug, trace); default warn"
+    )]
+    log_level: LevelFilter,
     #[argh(
         option,
         short = 'T',
@@ -67,6 +114,235 @@ struct Args {
     version: bool,
 }
 
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Check(CheckArgs),
+    Ctl(CtlArgs),
+    Daemon(DaemonArgs),
+    Digest(DigestArgs),
+    Export(ExportArgs),
+    ExportHtml(ExportHtmlArgs),
+    ImportBookmarks(ImportBookmarksArgs),
+    Init(InitArgs),
+    Keys(KeysArgs),
+    ServeFeed(ServeFeedArgs),
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "check",
+    description = "fetch all sources and report their state"
+)]
+struct CheckArgs {
+    #[argh(
+        switch,
+        description = "update the configuration with the urls of the sources which have been moved permanently"
+    )]
+    fix: bool,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "ctl",
+    description = "send a command to the running instance of tuifeed"
+)]
+struct CtlArgs {
+    #[argh(subcommand)]
+    command: CtlCommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum CtlCommand {
+    OpenUrl(CtlOpenUrlArgs),
+    Refresh(CtlRefreshArgs),
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "open-url",
+    description = "subscribe to the feed at url; feed: urls of the browsers are supported"
+)]
+struct CtlOpenUrlArgs {
+    #[argh(positional, description = "feed url")]
+    url: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "refresh", description = "fetch all sources again")]
+struct CtlRefreshArgs {}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "daemon",
+    description = "fetch sources in background, storing articles and notifying the new ones"
+)]
+struct DaemonArgs {
+    #[argh(switch, description = "fetch all sources once and exit")]
+    once: bool,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "digest",
+    description = "render the unread articles fetched recently into an HTML digest, and send it by email"
+)]
+struct DigestArgs {
+    #[argh(
+        option,
+        default = "chrono::Duration::hours(24)",
+        from_str_fn(parse_period),
+        description = "include the articles fetched in this period (e.g. 30m, 24h, 7d); default 24h"
+    )]
+    since: chrono::Duration,
+    #[argh(
+        option,
+        description = "send the digest to this address, instead of writing it to the standard output"
+    )]
+    mailto: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "export",
+    description = "write the starred articles into a single document"
+)]
+struct ExportArgs {
+    #[argh(option, description = "export only the articles with this tag")]
+    tag: Option<String>,
+    #[argh(
+        switch,
+        description = "export the articles which haven't been read, instead of the starred ones"
+    )]
+    unread: bool,
+    #[argh(
+        option,
+        short = 'f',
+        default = "DigestFormat::Markdown",
+        description = "document format (markdown, html); default markdown"
+    )]
+    format: DigestFormat,
+    #[argh(
+        option,
+        short = 'o',
+        description = "write the document to this file, instead of the standard output"
+    )]
+    output: Option<PathBuf>,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "export-html",
+    description = "render the stored articles of all sources into a static HTML page, sorted by date"
+)]
+struct ExportHtmlArgs {
+    #[argh(
+        option,
+        short = 'o',
+        description = "write index.html into this directory, creating it if necessary"
+    )]
+    out: PathBuf,
+    #[argh(option, description = "export only the sources of this category")]
+    category: Option<String>,
+    #[argh(option, description = "title of the page; default tuifeed planet")]
+    title: Option<String>,
+    #[argh(
+        option,
+        default = "100",
+        description = "max amount of articles on the page; default 100"
+    )]
+    max_articles: usize,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "import-bookmarks",
+    description = "look for the feeds of the websites in a bookmarks file exported by the browser, and choose which ones to subscribe to"
+)]
+struct ImportBookmarksArgs {
+    #[argh(
+        positional,
+        description = "bookmarks file, in the Netscape format (bookmarks.html)"
+    )]
+    file: PathBuf,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "init",
+    description = "write a commented example configuration to the configuration file"
+)]
+struct InitArgs {
+    #[argh(switch, description = "overwrite the configuration file, if it exists")]
+    force: bool,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "keys",
+    description = "print the key bindings, with your overrides, as a cheatsheet"
+)]
+struct KeysArgs {
+    #[argh(
+        option,
+        short = 'f',
+        default = "CheatsheetFormat::Text",
+        description = "cheatsheet format (md, txt); default txt"
+    )]
+    format: CheatsheetFormat,
+}
+
+#[derive(FromArgs)]
+#[argh(
+    subcommand,
+    name = "serve-feed",
+    description = "merge the stored articles matching the filter into a single RSS feed"
+)]
+struct ServeFeedArgs {
+    #[argh(
+        option,
+        description = "keep only the articles whose title matches this regex"
+    )]
+    title: Option<String>,
+    #[argh(
+        option,
+        description = "keep only the articles with an author matching this regex"
+    )]
+    author: Option<String>,
+    #[argh(
+        option,
+        description = "keep only the articles of the sources whose name matches this regex"
+    )]
+    feed: Option<String>,
+    #[argh(option, description = "merge only the sources of this category")]
+    category: Option<String>,
+    #[argh(option, description = "title of the feed; default tuifeed")]
+    name: Option<String>,
+    #[argh(
+        option,
+        default = "100",
+        description = "max amount of articles in the feed; default 100"
+    )]
+    max_articles: usize,
+    #[argh(
+        option,
+        short = 'o',
+        description = "write the feed to this file, instead of the standard output"
+    )]
+    out: Option<PathBuf>,
+}
+
 fn main() {
     let args: Args = argh::from_env();
     // Print version
@@ -77,9 +353,41 @@ fn main() {
         );
         exit(255);
     }
+    // Check profile
+    if let Some(profile) = args.profile.as_deref() {
+        if !path_helpers::is_valid_profile(profile) {
+            eprintln!(
+                "Invalid profile name \"{}\": only alphanumeric characters, '-' and '_' are allowed",
+                profile
+            );
+            exit(255);
+        }
+    }
+    let profile = args.profile.as_deref();
+    // Write example configuration
+    if let Some(Command::Init(init)) = &args.command {
+        match write_example_config(args.config, profile, init.force) {
+            Ok(p) => {
+                println!("Configuration written to {}", p.display());
+                exit(0)
+            }
+            Err(e) => {
+                eprintln!("Could not write configuration: {}", e);
+                exit(1);
+            }
+        }
+    }
+    // Get configuration file
+    let config_file = match get_config_file(args.config, profile) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Failed to initialize configuration file: {}", e);
+            exit(255);
+        }
+    };
     // Open config file
-    if args.config {
-        if let Err(e) = edit_config_file() {
+    if args.edit {
+        if let Err(e) = edit_config_file(config_file.as_deref()) {
             eprintln!("{}", e);
             exit(255)
         } else {
@@ -87,29 +395,183 @@ fn main() {
         }
     }
     // Get configuration
-    let config = match init_config() {
+    let config = match init_config(config_file.as_deref()) {
         Ok(cfg) => cfg,
         Err(e) => {
             eprintln!("Failed to initialize configuration: {}", e);
-            eprintln!("If necessary, you can run tuifeed with `-c` option to edit and eventually fix your configuration file");
+            if let Some(excerpt) = e.excerpt() {
+                eprintln!("{}", excerpt);
+            }
+            eprintln!("If necessary, you can run tuifeed with `-e` option to edit and eventually fix your configuration file");
             exit(255);
         }
     };
+    // Report invalid sources; the UI shows them in the error popup
+    if config.sources.is_empty() || args.command.is_some() {
+        for err in config.source_errors.iter() {
+            eprintln!("{}", err);
+        }
+    }
+    // Print key bindings
+    if let Some(Command::Keys(keys)) = &args.command {
+        print!("{}", config.keys.cheatsheet(keys.format));
+        exit(0);
+    }
+    // Import bookmarks; the configuration may have no sources yet
+    if let Some(Command::ImportBookmarks(import)) = &args.command {
+        match import_bookmarks(&config, config_file.as_deref(), import.file.as_path()) {
+            Ok(added) => {
+                println!("{} sources added", added);
+                exit(0)
+            }
+            Err(e) => {
+                eprintln!("Could not import bookmarks: {}", e);
+                exit(1);
+            }
+        }
+    }
     // Check if configured
     if config.sources.is_empty() {
-        eprintln!("tuifeed must be configured first. Run `tuifeed -c`");
+        eprintln!("tuifeed must be configured first. Run `tuifeed -e`");
         exit(255);
     }
+    // Check sources
+    if let Some(Command::Check(check)) = &args.command {
+        match check_sources(&config, config_file.as_deref(), check.fix) {
+            true => exit(0),
+            false => exit(1),
+        }
+    }
+    // Get data directory
+    let data_dir = match init_data_dir(args.data_dir.clone(), profile) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Failed to initialize data directory: {}", e);
+            exit(255);
+        }
+    };
+    // Send command to the running instance
+    if let Some(Command::Ctl(ctl)) = &args.command {
+        let request = match &ctl.command {
+            CtlCommand::OpenUrl(open) => ipc::Request::OpenUrl(open.url.clone()),
+            CtlCommand::Refresh(_) => ipc::Request::Refresh,
+        };
+        match send_request(data_dir.as_deref(), &request) {
+            Ok(()) => exit(0),
+            Err(e) => {
+                eprintln!("Could not send command: {}", e);
+                exit(1);
+            }
+        }
+    }
+    // Setup logging
+    let cache_dir = match args.data_dir {
+        // Isolated instances keep everything in their data directory
+        Some(_) => Ok(data_dir.clone()),
+        None => init_cache_dir(profile),
+    };
+    let log_file = match (args.log_file, cache_dir) {
+        (Some(p), _) => Some(p),
+        (None, Ok(cache_dir)) => cache_dir.as_deref().map(path_helpers::get_log_file),
+        (None, Err(e)) => {
+            eprintln!("Failed to initialize cache directory: {}", e);
+            exit(255);
+        }
+    };
+    if let Some(log_file) = log_file {
+        if let Err(e) = logger::init_logger(log_file.as_path(), args.log_level) {
+            eprintln!("Failed to initialize logging: {}", e);
+            exit(255);
+        }
+    }
+    info!("tuifeed {} started", TUIFEED_VERSION);
+    // Open article store
+    let store = match init_store(data_dir.as_deref(), &config.cache) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Failed to open article store: {}", e);
+            exit(255);
+        }
+    };
+    // Export articles
+    if let Some(Command::Export(export)) = &args.command {
+        match export_digest(&store, export) {
+            Ok(()) => exit(0),
+            Err(e) => {
+                eprintln!("Could not export articles: {}", e);
+                exit(1);
+            }
+        }
+    }
+    // Export static site
+    if let Some(Command::ExportHtml(export)) = &args.command {
+        match export_html(&config, &store, export) {
+            Ok(p) => {
+                println!("Site written to {}", p.display());
+                exit(0)
+            }
+            Err(e) => {
+                eprintln!("Could not export site: {}", e);
+                exit(1);
+            }
+        }
+    }
+    // Merge filtered feed
+    if let Some(Command::ServeFeed(serve)) = &args.command {
+        match serve_feed(&config, &store, serve) {
+            Ok(()) => exit(0),
+            Err(e) => {
+                eprintln!("Could not make feed: {}", e);
+                exit(1);
+            }
+        }
+    }
+    // Send digest
+    if let Some(Command::Digest(digest)) = &args.command {
+        match send_digest(&config, &store, digest) {
+            Ok(()) => exit(0),
+            Err(e) => {
+                eprintln!("Could not make digest: {}", e);
+                exit(1);
+            }
+        }
+    }
+    // Run daemon
+    if let Some(Command::Daemon(daemon)) = args.command {
+        if data_dir.is_none() {
+            eprintln!("tuifeed daemon requires a data directory to store articles into");
+            exit(255);
+        }
+        info!("running as daemon");
+        Daemon::new(config, config_file, store).run(daemon.once);
+        exit(0);
+    }
+    // Listen for commands
+    let control = data_dir.as_deref().and_then(init_control_socket);
     // Run ui
-    Ui::new(config, args.ticks).run();
+    match Ui::new(
+        config,
+        config_file,
+        data_dir.as_deref(),
+        store,
+        control,
+        args.offline,
+        args.ticks,
+    ) {
+        Ok(mut ui) => ui.run(),
+        Err(e) => {
+            eprintln!("Failed to load article store: {}", e);
+            exit(255);
+        }
+    }
 }
 
 /// ### edit_config_file
 ///
 /// Edit configuration file
-fn edit_config_file() -> Result<(), String> {
-    if let Some(p) = get_config_file() {
-        if let Err(e) = open_helpers::open_text_file(p.as_path()) {
+fn edit_config_file(config_file: Option<&Path>) -> Result<(), String> {
+    if let Some(p) = config_file {
+        if let Err(e) = open_helpers::open_text_file(p) {
             eprintln!();
             Err(format!("Could not open configuration file: {}", e))
         } else {
@@ -123,28 +585,493 @@ fn edit_config_file() -> Result<(), String> {
 /// ### init_config
 ///
 /// Initialize configuration
-fn init_config() -> Result<Config, String> {
-    let config_dir = path_helpers::init_config_dir()?;
-    if config_dir.is_none() {
-        return Ok(Config::default());
+fn init_config(config_file: Option<&Path>) -> Result<Config, config::SerializerError> {
+    match config_file {
+        // Parse configuration
+        Some(p) => Config::load(p),
+        None => Ok(Config::default()),
     }
-    let config_file = path_helpers::get_config_file(config_dir.unwrap().as_path())?;
-    // Parse configuration
-    let config_file =
-        file_helpers::open_file_read(config_file.as_path()).map_err(|e| e.to_string())?;
-    config_serializer::deserialize(config_file).map_err(|e| e.to_string())
 }
 
-/// ### get_config_file
+/// ### check_sources
+///
+/// Fetch all sources and print their state.
+/// If `fix` is set, the urls of the sources moved permanently are updated in the configuration.
+/// Returns whether all sources are fine
+fn check_sources(config: &Config, config_file: Option<&Path>, fix: bool) -> bool {
+    let client = feed::Client::default().ca_file(config.tls.ca_file.clone());
+    let mut names: Vec<&String> = config.sources.keys().collect();
+    names.sort();
+    let mut ok = true;
+    let mut moved = false;
+    for name in names.into_iter() {
+        let source = &config.sources[name];
+        let feed = match client.fetch(
+            source.url.as_str(),
+            &FeedLimits::default(),
+            &source.fetch_options(),
+        ) {
+            Ok(feed) => feed,
+            Err(err) => {
+                println!("✘ {}: {}", name, err);
+                ok = false;
+                continue;
+            }
+        };
+        match feed.moved_to() {
+            None => println!("✔ {}", name),
+            Some(url) if fix => {
+                let result = match config_file {
+                    Some(p) => {
+                        config_writer::set_source_url(p, name, url).map_err(|e| e.to_string())
+                    }
+                    None => Err(String::from("there's no configuration file")),
+                };
+                match result {
+                    Ok(()) => println!("✔ {}: moved to {}; configuration updated", name, url),
+                    Err(err) => {
+                        println!(
+                            "✘ {}: moved to {}, but the configuration could not be updated: {}",
+                            name, url, err
+                        );
+                        ok = false;
+                    }
+                }
+            }
+            Some(url) => {
+                println!("➜ {}: moved permanently to {}", name, url);
+                moved = true;
+            }
+        }
+    }
+    if moved {
+        println!("Run `tuifeed check --fix` to update the configuration");
+    }
+    ok
+}
+
+/// ### import_bookmarks
+///
+/// Look for the feeds of the websites bookmarked in the Netscape bookmark file at `p`, asking for each
+/// website which feed to subscribe to. Websites whose feeds are all subscribed already are skipped.
+/// Returns the amount of sources added to the configuration
+fn import_bookmarks(
+    config: &Config,
+    config_file: Option<&Path>,
+    p: &Path,
+) -> Result<usize, String> {
+    let config_file = config_file.ok_or_else(|| String::from("there's no configuration file"))?;
+    let bookmarks = feed::load_bookmarks(p)?;
+    if bookmarks.is_empty() {
+        return Err(format!("no bookmarks found in {}", p.display()));
+    }
+    let mut subscribed: HashSet<String> = config.sources.values().map(|x| x.url.clone()).collect();
+    let mut names: HashSet<String> = config.sources.keys().cloned().collect();
+    let mut added = 0;
+    for (i, bookmark) in bookmarks.iter().enumerate() {
+        if subscribed.contains(&bookmark.url) {
+            continue;
+        }
+        println!("[{}/{}] {}", i + 1, bookmarks.len(), bookmark.url);
+        let feeds: Vec<String> = match feed::discover_feeds(bookmark.url.as_str()) {
+            Ok(feeds) => feeds
+                .into_iter()
+                .filter(|x| !subscribed.contains(x))
+                .collect(),
+            Err(err) => {
+                println!("✘ {}", err);
+                continue;
+            }
+        };
+        if feeds.is_empty() {
+            println!("✔ already subscribed");
+            continue;
+        }
+        for (n, url) in feeds.iter().enumerate() {
+            println!("  {}) {}", n + 1, url);
+        }
+        let url = match ask_feed(feeds.len())? {
+            BookmarkAnswer::Add(n) => feeds[n].clone(),
+            BookmarkAnswer::Skip => continue,
+            BookmarkAnswer::Quit => break,
+        };
+        let name = Some(bookmark.title.clone())
+            .filter(|x| !x.is_empty())
+            .or_else(|| {
+                Url::parse(url.as_str())
+                    .ok()
+                    .and_then(|x| x.host_str().map(String::from))
+            })
+            .unwrap_or_else(|| url.clone());
+        if names.contains(&name) {
+            println!("✘ a source named \"{}\" already exists", name);
+            continue;
+        }
+        config_writer::add_source(config_file, name.as_str(), url.as_str())
+            .map_err(|e| e.to_string())?;
+        println!("✔ subscribed to \"{}\"", name);
+        subscribed.insert(url);
+        names.insert(name);
+        added += 1;
+    }
+    Ok(added)
+}
+
+/// ## BookmarkAnswer
+///
+/// What to do with the feeds found for a bookmark
+enum BookmarkAnswer {
+    /// Subscribe to the feed at this index
+    Add(usize),
+    Skip,
+    Quit,
+}
+
+/// ### ask_feed
+///
+/// Ask which of the `feeds` found for a bookmark to subscribe to: `y` chooses the first one, a number
+/// the feed listed with it, `q` stops importing; anything else skips the bookmark
+fn ask_feed(feeds: usize) -> Result<BookmarkAnswer, String> {
+    let choices = match feeds {
+        1 => String::from("y/N/q"),
+        n => format!("y/1-{}/N/q", n),
+    };
+    print!("Subscribe? [{}] ", choices);
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    let mut answer = String::new();
+    if io::stdin()
+        .read_line(&mut answer)
+        .map_err(|e| e.to_string())?
+        == 0
+    {
+        // End of input
+        return Ok(BookmarkAnswer::Quit);
+    }
+    let answer = answer.trim().to_lowercase();
+    Ok(match answer.as_str() {
+        "y" | "yes" => BookmarkAnswer::Add(0),
+        "q" | "quit" => BookmarkAnswer::Quit,
+        n => match n.parse::<usize>() {
+            Ok(n) if (1..=feeds).contains(&n) => BookmarkAnswer::Add(n - 1),
+            _ => BookmarkAnswer::Skip,
+        },
+    })
+}
+
+/// ### export_digest
+///
+/// Write the stored articles selected by `args` into a digest document
+fn export_digest(store: &Store, args: &ExportArgs) -> Result<(), String> {
+    let tags = store.tags().map_err(|e| e.to_string())?;
+    let mut notes = store.notes().map_err(|e| e.to_string())?;
+    let entries: Vec<DigestEntry> = store
+        .kept_articles(args.tag.as_deref(), args.unread)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(source, article)| DigestEntry {
+            tags: tags.get(&article.url).cloned().unwrap_or_default(),
+            note: notes.remove(&article.url),
+            source,
+            article,
+        })
+        .collect();
+    let today = chrono::Local::now().format("%Y-%m-%d");
+    let title = match args.tag.as_deref() {
+        Some(tag) => format!("tuifeed digest · #{} · {}", tag, today),
+        None => format!("tuifeed digest · {}", today),
+    };
+    let doc = digest::render(title.as_str(), entries.as_slice(), args.format);
+    match args.output.as_deref() {
+        Some(p) => std::fs::write(p, doc).map_err(|e| format!("{}: {}", p.display(), e)),
+        None => {
+            print!("{}", doc);
+            Ok(())
+        }
+    }
+}
+
+/// ### export_html
+///
+/// Render the stored articles of the sources selected by `args`, from the most recent, into the `index.html`
+/// page of the output directory, colored after the configured theme. Returns the path of the page
+fn export_html(config: &Config, store: &Store, args: &ExportHtmlArgs) -> Result<PathBuf, String> {
+    let entries = stored_entries(
+        config,
+        store,
+        args.category.as_deref(),
+        None,
+        args.max_articles,
+    )?;
+    let title = args
+        .title
+        .clone()
+        .or_else(|| {
+            args.category
+                .as_ref()
+                .map(|x| format!("tuifeed planet · {}", x))
+        })
+        .unwrap_or_else(|| String::from("tuifeed planet"));
+    let doc = digest::render_planet(title.as_str(), entries.as_slice(), config.theme.preset);
+    std::fs::create_dir_all(args.out.as_path())
+        .map_err(|e| format!("{}: {}", args.out.display(), e))?;
+    let p = args.out.join("index.html");
+    std::fs::write(p.as_path(), doc).map_err(|e| format!("{}: {}", p.display(), e))?;
+    Ok(p)
+}
+
+/// ### serve_feed
+///
+/// Merge the stored articles matching the filter of `args` into a RSS feed, written to the output file
+/// of `args` or to the standard output
+fn serve_feed(config: &Config, store: &Store, args: &ServeFeedArgs) -> Result<(), String> {
+    let pattern = match (&args.title, &args.author, &args.feed) {
+        (None, None, None) => None,
+        (title, author, feed) => Some(ArticlePattern::new(
+            title.clone(),
+            author.clone(),
+            feed.clone(),
+        )?),
+    };
+    let entries = stored_entries(
+        config,
+        store,
+        args.category.as_deref(),
+        pattern.as_ref(),
+        args.max_articles,
+    )?;
+    let doc = digest::render_rss(
+        args.name.as_deref().unwrap_or("tuifeed"),
+        entries.as_slice(),
+    );
+    match args.out.as_deref() {
+        Some(p) => std::fs::write(p, doc).map_err(|e| format!("{}: {}", p.display(), e)),
+        None => {
+            print!("{}", doc);
+            Ok(())
+        }
+    }
+}
+
+/// ### stored_entries
+///
+/// Get the stored articles of the sources in `category`, or of all sources, matching `pattern` if set.
+/// Articles are sorted from the most recent, up to `max_articles`; an article saved by many sources is listed once
+fn stored_entries(
+    config: &Config,
+    store: &Store,
+    category: Option<&str>,
+    pattern: Option<&ArticlePattern>,
+    max_articles: usize,
+) -> Result<Vec<DigestEntry>, String> {
+    let mut names: Vec<&String> = config
+        .sources
+        .iter()
+        .filter(|(_, source)| category.is_none() || source.category.as_deref() == category)
+        .map(|(name, _)| name)
+        .collect();
+    if names.is_empty() {
+        return Err(format!(
+            "there are no sources in category \"{}\"",
+            category.unwrap_or_default()
+        ));
+    }
+    names.sort();
+    let mut urls = HashSet::new();
+    let mut entries: Vec<DigestEntry> = Vec::new();
+    for name in names.into_iter() {
+        let feed = match store.load_feed(name).map_err(|e| e.to_string())? {
+            Some(feed) => feed,
+            None => continue,
+        };
+        entries.extend(
+            feed.articles()
+                .filter(|x| pattern.map(|p| p.matches(name, x)).unwrap_or(true))
+                .filter(|x| urls.insert(x.url.clone()))
+                .map(|article| DigestEntry {
+                    source: name.clone(),
+                    article: article.clone(),
+                    tags: Vec::new(),
+                    note: None,
+                }),
+        );
+    }
+    entries.sort_by_key(|x| std::cmp::Reverse(x.article.date));
+    entries.truncate(max_articles);
+    Ok(entries)
+}
+
+/// ### send_digest
+///
+/// Render the unread articles fetched in the period of `args` into an HTML digest, and send it to
+/// the address of `args` or write it to the standard output. Nothing is sent if there are no articles
+fn send_digest(config: &Config, store: &Store, args: &DigestArgs) -> Result<(), String> {
+    let since = chrono::Local::now() - args.since;
+    let tags = store.tags().map_err(|e| e.to_string())?;
+    let mut notes = store.notes().map_err(|e| e.to_string())?;
+    let entries: Vec<DigestEntry> = store
+        .unread_articles_since(since)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(source, article)| DigestEntry {
+            tags: tags.get(&article.url).cloned().unwrap_or_default(),
+            note: notes.remove(&article.url),
+            source,
+            article,
+        })
+        .collect();
+    let title = format!(
+        "tuifeed digest · {} · {} new articles",
+        chrono::Local::now().format("%Y-%m-%d"),
+        entries.len()
+    );
+    let doc = digest::render(title.as_str(), entries.as_slice(), DigestFormat::Html);
+    match args.mailto.as_deref() {
+        Some(_) if entries.is_empty() => {
+            println!("No new articles to send");
+            Ok(())
+        }
+        Some(to) => mail_helpers::send_html_email(&config.email, to, title.as_str(), doc),
+        None => {
+            print!("{}", doc);
+            Ok(())
+        }
+    }
+}
+
+/// ### init_data_dir
+///
+/// Get `data_dir` or the default data directory of `profile`, creating it if necessary.
+/// Returns None, if there's no data directory
+fn init_data_dir(
+    data_dir: Option<PathBuf>,
+    profile: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    match (data_dir, profile) {
+        (Some(p), _) => {
+            path_helpers::init_dir(p.as_path())?;
+            Ok(Some(p))
+        }
+        (None, Some(profile)) => match path_helpers::init_data_dir()? {
+            Some(p) => path_helpers::get_profile_dir(p.as_path(), profile).map(Some),
+            None => Ok(None),
+        },
+        (None, None) => path_helpers::init_data_dir(),
+    }
+}
+
+/// ### init_cache_dir
+///
+/// Get the default cache directory of `profile`, creating it if necessary.
+/// Returns None, if there's no cache directory
+fn init_cache_dir(profile: Option<&str>) -> Result<Option<PathBuf>, String> {
+    match (path_helpers::init_cache_dir()?, profile) {
+        (Some(p), Some(profile)) => path_helpers::get_profile_dir(p.as_path(), profile).map(Some),
+        (cache_dir, _) => Ok(cache_dir),
+    }
+}
+
+/// ### init_store
 ///
-/// Get configuration file path
-fn get_config_file() -> Option<PathBuf> {
-    let config_dir = match path_helpers::init_config_dir() {
-        Ok(Some(p)) => p,
-        _ => return None,
+/// Open the article store in `data_dir`, encrypted if `cache` says so.
+/// If there's no data directory, articles are kept in memory
+fn init_store(data_dir: Option<&Path>, cache: &CacheConfig) -> Result<Store, String> {
+    let store = match (data_dir, cache.key()?) {
+        (Some(data_dir), Some(key)) => Store::open_encrypted(
+            path_helpers::get_store_file(data_dir).as_path(),
+            key.as_str(),
+        ),
+        (Some(data_dir), None) => Store::open(path_helpers::get_store_file(data_dir).as_path()),
+        (None, _) => Store::memory(),
     };
-    match path_helpers::get_config_file(config_dir.as_path()) {
-        Ok(p) => Some(p),
-        _ => None,
+    store.map_err(|e| e.to_string())
+}
+
+/// ### init_control_socket
+///
+/// Listen on the control socket in `data_dir`, so that `tuifeed ctl` can drive this instance
+fn init_control_socket(data_dir: &Path) -> Option<ipc::Server> {
+    let socket_file = path_helpers::get_socket_file(data_dir);
+    match ipc::Server::bind(socket_file.as_path()) {
+        Ok(server) => Some(server),
+        Err(e) => {
+            warn!(
+                "could not listen on control socket {}: {}",
+                socket_file.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// ### send_request
+///
+/// Send `request` to the instance listening on the control socket in `data_dir`
+fn send_request(data_dir: Option<&Path>, request: &ipc::Request) -> Result<(), String> {
+    let data_dir = data_dir.ok_or_else(|| String::from("there's no data directory"))?;
+    ipc::send(path_helpers::get_socket_file(data_dir).as_path(), request)
+}
+
+/// ### get_config_file
+///
+/// Get configuration file path; if `path` is not provided, the default one for `profile` is used.
+/// Returns None, if there's no configuration directory
+fn get_config_file(
+    path: Option<PathBuf>,
+    profile: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    match path {
+        Some(p) => path_helpers::ensure_config_file(p).map(Some),
+        None => match path_helpers::init_config_dir()? {
+            Some(config_dir) => {
+                path_helpers::get_config_file(config_dir.as_path(), profile).map(Some)
+            }
+            None => Ok(None),
+        },
+    }
+}
+
+/// ### get_config_path
+///
+/// Get configuration file path, as `get_config_file` does, without creating the file
+fn get_config_path(
+    path: Option<PathBuf>,
+    profile: Option<&str>,
+) -> Result<Option<PathBuf>, String> {
+    match path {
+        Some(p) => Ok(Some(p)),
+        None => Ok(path_helpers::init_config_dir()?
+            .map(|config_dir| path_helpers::get_config_path(config_dir.as_path(), profile))),
+    }
+}
+
+/// ### write_example_config
+///
+/// Write the example configuration to the configuration file and return its path.
+/// An existing configuration is overwritten only if `force` is set, or if it hasn't been
+/// changed since the first run
+fn write_example_config(
+    path: Option<PathBuf>,
+    profile: Option<&str>,
+    force: bool,
+) -> Result<PathBuf, String> {
+    let p = get_config_path(path, profile)?
+        .ok_or_else(|| String::from("could not find the configuration directory"))?;
+    if !config::Toml::matches(p.as_path()) {
+        return Err(format!(
+            "the example configuration is written in TOML, but {} is not a TOML file",
+            p.display()
+        ));
+    }
+    if p.exists() && !force && !path_helpers::is_initial_config_file(p.as_path()) {
+        return Err(format!(
+            "{} already exists; pass --force to overwrite it",
+            p.display()
+        ));
+    }
+    if let Some(parent) = p.parent() {
+        path_helpers::init_dir(parent)?;
     }
+    file_helpers::write_file(p.as_path(), config::EXAMPLE_CONFIG).map_err(|e| e.to_string())?;
+    Ok(p)
 }
@@ -0,0 +1,319 @@
+//! # Script
+//!
+//! User script which filters and scores articles and implements custom actions.
+//! Scripts are written in [rhai](https://rhai.rs) and are available only if tuifeed is built with the `scripting` feature
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::feed::{Article, Feed};
+
+#[cfg(feature = "scripting")]
+use crate::helpers::hooks as hook_helpers;
+#[cfg(feature = "scripting")]
+use crate::helpers::open as open_helpers;
+#[cfg(feature = "scripting")]
+use log::warn;
+#[cfg(feature = "scripting")]
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::path::Path;
+
+/// Max amount of operations of a single call, so that a script stuck in a loop doesn't freeze tuifeed
+#[cfg(feature = "scripting")]
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// ## Script
+///
+/// A compiled user script. The script may define:
+///
+/// - `filter(source, article)`: articles for which it returns `false` are discarded
+/// - `score(source, article)`: the returned integer is added to the score of the article
+/// - actions, as functions taking `(source, article)`; the string they return, if any, is shown in the status bar
+///
+/// Articles are passed as maps with `title`, `authors`, `summary`, `url`, `comments`, `categories`, `date`
+/// (RFC 3339), `flair`, `score` and `interest`; missing values are `()`.
+/// Scripts can call `open(url)` to open a link in the browser and `run(command)` to run a command in background
+pub struct Script {
+    #[cfg(feature = "scripting")]
+    engine: Engine,
+    #[cfg(feature = "scripting")]
+    ast: AST,
+}
+
+#[cfg(feature = "scripting")]
+impl Script {
+    /// ### load
+    ///
+    /// Load and compile the script at `p`
+    pub fn load(p: &Path) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.register_fn("open", |url: &str| {
+            if let Err(err) = open_helpers::open_link(url) {
+                warn!("script could not open {}: {}", url, err);
+            }
+        });
+        engine.register_fn("run", |command: &str| {
+            if let Err(err) = hook_helpers::run_hook(command, "script", &[], String::new()) {
+                warn!("script could not run `{}`: {}", command, err);
+            }
+        });
+        let ast = engine
+            .compile_file(p.to_path_buf())
+            .map_err(|e| format!("could not load script {}: {}", p.display(), e))?;
+        Ok(Self { engine, ast })
+    }
+
+    /// ### process_feed
+    ///
+    /// Discard the articles of `feed` rejected by the `filter` function of the script,
+    /// then add the result of its `score` function to the score of the remaining ones.
+    /// Articles the script fails on are kept as they are
+    pub fn process_feed(&self, source: &str, feed: &mut Feed) {
+        if self.has_function("filter") {
+            feed.articles.retain(|article| {
+                self.call::<bool>("filter", source, article)
+                    .unwrap_or_else(|err| {
+                        warn!("script filter failed on {}: {}", article.url, err);
+                        true
+                    })
+            });
+        }
+        if self.has_function("score") {
+            for article in feed.articles.iter_mut() {
+                match self.call::<i64>("score", source, article) {
+                    Ok(delta) => article.interest = Some(article.interest.unwrap_or(0) + delta),
+                    Err(err) => warn!("script score failed on {}: {}", article.url, err),
+                }
+            }
+        }
+    }
+
+    /// ### run_action
+    ///
+    /// Call the action `function` on the `article` of `source`.
+    /// Returns the message returned by the action, if any
+    pub fn run_action(
+        &self,
+        function: &str,
+        source: &str,
+        article: &Article,
+    ) -> Result<Option<String>, String> {
+        if !self.has_function(function) {
+            return Err(format!("the script has no function `{}`", function));
+        }
+        self.call::<Dynamic>(function, source, article)
+            .map(|x| x.try_cast::<String>())
+    }
+
+    // -- private
+
+    /// ### has_function
+    ///
+    /// Returns whether the script defines `name`, taking the source and the article
+    fn has_function(&self, name: &str) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|x| x.name == name && x.params.len() == 2)
+    }
+
+    /// ### call
+    ///
+    /// Call `function` with `source` and `article`, casting its result to `T`
+    fn call<T: Clone + 'static>(
+        &self,
+        function: &str,
+        source: &str,
+        article: &Article,
+    ) -> Result<T, String> {
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(
+                &mut Scope::new(),
+                &self.ast,
+                function,
+                (source.to_string(), article_map(article)),
+            )
+            .map_err(|e| e.to_string())?;
+        let type_name = result.type_name();
+        result
+            .try_cast::<T>()
+            .ok_or_else(|| format!("`{}` returned an unexpected {}", function, type_name))
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+impl Script {
+    /// ### load
+    ///
+    /// Scripts are not available without the `scripting` feature
+    pub fn load(_p: &Path) -> Result<Self, String> {
+        Err(String::from(
+            "tuifeed has been built without the `scripting` feature",
+        ))
+    }
+
+    /// ### process_feed
+    ///
+    /// Does nothing without the `scripting` feature
+    pub fn process_feed(&self, _source: &str, _feed: &mut Feed) {}
+
+    /// ### run_action
+    ///
+    /// Scripts are not available without the `scripting` feature
+    pub fn run_action(
+        &self,
+        _function: &str,
+        _source: &str,
+        _article: &Article,
+    ) -> Result<Option<String>, String> {
+        Ok(None)
+    }
+}
+
+/// ### article_map
+///
+/// Make the map the scripts receive to describe `article`
+#[cfg(feature = "scripting")]
+fn article_map(article: &Article) -> Map {
+    fn opt_string(x: Option<&String>) -> Dynamic {
+        x.cloned().map(Dynamic::from).unwrap_or(Dynamic::UNIT)
+    }
+    fn strings(x: &[String]) -> Dynamic {
+        Dynamic::from(x.iter().cloned().map(Dynamic::from).collect::<Array>())
+    }
+    let mut map = Map::new();
+    map.insert("title".into(), opt_string(article.title.as_ref()));
+    map.insert("authors".into(), strings(article.authors.as_slice()));
+    map.insert("summary".into(), Dynamic::from(article.summary.clone()));
+    map.insert("url".into(), Dynamic::from(article.url.clone()));
+    map.insert("comments".into(), opt_string(article.comments.as_ref()));
+    map.insert("categories".into(), strings(article.categories.as_slice()));
+    map.insert(
+        "date".into(),
+        opt_string(article.date.map(|x| x.to_rfc3339()).as_ref()),
+    );
+    map.insert("flair".into(), opt_string(article.flair.as_ref()));
+    map.insert(
+        "score".into(),
+        article.score.map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map.insert(
+        "interest".into(),
+        article.interest.map(Dynamic::from).unwrap_or(Dynamic::UNIT),
+    );
+    map
+}
+
+#[cfg(all(test, feature = "scripting"))]
+mod test {
+
+    use super::*;
+    use crate::feed::Channel;
+
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const SCRIPT: &str = r#"
+fn filter(source, article) {
+    !article.title.contains("Sponsored")
+}
+
+fn score(source, article) {
+    if source == "hn" && article.authors[0] == "dtolnay" { 10 } else { 0 }
+}
+
+fn shout(source, article) {
+    article.title.to_upper()
+}
+"#;
+
+    fn make_article(title: &str, author: &str) -> Article {
+        Article {
+            title: Some(title.to_string()),
+            authors: vec![author.to_string()],
+            summary: String::new(),
+            links: Vec::new(),
+            url: format!("https://example.com/{}", title),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: Some(2),
+            score: None,
+        }
+    }
+
+    fn load_script() -> Script {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "{}", SCRIPT).unwrap();
+        Script::load(file.path()).unwrap()
+    }
+
+    #[test]
+    fn should_filter_and_score_feed() {
+        let script = load_script();
+        let mut feed = Feed {
+            articles: vec![
+                make_article("serde", "dtolnay"),
+                make_article("Sponsored", "acme"),
+                make_article("tokio", "carllerche"),
+            ],
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        script.process_feed("hn", &mut feed);
+        let scores: Vec<(Option<&str>, Option<i64>)> = feed
+            .articles()
+            .map(|x| (x.title.as_deref(), x.interest))
+            .collect();
+        assert_eq!(
+            scores,
+            vec![(Some("serde"), Some(12)), (Some("tokio"), Some(2))]
+        );
+    }
+
+    #[test]
+    fn should_run_script_action() {
+        let script = load_script();
+        let article = make_article("serde", "dtolnay");
+        assert_eq!(
+            script
+                .run_action("shout", "hn", &article)
+                .unwrap()
+                .as_deref(),
+            Some("SERDE")
+        );
+        assert!(script.run_action("missing", "hn", &article).is_err());
+    }
+
+    #[test]
+    fn should_not_load_invalid_script() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "fn filter(source, article) {{").unwrap();
+        assert!(Script::load(file.path()).is_err());
+    }
+}
@@ -0,0 +1,1339 @@
+//! # Store
+//!
+//! Article store, persisted in a SQLite database
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// -- modules
+mod result;
+
+// -- export
+pub use result::{StoreError, StoreResult};
+// -- deps
+use crate::feed::{Article, Channel, Enclosure, Feed};
+
+use chrono::{DateTime, Local, TimeZone};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+#[cfg(feature = "encryption")]
+use rusqlite::{DatabaseName, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "encryption")]
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+/// Header of the SQLite databases which are not encrypted
+#[cfg(feature = "encryption")]
+const PLAINTEXT_HEADER: &[u8] = b"SQLite format 3\0";
+/// How long to wait for the database to be unlocked by another process (e.g. the daemon)
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Current database schema version, stored in `user_version`
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS articles (
+    source TEXT NOT NULL,
+    url TEXT NOT NULL,
+    title TEXT,
+    authors TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    links TEXT NOT NULL,
+    comments TEXT,
+    categories TEXT NOT NULL DEFAULT '',
+    enclosures TEXT NOT NULL DEFAULT '',
+    date INTEGER,
+    first_seen INTEGER NOT NULL,
+    read INTEGER NOT NULL DEFAULT 0,
+    starred INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (source, url)
+);
+CREATE INDEX IF NOT EXISTS articles_url ON articles (url);
+CREATE TABLE IF NOT EXISTS tags (
+    url TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (url, tag)
+);
+CREATE TABLE IF NOT EXISTS notes (
+    url TEXT PRIMARY KEY,
+    note TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sources (
+    name TEXT PRIMARY KEY,
+    last_fetch INTEGER,
+    last_error TEXT
+);
+CREATE TABLE IF NOT EXISTS channels (
+    source TEXT PRIMARY KEY,
+    title TEXT,
+    description TEXT,
+    link TEXT,
+    language TEXT,
+    generator TEXT
+);
+CREATE TABLE IF NOT EXISTS daemon (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    heartbeat INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS session (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    feed TEXT NOT NULL,
+    article TEXT,
+    scroll INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    quit INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS read_state (
+    url TEXT PRIMARY KEY,
+    read INTEGER NOT NULL,
+    updated INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS article_sorts (
+    source TEXT PRIMARY KEY,
+    sort TEXT NOT NULL
+);
+"#;
+
+/// ## Session
+///
+/// Describes where the user was when tuifeed has been closed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    /// Selected source
+    pub feed: String,
+    /// Url of the selected article
+    pub article: Option<String>,
+    /// Scroll position in the article summary
+    pub scroll: usize,
+}
+
+/// ## ReadState
+///
+/// Read flag of an article and when it has been changed, as a unix timestamp
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadState {
+    pub read: bool,
+    pub updated: i64,
+}
+
+/// ## Store
+///
+/// Keeps the fetched articles, their read and starred flags and the fetch metadata of each source,
+/// so that they survive restarts
+#[derive(Debug)]
+pub struct Store {
+    db: Connection,
+}
+
+impl Store {
+    /// ### open
+    ///
+    /// Open the store database at `p`, creating it if it doesn't exist
+    pub fn open(p: &Path) -> StoreResult<Self> {
+        Self::init(Connection::open(p)?)
+    }
+
+    /// ### open_encrypted
+    ///
+    /// Open the store database at `p`, encrypted with `key`, creating it if it doesn't exist.
+    /// A database which isn't encrypted yet is encrypted in place
+    #[cfg(feature = "encryption")]
+    pub fn open_encrypted(p: &Path, key: &str) -> StoreResult<Self> {
+        if is_plaintext(p) {
+            encrypt_database(p, key)?;
+        }
+        let db = Connection::open(p)?;
+        db.pragma_update(None, "key", key)?;
+        // With a wrong key, the database looks like garbage
+        match db.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| {
+            row.get::<_, i64>(0)
+        }) {
+            Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::NotADatabase => {
+                Err(StoreError::WrongKey)
+            }
+            Err(e) => Err(StoreError::from(e)),
+            Ok(_) => Self::init(db),
+        }
+    }
+
+    /// ### open_encrypted
+    ///
+    /// Encrypted stores are not available without the `encryption` feature
+    #[cfg(not(feature = "encryption"))]
+    pub fn open_encrypted(_p: &Path, _key: &str) -> StoreResult<Self> {
+        Err(StoreError::EncryptionUnavailable)
+    }
+
+    /// ### memory
+    ///
+    /// Open a store which lives in memory only
+    pub fn memory() -> StoreResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// ### save_feed
+    ///
+    /// Save the articles of the feed fetched for `source`.
+    /// Articles which were already stored keep their flags, while new ones get the read flag they've been
+    /// given before being fetched, e.g. by sync
+    pub fn save_feed(&mut self, source: &str, feed: &Feed) -> StoreResult<()> {
+        let now = Local::now().timestamp();
+        let tx = self.db.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO articles (source, url, title, authors, summary, links, comments, categories,
+                    enclosures, date, first_seen, read)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11,
+                    COALESCE((SELECT read FROM read_state WHERE url = ?2), 0))
+                ON CONFLICT (source, url) DO UPDATE SET
+                    title = excluded.title,
+                    authors = excluded.authors,
+                    summary = excluded.summary,
+                    links = excluded.links,
+                    comments = excluded.comments,
+                    categories = excluded.categories,
+                    enclosures = excluded.enclosures,
+                    date = excluded.date",
+            )?;
+            for article in feed.articles() {
+                stmt.execute(params![
+                    source,
+                    article.url,
+                    article.title,
+                    article.authors.join("\n"),
+                    article.summary,
+                    article.links.join("\n"),
+                    article.comments,
+                    article.categories.join("\n"),
+                    join_enclosures(article.enclosures.as_slice()),
+                    article.date.map(|x| x.timestamp()),
+                    now
+                ])?;
+            }
+        }
+        tx.execute(
+            "INSERT INTO sources (name, last_fetch, last_error) VALUES (?1, ?2, NULL)
+            ON CONFLICT (name) DO UPDATE SET last_fetch = excluded.last_fetch, last_error = NULL",
+            params![source, now],
+        )?;
+        let channel = feed.channel();
+        tx.execute(
+            "INSERT OR REPLACE INTO channels (source, title, description, link, language, generator)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                source,
+                channel.title,
+                channel.description,
+                channel.link,
+                channel.language,
+                channel.generator
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// ### save_error
+    ///
+    /// Save the error returned by the last fetch of `source`
+    pub fn save_error(&mut self, source: &str, error: &str) -> StoreResult<()> {
+        self.db.execute(
+            "INSERT INTO sources (name, last_error) VALUES (?1, ?2)
+            ON CONFLICT (name) DO UPDATE SET last_error = excluded.last_error",
+            params![source, error],
+        )?;
+        Ok(())
+    }
+
+    /// ### remove_source
+    ///
+    /// Remove `source` and all of its articles from the store
+    pub fn remove_source(&mut self, source: &str) -> StoreResult<()> {
+        let tx = self.db.transaction()?;
+        tx.execute("DELETE FROM articles WHERE source = ?1", params![source])?;
+        tx.execute("DELETE FROM sources WHERE name = ?1", params![source])?;
+        tx.execute("DELETE FROM channels WHERE source = ?1", params![source])?;
+        tx.execute(
+            "DELETE FROM article_sorts WHERE source = ?1",
+            params![source],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// ### rename_source
+    ///
+    /// Move the articles and the metadata of `source` to `new_name`
+    pub fn rename_source(&mut self, source: &str, new_name: &str) -> StoreResult<()> {
+        let tx = self.db.transaction()?;
+        tx.execute(
+            "UPDATE articles SET source = ?2 WHERE source = ?1",
+            params![source, new_name],
+        )?;
+        tx.execute(
+            "UPDATE sources SET name = ?2 WHERE name = ?1",
+            params![source, new_name],
+        )?;
+        tx.execute(
+            "UPDATE channels SET source = ?2 WHERE source = ?1",
+            params![source, new_name],
+        )?;
+        tx.execute(
+            "UPDATE article_sorts SET source = ?2 WHERE source = ?1",
+            params![source, new_name],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// ### mark_read
+    ///
+    /// Mark the article with `url` as read, in every source
+    pub fn mark_read(&mut self, url: &str) -> StoreResult<()> {
+        self.set_read_state(
+            url,
+            ReadState {
+                read: true,
+                updated: Local::now().timestamp(),
+            },
+        )
+    }
+
+    /// ### mark_unread
+    ///
+    /// Mark the article with `url` as not read, in every source
+    pub fn mark_unread(&mut self, url: &str) -> StoreResult<()> {
+        self.set_read_state(
+            url,
+            ReadState {
+                read: false,
+                updated: Local::now().timestamp(),
+            },
+        )
+    }
+
+    /// ### set_read_state
+    ///
+    /// Set the read flag of the article with `url`, in every source, recording when it has been changed.
+    /// The state is kept even if the article hasn't been fetched yet
+    pub fn set_read_state(&mut self, url: &str, state: ReadState) -> StoreResult<()> {
+        let tx = self.db.transaction()?;
+        tx.execute(
+            "UPDATE articles SET read = ?2 WHERE url = ?1",
+            params![url, state.read],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO read_state (url, read, updated) VALUES (?1, ?2, ?3)",
+            params![url, state.read, state.updated],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// ### read_articles
+    ///
+    /// Get the urls of the articles which have been read
+    pub fn read_articles(&self) -> StoreResult<HashSet<String>> {
+        let mut stmt = self.db.prepare(
+            "SELECT url FROM articles WHERE read = 1
+            UNION SELECT url FROM read_state WHERE read = 1",
+        )?;
+        let urls = stmt.query_map([], |row| row.get(0))?;
+        urls.collect::<Result<HashSet<String>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### read_states
+    ///
+    /// Get the read flag of the articles which have been marked as read or unread, with when it has been changed.
+    /// Articles read before changes were recorded are returned as changed at the epoch
+    pub fn read_states(&self) -> StoreResult<HashMap<String, ReadState>> {
+        let mut stmt = self.db.prepare(
+            "SELECT url, read, updated FROM read_state
+            UNION SELECT DISTINCT url, 1, 0 FROM articles
+            WHERE read = 1 AND url NOT IN (SELECT url FROM read_state)",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                ReadState {
+                    read: row.get(1)?,
+                    updated: row.get(2)?,
+                },
+            ))
+        })?;
+        rows.collect::<Result<HashMap<String, ReadState>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### set_tags
+    ///
+    /// Replace the tags the user attached to the article with `url`
+    pub fn set_tags(&mut self, url: &str, tags: &[String]) -> StoreResult<()> {
+        let tx = self.db.transaction()?;
+        tx.execute("DELETE FROM tags WHERE url = ?1", params![url])?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (url, tag) VALUES (?1, ?2)",
+                params![url, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// ### tags
+    ///
+    /// Get the tags the user attached to the articles, by article url
+    pub fn tags(&self) -> StoreResult<HashMap<String, Vec<String>>> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT url, tag FROM tags ORDER BY url, tag")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (url, tag): (String, String) = row?;
+            tags.entry(url).or_default().push(tag);
+        }
+        Ok(tags)
+    }
+
+    /// ### set_note
+    ///
+    /// Replace the note the user attached to the article with `url`; an empty note removes it
+    pub fn set_note(&mut self, url: &str, note: &str) -> StoreResult<()> {
+        match note.is_empty() {
+            true => self
+                .db
+                .execute("DELETE FROM notes WHERE url = ?1", params![url])?,
+            false => self.db.execute(
+                "INSERT OR REPLACE INTO notes (url, note) VALUES (?1, ?2)",
+                params![url, note],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// ### notes
+    ///
+    /// Get the notes the user attached to the articles, by article url
+    pub fn notes(&self) -> StoreResult<HashMap<String, String>> {
+        let mut stmt = self.db.prepare("SELECT url, note FROM notes")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<HashMap<String, String>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### set_article_sort
+    ///
+    /// Set the order of the articles of `source` chosen by the user; `None` restores the default one
+    pub fn set_article_sort(&mut self, source: &str, sort: Option<&str>) -> StoreResult<()> {
+        match sort {
+            None => self.db.execute(
+                "DELETE FROM article_sorts WHERE source = ?1",
+                params![source],
+            )?,
+            Some(sort) => self.db.execute(
+                "INSERT OR REPLACE INTO article_sorts (source, sort) VALUES (?1, ?2)",
+                params![source, sort],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// ### article_sorts
+    ///
+    /// Get the order of the articles chosen by the user, by source name
+    pub fn article_sorts(&self) -> StoreResult<HashMap<String, String>> {
+        let mut stmt = self.db.prepare("SELECT source, sort FROM article_sorts")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<HashMap<String, String>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### tagged_articles
+    ///
+    /// Get the stored articles with a user tag, each one with its tag, from the most recent.
+    /// An article is returned once for each of its tags
+    pub fn tagged_articles(&self) -> StoreResult<Vec<(String, Article)>> {
+        let mut stmt = self.db.prepare(
+            "SELECT tags.tag, articles.url, articles.title, articles.authors, articles.summary, articles.links,
+                articles.comments, articles.categories, articles.enclosures, articles.date
+            FROM tags JOIN articles ON articles.url = tags.url
+            GROUP BY tags.tag, articles.url
+            ORDER BY articles.date DESC",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, article_from_row(row, 1)?)))?;
+        rows.collect::<Result<Vec<(String, Article)>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### kept_articles
+    ///
+    /// Get the stored articles, each one with its source, from the most recent, keeping:
+    ///
+    /// - only the articles with the user tag `tag`, if set;
+    /// - only the articles which haven't been read, if `unread`;
+    /// - the starred articles otherwise.
+    ///
+    /// An article saved by many sources is returned once
+    pub fn kept_articles(
+        &self,
+        tag: Option<&str>,
+        unread: bool,
+    ) -> StoreResult<Vec<(String, Article)>> {
+        let mut stmt = self.db.prepare(
+            "SELECT MIN(source), url, title, authors, summary, links, comments, categories, enclosures, date
+            FROM articles
+            WHERE (?1 IS NULL OR url IN (SELECT url FROM tags WHERE tag = ?1))
+                AND (?2 = 0 OR read = 0)
+                AND (?1 IS NOT NULL OR ?2 = 1 OR starred = 1)
+            GROUP BY url
+            ORDER BY date DESC",
+        )?;
+        let rows = stmt.query_map(params![tag, unread], |row| {
+            Ok((row.get(0)?, article_from_row(row, 1)?))
+        })?;
+        rows.collect::<Result<Vec<(String, Article)>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### unread_articles_since
+    ///
+    /// Get the stored articles which haven't been read and have been fetched for the first time since `since`,
+    /// each one with its source, from the most recent. An article saved by many sources is returned once
+    pub fn unread_articles_since(
+        &self,
+        since: DateTime<Local>,
+    ) -> StoreResult<Vec<(String, Article)>> {
+        let mut stmt = self.db.prepare(
+            "SELECT MIN(source), url, title, authors, summary, links, comments, categories, enclosures, date
+            FROM articles
+            WHERE read = 0 AND first_seen >= ?1
+            GROUP BY url
+            ORDER BY date DESC",
+        )?;
+        let rows = stmt.query_map(params![since.timestamp()], |row| {
+            Ok((row.get(0)?, article_from_row(row, 1)?))
+        })?;
+        rows.collect::<Result<Vec<(String, Article)>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### set_starred
+    ///
+    /// Set the starred flag of the article with `url`, in every source
+    pub fn set_starred(&mut self, url: &str, starred: bool) -> StoreResult<()> {
+        self.db.execute(
+            "UPDATE articles SET starred = ?2 WHERE url = ?1",
+            params![url, starred],
+        )?;
+        Ok(())
+    }
+
+    /// ### starred_articles
+    ///
+    /// Get the urls of the articles which have been starred
+    pub fn starred_articles(&self) -> StoreResult<HashSet<String>> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT DISTINCT url FROM articles WHERE starred = 1")?;
+        let urls = stmt.query_map([], |row| row.get(0))?;
+        urls.collect::<Result<HashSet<String>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### load_feed
+    ///
+    /// Load the stored articles of `source`, from the most recent.
+    /// Returns `None` if `source` has never been fetched
+    pub fn load_feed(&self, source: &str) -> StoreResult<Option<Feed>> {
+        let fetched: Option<Option<i64>> = self
+            .db
+            .query_row(
+                "SELECT last_fetch FROM sources WHERE name = ?1",
+                params![source],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if fetched.flatten().is_none() {
+            return Ok(None);
+        }
+        let mut stmt = self.db.prepare(
+            "SELECT url, title, authors, summary, links, comments, categories, enclosures, date
+            FROM articles
+            WHERE source = ?1
+            ORDER BY date DESC",
+        )?;
+        let rows = stmt.query_map(params![source], |row| article_from_row(row, 0))?;
+        let articles = rows.collect::<Result<Vec<Article>, _>>()?;
+        let channel = self
+            .db
+            .query_row(
+                "SELECT title, description, link, language, generator FROM channels
+                WHERE source = ?1",
+                params![source],
+                |row| {
+                    Ok(Channel {
+                        title: row.get(0)?,
+                        description: row.get(1)?,
+                        link: row.get(2)?,
+                        language: row.get(3)?,
+                        generator: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?
+            .unwrap_or_default();
+        Ok(Some(Feed {
+            articles,
+            moved_to: None,
+            channel,
+        }))
+    }
+
+    /// ### last_fetch
+    ///
+    /// Get the last time `source` has been fetched successfully, if it ever was
+    pub fn last_fetch(&self, source: &str) -> StoreResult<Option<DateTime<Local>>> {
+        let fetched: Option<Option<i64>> = self
+            .db
+            .query_row(
+                "SELECT last_fetch FROM sources WHERE name = ?1",
+                params![source],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(fetched
+            .flatten()
+            .and_then(|x| Local.timestamp_opt(x, 0).single()))
+    }
+
+    /// ### article_urls
+    ///
+    /// Get the urls of the stored articles of `source`
+    pub fn article_urls(&self, source: &str) -> StoreResult<HashSet<String>> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT url FROM articles WHERE source = ?1")?;
+        let urls = stmt.query_map(params![source], |row| row.get(0))?;
+        urls.collect::<Result<HashSet<String>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### articles_seen_since
+    ///
+    /// Get the urls of the articles which have been fetched for the first time since `since`
+    pub fn articles_seen_since(&self, since: DateTime<Local>) -> StoreResult<HashSet<String>> {
+        let mut stmt = self
+            .db
+            .prepare("SELECT DISTINCT url FROM articles WHERE first_seen >= ?1")?;
+        let urls = stmt.query_map(params![since.timestamp()], |row| row.get(0))?;
+        urls.collect::<Result<HashSet<String>, _>>()
+            .map_err(StoreError::from)
+    }
+
+    /// ### set_daemon_heartbeat
+    ///
+    /// Record that the daemon is running now
+    pub fn set_daemon_heartbeat(&mut self) -> StoreResult<()> {
+        self.db.execute(
+            "INSERT INTO daemon (id, heartbeat) VALUES (0, ?1)
+            ON CONFLICT (id) DO UPDATE SET heartbeat = excluded.heartbeat",
+            params![Local::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// ### daemon_heartbeat
+    ///
+    /// Get the last time the daemon reported to be running, if it ever ran
+    pub fn daemon_heartbeat(&self) -> StoreResult<Option<DateTime<Local>>> {
+        let heartbeat: Option<i64> = self
+            .db
+            .query_row("SELECT heartbeat FROM daemon WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        Ok(heartbeat.and_then(|x| Local.timestamp_opt(x, 0).single()))
+    }
+
+    /// ### save_session
+    ///
+    /// Save the session to restore on the next startup; `None` forgets the saved one
+    pub fn save_session(&mut self, session: Option<&Session>) -> StoreResult<()> {
+        match session {
+            Some(session) => self.db.execute(
+                "INSERT INTO session (id, feed, article, scroll) VALUES (0, ?1, ?2, ?3)
+                ON CONFLICT (id) DO UPDATE SET
+                    feed = excluded.feed,
+                    article = excluded.article,
+                    scroll = excluded.scroll",
+                params![session.feed, session.article, session.scroll as i64],
+            )?,
+            None => self.db.execute("DELETE FROM session", [])?,
+        };
+        Ok(())
+    }
+
+    /// ### session
+    ///
+    /// Get the session saved when tuifeed has been closed, if any
+    pub fn session(&self) -> StoreResult<Option<Session>> {
+        self.db
+            .query_row(
+                "SELECT feed, article, scroll FROM session WHERE id = 0",
+                [],
+                |row| {
+                    let scroll: i64 = row.get(2)?;
+                    Ok(Session {
+                        feed: row.get(0)?,
+                        article: row.get(1)?,
+                        scroll: scroll.max(0) as usize,
+                    })
+                },
+            )
+            .optional()
+            .map_err(StoreError::from)
+    }
+
+    /// ### set_last_quit
+    ///
+    /// Record that tuifeed has been closed now
+    pub fn set_last_quit(&mut self) -> StoreResult<()> {
+        self.db.execute(
+            "INSERT INTO runs (id, quit) VALUES (0, ?1)
+            ON CONFLICT (id) DO UPDATE SET quit = excluded.quit",
+            params![Local::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// ### last_quit
+    ///
+    /// Get the last time tuifeed has been closed, if it ever was
+    pub fn last_quit(&self) -> StoreResult<Option<DateTime<Local>>> {
+        let quit: Option<i64> = self
+            .db
+            .query_row("SELECT quit FROM runs WHERE id = 0", [], |row| row.get(0))
+            .optional()?;
+        Ok(quit.and_then(|x| Local.timestamp_opt(x, 0).single()))
+    }
+
+    // -- private
+
+    /// ### init
+    ///
+    /// Create the database schema if the database is new
+    fn init(db: Connection) -> StoreResult<Self> {
+        db.busy_timeout(BUSY_TIMEOUT)?;
+        let version: i64 = db.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(StoreError::UnsupportedVersion(version));
+        }
+        db.execute_batch(SCHEMA)?;
+        db.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(Self { db })
+    }
+}
+
+/// ### is_plaintext
+///
+/// Returns whether the database at `p` exists and is not encrypted
+#[cfg(feature = "encryption")]
+fn is_plaintext(p: &Path) -> bool {
+    let mut header = [0; 16];
+    std::fs::File::open(p)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| header == PLAINTEXT_HEADER)
+        .unwrap_or(false)
+}
+
+/// ### encrypt_database
+///
+/// Replace the database at `p` with a copy encrypted with `key`
+#[cfg(feature = "encryption")]
+fn encrypt_database(p: &Path, key: &str) -> StoreResult<()> {
+    let encrypted = p.with_extension("encrypting");
+    {
+        let db = Connection::open(p)?;
+        db.busy_timeout(BUSY_TIMEOUT)?;
+        let version: i64 = db.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        db.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            params![encrypted.to_string_lossy().to_string(), key],
+        )?;
+        db.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        db.pragma_update(
+            Some(DatabaseName::Attached("encrypted")),
+            "user_version",
+            version,
+        )?;
+        db.execute_batch("DETACH DATABASE encrypted")?;
+    }
+    std::fs::rename(&encrypted, p).map_err(|e| StoreError::Database(e.to_string()))
+}
+
+/// ### article_from_row
+///
+/// Make an article from the columns url, title, authors, summary, links, comments, categories,
+/// enclosures and date of `row`, starting at `offset`
+fn article_from_row(row: &Row, offset: usize) -> rusqlite::Result<Article> {
+    let authors: String = row.get(offset + 2)?;
+    let links: String = row.get(offset + 4)?;
+    let categories: String = row.get(offset + 6)?;
+    let enclosures: String = row.get(offset + 7)?;
+    let date: Option<i64> = row.get(offset + 8)?;
+    Ok(Article {
+        title: row.get(offset + 1)?,
+        authors: split_lines(authors.as_str()),
+        summary: row.get(offset + 3)?,
+        links: split_lines(links.as_str()),
+        url: row.get(offset)?,
+        comments: row.get(offset + 5)?,
+        categories: split_lines(categories.as_str()),
+        date: date.and_then(|x| Local.timestamp_opt(x, 0).single()),
+        duration: None,
+        enclosures: split_enclosures(enclosures.as_str()),
+        flair: None,
+        interest: None,
+        score: None,
+    })
+}
+
+/// ### split_lines
+///
+/// Split a column which joins values with newlines
+fn split_lines(s: &str) -> Vec<String> {
+    s.lines()
+        .filter(|x| !x.is_empty())
+        .map(|x| x.to_string())
+        .collect()
+}
+
+/// ### join_enclosures
+///
+/// Join enclosures into a column, one for each line with its url and content type separated by a tab
+fn join_enclosures(enclosures: &[Enclosure]) -> String {
+    enclosures
+        .iter()
+        .map(|x| match x.content_type.as_deref() {
+            Some(content_type) => format!("{}\t{}", x.url, content_type),
+            None => x.url.clone(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// ### split_enclosures
+///
+/// Split a column written by `join_enclosures`
+fn split_enclosures(s: &str) -> Vec<Enclosure> {
+    split_lines(s)
+        .into_iter()
+        .map(|line| match line.split_once('\t') {
+            Some((url, content_type)) => Enclosure {
+                url: url.to_string(),
+                content_type: Some(content_type.to_string()),
+            },
+            None => Enclosure {
+                url: line,
+                content_type: None,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::feed::Article;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_keep_read_state_across_fetches() {
+        let mut store = Store::memory().unwrap();
+        let feed = make_feed(&["https://example.com/a", "https://example.com/b"]);
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert!(store.mark_read("https://example.com/a").is_ok());
+        // Fetch again
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert_eq!(
+            store.read_articles().unwrap(),
+            HashSet::from([String::from("https://example.com/a")])
+        );
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM articles"), 2);
+        assert!(store.mark_unread("https://example.com/a").is_ok());
+        assert!(store.read_articles().unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_star_articles() {
+        let mut store = Store::memory().unwrap();
+        let feed = make_feed(&["https://example.com/a", "https://example.com/b"]);
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert!(store.save_feed("mirror", &feed).is_ok());
+        assert!(store.set_starred("https://example.com/a", true).is_ok());
+        // Fetch again
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert_eq!(
+            store.starred_articles().unwrap(),
+            HashSet::from([String::from("https://example.com/a")])
+        );
+        assert_eq!(
+            count(&store, "SELECT COUNT(*) FROM articles WHERE starred = 1"),
+            2
+        );
+        assert!(store.set_starred("https://example.com/a", false).is_ok());
+        assert!(store.starred_articles().unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_keep_read_states() {
+        let mut store = Store::memory().unwrap();
+        let feed = make_feed(&["https://example.com/a", "https://example.com/b"]);
+        assert!(store.save_feed("example", &feed).is_ok());
+        // Articles read before the states were recorded
+        assert!(store
+            .db
+            .execute(
+                "UPDATE articles SET read = 1 WHERE url = 'https://example.com/a'",
+                []
+            )
+            .is_ok());
+        assert!(store.mark_unread("https://example.com/b").is_ok());
+        let states = store.read_states().unwrap();
+        assert_eq!(states.len(), 2);
+        assert_eq!(
+            states["https://example.com/a"],
+            ReadState {
+                read: true,
+                updated: 0
+            }
+        );
+        assert_eq!(states["https://example.com/b"].read, false);
+        // Articles which haven't been fetched yet are read once they are
+        let state = ReadState {
+            read: true,
+            updated: 1000,
+        };
+        assert!(store.set_read_state("https://example.com/c", state).is_ok());
+        assert!(store
+            .read_articles()
+            .unwrap()
+            .contains("https://example.com/c"));
+        assert!(store
+            .save_feed("example", &make_feed(&["https://example.com/c"]))
+            .is_ok());
+        assert_eq!(
+            count(&store, "SELECT COUNT(*) FROM articles WHERE read = 1"),
+            2
+        );
+        assert_eq!(store.read_states().unwrap()["https://example.com/c"], state);
+    }
+
+    #[test]
+    fn should_persist_store_on_file() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        {
+            let mut store = Store::open(tmpfile.path()).unwrap();
+            assert!(store
+                .save_feed("example", &make_feed(&["https://example.com/a"]))
+                .is_ok());
+            assert!(store.mark_read("https://example.com/a").is_ok());
+        }
+        let store = Store::open(tmpfile.path()).unwrap();
+        assert_eq!(store.read_articles().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn should_get_last_fetch() {
+        let mut store = Store::memory().unwrap();
+        assert!(store.last_fetch("example").unwrap().is_none());
+        // Errors are not successful fetches
+        assert!(store.save_error("example", "HTTP request failed").is_ok());
+        assert!(store.last_fetch("example").unwrap().is_none());
+        assert!(store
+            .save_feed("example", &make_feed(&["https://example.com/a"]))
+            .is_ok());
+        let last_fetch = store.last_fetch("example").unwrap().unwrap();
+        assert!(Local::now().signed_duration_since(last_fetch) < chrono::Duration::minutes(1));
+    }
+
+    #[test]
+    fn should_rename_and_remove_sources() {
+        let mut store = Store::memory().unwrap();
+        assert!(store
+            .save_feed("example", &make_feed(&["https://example.com/a"]))
+            .is_ok());
+        assert!(store.save_error("other", "HTTP request failed").is_ok());
+        assert!(store.rename_source("example", "renamed").is_ok());
+        assert_eq!(
+            count(
+                &store,
+                "SELECT COUNT(*) FROM articles WHERE source = 'renamed'"
+            ),
+            1
+        );
+        assert!(store.remove_source("renamed").is_ok());
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM articles"), 0);
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM sources"), 1);
+    }
+
+    #[test]
+    fn should_tag_articles() {
+        let mut store = Store::memory().unwrap();
+        let feed = make_feed(&["https://example.com/a", "https://example.com/b"]);
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert!(store.save_feed("mirror", &feed).is_ok());
+        assert!(store
+            .set_tags(
+                "https://example.com/a",
+                &[String::from("rust"), String::from("later")]
+            )
+            .is_ok());
+        assert!(store
+            .set_tags("https://example.com/b", &[String::from("rust")])
+            .is_ok());
+        let tags = store.tags().unwrap();
+        assert_eq!(
+            tags.get("https://example.com/a").unwrap(),
+            &vec![String::from("later"), String::from("rust")]
+        );
+        // Articles are returned once for each tag, even if saved by many sources
+        let tagged = store.tagged_articles().unwrap();
+        assert_eq!(tagged.len(), 3);
+        let (_, article) = tagged
+            .iter()
+            .find(|(tag, article)| tag == "later" && article.url == "https://example.com/a")
+            .unwrap();
+        assert_eq!(article.title.as_deref(), Some("title"));
+        assert_eq!(article.authors, vec![String::from("author")]);
+        assert!(article.date.is_some());
+        // Replace tags
+        assert!(store.set_tags("https://example.com/a", &[]).is_ok());
+        assert_eq!(store.tagged_articles().unwrap().len(), 1);
+        assert!(!store.tags().unwrap().contains_key("https://example.com/a"));
+    }
+
+    #[test]
+    fn should_load_stored_feeds() {
+        let mut store = Store::memory().unwrap();
+        assert!(store.load_feed("example").unwrap().is_none());
+        assert!(store.save_error("example", "HTTP request failed").is_ok());
+        assert!(store.load_feed("example").unwrap().is_none());
+        let feed = make_feed(&["https://example.com/a", "https://example.com/b"]);
+        assert!(store.save_feed("example", &feed).is_ok());
+        let loaded = store.load_feed("example").unwrap().unwrap();
+        assert_eq!(loaded.articles.len(), 2);
+        assert_eq!(loaded.articles[0].title.as_deref(), Some("title"));
+        assert_eq!(
+            store.article_urls("example").unwrap(),
+            HashSet::from([
+                String::from("https://example.com/a"),
+                String::from("https://example.com/b")
+            ])
+        );
+        assert!(store.article_urls("other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_keep_article_comments_categories_and_enclosures() {
+        let mut store = Store::memory().unwrap();
+        let mut feed = make_feed(&["https://example.com/a"]);
+        feed.articles[0].comments = Some(String::from("https://example.com/a#comments"));
+        feed.articles[0].categories = vec![String::from("rust"), String::from("linux")];
+        feed.articles[0].enclosures = vec![
+            Enclosure {
+                url: String::from("https://example.com/a.mp3"),
+                content_type: Some(String::from("audio/mpeg")),
+            },
+            Enclosure {
+                url: String::from("https://example.com/a.png"),
+                content_type: None,
+            },
+        ];
+        assert!(store.save_feed("example", &feed).is_ok());
+        let loaded = store.load_feed("example").unwrap().unwrap();
+        assert_eq!(loaded.articles[0].comments, feed.articles[0].comments);
+        assert_eq!(loaded.articles[0].categories, feed.articles[0].categories);
+        assert_eq!(loaded.articles[0].enclosures, feed.articles[0].enclosures);
+    }
+
+    #[test]
+    fn should_keep_article_notes() {
+        let mut store = Store::memory().unwrap();
+        assert!(store
+            .set_note("https://example.com/a", "read later")
+            .is_ok());
+        assert!(store
+            .set_note("https://example.com/a", "great read")
+            .is_ok());
+        assert!(store.set_note("https://example.com/b", "meh").is_ok());
+        let notes = store.notes().unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(
+            notes.get("https://example.com/a").map(|x| x.as_str()),
+            Some("great read")
+        );
+        assert!(store.set_note("https://example.com/b", "").is_ok());
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM notes"), 1);
+    }
+
+    #[test]
+    fn should_keep_article_sorts() {
+        let mut store = Store::memory().unwrap();
+        assert!(store.set_article_sort("example", Some("date")).is_ok());
+        assert!(store.set_article_sort("example", Some("unread")).is_ok());
+        assert!(store.set_article_sort("other", Some("score")).is_ok());
+        assert!(store.rename_source("example", "renamed").is_ok());
+        let sorts = store.article_sorts().unwrap();
+        assert_eq!(sorts.len(), 2);
+        assert_eq!(sorts.get("renamed").map(|x| x.as_str()), Some("unread"));
+        assert!(store.set_article_sort("other", None).is_ok());
+        assert!(store.remove_source("renamed").is_ok());
+        assert!(store.article_sorts().unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_get_kept_articles() {
+        let mut store = Store::memory().unwrap();
+        let feed = make_feed(&[
+            "https://example.com/a",
+            "https://example.com/b",
+            "https://example.com/c",
+        ]);
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert!(store.save_feed("mirror", &feed).is_ok());
+        assert!(store
+            .set_tags("https://example.com/a", &[String::from("rust")])
+            .is_ok());
+        assert!(store.set_starred("https://example.com/a", true).is_ok());
+        assert!(store.set_starred("https://example.com/b", true).is_ok());
+        assert!(store.set_note("https://example.com/c", "meh").is_ok());
+        assert!(store.mark_read("https://example.com/a").is_ok());
+        let urls = |articles: Vec<(String, Article)>| -> Vec<String> {
+            let mut urls: Vec<String> = articles.into_iter().map(|(_, x)| x.url).collect();
+            urls.sort();
+            urls
+        };
+        // Starred, while a note alone is not enough
+        let kept = store.kept_articles(None, false).unwrap();
+        assert_eq!(kept[0].0.as_str(), "example");
+        assert_eq!(
+            urls(kept),
+            vec![
+                String::from("https://example.com/a"),
+                String::from("https://example.com/b")
+            ]
+        );
+        assert_eq!(
+            urls(store.kept_articles(Some("rust"), false).unwrap()),
+            vec![String::from("https://example.com/a")]
+        );
+        assert_eq!(
+            urls(store.kept_articles(None, true).unwrap()),
+            vec![
+                String::from("https://example.com/b"),
+                String::from("https://example.com/c")
+            ]
+        );
+        assert!(store.kept_articles(Some("rust"), true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_get_unread_articles_since() {
+        let mut store = Store::memory().unwrap();
+        let feed = make_feed(&["https://example.com/a", "https://example.com/b"]);
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert!(store.save_feed("mirror", &feed).is_ok());
+        assert!(store.mark_read("https://example.com/a").is_ok());
+        let articles = store
+            .unread_articles_since(Local::now() - chrono::Duration::seconds(10))
+            .unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].0.as_str(), "example");
+        assert_eq!(articles[0].1.url.as_str(), "https://example.com/b");
+        assert!(store
+            .unread_articles_since(Local::now() + chrono::Duration::seconds(10))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn should_keep_feed_channel() {
+        let mut store = Store::memory().unwrap();
+        let mut feed = make_feed(&["https://example.com/a"]);
+        feed.channel = Channel {
+            title: Some(String::from("Example")),
+            link: Some(String::from("https://example.com/")),
+            language: Some(String::from("en")),
+            ..Channel::default()
+        };
+        assert!(store.save_feed("example", &feed).is_ok());
+        assert_eq!(
+            store.load_feed("example").unwrap().unwrap().channel(),
+            feed.channel()
+        );
+        assert!(store.rename_source("example", "renamed").is_ok());
+        assert_eq!(
+            store.load_feed("renamed").unwrap().unwrap().channel(),
+            feed.channel()
+        );
+        assert!(store.remove_source("renamed").is_ok());
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM channels"), 0);
+    }
+
+    #[test]
+    fn should_keep_daemon_heartbeat() {
+        let mut store = Store::memory().unwrap();
+        assert!(store.daemon_heartbeat().unwrap().is_none());
+        assert!(store.set_daemon_heartbeat().is_ok());
+        assert!(store.set_daemon_heartbeat().is_ok());
+        assert!(store.daemon_heartbeat().unwrap().is_some());
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM daemon"), 1);
+    }
+
+    #[test]
+    fn should_save_session() {
+        let mut store = Store::memory().unwrap();
+        assert!(store.session().unwrap().is_none());
+        let mut session = Session {
+            feed: String::from("example"),
+            article: Some(String::from("https://example.com/a")),
+            scroll: 12,
+        };
+        assert!(store.save_session(Some(&session)).is_ok());
+        assert_eq!(store.session().unwrap(), Some(session.clone()));
+        session.article = None;
+        assert!(store.save_session(Some(&session)).is_ok());
+        assert_eq!(store.session().unwrap(), Some(session));
+        assert!(store.save_session(None).is_ok());
+        assert!(store.session().unwrap().is_none());
+    }
+
+    #[test]
+    fn should_get_articles_seen_since_last_quit() {
+        let mut store = Store::memory().unwrap();
+        assert!(store.last_quit().unwrap().is_none());
+        assert!(store
+            .save_feed("example", &make_feed(&["https://example.com/a"]))
+            .is_ok());
+        let since = Local::now() - chrono::Duration::seconds(10);
+        assert_eq!(
+            store.articles_seen_since(since).unwrap(),
+            HashSet::from([String::from("https://example.com/a")])
+        );
+        assert!(store
+            .articles_seen_since(Local::now() + chrono::Duration::seconds(10))
+            .unwrap()
+            .is_empty());
+        assert!(store.set_last_quit().is_ok());
+        assert!(store.set_last_quit().is_ok());
+        assert!(store.last_quit().unwrap().is_some());
+        assert_eq!(count(&store, "SELECT COUNT(*) FROM runs"), 1);
+    }
+
+    #[test]
+    fn should_not_open_newer_database() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        {
+            let db = Connection::open(tmpfile.path()).unwrap();
+            assert!(db.pragma_update(None, "user_version", 99).is_ok());
+        }
+        assert_eq!(
+            Store::open(tmpfile.path()).err().unwrap(),
+            StoreError::UnsupportedVersion(99)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "encryption")]
+    fn should_encrypt_store() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        {
+            let mut store = Store::open(tmpfile.path()).unwrap();
+            assert!(store
+                .save_feed("example", &make_feed(&["https://example.com/a"]))
+                .is_ok());
+        }
+        assert!(is_plaintext(tmpfile.path()));
+        {
+            let store = Store::open_encrypted(tmpfile.path(), "AGE-SECRET-KEY-1XYZ").unwrap();
+            assert_eq!(count(&store, "SELECT COUNT(*) FROM articles"), 1);
+        }
+        assert!(!is_plaintext(tmpfile.path()));
+        assert_eq!(
+            Store::open_encrypted(tmpfile.path(), "wrong")
+                .err()
+                .unwrap(),
+            StoreError::WrongKey
+        );
+        assert!(Store::open(tmpfile.path()).is_err());
+    }
+
+    #[test]
+    #[cfg(not(feature = "encryption"))]
+    fn should_not_open_encrypted_store_without_feature() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        assert_eq!(
+            Store::open_encrypted(tmpfile.path(), "key").err().unwrap(),
+            StoreError::EncryptionUnavailable
+        );
+    }
+
+    fn make_feed(urls: &[&str]) -> Feed {
+        Feed {
+            articles: urls
+                .iter()
+                .map(|url| Article {
+                    title: Some(String::from("title")),
+                    authors: vec![String::from("author")],
+                    summary: String::from("summary"),
+                    links: Vec::new(),
+                    url: url.to_string(),
+                    date: Some(Local::now()),
+                    duration: None,
+                    enclosures: Vec::new(),
+                    flair: None,
+                    interest: None,
+                    comments: None,
+                    categories: Vec::new(),
+                    score: None,
+                })
+                .collect(),
+            moved_to: None,
+            channel: Channel::default(),
+        }
+    }
+
+    fn count(store: &Store, query: &str) -> i64 {
+        store.db.query_row(query, [], |row| row.get(0)).unwrap()
+    }
+}
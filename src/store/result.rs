@@ -0,0 +1,73 @@
+//! # Result
+//!
+//! Store result types
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use rusqlite::Error as SqliteError;
+use thiserror::Error;
+
+/// ## StoreResult
+///
+/// A result returned by the article store
+pub type StoreResult<T> = Result<T, StoreError>;
+
+/// ## StoreError
+///
+/// Describes an article store error
+#[derive(Debug, Error, PartialEq)]
+pub enum StoreError {
+    #[error("Database error: {0}")]
+    Database(String),
+    #[cfg(not(feature = "encryption"))]
+    #[error("Encryption is not available; build tuifeed with the `encryption` feature")]
+    EncryptionUnavailable,
+    #[cfg(feature = "encryption")]
+    #[error("Could not decrypt the database; is the key right?")]
+    WrongKey,
+    #[error("Unsupported database version {0}; please update tuifeed")]
+    UnsupportedVersion(i64),
+}
+
+impl From<SqliteError> for StoreError {
+    fn from(e: SqliteError) -> Self {
+        StoreError::Database(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_convert_sqlite_error() {
+        assert_eq!(
+            StoreError::from(SqliteError::InvalidQuery),
+            StoreError::Database(String::from("Query is not read-only"))
+        );
+    }
+}
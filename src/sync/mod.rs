@@ -0,0 +1,242 @@
+//! # Sync
+//!
+//! Synchronize the read state of the articles between devices through a remote file
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+// -- deps
+use crate::config::SyncConfig;
+use crate::store::{ReadState, Store};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Version of the format of the synchronized file
+const SYNC_VERSION: u32 = 1;
+
+/// ## SyncFile
+///
+/// Content of the synchronized file: the read state of the articles, by url
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncFile {
+    version: u32,
+    articles: HashMap<String, ReadState>,
+}
+
+/// ### sync_read_state
+///
+/// Synchronize the read state of the store with the file set in `config`: the most recent change of each
+/// article wins. The file is written back only if it's outdated.
+/// Returns the amount of articles whose read state has been changed in the store
+pub fn sync_read_state(store: &mut Store, config: &SyncConfig) -> Result<usize, String> {
+    let url = config
+        .url
+        .as_deref()
+        .ok_or_else(|| String::from("sync url is not set"))?;
+    let remote = download(url, config)?;
+    if remote.version > SYNC_VERSION {
+        return Err(format!(
+            "{} has been written by a newer version of tuifeed",
+            url
+        ));
+    }
+    let local = store
+        .read_states()
+        .map_err(|e| format!("could not get read state: {}", e))?;
+    let merged = merge(&local, &remote.articles);
+    let mut changes = 0;
+    for (article, state) in merged.iter() {
+        if local.get(article) != Some(state) {
+            store
+                .set_read_state(article, *state)
+                .map_err(|e| format!("could not set read state: {}", e))?;
+            changes += 1;
+        }
+    }
+    if merged != remote.articles {
+        upload(
+            url,
+            config,
+            &SyncFile {
+                version: SYNC_VERSION,
+                articles: merged,
+            },
+        )?;
+    }
+    Ok(changes)
+}
+
+/// ### merge
+///
+/// Merge two sets of read states, keeping the most recent state of each article; on ties, `local` wins
+fn merge(
+    local: &HashMap<String, ReadState>,
+    remote: &HashMap<String, ReadState>,
+) -> HashMap<String, ReadState> {
+    let mut merged = remote.clone();
+    for (url, state) in local.iter() {
+        match merged.get(url) {
+            Some(other) if other.updated > state.updated => {}
+            _ => {
+                merged.insert(url.to_string(), *state);
+            }
+        }
+    }
+    merged
+}
+
+/// ### is_http
+///
+/// Returns whether `url` is an http(s) url; otherwise it's the path of a local file
+fn is_http(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// ### download
+///
+/// Download the synchronized file at `url`; if it doesn't exist yet, it's empty
+fn download(url: &str, config: &SyncConfig) -> Result<SyncFile, String> {
+    let data = match is_http(url) {
+        true => match authorize(ureq::get(url), config).call() {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| format!("could not read {}: {}", url, e))?,
+            Err(ureq::Error::Status(404, _)) => return Ok(SyncFile::default()),
+            Err(e) => return Err(format!("could not download {}: {}", url, e)),
+        },
+        false => match std::fs::read_to_string(local_path(url)) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SyncFile::default()),
+            Err(e) => return Err(format!("could not read {}: {}", url, e)),
+        },
+    };
+    serde_json::from_str(data.as_str()).map_err(|e| format!("could not parse {}: {}", url, e))
+}
+
+/// ### upload
+///
+/// Write `file` to `url`. Local files are replaced atomically
+fn upload(url: &str, config: &SyncConfig, file: &SyncFile) -> Result<(), String> {
+    let data = serde_json::to_string(file).map_err(|e| e.to_string())?;
+    match is_http(url) {
+        true => authorize(ureq::put(url), config)
+            .set("Content-Type", "application/json")
+            .send_string(data.as_str())
+            .map(|_| ())
+            .map_err(|e| format!("could not upload {}: {}", url, e)),
+        false => {
+            let p = local_path(url);
+            let tmp = p.with_extension("tmp");
+            std::fs::write(&tmp, data)
+                .and_then(|_| std::fs::rename(&tmp, p))
+                .map_err(|e| format!("could not write {}: {}", url, e))
+        }
+    }
+}
+
+/// ### authorize
+///
+/// Set the basic authentication of `config` on `request`, if a user is set
+fn authorize(request: ureq::Request, config: &SyncConfig) -> ureq::Request {
+    match config.user.as_deref() {
+        Some(user) => {
+            let credentials = format!("{}:{}", user, config.password.as_deref().unwrap_or(""));
+            request.set(
+                "Authorization",
+                format!("Basic {}", base64::encode(credentials)).as_str(),
+            )
+        }
+        None => request,
+    }
+}
+
+/// ### local_path
+///
+/// Get the path of the local file at `url`, which may start with `file://`
+fn local_path(url: &str) -> &Path {
+    Path::new(url.strip_prefix("file://").unwrap_or(url))
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn state(read: bool, updated: i64) -> ReadState {
+        ReadState { read, updated }
+    }
+
+    #[test]
+    fn should_merge_read_states() {
+        let mut local = HashMap::new();
+        local.insert(String::from("a"), state(true, 100));
+        local.insert(String::from("b"), state(true, 100));
+        local.insert(String::from("c"), state(false, 100));
+        let mut remote = HashMap::new();
+        remote.insert(String::from("b"), state(false, 200));
+        remote.insert(String::from("c"), state(true, 100));
+        remote.insert(String::from("d"), state(true, 50));
+        let merged = merge(&local, &remote);
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged["a"], state(true, 100));
+        assert_eq!(merged["b"], state(false, 200));
+        assert_eq!(merged["c"], state(false, 100));
+        assert_eq!(merged["d"], state(true, 50));
+    }
+
+    #[test]
+    fn should_sync_read_state_through_local_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("read.json");
+        let config = SyncConfig {
+            url: Some(file.to_string_lossy().to_string()),
+            ..SyncConfig::default()
+        };
+        let mut laptop = Store::memory().unwrap();
+        assert!(laptop
+            .set_read_state("https://example.com/a", state(true, 100))
+            .is_ok());
+        assert_eq!(sync_read_state(&mut laptop, &config).unwrap(), 0);
+        assert!(file.exists());
+        let mut desktop = Store::memory().unwrap();
+        assert!(desktop
+            .set_read_state("https://example.com/b", state(true, 200))
+            .is_ok());
+        assert_eq!(sync_read_state(&mut desktop, &config).unwrap(), 1);
+        assert_eq!(sync_read_state(&mut laptop, &config).unwrap(), 1);
+        assert_eq!(
+            laptop.read_states().unwrap(),
+            desktop.read_states().unwrap()
+        );
+    }
+
+    #[test]
+    fn should_not_sync_without_url() {
+        let mut store = Store::memory().unwrap();
+        assert!(sync_read_state(&mut store, &SyncConfig::default()).is_err());
+    }
+}
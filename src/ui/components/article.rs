@@ -26,17 +26,28 @@
  * SOFTWARE.
  */
 use super::Msg;
-use crate::helpers::fmt as fmt_helpers;
-use crate::helpers::strings as str_helpers;
+use crate::config::{Highlights, KeyBindings, Theme};
+use crate::helpers::strings::{self as str_helpers, CodeHighlighter, CodeToken};
+use crate::helpers::ui as ui_helpers;
 
-use chrono::{DateTime, Local};
 use tui_realm_stdlib::{Label, Paragraph, Textarea};
-use tuirealm::command::{Cmd, Direction, Position};
-use tuirealm::event::{Key, KeyEvent};
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::props::{
-    Alignment, BorderSides, BorderType, Borders, Color, TextModifiers, TextSpan,
+    Alignment, AttrValue, Attribute, BorderSides, BorderType, Borders, Color, PropPayload,
+    PropValue, TextModifiers, TextSpan,
 };
-use tuirealm::{Component, Event, MockComponent, NoUserEvent};
+use tuirealm::tui::{
+    layout::{Corner, Rect},
+    text::Spans,
+    widgets::{List as TuiList, ListItem, ListState},
+};
+use tuirealm::{Component, Event, Frame, MockComponent, NoUserEvent, Props, State, StateValue};
+
+/// Symbol displayed next to the paragraph at the scroll position in the summary
+const SUMMARY_HIGHLIGHTED_STR: &str = "• ";
+/// Links displayed by the summary, as `(x, y, marker, url)`, where the marker of the link is at `x` and `y`
+pub const SUMMARY_PROP_LINKS: &str = "links";
 
 #[derive(MockComponent)]
 pub struct ArticleTitle {
@@ -44,11 +55,11 @@ pub struct ArticleTitle {
 }
 
 impl ArticleTitle {
-    pub fn new(title: &str) -> Self {
+    pub fn new(title: &str, theme: &Theme) -> Self {
         Self {
             component: Paragraph::default()
                 .borders(Borders::default().sides(BorderSides::empty()))
-                .foreground(Color::LightYellow)
+                .foreground(theme.yellow)
                 .modifiers(TextModifiers::BOLD)
                 .text(&[TextSpan::from(title)]),
         }
@@ -67,16 +78,12 @@ pub struct ArticleDate {
 }
 
 impl ArticleDate {
-    pub fn new(datetime: Option<DateTime<Local>>) -> Self {
+    pub fn new<S: AsRef<str>>(date: S, theme: &Theme) -> Self {
         Self {
             component: Label::default()
-                .foreground(Color::LightGreen)
+                .foreground(theme.green)
                 .modifiers(TextModifiers::BOLD | TextModifiers::ITALIC)
-                .text(
-                    datetime
-                        .map(|x| fmt_helpers::format_datetime(x, "%A %d %B %Y, %H:%M"))
-                        .unwrap_or_default(),
-                ),
+                .text(date.as_ref()),
         }
     }
 }
@@ -93,10 +100,10 @@ pub struct ArticleAuthors {
 }
 
 impl ArticleAuthors {
-    pub fn new(authors: &[String]) -> Self {
+    pub fn new(authors: &[String], theme: &Theme) -> Self {
         Self {
             component: Label::default()
-                .foreground(Color::LightGreen)
+                .foreground(theme.green)
                 .modifiers(TextModifiers::BOLD | TextModifiers::ITALIC)
                 .text(authors.join(", ")),
         }
@@ -129,85 +136,632 @@ impl Component<Msg, NoUserEvent> for ArticleLink {
 }
 
 #[derive(MockComponent)]
+pub struct ArticleComments {
+    component: Label,
+}
+
+impl ArticleComments {
+    pub fn new(url: Option<&str>, theme: &Theme) -> Self {
+        Self {
+            component: Label::default()
+                .foreground(theme.blue)
+                .text(url.map(|x| format!("Comments: {}", x)).unwrap_or_default()),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ArticleComments {
+    fn on(&mut self, _: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ArticleTags {
+    component: Label,
+}
+
+impl ArticleTags {
+    pub fn new(categories: &[String], user_tags: &[String], theme: &Theme) -> Self {
+        let tags: Vec<String> = categories
+            .iter()
+            .cloned()
+            .chain(user_tags.iter().map(|x| format!("#{}", x)))
+            .collect();
+        let text = match tags.is_empty() {
+            true => String::new(),
+            false => format!("Tags: {}", tags.join(", ")),
+        };
+        Self {
+            component: Label::default()
+                .foreground(theme.magenta)
+                .modifiers(TextModifiers::ITALIC)
+                .text(text),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ArticleTags {
+    fn on(&mut self, _: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// ## SummaryRow
+///
+/// A row of the summary: a paragraph, or a line of a code block split into the `tokens` to highlight
+enum SummaryRow {
+    Text(String),
+    Code {
+        line: String,
+        tokens: Vec<(String, CodeToken)>,
+    },
+}
+
+impl SummaryRow {
+    fn text(&self) -> &str {
+        match self {
+            Self::Text(text) => text.as_str(),
+            Self::Code { line, .. } => line.as_str(),
+        }
+    }
+}
+
+/// ## SummarySearch
+///
+/// Text searched in the summary and the rows it has been found in
+struct SummarySearch {
+    query: String,
+    /// Indexes of the rows containing the query
+    matches: Vec<usize>,
+}
+
 pub struct ArticleSummary {
     component: Textarea,
+    /// Paragraphs of the summary, split into words so that they can be wrapped
+    rows: Vec<Vec<TextSpan>>,
+    keys: KeyBindings,
+    /// Urls of the numbered links in the summary
+    links: Vec<String>,
+    /// Position, marker and number of the links displayed when the summary has been rendered last time
+    displayed_links: Vec<(u16, u16, String, usize)>,
+    /// Link number being typed
+    pending_link: Option<usize>,
+    /// Height of the summary, in lines, and wrap width of its rows, as rendered last time
+    page: (usize, usize),
+    search: Option<SummarySearch>,
+    theme: Theme,
 }
 
 impl ArticleSummary {
-    pub fn new(summary: &str) -> Self {
-        Self {
+    pub fn new(
+        summary: &str,
+        links: &[String],
+        highlights: &Highlights,
+        keys: KeyBindings,
+        theme: &Theme,
+    ) -> Self {
+        let rows = Self::make_summary_rows(summary);
+        let mut summary = Self {
             component: Textarea::default()
                 .borders(
                     Borders::default()
-                        .color(Color::LightCyan)
+                        .color(theme.cyan)
                         .modifiers(BorderType::Rounded),
                 )
                 .foreground(Color::Reset)
                 .title("Summary", Alignment::Left)
                 .step(4)
-                .highlighted_str("• ")
-                .text_rows(Self::make_summary_rows(summary).as_slice()),
+                .highlighted_str(SUMMARY_HIGHLIGHTED_STR)
+                .text_rows(
+                    rows.iter()
+                        .map(|x| TextSpan::from(x.text()))
+                        .collect::<Vec<TextSpan>>()
+                        .as_slice(),
+                ),
+            rows: rows
+                .iter()
+                .map(|x| match x {
+                    SummaryRow::Text(text) => {
+                        Self::make_row_spans(text.as_str(), highlights, theme)
+                    }
+                    SummaryRow::Code { line, tokens } => {
+                        Self::make_code_spans(line.as_str(), tokens, theme)
+                    }
+                })
+                .collect(),
+            keys,
+            links: links.to_vec(),
+            displayed_links: Vec::new(),
+            pending_link: None,
+            page: (0, 0),
+            search: None,
+            theme: *theme,
+        };
+        summary.update_title();
+        summary
+    }
+
+    /// ### scroll
+    ///
+    /// Scroll the summary to the row at `index`
+    pub fn scroll(mut self, index: usize) -> Self {
+        self.component.states.list_index = index;
+        self.component.states.fix_list_index();
+        self.update_title();
+        self
+    }
+
+    /// ### search
+    ///
+    /// Highlight the occurrences of `query` in the summary, so that the keys to go to the next and to the previous match
+    /// jump between the rows containing it. Queries with the regex prefix are regular expressions
+    pub fn search(mut self, query: Option<&str>) -> Self {
+        let (query, regex) = match query.map(|x| (x, str_helpers::search_regex(x))) {
+            Some((query, Ok(regex))) => (query, regex),
+            _ => return self,
+        };
+        let mut matches = Vec::new();
+        for (index, row) in self.rows.iter_mut().enumerate() {
+            let text: String = row.iter().map(|x| x.content.as_str()).collect();
+            let ranges: Vec<(usize, usize)> = regex
+                .find_iter(text.as_str())
+                .map(|x| (x.start(), x.end()))
+                .filter(|(start, end)| start < end)
+                .collect();
+            if !ranges.is_empty() {
+                *row = Self::highlight_matches(row.as_slice(), ranges.as_slice());
+                matches.push(index);
+            }
         }
+        self.search = Some(SummarySearch {
+            query: query.to_string(),
+            matches,
+        });
+        self.update_title();
+        self
     }
 
-    /// ### make_summary_rows
+    /// ### first_match
     ///
-    /// Make summary rows
-    fn make_summary_rows(summary: &str) -> Vec<TextSpan> {
-        let summary =
-            str_helpers::replace_multiple_newlines(summary.trim_matches('\n').trim(), "\n");
-        // Split summary by newline
-        summary.split('\n').map(TextSpan::from).collect()
+    /// Scroll the summary to the first match of the search from the current row, if any
+    pub fn first_match(mut self) -> Self {
+        self.jump_to_match(true, true);
+        self
     }
-}
 
-impl Component<Msg, NoUserEvent> for ArticleSummary {
-    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
-        match ev {
-            Event::Keyboard(KeyEvent {
-                code: Key::Down, ..
-            }) => {
-                self.perform(Cmd::Scroll(Direction::Down));
-                Some(Msg::None)
+    /// ### jump_to_match
+    ///
+    /// Scroll the summary to the next row matching the search, or to the previous one if `forward` is `false`,
+    /// wrapping around the summary. The current row is taken into account only if `current` is `true`
+    fn jump_to_match(&mut self, forward: bool, current: bool) {
+        let matches = match &self.search {
+            Some(search) => &search.matches,
+            None => return,
+        };
+        let index = self.component.states.list_index;
+        let found = match forward {
+            true => matches
+                .iter()
+                .find(|x| **x > index || (current && **x == index))
+                .or_else(|| matches.first()),
+            false => matches
+                .iter()
+                .rev()
+                .find(|x| **x < index || (current && **x == index))
+                .or_else(|| matches.last()),
+        };
+        if let Some(found) = found.copied() {
+            self.component.states.list_index = found;
+        }
+        self.update_title();
+    }
+
+    /// ### highlight_matches
+    ///
+    /// Highlight the text of `spans` in the byte `ranges`, splitting the spans at the range boundaries
+    fn highlight_matches(spans: &[TextSpan], ranges: &[(usize, usize)]) -> Vec<TextSpan> {
+        let mut highlighted = Vec::with_capacity(spans.len());
+        let mut offset = 0;
+        for span in spans {
+            let len = span.content.len();
+            let mut cuts: Vec<usize> = ranges
+                .iter()
+                .flat_map(|(start, end)| [*start, *end])
+                .filter(|x| *x > offset && *x < offset + len)
+                .map(|x| x - offset)
+                .collect();
+            cuts.push(len);
+            let mut start = 0;
+            for cut in cuts.into_iter().filter(|x| *x > 0) {
+                let mut part = span.clone();
+                part.content = span.content[start..cut].to_string();
+                if ranges
+                    .iter()
+                    .any(|(x, y)| offset + start >= *x && offset + start < *y)
+                {
+                    part.modifiers |= TextModifiers::REVERSED;
+                }
+                highlighted.push(part);
+                start = cut;
             }
-            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
-                self.perform(Cmd::Scroll(Direction::Up));
-                Some(Msg::None)
+            offset += len;
+        }
+        highlighted
+    }
+
+    /// ### scroll_page
+    ///
+    /// Scroll the summary by a page, or by half a page if `half` is `true`.
+    /// Rows are wrapped, so the rows to scroll are counted by their wrapped lines; at least a row is scrolled
+    fn scroll_page(&mut self, forward: bool, half: bool) {
+        let (height, _) = self.page;
+        let lines = match half {
+            true => height / 2,
+            false => height,
+        };
+        let mut index = self.component.states.list_index;
+        let mut scrolled = 0;
+        // NOTE: the selected row is kept at the bottom of the pane, so the rows entering (or leaving) the bottom are counted
+        loop {
+            let (next, row_height) = match forward {
+                true if index + 1 < self.rows.len() => (index + 1, self.row_height(index + 1)),
+                false if index > 0 => (index - 1, self.row_height(index)),
+                _ => break,
+            };
+            if scrolled > 0 && scrolled + row_height > lines {
+                break;
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::PageDown,
-                ..
-            }) => {
-                // Scroll twice
-                self.perform(Cmd::Scroll(Direction::Down));
-                self.perform(Cmd::Scroll(Direction::Down));
-                Some(Msg::None)
+            scrolled += row_height;
+            index = next;
+        }
+        self.component.states.list_index = index;
+        self.update_title();
+    }
+
+    /// ### row_height
+    ///
+    /// Get the amount of lines the row at `index` is wrapped into
+    fn row_height(&self, index: usize) -> usize {
+        let (_, wrap_width) = self.page;
+        if wrap_width == 0 {
+            return 1;
+        }
+        tui_realm_stdlib::utils::wrap_spans(
+            self.rows[index].as_slice(),
+            wrap_width,
+            &Props::default(),
+        )
+        .len()
+        .max(1)
+    }
+
+    /// ### update_title
+    ///
+    /// Update title with the scroll position in the summary
+    fn update_title(&mut self) {
+        let states = &self.component.states;
+        let progress = match states.list_len {
+            0 | 1 => 100,
+            len => states.list_index * 100 / (len - 1),
+        };
+        let mut title = format!("Summary ({}%)", progress);
+        if let Some(search) = &self.search {
+            let position = search.matches.iter().position(|x| *x == states.list_index);
+            let found = match (search.matches.len(), position) {
+                (0, _) => String::from("not found"),
+                (len, Some(position)) => format!("{}/{}", position + 1, len),
+                (len, None) => format!("{} matches", len),
+            };
+            title = format!("{} - /{} {}", title, search.query, found);
+        }
+        if let Some(link) = self.pending_link {
+            title = format!("{} - open link [{}…]", title, link);
+        }
+        self.attr(Attribute::Title, AttrValue::Title((title, Alignment::Left)));
+    }
+
+    /// ### type_link_digit
+    ///
+    /// Handle a digit of the link number being typed.
+    /// The link is opened as soon as no other link number may start with the typed digits
+    fn type_link_digit(&mut self, digit: usize) -> Option<Msg> {
+        let link = self.pending_link.take().unwrap_or(0) * 10 + digit;
+        let msg = if link == 0 || link > self.links.len() {
+            Msg::None
+        } else if link * 10 > self.links.len() {
+            Msg::OpenLink(link)
+        } else {
+            self.pending_link = Some(link);
+            Msg::None
+        };
+        self.update_title();
+        Some(msg)
+    }
+
+    /// ### link_digit
+    ///
+    /// Get the digit typed with `key`, if any
+    fn link_digit(key: &KeyEvent) -> Option<usize> {
+        match key.code {
+            Key::Char(ch) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+                ch.to_digit(10).map(|x| x as usize)
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::PageUp, ..
-            }) => {
-                // Scroll twice
-                self.perform(Cmd::Scroll(Direction::Up));
-                self.perform(Cmd::Scroll(Direction::Up));
-                Some(Msg::None)
+            _ => None,
+        }
+    }
+
+    /// ### make_summary_rows
+    ///
+    /// Make summary rows. Empty lines are removed from paragraphs, while code blocks are kept as they are
+    fn make_summary_rows(summary: &str) -> Vec<SummaryRow> {
+        let mut rows: Vec<SummaryRow> = Vec::new();
+        let mut paragraphs: Vec<&str> = Vec::new();
+        // Highlighter of the code block being read, if any
+        let mut code: Option<CodeHighlighter> = None;
+        for line in summary.split('\n') {
+            match (code.as_mut(), line.strip_prefix(str_helpers::CODE_FENCE)) {
+                (None, Some(language)) => {
+                    Self::push_paragraphs(&mut rows, paragraphs.join("\n").as_str());
+                    paragraphs.clear();
+                    let language = Some(language.trim()).filter(|x| !x.is_empty());
+                    code = Some(CodeHighlighter::new(language));
+                }
+                (Some(_), Some("")) => code = None,
+                (Some(highlighter), _) => rows.push(SummaryRow::Code {
+                    line: line.to_string(),
+                    tokens: highlighter.highlight(line),
+                }),
+                (None, None) => paragraphs.push(line),
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Home, ..
-            }) => {
-                self.perform(Cmd::GoTo(Position::Begin));
-                Some(Msg::None)
+        }
+        Self::push_paragraphs(&mut rows, paragraphs.join("\n").as_str());
+        if rows.is_empty() {
+            rows.push(SummaryRow::Text(String::new()));
+        }
+        rows
+    }
+
+    /// ### push_paragraphs
+    ///
+    /// Push a row for each paragraph of `text`
+    fn push_paragraphs(rows: &mut Vec<SummaryRow>, text: &str) {
+        let text = str_helpers::replace_multiple_newlines(text.trim_matches('\n').trim(), "\n");
+        if !text.is_empty() {
+            rows.extend(text.split('\n').map(|x| SummaryRow::Text(x.to_string())));
+        }
+    }
+
+    /// ### find_displayed_links
+    ///
+    /// Find the markers of the links in the wrapped `rows` which are displayed in `area`, along with their position.
+    /// Rows are scrolled as the list rendering them does
+    fn find_displayed_links(
+        &self,
+        rows: &[Vec<Spans>],
+        area: Rect,
+    ) -> Vec<(u16, u16, String, usize)> {
+        let heights: Vec<usize> = rows.iter().map(Vec::len).collect();
+        let (start, end) = ui_helpers::list_bounds(
+            heights.as_slice(),
+            self.component.states.list_index,
+            area.height as usize,
+        );
+        let left = area.x as usize + str_helpers::string_width(SUMMARY_HIGHLIGHTED_STR);
+        let mut links = Vec::new();
+        for (y, line) in (area.y..area.bottom()).zip(rows[start..end].iter().flatten()) {
+            let text: String = line.0.iter().map(|x| x.content.as_ref()).collect();
+            for (column, marker, number) in str_helpers::find_link_markers(text.as_str()) {
+                let x = left + column;
+                if number > 0
+                    && number <= self.links.len()
+                    && x + marker.len() <= area.right() as usize
+                {
+                    links.push((x as u16, y, marker.to_string(), number));
+                }
             }
-            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
-                self.perform(Cmd::GoTo(Position::End));
-                Some(Msg::None)
+        }
+        links
+    }
+
+    /// ### make_code_spans
+    ///
+    /// Make the spans of a line of code, highlighting its tokens
+    fn make_code_spans(line: &str, tokens: &[(String, CodeToken)], theme: &Theme) -> Vec<TextSpan> {
+        // Empty lines must still take a row
+        if line.is_empty() {
+            return vec![TextSpan::from(" ")];
+        }
+        tokens
+            .iter()
+            .cloned()
+            .map(|(text, token)| match token {
+                CodeToken::Plain => TextSpan::from(text).fg(theme.code),
+                CodeToken::Keyword => TextSpan::from(text).fg(theme.magenta).bold(),
+                CodeToken::String => TextSpan::from(text).fg(theme.green),
+                CodeToken::Comment => TextSpan::from(text).fg(theme.dim).italic(),
+                CodeToken::Number => TextSpan::from(text).fg(theme.yellow),
+            })
+            .collect()
+    }
+
+    /// ### make_row_spans
+    ///
+    /// Make the spans of a summary row, one for each word. Highlighted words are styled
+    fn make_row_spans(row: &str, highlights: &Highlights, theme: &Theme) -> Vec<TextSpan> {
+        let highlight = theme.yellow;
+        highlights
+            .split(row)
+            .into_iter()
+            .flat_map(|(part, highlighted)| {
+                part.split_inclusive(' ')
+                    .map(move |word| match highlighted {
+                        true => TextSpan::from(word).fg(highlight).bold(),
+                        false => TextSpan::from(word),
+                    })
+            })
+            .collect()
+    }
+}
+
+impl MockComponent for ArticleSummary {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        // Rows are rendered here, since the textarea can't render more styles in a row
+        let focus = self
+            .query(Attribute::Focus)
+            .unwrap_or(AttrValue::Flag(false))
+            .unwrap_flag();
+        let title = self.query(Attribute::Title).map(|x| x.unwrap_title());
+        let div = tui_realm_stdlib::utils::get_block(
+            Borders::default()
+                .color(self.theme.cyan)
+                .modifiers(BorderType::Rounded),
+            title,
+            focus,
+            None,
+        );
+        // NOTE: wrap width is width of area minus 2 (block) minus width of highlighting string
+        let wrap_width = (area.width as usize)
+            .saturating_sub(str_helpers::string_width(SUMMARY_HIGHLIGHTED_STR) + 2);
+        self.page = ((area.height as usize).saturating_sub(2), wrap_width);
+        let props = Props::default();
+        let rows: Vec<Vec<Spans>> = self
+            .rows
+            .iter()
+            .map(|x| tui_realm_stdlib::utils::wrap_spans(x.as_slice(), wrap_width, &props))
+            .collect();
+        self.displayed_links = self.find_displayed_links(rows.as_slice(), div.inner(area));
+        let items: Vec<ListItem> = rows.into_iter().map(ListItem::new).collect();
+        let mut state = ListState::default();
+        state.select(Some(self.component.states.list_index));
+        let list = TuiList::new(items)
+            .block(div)
+            .start_corner(Corner::TopLeft)
+            .highlight_symbol(SUMMARY_HIGHLIGHTED_STR);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        match attr {
+            Attribute::Custom(SUMMARY_PROP_LINKS) => Some(AttrValue::Payload(PropPayload::Linked(
+                self.displayed_links
+                    .iter()
+                    .map(|(x, y, marker, number)| {
+                        PropPayload::Tup4((
+                            PropValue::U16(*x),
+                            PropValue::U16(*y),
+                            PropValue::Str(marker.clone()),
+                            PropValue::Str(self.links[number - 1].clone()),
+                        ))
+                    })
+                    .collect(),
+            ))),
+            attr => self.component.query(attr),
+        }
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.component.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        // Scroll position, to restore it on the next startup
+        State::One(StateValue::Usize(self.component.states.list_index))
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ArticleSummary {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        // Enter confirms the link number being typed; any other key discards it
+        if let Some(link) = self.pending_link {
+            if key.code == Key::Enter {
+                self.pending_link = None;
+                self.update_title();
+                return Some(Msg::OpenLink(link));
+            } else if Self::link_digit(&key).is_none() {
+                self.pending_link = None;
+                self.update_title();
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Left, ..
-            }) => Some(Msg::ArticleBlur),
-            Event::Keyboard(KeyEvent {
-                code: Key::Enter, ..
-            }) => Some(Msg::OpenArticle),
-            _ => None,
+        }
+        let keys = &self.keys;
+        if keys.move_down.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Down));
+            self.update_title();
+            Some(Msg::None)
+        } else if keys.move_up.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Up));
+            self.update_title();
+            Some(Msg::None)
+        } else if keys.scroll_down.matches(&key) {
+            self.scroll_page(true, true);
+            Some(Msg::None)
+        } else if keys.scroll_up.matches(&key) {
+            self.scroll_page(false, true);
+            Some(Msg::None)
+        } else if keys.page_down.matches(&key) {
+            self.scroll_page(true, false);
+            Some(Msg::None)
+        } else if keys.page_up.matches(&key) {
+            self.scroll_page(false, false);
+            Some(Msg::None)
+        } else if keys.go_to_begin.matches(&key) {
+            self.perform(Cmd::GoTo(Position::Begin));
+            self.update_title();
+            Some(Msg::None)
+        } else if keys.go_to_end.matches(&key) {
+            self.perform(Cmd::GoTo(Position::End));
+            self.update_title();
+            Some(Msg::None)
+        } else if keys.previous_pane.matches(&key) {
+            Some(Msg::ArticleBlur)
+        } else if keys.open_article.matches(&key) {
+            Some(Msg::OpenArticle)
+        } else if keys.open_comments.matches(&key) {
+            Some(Msg::OpenComments)
+        } else if keys.open_with.matches(&key) {
+            Some(Msg::ShowOpenWithPopup)
+        } else if keys.share.matches(&key) {
+            Some(Msg::ShowSharePopup)
+        } else if keys.tag_article.matches(&key) {
+            Some(Msg::ShowTagArticlePopup)
+        } else if keys.edit_note.matches(&key) {
+            Some(Msg::EditNote)
+        } else if keys.star_article.matches(&key) {
+            Some(Msg::StarArticle)
+        } else if keys.reading_mode.matches(&key) {
+            Some(Msg::ToggleReadingMode)
+        } else if keys.yank_link.matches(&key) {
+            Some(Msg::YankLink)
+        } else if keys.yank_article.matches(&key) {
+            Some(Msg::YankArticle)
+        } else if keys.archive_article.matches(&key) {
+            Some(Msg::ArchiveArticle)
+        } else if keys.speak_article.matches(&key) {
+            Some(Msg::SpeakArticle)
+        } else if keys.filter_articles.matches(&key) {
+            Some(Msg::ShowSearchPopup)
+        } else if self.search.is_some() && keys.next_match.matches(&key) {
+            self.jump_to_match(true, false);
+            Some(Msg::None)
+        } else if self.search.is_some() && keys.previous_match.matches(&key) {
+            self.jump_to_match(false, false);
+            Some(Msg::None)
+        } else if keys.next_article.matches(&key) {
+            Some(Msg::NextArticle)
+        } else if keys.previous_article.matches(&key) {
+            Some(Msg::PreviousArticle)
+        } else if let Some(digit) = Self::link_digit(&key).filter(|_| !self.links.is_empty()) {
+            self.type_link_digit(digit)
+        } else {
+            None
         }
     }
 }
@@ -25,8 +25,10 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
+use crate::config::Theme;
 use crate::ui::lib::FlatFeedState;
 
+use std::collections::{HashMap, HashSet};
 use tui_realm_stdlib::List;
 use tuirealm::command::{Cmd, CmdResult};
 use tuirealm::props::{
@@ -39,11 +41,11 @@ use tuirealm::tui::{
 };
 use tuirealm::{Frame, MockComponent, State};
 
-const SEQUENCE: [char; 8] = ['⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷'];
 pub const FEED_LIST_PROP_ITEMS: &str = "items";
 
 pub const FEED_STATE_ERROR: u8 = 1;
 pub const FEED_STATE_LOADING: u8 = 2;
+pub const FEED_STATE_RETRYING: u8 = 3;
 pub const FEED_STATE_SUCCESS: u8 = 0;
 
 struct OwnStates {
@@ -57,10 +59,10 @@ impl Default for OwnStates {
 }
 
 impl OwnStates {
-    pub fn step(&mut self) -> char {
-        let ch = SEQUENCE.get(self.step).cloned().unwrap_or(' ');
+    pub fn step(&mut self, sequence: &[char]) -> char {
+        let ch = sequence.get(self.step).cloned().unwrap_or(' ');
         // Incr step
-        if self.step + 1 >= SEQUENCE.len() {
+        if self.step + 1 >= sequence.len() {
             self.step = 0;
         } else {
             self.step += 1;
@@ -75,38 +77,77 @@ impl OwnStates {
 pub struct FeedList {
     list: List,
     items: Vec<(String, FlatFeedState)>,
+    /// Sources pinned to the top of the list
+    pinned: HashSet<String>,
+    /// Style of the name of each source, if configured
+    styles: HashMap<String, Style>,
     states: OwnStates,
+    theme: Theme,
 }
 
 impl FeedList {
-    pub fn new(items: Vec<(String, FlatFeedState)>) -> Self {
+    pub fn new(items: Vec<(String, FlatFeedState)>, theme: &Theme) -> Self {
         Self {
             list: List::default()
-                .highlighted_color(Color::LightBlue)
-                .highlighted_str("➤ ")
+                .highlighted_color(theme.blue)
+                .highlighted_str(theme.icons.selected)
                 .rewind(true)
                 .scroll(true)
                 .step(4)
                 .title("Feed", Alignment::Center)
                 .borders(
                     Borders::default()
-                        .color(Color::LightBlue)
+                        .color(theme.blue)
                         .modifiers(BorderType::Rounded),
                 )
                 .rows((0..items.len()).map(|_| vec![TextSpan::new("")]).collect()),
             items,
+            pinned: HashSet::new(),
+            styles: HashMap::new(),
             states: OwnStates::default(),
+            theme: *theme,
         }
     }
 
-    fn feed_state_to_span(state: &FlatFeedState, loading_step: char) -> Span {
+    /// ### selected
+    ///
+    /// Select the source at `index`
+    pub fn selected(mut self, index: usize) -> Self {
+        self.list.states.list_index = index;
+        self.list.states.fix_list_index();
+        self
+    }
+
+    /// ### styles
+    ///
+    /// Set the style of the names of the sources
+    pub fn styles(mut self, styles: HashMap<String, Style>) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// ### pinned
+    ///
+    /// Set the sources pinned to the top of the list, which are marked
+    pub fn pinned(mut self, pinned: HashSet<String>) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    fn feed_state_to_span(&self, state: &FlatFeedState, loading_step: char) -> Span<'_> {
         match *state {
-            FlatFeedState::Success => Span::from("  "),
+            FlatFeedState::Success => Span::from(format!("{} ", self.theme.icons.success)),
             FlatFeedState::Loading => Span::from(format!("{} ", loading_step)),
             FlatFeedState::Error => Span::styled(
-                "✘ ",
+                format!("{} ", self.theme.icons.error),
                 Style::default()
-                    .fg(Color::Red)
+                    .fg(self.theme.red)
+                    .add_modifier(TextModifiers::BOLD),
+            ),
+            FlatFeedState::Retrying => Span::styled(
+                format!("{} ", self.theme.icons.retrying),
+                Style::default()
+                    .fg(self.theme.warning)
                     .add_modifier(TextModifiers::BOLD),
             ),
         }
@@ -121,34 +162,42 @@ impl MockComponent for FeedList {
             .unwrap_flag();
         let div = tui_realm_stdlib::utils::get_block(
             Borders::default()
-                .color(Color::LightBlue)
+                .color(self.theme.blue)
                 .modifiers(BorderType::Rounded),
             Some(("Feed".to_string(), Alignment::Center)),
             focus,
             None,
         );
-        let step = self.states.step();
+        let step = self.states.step(self.theme.icons.loading);
         // Make list entries
         let list_items: Vec<ListItem> = self
             .items
             .iter()
             .map(|(name, state)| {
-                ListItem::new(Spans::from(vec![
-                    Self::feed_state_to_span(state, step),
-                    Span::from(name.as_str()),
-                ]))
+                let mut spans = vec![self.feed_state_to_span(state, step)];
+                if self.pinned.contains(name) {
+                    spans.push(Span::styled(
+                        format!("{} ", self.theme.icons.pinned),
+                        Style::default().fg(self.theme.warning),
+                    ));
+                }
+                spans.push(Span::styled(
+                    name.as_str(),
+                    self.styles.get(name).copied().unwrap_or_default(),
+                ));
+                ListItem::new(Spans::from(spans))
             })
             .collect();
         let (fg, bg): (Color, Color) = match focus {
-            true => (Color::Reset, Color::LightBlue),
-            false => (Color::LightBlue, Color::Reset),
+            true => (Color::Reset, self.theme.blue),
+            false => (self.theme.blue, Color::Reset),
         };
         // Make list
         let list = TuiList::new(list_items)
             .block(div)
             .start_corner(Corner::TopLeft)
             .highlight_style(Style::default().fg(fg).bg(bg))
-            .highlight_symbol("➤ ");
+            .highlight_symbol(self.theme.icons.selected);
         let mut state: ListState = ListState::default();
         state.select(Some(self.list.states.list_index));
         frame.render_stateful_widget(list, area, &mut state);
@@ -166,6 +215,7 @@ impl MockComponent for FeedList {
             let state = match state {
                 FEED_STATE_ERROR => FlatFeedState::Error,
                 FEED_STATE_LOADING => FlatFeedState::Loading,
+                FEED_STATE_RETRYING => FlatFeedState::Retrying,
                 FEED_STATE_SUCCESS => FlatFeedState::Success,
                 _ => panic!("Invalid state {}", state),
             };
@@ -28,60 +28,104 @@
 mod feed_list;
 
 pub use feed_list::{
-    FEED_LIST_PROP_ITEMS, FEED_STATE_ERROR, FEED_STATE_LOADING, FEED_STATE_SUCCESS,
+    FEED_LIST_PROP_ITEMS, FEED_STATE_ERROR, FEED_STATE_LOADING, FEED_STATE_RETRYING,
+    FEED_STATE_SUCCESS,
 };
 
+use crate::config::{Highlights, KeyBindings, Theme};
 use crate::ui::lib::FlatFeedState;
 
 use super::Msg;
 
+use crate::helpers::strings as str_helpers;
+
+use std::collections::{HashMap, HashSet};
 use tui_realm_stdlib::List;
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
-use tuirealm::event::{Key, KeyEvent};
-use tuirealm::props::{Alignment, BorderType, Borders, Color, TextSpan};
-use tuirealm::{Component, Event, MockComponent, NoUserEvent, State, StateValue};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+use tuirealm::props::{
+    Alignment, AttrValue, Attribute, BorderType, Borders, Color, Style, TextModifiers,
+};
+use tuirealm::tui::{
+    layout::{Corner, Rect},
+    text::{Span, Spans},
+    widgets::{List as TuiList, ListItem, ListState},
+};
+use tuirealm::{Component, Event, Frame, MockComponent, NoUserEvent, State, StateValue};
 
 #[derive(MockComponent)]
 pub struct FeedList {
     component: feed_list::FeedList,
+    keys: KeyBindings,
 }
 
 impl FeedList {
-    pub fn new(sources: Vec<(String, FlatFeedState)>) -> Self {
+    pub fn new(sources: Vec<(String, FlatFeedState)>, keys: KeyBindings, theme: &Theme) -> Self {
         Self {
-            component: feed_list::FeedList::new(sources),
+            component: feed_list::FeedList::new(sources, theme),
+            keys,
         }
     }
+
+    /// ### selected
+    ///
+    /// Select the source at `index`
+    pub fn selected(mut self, index: usize) -> Self {
+        self.component = self.component.selected(index);
+        self
+    }
+
+    /// ### styles
+    ///
+    /// Set the style of the names of the sources
+    pub fn styles(mut self, styles: HashMap<String, Style>) -> Self {
+        self.component = self.component.styles(styles);
+        self
+    }
+
+    /// ### pinned
+    ///
+    /// Set the sources pinned to the top of the list, which are marked
+    pub fn pinned(mut self, pinned: HashSet<String>) -> Self {
+        self.component = self.component.pinned(pinned);
+        self
+    }
 }
 
 impl Component<Msg, NoUserEvent> for FeedList {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
-        let cmd_result = match ev {
-            Event::Keyboard(KeyEvent {
-                code: Key::Down, ..
-            }) => self.perform(Cmd::Move(Direction::Down)),
-            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
-                self.perform(Cmd::Move(Direction::Up))
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::PageDown,
-                ..
-            }) => self.perform(Cmd::Scroll(Direction::Down)),
-            Event::Keyboard(KeyEvent {
-                code: Key::PageUp, ..
-            }) => self.perform(Cmd::Scroll(Direction::Up)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Home, ..
-            }) => self.perform(Cmd::GoTo(Position::Begin)),
-            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
-                self.perform(Cmd::GoTo(Position::End))
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Tab | Key::Right,
-                ..
-            }) => return Some(Msg::FeedListBlur),
+        let key = match ev {
+            Event::Keyboard(key) => key,
             _ => return None,
         };
+        let keys = &self.keys;
+        let cmd_result = if keys.move_down.matches(&key) {
+            self.perform(Cmd::Move(Direction::Down))
+        } else if keys.move_up.matches(&key) {
+            self.perform(Cmd::Move(Direction::Up))
+        } else if keys.scroll_down.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Down))
+        } else if keys.scroll_up.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Up))
+        } else if keys.go_to_begin.matches(&key) {
+            self.perform(Cmd::GoTo(Position::Begin))
+        } else if keys.go_to_end.matches(&key) {
+            self.perform(Cmd::GoTo(Position::End))
+        } else if keys.switch_list.matches(&key) || keys.next_pane.matches(&key) {
+            return Some(Msg::FeedListBlur);
+        } else if keys.delete_feed.matches(&key) {
+            return Some(Msg::ShowDeleteFeedPopup);
+        } else if keys.rename_feed.matches(&key) {
+            return Some(Msg::ShowRenameFeedPopup);
+        } else if keys.feed_info.matches(&key) {
+            return Some(Msg::ShowFeedInfoPopup);
+        } else if keys.open_article.matches(&key) {
+            return Some(Msg::OpenFeedHomepage);
+        } else if let Some(tab) = tab_number(&key) {
+            return Some(Msg::SwitchTab(tab));
+        } else {
+            return None;
+        };
         if let CmdResult::Changed(State::One(StateValue::Usize(index))) = cmd_result {
             Some(Msg::FeedChanged(index))
         } else {
@@ -90,70 +134,454 @@ impl Component<Msg, NoUserEvent> for FeedList {
     }
 }
 
-#[derive(MockComponent)]
+/// ## ArticleEntry
+///
+/// A row of the article list
+pub struct ArticleEntry {
+    /// Index of the article in the feed
+    pub index: usize,
+    pub date: Option<String>,
+    /// Source of the article, listed only in the aggregated feed
+    pub source: Option<String>,
+    /// Title, preceded by the flair if any
+    pub title: String,
+    /// Score and media duration, displayed after the title
+    pub suffix: String,
+    pub read: bool,
+    /// Whether the article has been fetched for the first time since the last run
+    pub new: bool,
+    /// Style of the title by the age of the article, if articles are styled by age
+    pub age_style: Option<Style>,
+    /// Day the article is grouped under, if articles are grouped by day
+    pub day: Option<String>,
+}
+
+/// ## Line
+///
+/// A line displayed in the article list
+enum Line {
+    /// Header preceding the articles of a day; it can't be selected
+    Day(String),
+    /// Row of the article at the index in the entries
+    Article(usize),
+}
+
+/// ## Columns
+///
+/// Width of the columns of the displayed article rows
+struct Columns {
+    date: usize,
+    source: usize,
+    /// Title, suffix included
+    title: usize,
+}
+
+/// ## ArticleList
+///
+/// The list of the articles of a feed. Rows may be a subset of the feed articles, in any order;
+/// its state is the index in the feed of the selected article.
+/// Rows are elided and styled only once they're displayed, so that huge feeds are cheap to list
 pub struct ArticleList {
+    /// Holds properties and selection; its rows are never set
     component: List,
+    keys: KeyBindings,
+    articles: Vec<ArticleEntry>,
+    /// Displayed lines, day headers included
+    lines: Vec<Line>,
+    /// Line of each article
+    article_lines: Vec<usize>,
+    highlights: Highlights,
+    theme: Theme,
+    /// Style of the names of the sources
+    styles: HashMap<String, Style>,
+    /// Width of the rows
+    width: usize,
+    /// First row displayed
+    offset: usize,
 }
 
 impl ArticleList {
-    pub fn new(articles: &[String]) -> Self {
+    /// ### new
+    ///
+    /// Instantiates a new `ArticleList` of rows `width` columns wide; each article is made up
+    /// of its date, its source if any and its title, where `highlights` are highlighted.
+    /// A header is inserted whenever the day of the articles changes
+    pub fn new(
+        articles: Vec<ArticleEntry>,
+        width: usize,
+        highlights: &Highlights,
+        keys: KeyBindings,
+        theme: &Theme,
+    ) -> Self {
+        let mut component = List::default()
+            .highlighted_color(theme.cyan)
+            .highlighted_str(theme.icons.selected)
+            .rewind(true)
+            .scroll(true)
+            .step(4)
+            .title("Articles", Alignment::Center)
+            .borders(
+                Borders::default()
+                    .color(theme.cyan)
+                    .modifiers(BorderType::Rounded),
+            );
+        component.states.set_list_len(articles.len());
+        let mut lines = Vec::with_capacity(articles.len());
+        let mut article_lines = Vec::with_capacity(articles.len());
+        let mut day = None;
+        for (row, article) in articles.iter().enumerate() {
+            if article.day.is_some() && article.day != day {
+                day = article.day.clone();
+                lines.push(Line::Day(day.clone().unwrap_or_default()));
+            }
+            article_lines.push(lines.len());
+            lines.push(Line::Article(row));
+        }
         Self {
-            component: List::default()
-                .highlighted_color(Color::LightCyan)
-                .highlighted_str("➤ ")
-                .rewind(true)
-                .scroll(true)
-                .step(4)
-                .title("Articles", Alignment::Center)
-                .borders(
-                    Borders::default()
-                        .color(Color::LightCyan)
-                        .modifiers(BorderType::Rounded),
-                )
-                .rows(
-                    articles
-                        .iter()
-                        .map(|x| vec![TextSpan::from(x.as_str())])
-                        .collect(),
-                ),
+            component,
+            keys,
+            articles,
+            lines,
+            article_lines,
+            highlights: highlights.clone(),
+            theme: *theme,
+            styles: HashMap::new(),
+            width,
+            offset: 0,
+        }
+    }
+
+    /// ### title
+    ///
+    /// Set the title of the list, which is `Articles` by default
+    pub fn title<S: AsRef<str>>(mut self, title: S) -> Self {
+        self.component.attr(
+            Attribute::Title,
+            AttrValue::Title((title.as_ref().to_string(), Alignment::Center)),
+        );
+        self.component.states.set_list_len(self.articles.len());
+        self
+    }
+
+    /// ### styles
+    ///
+    /// Set the style of the names of the sources, which are blue by default
+    pub fn styles(mut self, styles: HashMap<String, Style>) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// ### selected
+    ///
+    /// Select the article at `index` in the feed. If it's not in the list, the first row is selected
+    pub fn selected(mut self, index: usize) -> Self {
+        self.component.states.list_index = self
+            .articles
+            .iter()
+            .position(|x| x.index == index)
+            .unwrap_or(0);
+        self.component.states.fix_list_index();
+        self
+    }
+
+    /// ### make_row
+    ///
+    /// Make list row for article, made up of its columns, `columns` wide, and its title;
+    /// read articles are dimmed, unread ones may be styled by age, while new ones are marked
+    fn make_row(&self, article: &ArticleEntry, columns: &Columns) -> Spans<'static> {
+        // Read articles are dimmed, highlighted words included
+        let (fg, highlight_fg) = match article.read {
+            true => (self.theme.dim, self.theme.dim),
+            false => (Color::Reset, self.theme.yellow),
+        };
+        // Unread articles are styled by their age, if configured
+        let style = match (article.read, article.age_style) {
+            (false, Some(style)) => style,
+            _ => Style::default().fg(fg),
+        };
+        // New articles are marked regardless of the read flag
+        let marker = match article.new {
+            true => Some(Span::styled(
+                format!("{} ", self.theme.icons.new),
+                Style::default()
+                    .fg(self.theme.magenta)
+                    .add_modifier(TextModifiers::BOLD),
+            )),
+            false => None,
+        };
+        let title_len = match marker {
+            Some(_) => columns.title.saturating_sub(2),
+            None => columns.title,
+        };
+        let suffix_len = str_helpers::string_width(article.suffix.as_str());
+        let title = match suffix_len {
+            len if len > 0 && title_len > len + 1 => format!(
+                "{} {}",
+                str_helpers::elide_string_at(article.title.as_str(), title_len - len - 1),
+                article.suffix
+            ),
+            _ => str_helpers::elide_string_at(article.title.as_str(), title_len),
+        };
+        let title =
+            marker
+                .into_iter()
+                .chain(self.highlights.split(title.as_str()).into_iter().map(
+                    |(part, highlighted)| {
+                        match highlighted {
+                            true => Span::styled(
+                                part.to_string(),
+                                Style::default()
+                                    .fg(highlight_fg)
+                                    .add_modifier(TextModifiers::BOLD),
+                            ),
+                            false => Span::styled(part.to_string(), style),
+                        }
+                    },
+                ));
+        let source_style = article
+            .source
+            .as_ref()
+            .and_then(|x| self.styles.get(x))
+            .copied()
+            .unwrap_or_else(|| Style::default().fg(self.theme.blue));
+        let columns = [
+            (
+                columns.date,
+                article.date.as_deref(),
+                Style::default().fg(self.theme.green),
+            ),
+            (columns.source, article.source.as_deref(), source_style),
+        ];
+        let columns =
+            columns
+                .into_iter()
+                .filter(|(len, _, _)| *len > 0)
+                .flat_map(|(len, text, style)| {
+                    let text = str_helpers::pad_string_to(
+                        str_helpers::elide_string_at(text.unwrap_or(""), len).as_str(),
+                        len,
+                    );
+                    [Span::styled(text, style), Span::from(" ")]
+                });
+        Spans::from(columns.chain(title).collect::<Vec<Span>>())
+    }
+
+    /// ### columns
+    ///
+    /// Get the width of the columns of the article `rows` to display: dates and sources are as wide
+    /// as the longest ones, but never wider than half and a quarter of the row respectively.
+    /// Only the displayed rows are measured, so that long lists cost no more than short ones
+    fn columns<'a>(&self, rows: impl Iterator<Item = &'a ArticleEntry> + Clone) -> Columns {
+        let date = rows
+            .clone()
+            .filter_map(|x| x.date.as_deref().map(str_helpers::string_width))
+            .max()
+            .unwrap_or(0)
+            .min(self.width / 2);
+        let source = rows
+            .filter_map(|x| x.source.as_deref().map(str_helpers::string_width))
+            .max()
+            .unwrap_or(0)
+            .min(self.width / 4);
+        let title = [date, source]
+            .iter()
+            .filter(|x| **x > 0)
+            .fold(self.width, |width, x| width.saturating_sub(x + 1));
+        Columns {
+            date,
+            source,
+            title,
+        }
+    }
+
+    /// ### scroll_to_selected
+    ///
+    /// Move the first displayed line so that the selected article is visible in `height` rows,
+    /// along with the header of its day when scrolling up
+    fn scroll_to_selected(&mut self, height: usize) {
+        let selected = self.selected_line();
+        let top = match selected.checked_sub(1).and_then(|x| self.lines.get(x)) {
+            Some(Line::Day(_)) => selected - 1,
+            _ => selected,
+        };
+        if top < self.offset {
+            self.offset = top;
+        } else if height > 0 && selected >= self.offset + height {
+            self.offset = selected + 1 - height;
+        }
+    }
+
+    /// ### selected_line
+    ///
+    /// Get the line of the selected article
+    fn selected_line(&self) -> usize {
+        self.article_lines
+            .get(self.component.states.list_index)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// ### make_day_row
+    ///
+    /// Make the header of the articles of `day`
+    fn make_day_row(&self, day: &str) -> Spans<'static> {
+        Spans::from(Span::styled(
+            day.to_string(),
+            Style::default()
+                .fg(self.theme.blue)
+                .add_modifier(TextModifiers::BOLD | TextModifiers::UNDERLINED),
+        ))
+    }
+
+    /// ### article_at
+    ///
+    /// Map a list state to the state holding the index of the article in the feed
+    fn article_at(&self, state: State) -> State {
+        match state {
+            State::One(StateValue::Usize(row)) => match self.articles.get(row) {
+                Some(article) => State::One(StateValue::Usize(article.index)),
+                None => State::None,
+            },
+            state => state,
+        }
+    }
+}
+
+impl MockComponent for ArticleList {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let focus = self
+            .query(Attribute::Focus)
+            .unwrap_or(AttrValue::Flag(false))
+            .unwrap_flag();
+        let title = self.query(Attribute::Title).map(|x| x.unwrap_title());
+        let div = tui_realm_stdlib::utils::get_block(
+            Borders::default()
+                .color(self.theme.cyan)
+                .modifiers(BorderType::Rounded),
+            title,
+            focus,
+            None,
+        );
+        // Only the rows in the area are made
+        self.scroll_to_selected(area.height.saturating_sub(2) as usize);
+        let lines = self
+            .lines
+            .iter()
+            .skip(self.offset)
+            .take(area.height as usize);
+        let columns = self.columns(lines.clone().filter_map(|x| match x {
+            Line::Article(row) => Some(&self.articles[*row]),
+            Line::Day(_) => None,
+        }));
+        let rows: Vec<ListItem> = lines
+            .map(|x| match x {
+                Line::Day(day) => ListItem::new(self.make_day_row(day.as_str())),
+                Line::Article(row) => ListItem::new(self.make_row(&self.articles[*row], &columns)),
+            })
+            .collect();
+        let (fg, bg): (Color, Color) = match focus {
+            true => (Color::Reset, self.theme.cyan),
+            false => (self.theme.cyan, Color::Reset),
+        };
+        let list = TuiList::new(rows)
+            .block(div)
+            .start_corner(Corner::TopLeft)
+            .highlight_style(Style::default().fg(fg).bg(bg))
+            .highlight_symbol(self.theme.icons.selected);
+        let mut state: ListState = ListState::default();
+        if !self.articles.is_empty() {
+            state.select(Some(self.selected_line() - self.offset));
+        }
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.component.attr(attr, value);
+        // NOTE: the list resets its length to its rows, which are never set
+        self.component.states.set_list_len(self.articles.len());
+        self.component.states.fix_list_index();
+    }
+
+    fn state(&self) -> State {
+        self.article_at(self.component.state())
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        match self.component.perform(cmd) {
+            CmdResult::Changed(state) => CmdResult::Changed(self.article_at(state)),
+            result => result,
         }
     }
 }
 
 impl Component<Msg, NoUserEvent> for ArticleList {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
-        let cmd_result = match ev {
-            Event::Keyboard(KeyEvent {
-                code: Key::Down, ..
-            }) => self.perform(Cmd::Move(Direction::Down)),
-            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
-                self.perform(Cmd::Move(Direction::Up))
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::PageDown,
-                ..
-            }) => self.perform(Cmd::Scroll(Direction::Down)),
-            Event::Keyboard(KeyEvent {
-                code: Key::PageUp, ..
-            }) => self.perform(Cmd::Scroll(Direction::Up)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Home, ..
-            }) => self.perform(Cmd::GoTo(Position::Begin)),
-            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
-                self.perform(Cmd::GoTo(Position::End))
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Tab | Key::Left,
-                ..
-            }) => return Some(Msg::ArticleListBlur),
-            Event::Keyboard(KeyEvent {
-                code: Key::Right, ..
-            }) => return Some(Msg::GoReadArticle),
-            Event::Keyboard(KeyEvent {
-                code: Key::Enter, ..
-            }) => return Some(Msg::OpenArticle),
+        let key = match ev {
+            Event::Keyboard(key) => key,
             _ => return None,
         };
+        let keys = &self.keys;
+        let cmd_result = if keys.move_down.matches(&key) {
+            self.perform(Cmd::Move(Direction::Down))
+        } else if keys.move_up.matches(&key) {
+            self.perform(Cmd::Move(Direction::Up))
+        } else if keys.scroll_down.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Down))
+        } else if keys.scroll_up.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Up))
+        } else if keys.go_to_begin.matches(&key) {
+            self.perform(Cmd::GoTo(Position::Begin))
+        } else if keys.go_to_end.matches(&key) {
+            self.perform(Cmd::GoTo(Position::End))
+        } else if keys.switch_list.matches(&key) || keys.previous_pane.matches(&key) {
+            return Some(Msg::ArticleListBlur);
+        } else if keys.next_pane.matches(&key) {
+            return Some(Msg::GoReadArticle);
+        } else if keys.open_article.matches(&key) {
+            return Some(Msg::OpenArticle);
+        } else if keys.open_comments.matches(&key) {
+            return Some(Msg::OpenComments);
+        } else if keys.open_with.matches(&key) {
+            return Some(Msg::ShowOpenWithPopup);
+        } else if keys.share.matches(&key) {
+            return Some(Msg::ShowSharePopup);
+        } else if keys.filter_articles.matches(&key) {
+            return Some(Msg::ShowFilterPopup);
+        } else if keys.filter_tag.matches(&key) {
+            return Some(Msg::ShowTagFilterPopup);
+        } else if keys.tag_article.matches(&key) {
+            return Some(Msg::ShowTagArticlePopup);
+        } else if keys.edit_note.matches(&key) {
+            return Some(Msg::EditNote);
+        } else if keys.star_article.matches(&key) {
+            return Some(Msg::StarArticle);
+        } else if keys.hide_read.matches(&key) {
+            return Some(Msg::ToggleHideRead);
+        } else if keys.mark_all_read.matches(&key) {
+            return Some(Msg::MarkAllRead);
+        } else if keys.sort_by_score.matches(&key) {
+            return Some(Msg::ToggleSortByScore);
+        } else if keys.sort_by_unread.matches(&key) {
+            return Some(Msg::ToggleSortByUnread);
+        } else if keys.reading_mode.matches(&key) {
+            return Some(Msg::ToggleReadingMode);
+        } else if keys.yank_link.matches(&key) {
+            return Some(Msg::YankLink);
+        } else if keys.yank_article.matches(&key) {
+            return Some(Msg::YankArticle);
+        } else if keys.archive_article.matches(&key) {
+            return Some(Msg::ArchiveArticle);
+        } else if keys.speak_article.matches(&key) {
+            return Some(Msg::SpeakArticle);
+        } else if let Some(tab) = tab_number(&key) {
+            return Some(Msg::SwitchTab(tab));
+        } else {
+            return None;
+        };
         if let CmdResult::Changed(State::One(StateValue::Usize(index))) = cmd_result {
             Some(Msg::ArticleChanged(index))
         } else {
@@ -161,3 +589,15 @@ impl Component<Msg, NoUserEvent> for ArticleList {
         }
     }
 }
+
+/// ### tab_number
+///
+/// Get the index of the tab selected typing its number (1-9) with `key`, if any
+fn tab_number(key: &KeyEvent) -> Option<usize> {
+    match key.code {
+        Key::Char(ch) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+            ch.to_digit(10).filter(|x| *x > 0).map(|x| x as usize - 1)
+        }
+        _ => None,
+    }
+}
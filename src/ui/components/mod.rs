@@ -26,39 +26,98 @@
  * SOFTWARE.
  */
 use super::Msg;
+use crate::config::{KeyBindings, ScriptAction};
 
 mod article;
 pub mod lists;
 mod popups;
+mod status;
 
-pub use article::{ArticleAuthors, ArticleDate, ArticleLink, ArticleSummary, ArticleTitle};
-pub use lists::{ArticleList, FeedList};
-pub use popups::{ErrorPopup, QuitPopup};
+pub use article::{
+    ArticleAuthors, ArticleComments, ArticleDate, ArticleLink, ArticleSummary, ArticleTags,
+    ArticleTitle, SUMMARY_PROP_LINKS,
+};
+pub use lists::{ArticleEntry, ArticleList, FeedList};
+pub use popups::{
+    AddFeedPopup, CommandLine, DeleteFeedPopup, ErrorPopup, FeedInfoPopup, FeedSwitcherPopup,
+    FilterPopup, HelpPopup, OpenWithPopup, PodcastResultsPopup, PodcastSearchPopup, QuitPopup,
+    RenameFeedPopup, SearchPopup, SharePopup, TagArticlePopup, TagFilterPopup,
+};
+pub use status::{StatusBar, TabBar};
 
 use tui_realm_stdlib::Phantom;
-use tuirealm::{
-    event::{Key, KeyEvent, KeyModifiers},
-    Component, Event, MockComponent, NoUserEvent,
-};
+use tuirealm::{Component, Event, MockComponent, NoUserEvent};
+
+/// Attribute which, when set to `true`, prevents the global listener from receiving events;
+/// it must be set while an input is active, otherwise typing would trigger global commands
+pub const GLOBAL_LISTENER_LOCKED: &str = "locked";
 
-#[derive(Default, MockComponent)]
+#[derive(MockComponent)]
 pub struct GlobalListener {
     component: Phantom,
+    keys: KeyBindings,
+    /// Actions of the script, bound to their keys
+    actions: Vec<ScriptAction>,
+}
+
+impl GlobalListener {
+    pub fn new(keys: KeyBindings, actions: Vec<ScriptAction>) -> Self {
+        Self {
+            component: Phantom::default(),
+            keys,
+            actions,
+        }
+    }
 }
 
 impl Component<Msg, NoUserEvent> for GlobalListener {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
-        match ev {
-            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::ShowQuitPopup),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('r'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => Some(Msg::FetchAllSources),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('r'),
-                ..
-            }) => Some(Msg::FetchSource),
-            _ => None,
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            Event::WindowResize(_, _) => return Some(Msg::WindowResized),
+            Event::Tick => return Some(Msg::RefreshDates),
+            _ => return None,
+        };
+        let keys = &self.keys;
+        if keys.quit.matches(&key) {
+            Some(Msg::ShowQuitPopup)
+        } else if keys.help.matches(&key) {
+            Some(Msg::ShowHelpPopup)
+        } else if keys.fetch_all_sources.matches(&key) {
+            Some(Msg::FetchAllSources)
+        } else if keys.force_fetch_all_sources.matches(&key) {
+            Some(Msg::ForceFetchAllSources)
+        } else if keys.fetch_category.matches(&key) {
+            Some(Msg::FetchCategory)
+        } else if keys.fetch_source.matches(&key) {
+            Some(Msg::FetchSource)
+        } else if keys.edit_config.matches(&key) {
+            Some(Msg::EditConfig)
+        } else if keys.add_feed.matches(&key) {
+            Some(Msg::ShowAddFeedPopup)
+        } else if keys.search_podcasts.matches(&key) {
+            Some(Msg::ShowPodcastSearchPopup)
+        } else if keys.add_from_clipboard.matches(&key) {
+            Some(Msg::SubscribeFromClipboard)
+        } else if keys.next_unread.matches(&key) {
+            Some(Msg::NextUnread)
+        } else if keys.previous_unread.matches(&key) {
+            Some(Msg::PreviousUnread)
+        } else if keys.undo.matches(&key) {
+            Some(Msg::Undo)
+        } else if keys.switch_feed.matches(&key) {
+            Some(Msg::ShowFeedSwitcherPopup)
+        } else if keys.command.matches(&key) {
+            Some(Msg::ShowCommandLine)
+        } else if keys.stop_speaking.matches(&key) {
+            Some(Msg::StopSpeaking)
+        } else if keys.toggle_offline.matches(&key) {
+            Some(Msg::ToggleOffline)
+        } else {
+            self.actions
+                .iter()
+                .find(|x| x.key.matches(&key))
+                .map(|x| Msg::RunScript(x.function.clone()))
         }
     }
 }
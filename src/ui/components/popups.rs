@@ -26,27 +26,35 @@
  * SOFTWARE.
  */
 use super::Msg;
+use crate::config::{KeyBindings, ShareTarget, Theme};
+use crate::feed::{Channel, Podcast};
+use crate::helpers::strings as str_helpers;
+use crate::ui::lib::OpenerEntry;
 
-use tui_realm_stdlib::{Paragraph, Radio};
-use tuirealm::command::{Cmd, CmdResult, Direction};
-use tuirealm::event::{Key, KeyEvent};
-use tuirealm::props::{Alignment, BorderType, Borders, Color, TextModifiers, TextSpan};
-use tuirealm::{Component, Event, MockComponent, NoUserEvent, State, StateValue};
+use tui_realm_stdlib::{Input, List, Paragraph, Radio};
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+use tuirealm::props::{
+    Alignment, AttrValue, Attribute, BorderType, Borders, Color, InputType, TableBuilder,
+    TextModifiers, TextSpan,
+};
+use tuirealm::tui::layout::{Constraint, Direction as LayoutDirection, Layout, Rect};
+use tuirealm::{Component, Event, Frame, MockComponent, NoUserEvent, State, StateValue};
 
 #[derive(MockComponent)]
 pub struct QuitPopup {
     component: Radio,
 }
 
-impl Default for QuitPopup {
-    fn default() -> Self {
+impl QuitPopup {
+    pub fn new(theme: &Theme) -> Self {
         Self {
             component: Radio::default()
-                .foreground(Color::Yellow)
-                .background(Color::Black)
+                .foreground(theme.warning)
+                .background(theme.background)
                 .borders(
                     Borders::default()
-                        .color(Color::Yellow)
+                        .color(theme.warning)
                         .modifiers(BorderType::Rounded),
                 )
                 .title("Are sure you want to quit?", Alignment::Center)
@@ -87,37 +95,1126 @@ impl Component<Msg, NoUserEvent> for QuitPopup {
     }
 }
 
+/// ## ErrorPopup
+///
+/// A popup showing an error of the error queue.
+/// When there are more errors, the title reports the position in the queue
 #[derive(MockComponent)]
 pub struct ErrorPopup {
     component: Paragraph,
 }
 
 impl ErrorPopup {
-    pub fn new<S: AsRef<str>>(msg: S) -> Self {
+    pub fn new<S: AsRef<str>>(msg: S, position: usize, errors: usize, theme: &Theme) -> Self {
+        let mut component = Paragraph::default()
+            .borders(
+                Borders::default()
+                    .color(theme.red)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.red)
+            .background(theme.background)
+            .modifiers(TextModifiers::BOLD)
+            .alignment(Alignment::Center)
+            .text(vec![TextSpan::from(msg.as_ref().to_string())].as_slice());
+        if errors > 1 {
+            component = component.title(
+                format!(
+                    "Error {} of {} (←/→ to browse, Esc to dismiss all)",
+                    position, errors
+                ),
+                Alignment::Center,
+            );
+        }
+        Self { component }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ErrorPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::DismissError),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::CloseErrorPopup),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right | Key::Tab,
+                ..
+            }) => Some(Msg::NextError),
+            Event::Keyboard(KeyEvent {
+                code: Key::Left | Key::BackTab,
+                ..
+            }) => Some(Msg::PreviousError),
+            _ => None,
+        }
+    }
+}
+
+/// ## AddFeedPopup
+///
+/// A popup with two inputs to subscribe to a new feed: the first one is the source name,
+/// while the second one is the feed url
+pub struct AddFeedPopup {
+    inputs: [Input; 2],
+    focus: usize,
+}
+
+impl AddFeedPopup {
+    pub fn new(theme: &Theme) -> Self {
+        let mut popup = Self {
+            inputs: [
+                Self::input("Feed name", "New York Times", theme),
+                Self::input(
+                    "Feed URL",
+                    "https://rss.nytimes.com/services/xml/rss/nyt/World.xml",
+                    theme,
+                ),
+            ],
+            focus: 0,
+        };
+        popup.inputs[0].attr(Attribute::Focus, AttrValue::Flag(true));
+        popup
+    }
+
+    /// ### url
+    ///
+    /// Fill the feed url input with `url`
+    pub fn url(mut self, url: &str) -> Self {
+        let input = std::mem::take(&mut self.inputs[1]);
+        self.inputs[1] = input.value(url);
+        self
+    }
+
+    fn input(title: &str, placeholder: &str, theme: &Theme) -> Input {
+        Input::default()
+            .borders(
+                Borders::default()
+                    .color(theme.green)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.green)
+            .background(theme.background)
+            .input_type(InputType::Text)
+            .placeholder(
+                placeholder,
+                tuirealm::tui::style::Style::default().fg(Color::Rgb(128, 128, 128)),
+            )
+            .title(title, Alignment::Left)
+    }
+
+    /// ### change_focus
+    ///
+    /// Move focus to the other input
+    fn change_focus(&mut self) {
+        self.inputs[self.focus].attr(Attribute::Focus, AttrValue::Flag(false));
+        self.focus = (self.focus + 1) % self.inputs.len();
+        self.inputs[self.focus].attr(Attribute::Focus, AttrValue::Flag(true));
+    }
+
+    fn value(&self, input: usize) -> String {
+        match self.inputs[input].state() {
+            State::One(StateValue::String(value)) => value,
+            _ => String::new(),
+        }
+    }
+}
+
+impl MockComponent for AddFeedPopup {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Length(3)].as_ref())
+            .split(area);
+        for (input, chunk) in self.inputs.iter_mut().zip(chunks.iter()) {
+            input.view(frame, *chunk);
+        }
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.inputs[self.focus].query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.inputs[self.focus].attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::Tup2((
+            StateValue::String(self.value(0)),
+            StateValue::String(self.value(1)),
+        ))
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.inputs[self.focus].perform(cmd)
+    }
+}
+
+impl Component<Msg, NoUserEvent> for AddFeedPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Tab | Key::BackTab | Key::Up | Key::Down => self.change_focus(),
+            // Move to url if name is the active input
+            Key::Enter if self.focus == 0 => self.change_focus(),
+            Key::Enter => return Some(Msg::SubscribeFeed(self.value(0), self.value(1))),
+            Key::Esc => return Some(Msg::CloseAddFeedPopup),
+            _ => return None,
+        }
+        Some(Msg::None)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct RenameFeedPopup {
+    component: Input,
+    name: String,
+}
+
+impl RenameFeedPopup {
+    pub fn new(name: &str, theme: &Theme) -> Self {
+        let mut component = Input::default()
+            .borders(
+                Borders::default()
+                    .color(theme.yellow)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.yellow)
+            .background(theme.background)
+            .input_type(InputType::Text)
+            .title(format!(r#"Rename "{}""#, name), Alignment::Left)
+            .value(name);
+        component.perform(Cmd::GoTo(Position::End));
         Self {
-            component: Paragraph::default()
+            component,
+            name: name.to_string(),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for RenameFeedPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Enter => match self.state() {
+                State::One(StateValue::String(new_name)) => {
+                    Some(Msg::RenameFeed(self.name.clone(), new_name))
+                }
+                _ => Some(Msg::None),
+            },
+            Key::Esc => Some(Msg::CloseRenameFeedPopup),
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DeleteFeedPopup {
+    component: Radio,
+    name: String,
+}
+
+impl DeleteFeedPopup {
+    pub fn new(name: &str, theme: &Theme) -> Self {
+        Self {
+            component: Radio::default()
+                .foreground(theme.red)
+                .background(theme.background)
                 .borders(
                     Borders::default()
-                        .color(Color::Red)
+                        .color(theme.red)
                         .modifiers(BorderType::Rounded),
                 )
-                .foreground(Color::Red)
-                .background(Color::Black)
-                .modifiers(TextModifiers::BOLD)
-                .alignment(Alignment::Center)
-                .text(vec![TextSpan::from(msg.as_ref().to_string())].as_slice()),
+                .title(
+                    format!(r#"Unsubscribe from "{}"?"#, name),
+                    Alignment::Center,
+                )
+                .rewind(true)
+                .choices(&["Yes", "No"])
+                .value(1),
+            name: name.to_string(),
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for ErrorPopup {
+impl Component<Msg, NoUserEvent> for DeleteFeedPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
-        match ev {
+        let cmd_result = match ev {
             Event::Keyboard(KeyEvent {
-                code: Key::Enter | Key::Esc,
-                ..
-            }) => Some(Msg::CloseErrorPopup),
+                code: Key::Left, ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => self.perform(Cmd::Submit),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                return Some(Msg::CloseDeleteFeedPopup)
+            }
+            _ => return None,
+        };
+        if matches!(
+            cmd_result,
+            CmdResult::Submit(State::One(StateValue::Usize(0)))
+        ) {
+            Some(Msg::UnsubscribeFeed(self.name.clone()))
+        } else if matches!(
+            cmd_result,
+            CmdResult::Submit(State::One(StateValue::Usize(1)))
+        ) {
+            Some(Msg::CloseDeleteFeedPopup)
+        } else {
+            Some(Msg::None)
+        }
+    }
+}
+
+/// ## FilterPopup
+///
+/// An input which reports its value on each change, used to filter the entries of a list
+#[derive(MockComponent)]
+pub struct FilterPopup {
+    component: Input,
+}
+
+impl FilterPopup {
+    pub fn new(title: &str, theme: &Theme) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.cyan)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.cyan)
+                .background(theme.background)
+                .input_type(InputType::Text)
+                .title(title, Alignment::Left),
+        }
+    }
+
+    fn value(&self) -> String {
+        match self.state() {
+            State::One(StateValue::String(value)) => value,
+            _ => String::new(),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FilterPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            return match self.perform(cmd) {
+                CmdResult::Changed(_) => Some(Msg::FilterChanged(self.value())),
+                _ => Some(Msg::None),
+            };
+        }
+        match key.code {
+            Key::Enter => Some(Msg::SubmitFilter),
+            Key::Esc => Some(Msg::CloseFilterPopup),
+            _ => None,
+        }
+    }
+}
+
+/// ## TagFilterPopup
+///
+/// An input to type the tag the articles must have to be displayed; an empty tag removes the filter
+#[derive(MockComponent)]
+pub struct TagFilterPopup {
+    component: Input,
+}
+
+impl TagFilterPopup {
+    pub fn new(tag: &str, theme: &Theme) -> Self {
+        let mut component = Input::default()
+            .borders(
+                Borders::default()
+                    .color(theme.magenta)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.magenta)
+            .background(theme.background)
+            .input_type(InputType::Text)
+            .title("Show articles tagged (empty for all)", Alignment::Left)
+            .value(tag);
+        component.perform(Cmd::GoTo(Position::End));
+        Self { component }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for TagFilterPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Enter => match self.state() {
+                State::One(StateValue::String(tag)) => Some(Msg::FilterTag(tag)),
+                _ => Some(Msg::FilterTag(String::new())),
+            },
+            Key::Esc => Some(Msg::CloseTagFilterPopup),
             _ => None,
         }
     }
 }
+
+/// ## SearchPopup
+///
+/// An input to type the text to search in the article summary; an empty text ends the search
+#[derive(MockComponent)]
+pub struct SearchPopup {
+    component: Input,
+}
+
+impl SearchPopup {
+    pub fn new(query: &str, theme: &Theme) -> Self {
+        let mut component = Input::default()
+            .borders(
+                Borders::default()
+                    .color(theme.yellow)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.yellow)
+            .background(theme.background)
+            .input_type(InputType::Text)
+            .title("Search in the article (empty to stop)", Alignment::Left)
+            .value(query);
+        component.perform(Cmd::GoTo(Position::End));
+        Self { component }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SearchPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Enter => match self.state() {
+                State::One(StateValue::String(query)) => Some(Msg::SearchArticle(query)),
+                _ => Some(Msg::SearchArticle(String::new())),
+            },
+            Key::Esc => Some(Msg::CloseSearchPopup),
+            _ => None,
+        }
+    }
+}
+
+/// ## PodcastSearchPopup
+///
+/// An input to type the name of the podcast to search in the directory
+#[derive(MockComponent)]
+pub struct PodcastSearchPopup {
+    component: Input,
+}
+
+impl PodcastSearchPopup {
+    pub fn new(theme: &Theme) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.yellow)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.yellow)
+                .background(theme.background)
+                .input_type(InputType::Text)
+                .title("Search podcasts", Alignment::Left),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for PodcastSearchPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Enter => match self.state() {
+                State::One(StateValue::String(query)) if !query.trim().is_empty() => {
+                    Some(Msg::SearchPodcasts(query))
+                }
+                _ => Some(Msg::None),
+            },
+            Key::Esc => Some(Msg::ClosePodcastSearchPopup),
+            _ => None,
+        }
+    }
+}
+
+/// ## PodcastResultsPopup
+///
+/// A list of the podcasts found in the directory; the selected one is subscribed to
+#[derive(MockComponent)]
+pub struct PodcastResultsPopup {
+    component: List,
+    keys: KeyBindings,
+}
+
+impl PodcastResultsPopup {
+    pub fn new(podcasts: &[Podcast], keys: KeyBindings, theme: &Theme) -> Self {
+        let mut rows = TableBuilder::default();
+        for (i, podcast) in podcasts.iter().enumerate() {
+            if i > 0 {
+                rows.add_row();
+            }
+            rows.add_col(TextSpan::from(podcast.name.as_str()).bold());
+            if let Some(author) = podcast.author.as_deref() {
+                rows.add_col(TextSpan::from(format!(" — {}", author)).fg(theme.cyan));
+            }
+        }
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.yellow)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.yellow)
+                .background(theme.background)
+                .title("Subscribe to podcast", Alignment::Center)
+                .scroll(true)
+                .step(8)
+                .highlighted_color(theme.yellow)
+                .highlighted_str(theme.icons.selected)
+                .rows(rows.build()),
+            keys,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for PodcastResultsPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        let keys = &self.keys;
+        if key.code == Key::Esc {
+            return Some(Msg::ClosePodcastResultsPopup);
+        } else if key.code == Key::Enter {
+            return Some(match self.state() {
+                State::One(StateValue::Usize(index)) => Msg::SubscribePodcast(index),
+                _ => Msg::None,
+            });
+        } else if keys.move_down.matches(&key) {
+            self.perform(Cmd::Move(Direction::Down));
+        } else if keys.move_up.matches(&key) {
+            self.perform(Cmd::Move(Direction::Up));
+        } else if keys.scroll_down.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Down));
+        } else if keys.scroll_up.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Up));
+        } else if keys.go_to_begin.matches(&key) {
+            self.perform(Cmd::GoTo(Position::Begin));
+        } else if keys.go_to_end.matches(&key) {
+            self.perform(Cmd::GoTo(Position::End));
+        } else {
+            return None;
+        }
+        Some(Msg::None)
+    }
+}
+
+/// ## TagArticlePopup
+///
+/// An input to type the comma-separated tags to attach to the selected article
+#[derive(MockComponent)]
+pub struct TagArticlePopup {
+    component: Input,
+}
+
+impl TagArticlePopup {
+    pub fn new(tags: &[String], theme: &Theme) -> Self {
+        let mut component = Input::default()
+            .borders(
+                Borders::default()
+                    .color(theme.green)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.green)
+            .background(theme.background)
+            .input_type(InputType::Text)
+            .title("Tag article (comma-separated)", Alignment::Left)
+            .value(tags.join(", "));
+        component.perform(Cmd::GoTo(Position::End));
+        Self { component }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for TagArticlePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Enter => match self.state() {
+                State::One(StateValue::String(tags)) => Some(Msg::TagArticle(tags)),
+                _ => Some(Msg::TagArticle(String::new())),
+            },
+            Key::Esc => Some(Msg::CloseTagArticlePopup),
+            _ => None,
+        }
+    }
+}
+
+/// ## CommandLine
+///
+/// An input to type an ex-style command, such as `refresh` or `set sort=date`
+#[derive(MockComponent)]
+pub struct CommandLine {
+    component: Input,
+}
+
+impl CommandLine {
+    pub fn new(theme: &Theme) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.yellow)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.yellow)
+                .background(theme.background)
+                .input_type(InputType::Text)
+                .placeholder(
+                    "refresh, open, save <path>, set sort=date...",
+                    tuirealm::tui::style::Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title(":", Alignment::Left),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for CommandLine {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Enter => match self.state() {
+                State::One(StateValue::String(line)) if !line.trim().is_empty() => {
+                    Some(Msg::RunCommand(line))
+                }
+                _ => Some(Msg::CloseCommandLine),
+            },
+            Key::Esc => Some(Msg::CloseCommandLine),
+            _ => None,
+        }
+    }
+}
+
+/// ## FeedSwitcherPopup
+///
+/// An input to fuzzy find a feed by name, with the feeds matching the query listed below it
+pub struct FeedSwitcherPopup {
+    input: Input,
+    list: List,
+    /// Feed names, in the same order as the feed list
+    feeds: Vec<String>,
+    /// Feed names matching the query, from the best match
+    matches: Vec<String>,
+}
+
+impl FeedSwitcherPopup {
+    pub fn new(feeds: Vec<String>, theme: &Theme) -> Self {
+        let mut input = Input::default()
+            .borders(
+                Borders::default()
+                    .color(theme.cyan)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.cyan)
+            .background(theme.background)
+            .input_type(InputType::Text)
+            .title("Go to feed", Alignment::Left);
+        input.attr(Attribute::Focus, AttrValue::Flag(true));
+        let mut list = List::default()
+            .borders(
+                Borders::default()
+                    .color(theme.cyan)
+                    .modifiers(BorderType::Rounded),
+            )
+            .foreground(theme.cyan)
+            .background(theme.background)
+            .scroll(true)
+            .highlighted_color(theme.cyan)
+            .highlighted_str(theme.icons.selected);
+        // Keep the list highlighted while typing
+        list.attr(Attribute::Focus, AttrValue::Flag(true));
+        let mut popup = Self {
+            input,
+            list,
+            feeds,
+            matches: Vec::new(),
+        };
+        popup.update_matches();
+        popup
+    }
+
+    fn query_value(&self) -> String {
+        match self.input.state() {
+            State::One(StateValue::String(value)) => value,
+            _ => String::new(),
+        }
+    }
+
+    /// ### update_matches
+    ///
+    /// Filter the feeds with the current query and select the best match
+    fn update_matches(&mut self) {
+        let query = self.query_value();
+        let mut matches: Vec<(i64, &String)> = self
+            .feeds
+            .iter()
+            .filter_map(|x| str_helpers::fuzzy_match(query.as_str(), x).map(|score| (score, x)))
+            .collect();
+        // Stable sort: feeds with the same score keep the feed list order
+        matches.sort_by_key(|x| std::cmp::Reverse(x.0));
+        self.matches = matches.into_iter().map(|(_, x)| x.clone()).collect();
+        let rows = self
+            .matches
+            .iter()
+            .map(|x| vec![TextSpan::from(x.as_str())])
+            .collect();
+        self.list.attr(Attribute::Content, AttrValue::Table(rows));
+        self.list.states.list_index_at_first();
+    }
+
+    fn selected(&self) -> Option<&String> {
+        self.matches.get(self.list.states.list_index)
+    }
+}
+
+impl MockComponent for FeedSwitcherPopup {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(LayoutDirection::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+            .split(area);
+        self.input.view(frame, chunks[0]);
+        self.list.view(frame, chunks[1]);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.input.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.input.attr(attr, value)
+    }
+
+    fn state(&self) -> State {
+        match self.selected() {
+            Some(feed) => State::One(StateValue::String(feed.clone())),
+            None => State::None,
+        }
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        let result = self.input.perform(cmd);
+        if matches!(result, CmdResult::Changed(_)) {
+            self.update_matches();
+        }
+        result
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FeedSwitcherPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if let Some(cmd) = input_cmd(&key) {
+            self.perform(cmd);
+            return Some(Msg::None);
+        }
+        match key.code {
+            Key::Down | Key::Tab => {
+                self.list.perform(Cmd::Move(Direction::Down));
+            }
+            Key::Up | Key::BackTab => {
+                self.list.perform(Cmd::Move(Direction::Up));
+            }
+            Key::Enter => {
+                return Some(match self.selected() {
+                    Some(feed) => Msg::SwitchFeed(feed.clone()),
+                    None => Msg::None,
+                })
+            }
+            Key::Esc => return Some(Msg::CloseFeedSwitcherPopup),
+            _ => return None,
+        }
+        Some(Msg::None)
+    }
+}
+
+/// ## OpenWithPopup
+///
+/// A menu listing the commands which can open the links of the selected article
+#[derive(MockComponent)]
+pub struct OpenWithPopup {
+    component: List,
+    entries: Vec<OpenerEntry>,
+    keys: KeyBindings,
+}
+
+impl OpenWithPopup {
+    pub fn new(entries: Vec<OpenerEntry>, keys: KeyBindings, theme: &Theme) -> Self {
+        let mut rows = TableBuilder::default();
+        for (i, entry) in entries.iter().enumerate() {
+            if i > 0 {
+                rows.add_row();
+            }
+            rows.add_col(TextSpan::from(format!("{:<16}", entry.name)).bold())
+                .add_col(TextSpan::from(format!("{:<12}", entry.target)).fg(theme.cyan))
+                .add_col(TextSpan::from(entry.url.as_str()));
+        }
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.green)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.green)
+                .background(theme.background)
+                .title("Open with", Alignment::Center)
+                .scroll(true)
+                .step(8)
+                .highlighted_color(theme.green)
+                .highlighted_str(theme.icons.selected)
+                .rows(rows.build()),
+            entries,
+            keys,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for OpenWithPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        let keys = &self.keys;
+        if key.code == Key::Esc || keys.open_with.matches(&key) {
+            return Some(Msg::CloseOpenWithPopup);
+        } else if key.code == Key::Enter {
+            return Some(match self.state() {
+                State::One(StateValue::Usize(index)) => match self.entries.get(index) {
+                    Some(entry) => Msg::OpenWith(entry.command.clone(), entry.url.clone()),
+                    None => Msg::None,
+                },
+                _ => Msg::None,
+            });
+        } else if keys.move_down.matches(&key) {
+            self.perform(Cmd::Move(Direction::Down));
+        } else if keys.move_up.matches(&key) {
+            self.perform(Cmd::Move(Direction::Up));
+        } else if keys.scroll_down.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Down));
+        } else if keys.scroll_up.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Up));
+        } else if keys.go_to_begin.matches(&key) {
+            self.perform(Cmd::GoTo(Position::Begin));
+        } else if keys.go_to_end.matches(&key) {
+            self.perform(Cmd::GoTo(Position::End));
+        } else {
+            return None;
+        }
+        Some(Msg::None)
+    }
+}
+
+/// ## SharePopup
+///
+/// A menu listing the commands the selected article can be shared with
+#[derive(MockComponent)]
+pub struct SharePopup {
+    component: List,
+    keys: KeyBindings,
+}
+
+impl SharePopup {
+    pub fn new(targets: &[ShareTarget], keys: KeyBindings, theme: &Theme) -> Self {
+        let mut rows = TableBuilder::default();
+        for (i, target) in targets.iter().enumerate() {
+            if i > 0 {
+                rows.add_row();
+            }
+            rows.add_col(TextSpan::from(format!("{:<16}", target.name)).bold())
+                .add_col(TextSpan::from(target.command.as_str()).fg(theme.cyan));
+        }
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.green)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.green)
+                .background(theme.background)
+                .title("Share with", Alignment::Center)
+                .scroll(true)
+                .step(8)
+                .highlighted_color(theme.green)
+                .highlighted_str(theme.icons.selected)
+                .rows(rows.build()),
+            keys,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SharePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        let keys = &self.keys;
+        if key.code == Key::Esc || keys.share.matches(&key) {
+            return Some(Msg::CloseSharePopup);
+        } else if key.code == Key::Enter {
+            return Some(match self.state() {
+                State::One(StateValue::Usize(index)) => Msg::Share(index),
+                _ => Msg::None,
+            });
+        } else if keys.move_down.matches(&key) {
+            self.perform(Cmd::Move(Direction::Down));
+        } else if keys.move_up.matches(&key) {
+            self.perform(Cmd::Move(Direction::Up));
+        } else if keys.scroll_down.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Down));
+        } else if keys.scroll_up.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Up));
+        } else if keys.go_to_begin.matches(&key) {
+            self.perform(Cmd::GoTo(Position::Begin));
+        } else if keys.go_to_end.matches(&key) {
+            self.perform(Cmd::GoTo(Position::End));
+        } else {
+            return None;
+        }
+        Some(Msg::None)
+    }
+}
+
+/// ## FeedInfoPopup
+///
+/// A popup which describes a feed, as declared in its channel element
+#[derive(MockComponent)]
+pub struct FeedInfoPopup {
+    component: Paragraph,
+    keys: KeyBindings,
+}
+
+impl FeedInfoPopup {
+    pub fn new(name: &str, channel: &Channel, keys: KeyBindings, theme: &Theme) -> Self {
+        let details = [
+            ("Title", channel.title.as_deref()),
+            ("Description", channel.description.as_deref()),
+            ("Website", channel.link.as_deref()),
+            ("Language", channel.language.as_deref()),
+            ("Generator", channel.generator.as_deref()),
+        ];
+        let mut lines: Vec<TextSpan> = details
+            .iter()
+            .filter_map(|(label, value)| value.map(|x| format!("{:<12} {}", label, x)))
+            .map(TextSpan::from)
+            .collect();
+        if lines.is_empty() {
+            lines.push(TextSpan::from("The feed doesn't describe itself"));
+        }
+        if channel.link.is_some() {
+            lines.push(TextSpan::from(""));
+            lines.push(
+                TextSpan::from(format!("Press {} to open the website", keys.open_article))
+                    .fg(theme.dim),
+            );
+        }
+        Self {
+            component: Paragraph::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.cyan)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.yellow)
+                .background(theme.background)
+                .title(name, Alignment::Center)
+                .wrap(true)
+                .text(lines.as_slice()),
+            keys,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FeedInfoPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        if key.code == Key::Esc || self.keys.feed_info.matches(&key) {
+            Some(Msg::CloseFeedInfoPopup)
+        } else if self.keys.open_article.matches(&key) {
+            Some(Msg::OpenFeedHomepage)
+        } else {
+            None
+        }
+    }
+}
+
+/// ## HelpPopup
+///
+/// A scrollable popup which lists the active key bindings grouped by context
+#[derive(MockComponent)]
+pub struct HelpPopup {
+    component: List,
+    keys: KeyBindings,
+}
+
+impl HelpPopup {
+    pub fn new(keys: KeyBindings, theme: &Theme) -> Self {
+        let mut rows = TableBuilder::default();
+        for (i, (context, bindings)) in keys.describe().into_iter().enumerate() {
+            // Separate contexts with an empty row
+            if i > 0 {
+                rows.add_row().add_col(TextSpan::from("")).add_row();
+            }
+            rows.add_col(
+                TextSpan::from(context.to_string())
+                    .fg(theme.cyan)
+                    .bold()
+                    .underlined(),
+            );
+            for (binding, description) in bindings {
+                rows.add_row()
+                    .add_col(TextSpan::from(format!("{:<20}", binding.to_string())).bold())
+                    .add_col(TextSpan::from(description));
+            }
+        }
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(theme.cyan)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(theme.yellow)
+                .background(theme.background)
+                .title("Key bindings", Alignment::Center)
+                .scroll(true)
+                .step(8)
+                .highlighted_color(theme.yellow)
+                .rows(rows.build()),
+            keys,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for HelpPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let key = match ev {
+            Event::Keyboard(key) => key,
+            _ => return None,
+        };
+        let keys = &self.keys;
+        if matches!(key.code, Key::Esc | Key::Enter) || keys.help.matches(&key) {
+            return Some(Msg::CloseHelpPopup);
+        } else if keys.move_down.matches(&key) {
+            self.perform(Cmd::Move(Direction::Down));
+        } else if keys.move_up.matches(&key) {
+            self.perform(Cmd::Move(Direction::Up));
+        } else if keys.scroll_down.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Down));
+        } else if keys.scroll_up.matches(&key) {
+            self.perform(Cmd::Scroll(Direction::Up));
+        } else if keys.go_to_begin.matches(&key) {
+            self.perform(Cmd::GoTo(Position::Begin));
+        } else if keys.go_to_end.matches(&key) {
+            self.perform(Cmd::GoTo(Position::End));
+        } else {
+            return None;
+        }
+        Some(Msg::None)
+    }
+}
+
+/// ### input_cmd
+///
+/// Get the command to perform on an input field for the provided key, if it is an editing key
+fn input_cmd(key: &KeyEvent) -> Option<Cmd> {
+    match key {
+        KeyEvent {
+            code: Key::Left, ..
+        } => Some(Cmd::Move(Direction::Left)),
+        KeyEvent {
+            code: Key::Right, ..
+        } => Some(Cmd::Move(Direction::Right)),
+        KeyEvent {
+            code: Key::Home, ..
+        } => Some(Cmd::GoTo(Position::Begin)),
+        KeyEvent { code: Key::End, .. } => Some(Cmd::GoTo(Position::End)),
+        KeyEvent {
+            code: Key::Delete, ..
+        } => Some(Cmd::Cancel),
+        KeyEvent {
+            code: Key::Backspace,
+            ..
+        } => Some(Cmd::Delete),
+        KeyEvent {
+            code: Key::Char(ch),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+        } => Some(Cmd::Type(*ch)),
+        _ => None,
+    }
+}
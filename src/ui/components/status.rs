@@ -0,0 +1,267 @@
+//! # Status
+//!
+//! Status bar component
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use super::Msg;
+use crate::config::Theme;
+use crate::helpers::strings as str_helpers;
+
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::props::{AttrValue, Attribute, Props, Style, TextModifiers};
+use tuirealm::tui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    symbols::line,
+    text::{Span, Spans},
+    widgets::{LineGauge, Paragraph, Tabs},
+};
+use tuirealm::{Component, Event, Frame, MockComponent, NoUserEvent, State};
+
+/// ## StatusBar
+///
+/// A single row bar which shows the fetch progress while sources are being loaded,
+/// the amount of sources otherwise. A message can be displayed in place of the progress.
+/// While offline, the bar tells it before anything else
+pub struct StatusBar {
+    props: Props,
+    fetched: usize,
+    total: usize,
+    help: String,
+    message: Option<String>,
+    offline: bool,
+    theme: Theme,
+}
+
+impl StatusBar {
+    /// ### new
+    ///
+    /// Instantiates a new `StatusBar` for `fetched` sources out of `total`.
+    /// `help` is the key to press to show the key bindings
+    pub fn new(fetched: usize, total: usize, help: String, theme: &Theme) -> Self {
+        Self {
+            props: Props::default(),
+            fetched,
+            total,
+            help,
+            message: None,
+            offline: false,
+            theme: *theme,
+        }
+    }
+
+    /// ### message
+    ///
+    /// Set message to display instead of the fetch progress
+    pub fn message(mut self, message: Option<String>) -> Self {
+        self.message = message;
+        self
+    }
+
+    /// ### offline
+    ///
+    /// Set whether tuifeed is offline
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// ### loading
+    ///
+    /// Returns whether some sources are still being fetched
+    fn loading(&self) -> bool {
+        self.fetched < self.total
+    }
+
+    /// ### ratio
+    ///
+    /// Returns the ratio of fetched sources
+    fn ratio(&self) -> f64 {
+        match self.total {
+            0 => 1.0,
+            total => self.fetched as f64 / total as f64,
+        }
+    }
+}
+
+impl MockComponent for StatusBar {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let help = format!("Press {} for help", self.help);
+        let offline = match self.offline {
+            true => "OFFLINE ",
+            false => "",
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Length(str_helpers::string_width(offline) as u16),
+                    Constraint::Min(1),
+                    Constraint::Length(str_helpers::string_width(help.as_str()) as u16),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                offline,
+                Style::default()
+                    .fg(self.theme.red)
+                    .add_modifier(TextModifiers::BOLD | TextModifiers::REVERSED),
+            )),
+            chunks[0],
+        );
+        let progress = format!("{}/{} feeds fetched", self.fetched, self.total);
+        if let Some(message) = self.message.as_deref() {
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    message,
+                    Style::default()
+                        .fg(self.theme.green)
+                        .add_modifier(TextModifiers::BOLD),
+                )),
+                chunks[1],
+            );
+        } else if self.loading() {
+            frame.render_widget(
+                LineGauge::default()
+                    .gauge_style(Style::default().fg(self.theme.yellow))
+                    .line_set(line::THICK)
+                    .ratio(self.ratio())
+                    .label(Span::styled(
+                        format!("{} ", progress),
+                        Style::default()
+                            .fg(self.theme.yellow)
+                            .add_modifier(TextModifiers::BOLD),
+                    )),
+                chunks[1],
+            );
+        } else {
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    progress,
+                    Style::default().fg(self.theme.green),
+                )),
+                chunks[1],
+            );
+        }
+        frame.render_widget(
+            Paragraph::new(Spans::from(Span::styled(
+                help,
+                Style::default().add_modifier(TextModifiers::ITALIC),
+            ))),
+            chunks[2],
+        );
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, NoUserEvent> for StatusBar {
+    fn on(&mut self, _: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// ## TabBar
+///
+/// A single row bar listing the tabs, each one preceded by the number which selects it
+pub struct TabBar {
+    props: Props,
+    tabs: Vec<String>,
+    selected: usize,
+    theme: Theme,
+}
+
+impl TabBar {
+    /// ### new
+    ///
+    /// Instantiates a new `TabBar` with the names of the `tabs`, where the tab at `selected` is highlighted
+    pub fn new(tabs: Vec<String>, selected: usize, theme: &Theme) -> Self {
+        Self {
+            props: Props::default(),
+            tabs,
+            selected,
+            theme: *theme,
+        }
+    }
+}
+
+impl MockComponent for TabBar {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        let titles: Vec<Spans> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Spans::from(format!("{} {}", i + 1, name)))
+            .collect();
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(self.selected)
+                .style(Style::default().fg(self.theme.dim))
+                .highlight_style(
+                    Style::default()
+                        .fg(self.theme.cyan)
+                        .add_modifier(TextModifiers::BOLD),
+                ),
+            area,
+        );
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.props.get(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.props.set(attr, value)
+    }
+
+    fn state(&self) -> State {
+        State::None
+    }
+
+    fn perform(&mut self, _: Cmd) -> CmdResult {
+        CmdResult::None
+    }
+}
+
+impl Component<Msg, NoUserEvent> for TabBar {
+    fn on(&mut self, _: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
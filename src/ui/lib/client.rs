@@ -25,23 +25,48 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use crate::feed::{Client, Feed, FeedResult};
+use crate::feed::{Client, Feed, FeedLimits, FeedResult, FetchOptions};
 use std::sync::{Arc, RwLock};
 use std::thread::{self, JoinHandle};
 
 #[derive(Debug, Default)]
 pub struct FeedClient {
+    /// Client used by workers
+    client: Client,
     /// Join handle
     workers: Vec<WorkerThread>,
 }
 
 impl FeedClient {
+    /// ### new
+    ///
+    /// Instantiates a new `FeedClient`, fetching sources with `client`
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            workers: Vec::new(),
+        }
+    }
+
+    /// ### set_client
+    ///
+    /// Replace the client used to fetch the next sources
+    pub fn set_client(&mut self, client: Client) {
+        self.client = client;
+    }
+
     /// ### fetch
     ///
-    /// Fetch source.
+    /// Fetch source, applying `limits` to the fetched feed and `options` to the requests.
     /// Panics if fails to send request
-    pub fn fetch(&mut self, name: &str, uri: &str) {
-        self.workers.push(WorkerThread::start(name, uri));
+    pub fn fetch(&mut self, name: &str, uri: &str, limits: FeedLimits, options: FetchOptions) {
+        self.workers.push(WorkerThread::start(
+            self.client.clone(),
+            name,
+            uri,
+            limits,
+            options,
+        ));
     }
 
     /// ### poll
@@ -95,12 +120,20 @@ impl WorkerThread {
     /// ### start
     ///
     /// Start a new worker thread
-    pub fn start(name: &str, uri: &str) -> Self {
+    pub fn start(
+        client: Client,
+        name: &str,
+        uri: &str,
+        limits: FeedLimits,
+        options: FetchOptions,
+    ) -> Self {
         let completed = Arc::new(RwLock::new(false));
         let completed_t = Arc::clone(&completed);
         let name = name.to_string();
         let uri = uri.to_string();
-        let thread = thread::spawn(|| Worker::new(completed_t, name, uri).run());
+        let thread = thread::spawn(move || {
+            Worker::new(completed_t, client, name, uri, limits, options).run()
+        });
         Self(completed, thread)
     }
 
@@ -130,16 +163,29 @@ impl WorkerThread {
 /// Worker thread which fetches async the feed sources
 pub struct Worker {
     completed: Arc<RwLock<bool>>,
+    client: Client,
     name: String,
     uri: String,
+    limits: FeedLimits,
+    options: FetchOptions,
 }
 
 impl Worker {
-    pub fn new(completed: Arc<RwLock<bool>>, name: String, uri: String) -> Self {
+    pub fn new(
+        completed: Arc<RwLock<bool>>,
+        client: Client,
+        name: String,
+        uri: String,
+        limits: FeedLimits,
+        options: FetchOptions,
+    ) -> Self {
         Self {
             completed,
+            client,
             name,
             uri,
+            limits,
+            options,
         }
     }
 
@@ -152,7 +198,8 @@ impl Worker {
         // Return to handle
         (
             self.name.clone(),
-            Client::default().fetch(self.uri.as_str()),
+            self.client
+                .fetch(self.uri.as_str(), &self.limits, &self.options),
         )
     }
 
@@ -181,6 +228,8 @@ mod test {
         client.fetch(
             "Le Figaro",
             "https://www.lefigaro.fr/rss/figaro_actualites.xml",
+            FeedLimits::default(),
+            FetchOptions::default(),
         );
         assert_eq!(client.running(), true);
         // Wait up to 10 seconds before failing
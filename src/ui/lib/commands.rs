@@ -0,0 +1,310 @@
+//! # Commands
+//!
+//! Commands typed in the command line
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// ## Command
+///
+/// A command typed in the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Subscribe to a new feed, optionally with its url
+    Add(Option<String>),
+    /// Open the comments page of the selected article
+    Comments,
+    /// Edit configuration in `$EDITOR`
+    EditConfig,
+    /// Go to the feed whose name matches best
+    Go(String),
+    /// Show key bindings
+    Help,
+    /// Mark all the articles in the list as read
+    MarkAllRead,
+    /// Open the selected article in the browser
+    Open,
+    /// Search podcasts matching the query, or type it in a popup
+    Podcast(Option<String>),
+    /// Quit tuifeed, without asking for confirmation
+    Quit,
+    /// Reload the named source, or the selected one
+    Refresh(Option<String>),
+    /// Reload all sources, except for the ones fetched more recently than their refresh interval
+    RefreshAll,
+    /// Reload all sources, even if fetched recently
+    ForceRefreshAll,
+    /// Save the selected article as text to a file
+    Save(PathBuf),
+    /// Call a function of the script on the selected article
+    Script(String),
+    /// Change a setting for this session
+    Set(Setting),
+    /// Set the order of the articles of the selected feed, or restore the default one
+    Sort(Option<ArticleSort>),
+    /// Show only the articles with a tag, or all of them
+    Tag(Option<String>),
+    /// Undo the last change of read articles, including the ones of mute rules
+    Undo,
+}
+
+/// ## Setting
+///
+/// A setting which can be changed from the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    HideRead(bool),
+    Offline(bool),
+    ReadingMode(bool),
+    Sort(ArticleSort),
+}
+
+/// ## ArticleSort
+///
+/// Order of the articles in the article list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArticleSort {
+    /// As they appear in the feed
+    Feed,
+    /// From the newest
+    Date,
+    /// By the score computed by the scoring rules, from the highest
+    Score,
+    /// Unread articles first, then from the newest
+    Unread,
+}
+
+impl ArticleSort {
+    /// ### by_score
+    ///
+    /// Get the sort set by the `sort_by_score` option of the configuration
+    pub fn by_score(sort_by_score: bool) -> Self {
+        match sort_by_score {
+            true => Self::Score,
+            false => Self::Feed,
+        }
+    }
+
+    /// ### as_str
+    ///
+    /// Get the name of the sort, as parsed by `from_str`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Feed => "feed",
+            Self::Date => "date",
+            Self::Score => "score",
+            Self::Unread => "unread",
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix(':').unwrap_or(s).trim_start();
+        let (name, args) = match s.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (s, ""),
+        };
+        let arg = Some(args).filter(|x| !x.is_empty()).map(String::from);
+        let command = match name {
+            "add" => Self::Add(arg),
+            "comments" => Self::Comments,
+            "edit-config" => Self::EditConfig,
+            "go" | "feed" => Self::Go(arg.ok_or_else(|| format!("{}: missing feed name", name))?),
+            "h" | "help" => Self::Help,
+            "mark-all-read" => Self::MarkAllRead,
+            "o" | "open" => Self::Open,
+            "podcast" => Self::Podcast(arg),
+            "q" | "quit" => Self::Quit,
+            "r" | "refresh" => Self::Refresh(arg),
+            "refresh-all" => Self::RefreshAll,
+            "refresh-all!" => Self::ForceRefreshAll,
+            "w" | "save" => Self::Save(PathBuf::from(
+                arg.ok_or_else(|| format!("{}: missing path", name))?,
+            )),
+            "script" => Self::Script(arg.ok_or_else(|| format!("{}: missing function", name))?),
+            "set" => Self::Set(Setting::from_str(args)?),
+            "sort" => Self::Sort(
+                arg.as_deref()
+                    .map(ArticleSort::from_str)
+                    .transpose()
+                    .map_err(|e| format!("{}: {}", name, e))?,
+            ),
+            "tag" => Self::Tag(arg),
+            "u" | "undo" => Self::Undo,
+            "" => return Err(String::from("no command")),
+            name => return Err(format!("unknown command: {}", name)),
+        };
+        // Commands without arguments reject them, so that typos aren't silently ignored
+        match (&command, args.is_empty()) {
+            (
+                Self::Comments
+                | Self::EditConfig
+                | Self::Help
+                | Self::MarkAllRead
+                | Self::Open
+                | Self::Quit
+                | Self::RefreshAll
+                | Self::ForceRefreshAll
+                | Self::Undo,
+                false,
+            ) => Err(format!("{}: unexpected arguments", name)),
+            _ => Ok(command),
+        }
+    }
+}
+
+impl FromStr for Setting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = match s.split_once('=') {
+            Some((name, value)) => (name.trim(), value.trim()),
+            None => (s.trim(), "true"),
+        };
+        match name {
+            "hide_read" => Ok(Self::HideRead(parse_flag(name, value)?)),
+            "offline" => Ok(Self::Offline(parse_flag(name, value)?)),
+            "reading_mode" => Ok(Self::ReadingMode(parse_flag(name, value)?)),
+            "sort" => Ok(Self::Sort(
+                ArticleSort::from_str(value).map_err(|e| format!("set: {}", e))?,
+            )),
+            "" => Err(String::from("set: missing setting")),
+            name => Err(format!("set: unknown setting: {}", name)),
+        }
+    }
+}
+
+impl FromStr for ArticleSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "feed" => Ok(Self::Feed),
+            "date" => Ok(Self::Date),
+            "score" => Ok(Self::Score),
+            "unread" => Ok(Self::Unread),
+            s => Err(format!(
+                "invalid sort: {} (expected feed, date, score or unread)",
+                s
+            )),
+        }
+    }
+}
+
+/// ### parse_flag
+///
+/// Parse the boolean value of setting `name`
+fn parse_flag(name: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "on" | "yes" => Ok(true),
+        "false" | "off" | "no" => Ok(false),
+        value => Err(format!(
+            "set: invalid value for {}: {} (expected true or false)",
+            name, value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_parse_commands() {
+        assert_eq!(Command::from_str(":q").unwrap(), Command::Quit);
+        assert_eq!(
+            Command::from_str("  refresh ").unwrap(),
+            Command::Refresh(None)
+        );
+        assert_eq!(
+            Command::from_str(":refresh New York Times").unwrap(),
+            Command::Refresh(Some(String::from("New York Times")))
+        );
+        assert_eq!(
+            Command::from_str(":refresh-all!").unwrap(),
+            Command::ForceRefreshAll
+        );
+        assert_eq!(
+            Command::from_str(":save /tmp/article.txt").unwrap(),
+            Command::Save(PathBuf::from("/tmp/article.txt"))
+        );
+        assert_eq!(
+            Command::from_str(":set sort=date").unwrap(),
+            Command::Set(Setting::Sort(ArticleSort::Date))
+        );
+        assert_eq!(
+            Command::from_str(":set hide_read").unwrap(),
+            Command::Set(Setting::HideRead(true))
+        );
+        assert_eq!(
+            Command::from_str(":set reading_mode = off").unwrap(),
+            Command::Set(Setting::ReadingMode(false))
+        );
+        assert_eq!(
+            Command::from_str(":set offline=no").unwrap(),
+            Command::Set(Setting::Offline(false))
+        );
+        assert_eq!(
+            Command::from_str(":script save_to_wallabag").unwrap(),
+            Command::Script(String::from("save_to_wallabag"))
+        );
+        assert_eq!(
+            Command::from_str(":podcast rustacean station").unwrap(),
+            Command::Podcast(Some(String::from("rustacean station")))
+        );
+        assert_eq!(
+            Command::from_str(":go hn").unwrap(),
+            Command::Go(String::from("hn"))
+        );
+        assert_eq!(Command::from_str(":tag").unwrap(), Command::Tag(None));
+        assert_eq!(
+            Command::from_str(":sort unread").unwrap(),
+            Command::Sort(Some(ArticleSort::Unread))
+        );
+        assert_eq!(Command::from_str(":sort").unwrap(), Command::Sort(None));
+    }
+
+    #[test]
+    fn should_reject_invalid_commands() {
+        assert!(Command::from_str(":").is_err());
+        assert!(Command::from_str(":foo").is_err());
+        assert!(Command::from_str(":open now").is_err());
+        assert!(Command::from_str(":save").is_err());
+        assert!(Command::from_str(":go").is_err());
+        assert!(Command::from_str(":set").is_err());
+        assert!(Command::from_str(":set sort=random").is_err());
+        assert!(Command::from_str(":sort random").is_err());
+        assert!(Command::from_str(":set hide_read=maybe").is_err());
+        assert!(Command::from_str(":set color=red").is_err());
+    }
+}
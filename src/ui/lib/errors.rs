@@ -0,0 +1,164 @@
+//! # Errors
+//!
+//! Queue of the errors to display
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+/// ## ErrorQueue
+///
+/// Queue of the errors reported to the user; the error popup shows one error at a time
+#[derive(Debug, Default)]
+pub struct ErrorQueue {
+    errors: Vec<String>,
+    current: usize,
+}
+
+impl ErrorQueue {
+    /// ### push
+    ///
+    /// Push a new error at the end of the queue
+    pub fn push(&mut self, err: impl ToString) {
+        self.errors.push(err.to_string());
+    }
+
+    /// ### current
+    ///
+    /// Get the error currently displayed, if any
+    pub fn current(&self) -> Option<&str> {
+        self.errors.get(self.current).map(|x| x.as_str())
+    }
+
+    /// ### position
+    ///
+    /// Get the position (starting from 1) of the current error and the amount of errors
+    pub fn position(&self) -> (usize, usize) {
+        (self.current + 1, self.errors.len())
+    }
+
+    /// ### is_empty
+    ///
+    /// Returns whether there are no errors in the queue
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// ### next
+    ///
+    /// Move to the next error; wraps around at the end of the queue
+    pub fn next(&mut self) {
+        if !self.is_empty() {
+            self.current = (self.current + 1) % self.errors.len();
+        }
+    }
+
+    /// ### previous
+    ///
+    /// Move to the previous error; wraps around at the beginning of the queue
+    pub fn previous(&mut self) {
+        if !self.is_empty() {
+            self.current = (self.current + self.errors.len() - 1) % self.errors.len();
+        }
+    }
+
+    /// ### dismiss
+    ///
+    /// Remove the current error from the queue
+    pub fn dismiss(&mut self) {
+        if self.current < self.errors.len() {
+            self.errors.remove(self.current);
+        }
+        if self.current >= self.errors.len() {
+            self.current = self.errors.len().saturating_sub(1);
+        }
+    }
+
+    /// ### clear
+    ///
+    /// Remove all the errors from the queue
+    pub fn clear(&mut self) {
+        self.errors.clear();
+        self.current = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_queue_errors() {
+        let mut queue = ErrorQueue::default();
+        assert!(queue.is_empty());
+        assert!(queue.current().is_none());
+        queue.push("first");
+        queue.push("second");
+        queue.push("third");
+        assert_eq!(queue.is_empty(), false);
+        assert_eq!(queue.current().unwrap(), "first");
+        assert_eq!(queue.position(), (1, 3));
+    }
+
+    #[test]
+    fn should_navigate_errors() {
+        let mut queue = ErrorQueue::default();
+        queue.push("first");
+        queue.push("second");
+        queue.push("third");
+        queue.next();
+        assert_eq!(queue.current().unwrap(), "second");
+        queue.next();
+        queue.next();
+        assert_eq!(queue.current().unwrap(), "first");
+        queue.previous();
+        assert_eq!(queue.current().unwrap(), "third");
+        assert_eq!(queue.position(), (3, 3));
+    }
+
+    #[test]
+    fn should_dismiss_errors() {
+        let mut queue = ErrorQueue::default();
+        queue.push("first");
+        queue.push("second");
+        queue.push("third");
+        queue.next();
+        queue.dismiss();
+        assert_eq!(queue.current().unwrap(), "third");
+        assert_eq!(queue.position(), (2, 2));
+        queue.dismiss();
+        assert_eq!(queue.current().unwrap(), "first");
+        queue.dismiss();
+        assert!(queue.is_empty());
+        assert!(queue.current().is_none());
+        // Dismiss on empty queue
+        queue.dismiss();
+        queue.push("fourth");
+        queue.push("fifth");
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.position(), (1, 0));
+    }
+}
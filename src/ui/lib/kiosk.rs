@@ -25,18 +25,51 @@
  * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
  * SOFTWARE.
  */
-use crate::feed::{Feed, FeedError};
+use super::ArticleSort;
+use crate::feed::{Article, Channel, Feed, FeedError};
+use crate::store::{Session, Store, StoreResult};
 
-use std::collections::HashMap;
+use chrono::{DateTime, Local};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Prefix of the names of the feeds which collect the articles with a user tag
+pub const TAG_FEED_PREFIX: &str = "#";
+/// Name of the feed which collects the articles of all the sources; tags are lowercase, so it's never a tag feed
+pub const ALL_FEED: &str = "#All";
 
 /// ## Kiosk
 ///
 /// Describes the current feed holder.
-/// It contains different sources, each one with its own feed fetch state
-#[derive(Debug, Default)]
+/// It contains different sources, each one with its own feed fetch state.
+/// Fetched feeds and read articles are saved into the article store
+#[derive(Debug)]
 pub struct Kiosk {
     /// Association between Source name and Feed
     feed: HashMap<String, FeedState>,
+    /// Association between source name and the time of its last successful fetch
+    fetched: HashMap<String, DateTime<Local>>,
+    /// Urls of the articles which have been read
+    read: HashSet<String>,
+    /// Urls of the articles which have been starred
+    starred: HashSet<String>,
+    /// Last time tuifeed has been closed, before this session
+    last_quit: Option<DateTime<Local>>,
+    /// Urls of the articles which have been fetched for the first time since `last_quit`
+    new: HashSet<String>,
+    /// Association between article url and the tags the user attached to it
+    tags: HashMap<String, Vec<String>>,
+    /// Association between article url and the note the user attached to it
+    notes: HashMap<String, String>,
+    /// Association between tag feed name and the tagged articles
+    tagged: HashMap<String, FeedState>,
+    /// Association between the aggregated feed name and the articles of all the sources
+    all: HashMap<String, FeedState>,
+    /// Source of each article of the aggregated feed
+    all_sources: Vec<String>,
+    /// Association between source name and the order of its articles chosen by the user
+    sorts: HashMap<String, ArticleSort>,
+    store: Store,
 }
 
 /// ## FeedState
@@ -61,24 +94,175 @@ pub enum FlatFeedState {
     Success,
     /// Failed to fetch / parse feed
     Error,
+    /// Failed to fetch / parse feed; it will be fetched again
+    Retrying,
     /// Loading feed
     Loading,
 }
 
 impl Kiosk {
+    /// ### new
+    ///
+    /// Instantiates a new `Kiosk` backed by `store`
+    pub fn new(store: Store) -> StoreResult<Self> {
+        let mut kiosk = Self {
+            feed: HashMap::new(),
+            fetched: HashMap::new(),
+            read: store.read_articles()?,
+            starred: store.starred_articles()?,
+            last_quit: store.last_quit()?,
+            new: HashSet::new(),
+            tags: HashMap::new(),
+            notes: store.notes()?,
+            tagged: HashMap::new(),
+            all: HashMap::new(),
+            all_sources: Vec::new(),
+            sorts: store
+                .article_sorts()?
+                .into_iter()
+                .filter_map(|(source, sort)| {
+                    ArticleSort::from_str(sort.as_str())
+                        .ok()
+                        .map(|sort| (source, sort))
+                })
+                .collect(),
+            store,
+        };
+        kiosk.load_tags()?;
+        kiosk.load_new()?;
+        kiosk.collect_all();
+        Ok(kiosk)
+    }
+
     /// ### insert_feed
     ///
-    /// Insert a feed into kiosk
-    pub fn insert_feed<S: AsRef<str>>(&mut self, source: S, state: FeedState) {
-        self.feed.insert(source.as_ref().to_string(), state);
+    /// Insert a feed into kiosk. Fetched feeds and fetch errors are saved into the store
+    pub fn insert_feed<S: AsRef<str>>(&mut self, source: S, state: FeedState) -> StoreResult<()> {
+        let source = source.as_ref();
+        let result = match &state {
+            FeedState::Success(feed) => {
+                self.fetched.insert(source.to_string(), Local::now());
+                self.store
+                    .save_feed(source, feed)
+                    .and_then(|_| self.load_new())
+            }
+            FeedState::Error(err) => self.store.save_error(source, err.to_string().as_str()),
+            FeedState::Loading => Ok(()),
+        };
+        // NOTE: the aggregated feed is collected again once the source has been fetched, not while it's loading
+        let loading = matches!(state, FeedState::Loading);
+        self.feed.insert(source.to_string(), state);
+        if !loading {
+            self.collect_all();
+        }
+        result
+    }
+
+    /// ### load_feed
+    ///
+    /// Load the feed of `source` from the store, as saved by the last fetch, and the read flags.
+    /// Returns `None` if `source` has never been fetched
+    pub fn load_feed(&mut self, source: &str) -> StoreResult<Option<Feed>> {
+        self.read = self.store.read_articles()?;
+        self.load_new()?;
+        self.store.load_feed(source)
+    }
+
+    /// ### insert_stored_feed
+    ///
+    /// Insert a feed loaded from the store into kiosk, without saving it again
+    pub fn insert_stored_feed<S: AsRef<str>>(&mut self, source: S, feed: Feed) {
+        let source = source.as_ref();
+        if let Ok(Some(fetched)) = self.store.last_fetch(source) {
+            self.fetched.insert(source.to_string(), fetched);
+        }
+        self.feed
+            .insert(source.to_string(), FeedState::Success(feed));
+        self.collect_all();
+    }
+
+    /// ### last_fetch
+    ///
+    /// Get the last time `source` has been fetched successfully, by tuifeed or by the daemon.
+    /// The store is looked up only for the sources which haven't been loaded yet
+    pub fn last_fetch(&self, source: &str) -> StoreResult<Option<DateTime<Local>>> {
+        match self.fetched.get(source) {
+            Some(fetched) => Ok(Some(*fetched)),
+            None => self.store.last_fetch(source),
+        }
+    }
+
+    /// ### article_urls
+    ///
+    /// Get the urls of the articles of `source` in the store
+    pub fn article_urls(&self, source: &str) -> StoreResult<HashSet<String>> {
+        self.store.article_urls(source)
+    }
+
+    /// ### daemon_heartbeat
+    ///
+    /// Get the last time the daemon reported to be running
+    pub fn daemon_heartbeat(&self) -> StoreResult<Option<DateTime<Local>>> {
+        self.store.daemon_heartbeat()
+    }
+
+    /// ### save_session
+    ///
+    /// Save the session to restore on the next startup
+    pub fn save_session(&mut self, session: Option<&Session>) -> StoreResult<()> {
+        self.store.save_session(session)
+    }
+
+    /// ### save_quit
+    ///
+    /// Record that tuifeed is being closed, so that the next session tells which articles are new
+    pub fn save_quit(&mut self) -> StoreResult<()> {
+        self.store.set_last_quit()
+    }
+
+    /// ### session
+    ///
+    /// Get the session saved on the last quit
+    pub fn session(&self) -> StoreResult<Option<Session>> {
+        self.store.session()
+    }
+
+    /// ### remove_feed
+    ///
+    /// Remove a feed from kiosk and its articles from the store
+    pub fn remove_feed(&mut self, source: &str) -> StoreResult<()> {
+        self.feed.remove(source);
+        self.fetched.remove(source);
+        self.sorts.remove(source);
+        self.collect_all();
+        self.store.remove_source(source)
+    }
+
+    /// ### rename_feed
+    ///
+    /// Move the feed state of `source` to `new_name`
+    pub fn rename_feed(&mut self, source: &str, new_name: &str) -> StoreResult<()> {
+        if let Some(state) = self.feed.remove(source) {
+            self.feed.insert(new_name.to_string(), state);
+        }
+        if let Some(fetched) = self.fetched.remove(source) {
+            self.fetched.insert(new_name.to_string(), fetched);
+        }
+        if let Some(sort) = self.sorts.remove(source) {
+            self.sorts.insert(new_name.to_string(), sort);
+        }
+        self.collect_all();
+        self.store.rename_source(source, new_name)
     }
 
     /// ### get_state
     ///
-    /// Returns the list of sources associated to their feed list
+    /// Returns the list of sources associated to their feed list, tag feeds and aggregated feed included
     pub fn get_state(&self) -> Vec<(String, FlatFeedState)> {
         self.feed
             .iter()
+            .chain(self.tagged.iter())
+            .chain(self.all.iter())
             .map(|(name, state)| (name.to_string(), FlatFeedState::from(state)))
             .collect()
     }
@@ -87,7 +271,10 @@ impl Kiosk {
     ///
     /// Get current feed state
     pub fn get_feed_state(&self, source: &str) -> Option<&FeedState> {
-        self.feed.get(source)
+        self.feed
+            .get(source)
+            .or_else(|| self.tagged.get(source))
+            .or_else(|| self.all.get(source))
     }
 
     /// ### get_feed
@@ -102,11 +289,230 @@ impl Kiosk {
         }
     }
 
+    /// ### fetch_progress
+    ///
+    /// Returns the amount of sources which are not loading anymore and the total amount of sources
+    pub fn fetch_progress(&self) -> (usize, usize) {
+        let loading = self
+            .feed
+            .values()
+            .filter(|x| matches!(x, FeedState::Loading))
+            .count();
+        (self.feed.len() - loading, self.feed.len())
+    }
+
+    /// ### mark_read
+    ///
+    /// Mark the article with `url` as read
+    pub fn mark_read(&mut self, url: &str) -> StoreResult<()> {
+        self.read.insert(url.to_string());
+        self.store.mark_read(url)
+    }
+
+    /// ### mark_unread
+    ///
+    /// Mark the article with `url` as not read
+    pub fn mark_unread(&mut self, url: &str) -> StoreResult<()> {
+        self.read.remove(url);
+        self.store.mark_unread(url)
+    }
+
+    /// ### reload_read
+    ///
+    /// Load the read articles from the store again, after they've been changed by sync
+    pub fn reload_read(&mut self) -> StoreResult<()> {
+        self.read = self.store.read_articles()?;
+        Ok(())
+    }
+
+    /// ### is_read
+    ///
+    /// Returns whether the article with `url` has been read
+    pub fn is_read(&self, url: &str) -> bool {
+        self.read.contains(url)
+    }
+
+    /// ### set_starred
+    ///
+    /// Set the starred flag of the article with `url`
+    pub fn set_starred(&mut self, url: &str, starred: bool) -> StoreResult<()> {
+        self.store.set_starred(url, starred)?;
+        match starred {
+            true => self.starred.insert(url.to_string()),
+            false => self.starred.remove(url),
+        };
+        Ok(())
+    }
+
+    /// ### is_starred
+    ///
+    /// Returns whether the article with `url` has been starred
+    pub fn is_starred(&self, url: &str) -> bool {
+        self.starred.contains(url)
+    }
+
+    /// ### is_new
+    ///
+    /// Returns whether the article with `url` has been fetched for the first time since tuifeed was last closed.
+    /// On the first run no article is new
+    pub fn is_new(&self, url: &str) -> bool {
+        self.new.contains(url)
+    }
+
+    /// ### set_tags
+    ///
+    /// Replace the tags the user attached to the article with `url`; an empty list removes them
+    pub fn set_tags(&mut self, url: &str, tags: &[String]) -> StoreResult<()> {
+        self.store.set_tags(url, tags)?;
+        self.load_tags()
+    }
+
+    /// ### get_tags
+    ///
+    /// Get the tags the user attached to the article with `url`
+    pub fn get_tags(&self, url: &str) -> &[String] {
+        self.tags.get(url).map(|x| x.as_slice()).unwrap_or(&[])
+    }
+
+    /// ### set_note
+    ///
+    /// Replace the note the user attached to the article with `url`; a blank note removes it
+    pub fn set_note(&mut self, url: &str, note: &str) -> StoreResult<()> {
+        let note = note.trim();
+        self.store.set_note(url, note)?;
+        match note.is_empty() {
+            true => self.notes.remove(url),
+            false => self.notes.insert(url.to_string(), note.to_string()),
+        };
+        Ok(())
+    }
+
+    /// ### get_note
+    ///
+    /// Get the note the user attached to the article with `url`
+    pub fn get_note(&self, url: &str) -> Option<&str> {
+        self.notes.get(url).map(|x| x.as_str())
+    }
+
+    /// ### set_article_sort
+    ///
+    /// Set the order of the articles of `source`, which is remembered across sessions;
+    /// `None` restores the default order
+    pub fn set_article_sort(&mut self, source: &str, sort: Option<ArticleSort>) -> StoreResult<()> {
+        self.store
+            .set_article_sort(source, sort.map(|x| x.as_str()))?;
+        match sort {
+            Some(sort) => self.sorts.insert(source.to_string(), sort),
+            None => self.sorts.remove(source),
+        };
+        Ok(())
+    }
+
+    /// ### get_article_sort
+    ///
+    /// Get the order of the articles chosen for `source`, if any
+    pub fn get_article_sort(&self, source: &str) -> Option<ArticleSort> {
+        self.sorts.get(source).copied()
+    }
+
+    /// ### is_tag_feed
+    ///
+    /// Returns whether `source` is the feed of a user tag
+    pub fn is_tag_feed(&self, source: &str) -> bool {
+        self.tagged.contains_key(source)
+    }
+
+    /// ### is_all_feed
+    ///
+    /// Returns whether `source` is the aggregated feed, which collects the articles of all the sources
+    pub fn is_all_feed(&self, source: &str) -> bool {
+        self.all.contains_key(source)
+    }
+
+    /// ### get_article_source
+    ///
+    /// Get the source of the article at `index` in the aggregated feed
+    pub fn get_article_source(&self, index: usize) -> Option<&str> {
+        self.all_sources.get(index).map(|x| x.as_str())
+    }
+
     /// ### sources
     ///
-    /// Get sources in kiosk
+    /// Get sources in kiosk, tag feeds and aggregated feed included
     pub fn sources(&self) -> Vec<&String> {
-        self.feed.keys().into_iter().collect()
+        self.feed
+            .keys()
+            .chain(self.tagged.keys())
+            .chain(self.all.keys())
+            .collect()
+    }
+
+    // -- private
+
+    /// ### collect_all
+    ///
+    /// Collect the articles of all the fetched sources into the aggregated feed, from the most recent.
+    /// An article saved by many sources is collected once
+    fn collect_all(&mut self) {
+        let mut sources: Vec<(&String, &Feed)> = self
+            .feed
+            .iter()
+            .filter_map(|(name, state)| match state {
+                FeedState::Success(feed) => Some((name, feed)),
+                _ => None,
+            })
+            .collect();
+        sources.sort_by(|a, b| a.0.cmp(b.0));
+        let mut urls: HashSet<&str> = HashSet::new();
+        let mut articles: Vec<(&String, &Article)> = sources
+            .into_iter()
+            .flat_map(|(name, feed)| feed.articles().map(move |x| (name, x)))
+            .filter(|(_, article)| urls.insert(article.url.as_str()))
+            .collect();
+        // NOTE: sort is stable, so undated articles keep the source order at the bottom
+        articles.sort_by_key(|(_, article)| std::cmp::Reverse(article.date));
+        self.all_sources = articles.iter().map(|(name, _)| name.to_string()).collect();
+        let feed = Feed {
+            articles: articles.into_iter().map(|(_, x)| x.clone()).collect(),
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        self.all
+            .insert(ALL_FEED.to_string(), FeedState::Success(feed));
+    }
+
+    /// ### load_new
+    ///
+    /// Load the urls of the articles fetched for the first time since the last quit
+    fn load_new(&mut self) -> StoreResult<()> {
+        if let Some(last_quit) = self.last_quit {
+            self.new = self.store.articles_seen_since(last_quit)?;
+        }
+        Ok(())
+    }
+
+    /// ### load_tags
+    ///
+    /// Load the user tags and the tag feeds from the store
+    fn load_tags(&mut self) -> StoreResult<()> {
+        self.tags = self.store.tags()?;
+        let mut tagged: HashMap<String, Feed> = HashMap::new();
+        for (tag, article) in self.store.tagged_articles()? {
+            tagged
+                .entry(format!("{}{}", TAG_FEED_PREFIX, tag))
+                .or_insert_with(|| Feed {
+                    articles: Vec::new(),
+                    moved_to: None,
+                    channel: Channel::default(),
+                })
+                .articles
+                .push(article);
+        }
+        self.tagged = tagged
+            .into_iter()
+            .map(|(name, feed)| (name, FeedState::Success(feed)))
+            .collect();
+        Ok(())
     }
 }
 
@@ -125,46 +531,135 @@ mod test {
 
     use super::*;
 
+    use chrono::TimeZone;
     use pretty_assertions::assert_eq;
 
+    fn make_kiosk() -> Kiosk {
+        Kiosk::new(Store::memory().unwrap()).unwrap()
+    }
+
     #[test]
     fn should_create_kiosk() {
-        let kiosk = Kiosk::default();
+        let kiosk = make_kiosk();
         assert!(kiosk.feed.is_empty());
     }
 
     #[test]
     fn should_insert_feed_into_kiosk() {
-        let mut kiosk = Kiosk::default();
-        kiosk.insert_feed(
-            "lefigaro",
-            FeedState::Success(Feed {
-                articles: Vec::default(),
-            }),
-        );
+        let mut kiosk = make_kiosk();
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Success(Feed {
+                    articles: Vec::default(),
+                    moved_to: None,
+                    channel: Channel::default(),
+                }),
+            )
+            .is_ok());
         assert_eq!(kiosk.feed.len(), 1);
     }
 
     #[test]
-    fn should_get_feed_from_kiosk() {
-        let mut kiosk = Kiosk::default();
-        kiosk.insert_feed(
-            "lefigaro",
-            FeedState::Success(Feed {
-                articles: Vec::default(),
-            }),
+    fn should_remove_feed_from_kiosk() {
+        let mut kiosk = make_kiosk();
+        assert!(kiosk.insert_feed("lefigaro", FeedState::Loading).is_ok());
+        assert!(kiosk.remove_feed("lefigaro").is_ok());
+        assert!(kiosk.feed.is_empty());
+    }
+
+    #[test]
+    fn should_rename_feed_in_kiosk() {
+        let mut kiosk = make_kiosk();
+        assert!(kiosk.insert_feed("lefigaro", FeedState::Loading).is_ok());
+        assert!(kiosk.rename_feed("lefigaro", "Le Figaro").is_ok());
+        assert!(kiosk.get_feed_state("lefigaro").is_none());
+        assert_eq!(
+            kiosk.get_feed_state("Le Figaro").unwrap(),
+            &FeedState::Loading
         );
+    }
+
+    #[test]
+    fn should_track_last_fetch_in_kiosk() {
+        let mut kiosk = make_kiosk();
+        assert!(kiosk.last_fetch("lefigaro").unwrap().is_none());
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Error(FeedError::Parse(String::from("invalid xml"))),
+            )
+            .is_ok());
+        assert!(kiosk.last_fetch("lefigaro").unwrap().is_none());
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Success(Feed {
+                    articles: Vec::default(),
+                    moved_to: None,
+                    channel: Channel::default(),
+                }),
+            )
+            .is_ok());
+        let fetched = kiosk.last_fetch("lefigaro").unwrap().unwrap();
+        assert!(kiosk.rename_feed("lefigaro", "Le Figaro").is_ok());
+        assert_eq!(kiosk.last_fetch("Le Figaro").unwrap(), Some(fetched));
+        assert!(kiosk.remove_feed("Le Figaro").is_ok());
+        assert!(kiosk.last_fetch("Le Figaro").unwrap().is_none());
+    }
+
+    #[test]
+    fn should_get_fetch_progress_from_kiosk() {
+        let mut kiosk = make_kiosk();
+        assert_eq!(kiosk.fetch_progress(), (0, 0));
+        assert!(kiosk.insert_feed("lefigaro", FeedState::Loading).is_ok());
+        assert!(kiosk.insert_feed("nytimes", FeedState::Loading).is_ok());
+        assert!(kiosk
+            .insert_feed(
+                "corriere",
+                FeedState::Success(Feed {
+                    articles: Vec::default(),
+                    moved_to: None,
+                    channel: Channel::default(),
+                }),
+            )
+            .is_ok());
+        assert_eq!(kiosk.fetch_progress(), (1, 3));
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Error(FeedError::Parse(String::from("invalid xml"))),
+            )
+            .is_ok());
+        assert_eq!(kiosk.fetch_progress(), (2, 3));
+    }
+
+    #[test]
+    fn should_get_feed_from_kiosk() {
+        let mut kiosk = make_kiosk();
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Success(Feed {
+                    articles: Vec::default(),
+                    moved_to: None,
+                    channel: Channel::default(),
+                }),
+            )
+            .is_ok());
         assert!(kiosk.get_feed("lefigaro").is_some());
         assert!(kiosk.get_feed("foobar").is_none());
     }
 
     #[test]
     fn should_get_feed_state_from_kiosk() {
-        let mut kiosk = Kiosk::default();
-        kiosk.insert_feed(
-            "lefigaro",
-            FeedState::Error(FeedError::Parse(String::from("parse error"))),
-        );
+        let mut kiosk = make_kiosk();
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Error(FeedError::Parse(String::from("parse error"))),
+            )
+            .is_ok());
         assert_eq!(
             kiosk.get_feed_state("lefigaro").unwrap(),
             &FeedState::Error(FeedError::Parse(String::from("parse error")))
@@ -173,28 +668,309 @@ mod test {
 
     #[test]
     fn should_get_sources_from_kiosk() {
-        let mut kiosk = Kiosk::default();
-        kiosk.insert_feed(
-            "lefigaro",
-            FeedState::Success(Feed {
-                articles: Vec::default(),
-            }),
+        let mut kiosk = make_kiosk();
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Success(Feed {
+                    articles: Vec::default(),
+                    moved_to: None,
+                    channel: Channel::default(),
+                }),
+            )
+            .is_ok());
+        let mut sources = kiosk.sources();
+        sources.sort();
+        assert_eq!(
+            sources,
+            vec![&String::from("#All"), &String::from("lefigaro")]
         );
-        assert_eq!(kiosk.sources(), vec![&String::from("lefigaro")]);
     }
 
     #[test]
     fn should_return_kiosk_state() {
-        let mut kiosk = Kiosk::default();
-        kiosk.insert_feed(
-            "lefigaro",
-            FeedState::Success(Feed {
-                articles: Vec::default(),
-            }),
+        let mut kiosk = make_kiosk();
+        assert!(kiosk
+            .insert_feed(
+                "lefigaro",
+                FeedState::Success(Feed {
+                    articles: Vec::default(),
+                    moved_to: None,
+                    channel: Channel::default(),
+                }),
+            )
+            .is_ok());
+        assert!(kiosk
+            .insert_feed(
+                "nytimes",
+                FeedState::Error(FeedError::Parse(String::from("parse error"))),
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn should_mark_articles_as_read() {
+        let mut kiosk = make_kiosk();
+        assert!(!kiosk.is_read("https://example.com/article"));
+        assert!(kiosk.mark_read("https://example.com/article").is_ok());
+        assert!(kiosk.is_read("https://example.com/article"));
+        assert!(!kiosk.is_read("https://example.com/other"));
+    }
+
+    #[test]
+    fn should_collect_tagged_articles_in_tag_feeds() {
+        let mut kiosk = make_kiosk();
+        let feed = Feed {
+            articles: vec![make_article("https://example.com/a")],
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        assert!(kiosk
+            .insert_feed("example", FeedState::Success(feed))
+            .is_ok());
+        assert!(kiosk
+            .set_tags(
+                "https://example.com/a",
+                &[String::from("rust"), String::from("later")]
+            )
+            .is_ok());
+        assert_eq!(
+            kiosk.get_tags("https://example.com/a"),
+            &[String::from("later"), String::from("rust")]
         );
-        kiosk.insert_feed(
-            "nytimes",
-            FeedState::Error(FeedError::Parse(String::from("parse error"))),
+        assert!(kiosk.get_tags("https://example.com/b").is_empty());
+        assert!(kiosk.is_tag_feed("#rust"));
+        assert!(!kiosk.is_tag_feed("example"));
+        let mut sources = kiosk.sources();
+        sources.sort();
+        assert_eq!(
+            sources,
+            vec![
+                &String::from("#All"),
+                &String::from("#later"),
+                &String::from("#rust"),
+                &String::from("example")
+            ]
+        );
+        assert_eq!(kiosk.get_feed("#rust").unwrap().articles.len(), 1);
+        // Tag feeds are never fetched
+        assert_eq!(kiosk.fetch_progress(), (1, 1));
+        // Untag
+        assert!(kiosk.set_tags("https://example.com/a", &[]).is_ok());
+        assert!(kiosk.get_feed("#rust").is_none());
+        let mut sources = kiosk.sources();
+        sources.sort();
+        assert_eq!(
+            sources,
+            vec![&String::from("#All"), &String::from("example")]
+        );
+    }
+
+    #[test]
+    fn should_collect_all_articles_in_aggregated_feed() {
+        let mut kiosk = make_kiosk();
+        let article = |url: &str, day: u32| Article {
+            date: Some(Local.ymd(2021, 12, day).and_hms(12, 0, 0)),
+            ..make_article(url)
+        };
+        let feed = Feed {
+            articles: vec![
+                article("https://example.com/a", 1),
+                article("https://example.com/c", 3),
+            ],
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        assert!(kiosk
+            .insert_feed("example", FeedState::Success(feed))
+            .is_ok());
+        let feed = Feed {
+            articles: vec![
+                article("https://example.com/b", 2),
+                article("https://example.com/c", 3),
+            ],
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        assert!(kiosk.insert_feed("other", FeedState::Success(feed)).is_ok());
+        assert!(kiosk.is_all_feed(ALL_FEED));
+        assert!(!kiosk.is_all_feed("example"));
+        // From the most recent, each article once
+        let urls: Vec<&str> = kiosk
+            .get_feed(ALL_FEED)
+            .unwrap()
+            .articles()
+            .map(|x| x.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/c",
+                "https://example.com/b",
+                "https://example.com/a"
+            ]
+        );
+        assert_eq!(kiosk.get_article_source(0), Some("example"));
+        assert_eq!(kiosk.get_article_source(1), Some("other"));
+        assert_eq!(kiosk.get_article_source(3), None);
+        // Sources being fetched are collected once fetched
+        assert!(kiosk.insert_feed("other", FeedState::Loading).is_ok());
+        assert_eq!(kiosk.get_feed(ALL_FEED).unwrap().articles.len(), 3);
+        assert!(kiosk.remove_feed("other").is_ok());
+        assert_eq!(kiosk.get_feed(ALL_FEED).unwrap().articles.len(), 2);
+        assert!(kiosk.remove_feed("example").is_ok());
+        assert!(kiosk.get_feed(ALL_FEED).unwrap().articles.is_empty());
+    }
+
+    #[test]
+    fn should_keep_article_notes() {
+        let mut kiosk = make_kiosk();
+        assert!(kiosk.get_note("https://example.com/a").is_none());
+        assert!(kiosk
+            .set_note("https://example.com/a", "  read it again\n")
+            .is_ok());
+        assert_eq!(
+            kiosk.get_note("https://example.com/a"),
+            Some("read it again")
         );
+        assert!(kiosk.set_note("https://example.com/a", " \n").is_ok());
+        assert!(kiosk.get_note("https://example.com/a").is_none());
+    }
+
+    #[test]
+    fn should_keep_article_sorts() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        {
+            let mut kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+            assert!(kiosk.get_article_sort("example").is_none());
+            assert!(kiosk
+                .set_article_sort("example", Some(ArticleSort::Unread))
+                .is_ok());
+            assert!(kiosk
+                .set_article_sort("other", Some(ArticleSort::Date))
+                .is_ok());
+            assert!(kiosk.set_article_sort("other", None).is_ok());
+        }
+        let kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+        assert_eq!(kiosk.get_article_sort("example"), Some(ArticleSort::Unread));
+        assert!(kiosk.get_article_sort("other").is_none());
+    }
+
+    #[test]
+    fn should_restore_read_articles_from_store() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        {
+            let mut kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+            let feed = Feed {
+                articles: vec![Article {
+                    title: Some(String::from("Hello")),
+                    authors: Vec::new(),
+                    summary: String::new(),
+                    links: Vec::new(),
+                    url: String::from("https://example.com/article"),
+                    date: None,
+                    duration: None,
+                    enclosures: Vec::new(),
+                    flair: None,
+                    interest: None,
+                    comments: None,
+                    categories: Vec::new(),
+                    score: None,
+                }],
+                moved_to: None,
+                channel: Channel::default(),
+            };
+            assert!(kiosk
+                .insert_feed("example", FeedState::Success(feed))
+                .is_ok());
+            assert!(kiosk.mark_read("https://example.com/article").is_ok());
+        }
+        let kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+        assert!(kiosk.is_read("https://example.com/article"));
+    }
+
+    #[test]
+    fn should_restore_starred_articles_from_store() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        {
+            let mut kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+            let feed = Feed {
+                articles: vec![Article {
+                    title: Some(String::from("Hello")),
+                    authors: Vec::new(),
+                    summary: String::new(),
+                    links: Vec::new(),
+                    url: String::from("https://example.com/a"),
+                    date: None,
+                    duration: None,
+                    enclosures: Vec::new(),
+                    flair: None,
+                    interest: None,
+                    comments: None,
+                    categories: Vec::new(),
+                    score: None,
+                }],
+                moved_to: None,
+                channel: Channel::default(),
+            };
+            assert!(kiosk
+                .insert_feed("example", FeedState::Success(feed))
+                .is_ok());
+            assert!(!kiosk.is_starred("https://example.com/a"));
+            assert!(kiosk.set_starred("https://example.com/a", true).is_ok());
+            assert!(kiosk.is_starred("https://example.com/a"));
+        }
+        let mut kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+        assert!(kiosk.is_starred("https://example.com/a"));
+        assert!(kiosk.set_starred("https://example.com/a", false).is_ok());
+        assert!(!kiosk.is_starred("https://example.com/a"));
+    }
+
+    #[test]
+    fn should_tell_articles_new_since_last_quit() {
+        let tmpfile = tempfile::NamedTempFile::new().unwrap();
+        let feed = |urls: &[&str]| Feed {
+            articles: urls.iter().map(|x| make_article(x)).collect(),
+            moved_to: None,
+            channel: Channel::default(),
+        };
+        {
+            // On the first run nothing is new
+            let mut kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+            assert!(kiosk
+                .insert_feed(
+                    "example",
+                    FeedState::Success(feed(&["https://example.com/a"]))
+                )
+                .is_ok());
+            assert!(!kiosk.is_new("https://example.com/a"));
+            assert!(kiosk.save_quit().is_ok());
+        }
+        let mut kiosk = Kiosk::new(Store::open(tmpfile.path()).unwrap()).unwrap();
+        assert!(kiosk
+            .insert_feed(
+                "example",
+                FeedState::Success(feed(&["https://example.com/a", "https://example.com/b"]))
+            )
+            .is_ok());
+        assert!(kiosk.is_new("https://example.com/b"));
+    }
+
+    fn make_article(url: &str) -> Article {
+        Article {
+            title: Some(String::from("Hello")),
+            authors: Vec::new(),
+            summary: String::new(),
+            links: Vec::new(),
+            url: url.to_string(),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: Vec::new(),
+            flair: None,
+            interest: None,
+            score: None,
+        }
     }
 }
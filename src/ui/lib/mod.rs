@@ -26,7 +26,17 @@
  * SOFTWARE.
  */
 mod client;
+mod commands;
+mod errors;
 mod kiosk;
+mod openers;
+mod retry;
+mod wake;
 
 pub use client::FeedClient;
-pub use kiosk::{FeedState, FlatFeedState, Kiosk};
+pub use commands::{ArticleSort, Command, Setting};
+pub use errors::ErrorQueue;
+pub use kiosk::{FeedState, FlatFeedState, Kiosk, TAG_FEED_PREFIX};
+pub use openers::{opener_entries, OpenTarget, OpenerEntry};
+pub use retry::RetryScheduler;
+pub use wake::WakePort;
@@ -0,0 +1,214 @@
+//! # Openers
+//!
+//! Entries of the "open with" menu
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::config::{Opener, UrlsConfig};
+use crate::feed::Article;
+use crate::helpers::urls as url_helpers;
+
+/// ## OpenTarget
+///
+/// A link of an article which can be opened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenTarget {
+    /// What the link is (e.g. "Article", "Enclosure")
+    pub label: String,
+    pub url: String,
+    /// Declared or guessed content type of the link
+    pub content_type: Option<String>,
+}
+
+/// ## OpenerEntry
+///
+/// An entry of the "open with" menu: a link and the command to open it with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenerEntry {
+    /// Name of the opener
+    pub name: String,
+    /// What the link is
+    pub target: String,
+    pub url: String,
+    /// Command to run; if not set, the link is opened in the browser
+    pub command: Option<String>,
+}
+
+impl OpenTarget {
+    fn new(label: &str, url: String, content_type: Option<String>) -> Self {
+        let content_type = content_type
+            .or_else(|| url_helpers::guess_content_type(url.as_str()).map(String::from));
+        Self {
+            label: label.to_string(),
+            url,
+            content_type,
+        }
+    }
+
+    /// ### of_article
+    ///
+    /// Get the links of `article`: the article itself, its comments page, its enclosures and the links in its summary.
+    /// Urls are cleaned with `urls`
+    pub fn of_article(article: &Article, urls: &UrlsConfig) -> Vec<Self> {
+        let mut targets = vec![Self::new("Article", urls.clean(article.url.as_str()), None)];
+        if let Some(comments) = article.comments.as_deref() {
+            targets.push(Self::new("Comments", urls.clean(comments), None));
+        }
+        for enclosure in article.enclosures.iter() {
+            targets.push(Self::new(
+                "Enclosure",
+                urls.clean(enclosure.url.as_str()),
+                enclosure.content_type.clone(),
+            ));
+        }
+        for (i, link) in article.links.iter().enumerate() {
+            targets.push(Self::new(
+                format!("Link [{}]", i + 1).as_str(),
+                urls.clean(link),
+                None,
+            ));
+        }
+        targets
+    }
+}
+
+/// ### opener_entries
+///
+/// Get the "open with" menu entries for `targets`. For each link, the `openers` matching it are listed first,
+/// then the video `player`, for videos, and finally the browser, which opens everything
+pub fn opener_entries(
+    openers: &[Opener],
+    player: Option<&str>,
+    targets: &[OpenTarget],
+) -> Vec<OpenerEntry> {
+    let mut entries: Vec<OpenerEntry> = Vec::new();
+    for target in targets.iter() {
+        let entry = |name: &str, command: Option<&str>| OpenerEntry {
+            name: name.to_string(),
+            target: target.label.clone(),
+            url: target.url.clone(),
+            command: command.map(String::from),
+        };
+        let content_type = target.content_type.as_deref();
+        for opener in openers
+            .iter()
+            .filter(|x| x.matches(target.url.as_str(), content_type))
+        {
+            entries.push(entry(opener.name.as_str(), Some(opener.command.as_str())));
+        }
+        let is_video = url_helpers::is_video_url(target.url.as_str())
+            || content_type
+                .map(|x| x.starts_with("video/"))
+                .unwrap_or(false);
+        if let Some(player) = player.filter(|_| is_video) {
+            entries.push(entry("Video player", Some(player)));
+        }
+        entries.push(entry("Browser", None));
+    }
+    entries
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use crate::feed::Enclosure;
+
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Wrapper {
+        openers: Vec<Opener>,
+    }
+
+    fn article() -> Article {
+        Article {
+            title: Some(String::from("Episode 1")),
+            authors: Vec::new(),
+            summary: String::from("Cover [1]"),
+            links: vec![String::from("https://example.com/cover.png")],
+            url: String::from("https://www.youtube.com/watch?v=1"),
+            comments: None,
+            categories: Vec::new(),
+            date: None,
+            duration: None,
+            enclosures: vec![Enclosure {
+                url: String::from("https://example.com/episode-1"),
+                content_type: Some(String::from("audio/mpeg")),
+            }],
+            flair: None,
+            interest: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn should_get_article_targets() {
+        let targets = OpenTarget::of_article(&article(), &UrlsConfig::default());
+        assert_eq!(
+            targets
+                .iter()
+                .map(|x| (x.label.as_str(), x.content_type.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Article", None),
+                ("Enclosure", Some("audio/mpeg")),
+                ("Link [1]", Some("image/png")),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_get_opener_entries() {
+        let wrapper: Wrapper = toml::de::from_str(
+            r#"
+[[openers]]
+command = "imv"
+types = ["image/*"]
+
+[[openers]]
+command = "mpv --no-video"
+types = ["audio/*"]
+"#,
+        )
+        .unwrap();
+        let targets = OpenTarget::of_article(&article(), &UrlsConfig::default());
+        let entries = opener_entries(wrapper.openers.as_slice(), Some("mpv"), targets.as_slice());
+        assert_eq!(
+            entries
+                .iter()
+                .map(|x| (x.name.as_str(), x.target.as_str(), x.command.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Video player", "Article", Some("mpv")),
+                ("Browser", "Article", None),
+                ("mpv", "Enclosure", Some("mpv --no-video")),
+                ("Browser", "Enclosure", None),
+                ("imv", "Link [1]", Some("imv")),
+                ("Browser", "Link [1]", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! # Retry
+//!
+//! Scheduling of the automatic retries of failed sources
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use crate::config::RetryConfig;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// ## RetryScheduler
+///
+/// Keeps track of the sources to fetch again after a failure, doubling the delay at each attempt
+#[derive(Debug, Default)]
+pub struct RetryScheduler {
+    config: RetryConfig,
+    retries: HashMap<String, Retry>,
+}
+
+/// ## Retry
+///
+/// Retry state of a source
+#[derive(Debug, Default)]
+struct Retry {
+    /// Retries scheduled so far
+    attempts: u32,
+    /// When the source should be fetched again; None if no retry is pending
+    at: Option<Instant>,
+}
+
+impl RetryScheduler {
+    /// ### new
+    ///
+    /// Instantiates a new `RetryScheduler`
+    pub fn new(config: RetryConfig) -> Self {
+        Self {
+            config,
+            retries: HashMap::new(),
+        }
+    }
+
+    /// ### set_config
+    ///
+    /// Update retry configuration
+    pub fn set_config(&mut self, config: RetryConfig) {
+        self.config = config;
+    }
+
+    /// ### schedule
+    ///
+    /// Schedule a new retry for `source`.
+    /// Returns the delay before the retry, or None if the source won't be retried anymore
+    pub fn schedule(&mut self, source: &str) -> Option<Duration> {
+        if !self.config.enabled {
+            return None;
+        }
+        let delay = self.delay(self.attempts(source));
+        let max_attempts = self.config.max_attempts;
+        let retry = self.retries.entry(source.to_string()).or_default();
+        if retry.attempts >= max_attempts {
+            retry.at = None;
+            return None;
+        }
+        retry.attempts += 1;
+        retry.at = Some(Instant::now() + delay);
+        Some(delay)
+    }
+
+    /// ### attempts
+    ///
+    /// Returns the amount of retries scheduled for `source`
+    pub fn attempts(&self, source: &str) -> u32 {
+        self.retries.get(source).map(|x| x.attempts).unwrap_or(0)
+    }
+
+    /// ### is_scheduled
+    ///
+    /// Returns whether a retry is pending for `source`
+    pub fn is_scheduled(&self, source: &str) -> bool {
+        self.retries
+            .get(source)
+            .map(|x| x.at.is_some())
+            .unwrap_or(false)
+    }
+
+    /// ### due
+    ///
+    /// Take the sources which must be fetched again now
+    pub fn due(&mut self) -> Vec<String> {
+        let now = Instant::now();
+        self.retries
+            .iter_mut()
+            .filter(|(_, retry)| retry.at.map(|at| at <= now).unwrap_or(false))
+            .map(|(name, retry)| {
+                retry.at = None;
+                name.clone()
+            })
+            .collect()
+    }
+
+    /// ### cancel
+    ///
+    /// Forget retries of `source`, e.g. because it has been fetched successfully
+    pub fn cancel(&mut self, source: &str) {
+        self.retries.remove(source);
+    }
+
+    /// ### rename
+    ///
+    /// Move retries of `source` to `new_name`
+    pub fn rename(&mut self, source: &str, new_name: &str) {
+        if let Some(retry) = self.retries.remove(source) {
+            self.retries.insert(new_name.to_string(), retry);
+        }
+    }
+
+    /// ### delay
+    ///
+    /// Get the delay before the retry, after `attempts` retries
+    fn delay(&self, attempts: u32) -> Duration {
+        let factor = 1_u64.checked_shl(attempts).unwrap_or(u64::MAX);
+        Duration::from_secs(
+            self.config
+                .initial_delay
+                .saturating_mul(factor)
+                .min(self.config.max_delay),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    fn make_scheduler(initial_delay: u64) -> RetryScheduler {
+        RetryScheduler::new(RetryConfig {
+            enabled: true,
+            initial_delay,
+            max_delay: 100,
+            max_attempts: 3,
+        })
+    }
+
+    #[test]
+    fn should_double_delay() {
+        let scheduler = make_scheduler(10);
+        assert_eq!(scheduler.delay(0), Duration::from_secs(10));
+        assert_eq!(scheduler.delay(1), Duration::from_secs(20));
+        assert_eq!(scheduler.delay(3), Duration::from_secs(80));
+        assert_eq!(scheduler.delay(4), Duration::from_secs(100));
+        assert_eq!(scheduler.delay(200), Duration::from_secs(100));
+    }
+
+    #[test]
+    fn should_schedule_retries() {
+        let mut scheduler = make_scheduler(10);
+        assert_eq!(scheduler.is_scheduled("nytimes"), false);
+        assert_eq!(scheduler.schedule("nytimes"), Some(Duration::from_secs(10)));
+        assert!(scheduler.is_scheduled("nytimes"));
+        assert_eq!(scheduler.schedule("nytimes"), Some(Duration::from_secs(20)));
+        assert_eq!(scheduler.schedule("nytimes"), Some(Duration::from_secs(40)));
+        assert_eq!(scheduler.attempts("nytimes"), 3);
+        // Give up
+        assert_eq!(scheduler.schedule("nytimes"), None);
+        assert_eq!(scheduler.is_scheduled("nytimes"), false);
+        // Not due yet
+        assert!(scheduler.schedule("lefigaro").is_some());
+        assert!(scheduler.due().is_empty());
+        // Cancel
+        scheduler.cancel("nytimes");
+        assert_eq!(scheduler.attempts("nytimes"), 0);
+        // Rename
+        scheduler.rename("lefigaro", "le figaro");
+        assert_eq!(scheduler.is_scheduled("lefigaro"), false);
+        assert!(scheduler.is_scheduled("le figaro"));
+    }
+
+    #[test]
+    fn should_take_due_retries() {
+        let mut scheduler = make_scheduler(0);
+        assert!(scheduler.schedule("nytimes").is_some());
+        assert_eq!(scheduler.due(), vec![String::from("nytimes")]);
+        assert_eq!(scheduler.is_scheduled("nytimes"), false);
+        assert!(scheduler.due().is_empty());
+        assert_eq!(scheduler.attempts("nytimes"), 1);
+    }
+
+    #[test]
+    fn should_not_schedule_if_disabled() {
+        let mut scheduler = RetryScheduler::new(RetryConfig {
+            enabled: false,
+            ..RetryConfig::default()
+        });
+        assert!(scheduler.schedule("nytimes").is_none());
+    }
+}
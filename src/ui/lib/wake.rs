@@ -0,0 +1,72 @@
+//! # Wake
+//!
+//! Event listener port which keeps the main loop running while the ui is busy
+
+/**
+ * MIT License
+ *
+ * tuifeed - Copyright (c) 2021 Christian Visintin
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tuirealm::listener::{ListenerResult, Poll};
+use tuirealm::{Event, NoUserEvent};
+
+/// ## WakePort
+///
+/// A port which emits an empty event each time it is polled while `busy` is set, so that the main
+/// loop wakes up to animate the feed list and to collect the fetched sources.
+/// While idle it emits nothing, and the main loop sleeps until the next input event
+pub struct WakePort {
+    busy: Arc<AtomicBool>,
+}
+
+impl WakePort {
+    pub fn new(busy: Arc<AtomicBool>) -> Self {
+        Self { busy }
+    }
+}
+
+impl Poll<NoUserEvent> for WakePort {
+    fn poll(&mut self) -> ListenerResult<Option<Event<NoUserEvent>>> {
+        Ok(match self.busy.load(Ordering::Relaxed) {
+            true => Some(Event::None),
+            false => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn should_wake_only_while_busy() {
+        let busy = Arc::new(AtomicBool::new(false));
+        let mut port = WakePort::new(Arc::clone(&busy));
+        assert_eq!(port.poll().ok().unwrap(), None);
+        busy.store(true, Ordering::Relaxed);
+        assert_eq!(port.poll().ok().unwrap(), Some(Event::None));
+    }
+}
@@ -29,25 +29,69 @@ mod components;
 mod lib;
 mod model;
 
-use components::{ErrorPopup, GlobalListener};
+use components::{AddFeedPopup, GlobalListener, GLOBAL_LISTENER_LOCKED};
 use model::Model;
 
-use crate::config::Config;
-use lib::{FeedClient, FeedState, Kiosk};
+use crate::config::{
+    serializer::SerializerError, writer as config_writer, Config, ErrorOutput, PushTarget, Source,
+};
+use crate::feed::{self, Article, Client, FeedError, FeedResult, Podcast};
+use crate::helpers::clipboard::Clipboard;
+use crate::helpers::notify as notify_helpers;
+use crate::helpers::open as open_helpers;
+use crate::helpers::path as path_helpers;
+use crate::helpers::speech::Speaker;
+use crate::helpers::urls as url_helpers;
+use crate::ipc::{Request, Server as ControlServer};
+use crate::script::Script;
+use crate::store::{Store, StoreResult};
+use crate::sync;
+use lib::{ArticleSort, FeedClient, FeedState, Kiosk, WakePort};
+use log::{debug, info, warn};
 
-use std::time::Duration;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 use tuirealm::{
     application::PollStrategy,
-    event::{Key, KeyEvent, KeyModifiers},
     props::{PropPayload, PropValue},
     terminal::TerminalBridge,
-    Application, AttrValue, Attribute, EventListenerCfg, NoUserEvent, Sub, SubClause,
-    SubEventClause,
+    Application, AttrValue, Attribute, EventListenerCfg, NoUserEvent, State, StateValue, Sub,
+    SubClause, SubEventClause,
 };
+use url::Url;
 
 use self::lib::FlatFeedState;
 
 const FORCED_REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+/// How long the main loop waits for events while idle
+const IDLE_POLL_TIMEOUT: Duration = Duration::from_millis(250);
+/// Interval between each tick, which refreshes relative dates and the last fetch time
+const DATES_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// How long a message is displayed in the status bar
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+/// Channel of the podcast search results
+type PodcastSearchChannel = (
+    Sender<FeedResult<Vec<Podcast>>>,
+    Receiver<FeedResult<Vec<Podcast>>>,
+);
+/// Channel of the feed discovery results, with the url they started from
+type DiscoveryChannel = (
+    Sender<(String, FeedResult<Vec<String>>)>,
+    Receiver<(String, FeedResult<Vec<String>>)>,
+);
+/// Channel of the read state synchronization results, with the amount of articles changed
+type SyncChannel = (
+    Sender<Result<usize, String>>,
+    Receiver<Result<usize, String>>,
+);
 
 /// ## Id
 ///
@@ -62,7 +106,26 @@ pub enum Id {
     ArticleAuthors,
     ArticleSummary,
     ArticleLink,
+    ArticleComments,
+    ArticleTags,
+    StatusBar,
+    TabBar,
+    AddFeedPopup,
+    CommandLine,
+    DeleteFeedPopup,
+    FeedInfoPopup,
+    FeedSwitcherPopup,
+    FilterPopup,
+    HelpPopup,
+    OpenWithPopup,
+    PodcastResultsPopup,
+    PodcastSearchPopup,
     QuitPopup,
+    RenameFeedPopup,
+    SearchPopup,
+    SharePopup,
+    TagArticlePopup,
+    TagFilterPopup,
     ErrorPopup,
 }
 
@@ -71,19 +134,95 @@ pub enum Id {
 /// Messages produced by components
 #[derive(Debug, PartialEq)]
 pub enum Msg {
+    ArchiveArticle,
     ArticleBlur,
     ArticleChanged(usize),
     ArticleListBlur,
+    CloseAddFeedPopup,
     CloseApp,
+    CloseCommandLine,
+    CloseDeleteFeedPopup,
     CloseErrorPopup,
+    CloseFeedInfoPopup,
+    CloseFeedSwitcherPopup,
+    CloseFilterPopup,
+    CloseHelpPopup,
+    CloseOpenWithPopup,
+    ClosePodcastResultsPopup,
+    ClosePodcastSearchPopup,
+    CloseSharePopup,
     CloseQuitPopup,
+    CloseRenameFeedPopup,
+    CloseSearchPopup,
+    CloseTagArticlePopup,
+    CloseTagFilterPopup,
+    DismissError,
+    EditConfig,
+    EditNote,
     FeedChanged(usize),
     FeedListBlur,
     FetchSource,
+    FetchCategory,
     FetchAllSources,
+    ForceFetchAllSources,
+    FilterChanged(String),
+    FilterTag(String),
     GoReadArticle,
+    MarkAllRead,
+    NextArticle,
+    NextError,
+    NextUnread,
     OpenArticle,
+    OpenComments,
+    OpenFeedHomepage,
+    OpenLink(usize),
+    OpenWith(Option<String>, String),
+    PreviousArticle,
+    PreviousError,
+    PreviousUnread,
+    RefreshDates,
+    RenameFeed(String, String),
+    RunCommand(String),
+    RunScript(String),
+    SearchArticle(String),
+    SearchPodcasts(String),
+    ShowAddFeedPopup,
+    ShowCommandLine,
+    ShowDeleteFeedPopup,
+    ShowFeedInfoPopup,
+    ShowFeedSwitcherPopup,
+    ShowFilterPopup,
+    ShowHelpPopup,
+    ShowOpenWithPopup,
+    ShowPodcastSearchPopup,
     ShowQuitPopup,
+    ShowRenameFeedPopup,
+    ShowSearchPopup,
+    ShowSharePopup,
+    ShowTagArticlePopup,
+    ShowTagFilterPopup,
+    Share(usize),
+    SpeakArticle,
+    StarArticle,
+    StopSpeaking,
+    SubmitFilter,
+    SubscribeFeed(String, String),
+    SubscribeFromClipboard,
+    SubscribePodcast(usize),
+    SwitchFeed(String),
+    /// Select the tab at this index
+    SwitchTab(usize),
+    TagArticle(String),
+    ToggleHideRead,
+    ToggleOffline,
+    ToggleReadingMode,
+    ToggleSortByScore,
+    ToggleSortByUnread,
+    Undo,
+    UnsubscribeFeed(String),
+    WindowResized,
+    YankArticle,
+    YankLink,
     None,
 }
 
@@ -92,31 +231,113 @@ pub enum Msg {
 /// A task requested by the model in the Update routine, to be performed by the ui
 #[derive(Debug, Clone, PartialEq)]
 pub enum Task {
+    /// Archive the page of the article with this url and title
+    ArchiveArticle(String, Option<String>),
+    CopyToClipboard(String),
+    EditConfig,
+    /// Edit the note of the article with this url
+    EditNote(String),
     FetchSource(String),
-    FetchSources,
+    /// Fetch the sources in the category of this source
+    FetchCategory(String),
+    /// Fetch all sources; sources fetched more recently than their refresh interval only if forced
+    FetchSources(bool),
+    /// Post the new article of the source to the webhook at the url
+    PostWebhook(String, String, Box<Article>),
+    /// Push the new article of the source to the push notification service
+    Push(PushTarget, String, Box<Article>),
+    RenameFeed(String, String),
+    /// Search the podcasts matching the query in the directory
+    SearchPodcasts(String),
     ShowError(String),
+    /// Read text aloud with the text-to-speech command
+    Speak(String),
+    StopSpeaking,
+    SubscribeFeed(String, String),
+    /// Discover the feeds of the url in the clipboard and subscribe to the first one
+    SubscribeFromClipboard,
+    UnsubscribeFeed(String),
 }
 
 pub struct Ui {
+    /// Default directory of the archived articles, in the data directory
+    archive_dir: Option<PathBuf>,
+    /// Results of the articles being archived in background
+    archives: (Sender<FeedResult<PathBuf>>, Receiver<FeedResult<PathBuf>>),
+    /// Amount of articles being archived
+    archiving: usize,
+    /// Tells the event listener to keep waking the main loop
+    busy: Arc<AtomicBool>,
     client: FeedClient,
+    clipboard: Clipboard,
     config: Config,
+    config_file: Option<PathBuf>,
+    control: Option<ControlServer>,
+    /// Results of the podcast searches running in background
+    podcast_searches: PodcastSearchChannel,
+    /// Amount of podcast searches running
+    searching: usize,
+    /// Results of the feed discoveries running in background, with the url they started from
+    discoveries: DiscoveryChannel,
+    /// Amount of feed discoveries running
+    discovering: usize,
+    /// Article store file, synchronized in background
+    store_file: Option<PathBuf>,
+    /// Results of the read state synchronizations running in background
+    syncs: SyncChannel,
+    /// When the read state has been synchronized last
+    last_sync: Option<Instant>,
+    /// Whether the read state is being synchronized
+    syncing: bool,
     model: Model,
     app: Application<Id, Msg, NoUserEvent>,
+    speaker: Speaker,
+    tick: u64,
 }
 
 impl Ui {
     /// ### new
     ///
-    /// Instantiates a new Ui
-    pub fn new(config: Config, tick: u64) -> Self {
-        let model = Model::new(&config, Self::init_terminal());
-        let app = Self::init_application(&model, tick);
-        Self {
+    /// Instantiates a new Ui, keeping articles in `store` and receiving requests from `control`.
+    /// Articles are archived in `data_dir`, unless configured otherwise.
+    /// If `offline` is set, sources are loaded from the store until tuifeed goes online
+    pub fn new(
+        config: Config,
+        config_file: Option<PathBuf>,
+        data_dir: Option<&Path>,
+        store: Store,
+        control: Option<ControlServer>,
+        offline: bool,
+        tick: u64,
+    ) -> StoreResult<Self> {
+        let kiosk = Kiosk::new(store)?;
+        let mut model = Model::new(&config, kiosk, Self::init_terminal());
+        model.set_offline(offline);
+        let busy = Arc::new(AtomicBool::new(false));
+        let app = Self::init_application(&model, &config, tick, &busy);
+        Ok(Self {
+            archive_dir: data_dir.map(path_helpers::get_archive_dir),
+            archives: mpsc::channel(),
+            archiving: 0,
+            busy,
+            client: FeedClient::new(Self::feed_client(&config)),
             config,
-            client: FeedClient::default(),
+            config_file,
+            control,
+            podcast_searches: mpsc::channel(),
+            searching: 0,
+            discoveries: mpsc::channel(),
+            discovering: 0,
+            store_file: data_dir.map(path_helpers::get_store_file),
+            syncs: mpsc::channel(),
+            last_sync: None,
+            syncing: false,
+            clipboard: Clipboard::default(),
             model,
             app,
-        }
+            speaker: Speaker::default(),
+            tick,
+        })
     }
 
     /// ### run
@@ -124,8 +345,10 @@ impl Ui {
     /// Main loop for Ui thread
     pub fn run(&mut self) {
         self.model.init_terminal();
+        self.report_source_errors();
+        self.load_script();
         // Fetch sources once
-        self.fetch_all_sources();
+        self.fetch_all_sources(false);
         // Main loop
         while !self.model.quit() {
             if let Err(err) = self.app.tick(&mut self.model, PollStrategy::UpTo(3)) {
@@ -133,12 +356,29 @@ impl Ui {
             }
             // Poll fetched sources
             self.poll_fetched_sources();
+            self.poll_archived_articles();
+            self.poll_podcast_searches();
+            self.poll_discoveries();
+            self.sync_read_state();
+            self.poll_syncs();
+            self.retry_sources();
+            self.poll_control_requests();
             // Run tasks
             self.run_tasks();
             // Check whether to force redraw
             self.check_force_redraw();
+            self.check_status_message();
             // View
             self.model.view(&mut self.app);
+            // Keep waking up while sources are being fetched, sleep until the next input otherwise
+            self.busy.store(
+                self.client.running()
+                    || self.archiving > 0
+                    || self.searching > 0
+                    || self.discovering > 0
+                    || self.syncing,
+                Ordering::Relaxed,
+            );
         }
         self.model.finalize_terminal();
     }
@@ -151,16 +391,52 @@ impl Ui {
     fn run_tasks(&mut self) {
         for task in self.model.get_tasks().into_iter() {
             match task {
+                Task::ArchiveArticle(url, title) => self.archive_article(url, title),
+                Task::CopyToClipboard(text) => self.copy_to_clipboard(text.as_str()),
+                Task::EditConfig => self.edit_config(),
+                Task::EditNote(url) => self.edit_note(url.as_str()),
                 Task::FetchSource(name) => {
-                    let uri = self.config.sources.get(&name).cloned();
-                    if let Some(uri) = uri {
-                        self.fetch_source(name.as_str(), uri.as_str())
+                    let source = self.config.sources.get(&name).cloned();
+                    if let Some(source) = source {
+                        self.model.retries().cancel(name.as_str());
+                        self.fetch_source(name.as_str(), &source)
                     }
                 }
-                Task::FetchSources => self.fetch_all_sources(),
-                Task::ShowError(err) => self.mount_error_popup(err),
+                Task::FetchCategory(name) => self.fetch_category(name.as_str()),
+                Task::FetchSources(force) => self.fetch_all_sources(force),
+                Task::PostWebhook(url, source, article) => Self::post_webhook(url, source, article),
+                Task::Push(target, source, article) => Self::push_article(target, source, article),
+                Task::RenameFeed(name, new_name) => self.rename_feed(name, new_name),
+                Task::SearchPodcasts(query) => self.search_podcasts(query),
+                Task::ShowError(err) => {
+                    warn!("{}", err);
+                    self.mount_error_popup(err)
+                }
+                Task::Speak(text) => self.speak(text.as_str()),
+                Task::StopSpeaking => self.stop_speaking(),
+                Task::SubscribeFeed(name, uri) => self.subscribe_feed(name, uri),
+                Task::SubscribeFromClipboard => self.subscribe_from_clipboard(),
+                Task::UnsubscribeFeed(name) => self.unsubscribe_feed(name),
+            }
+        }
+    }
+
+    /// ### poll_control_requests
+    ///
+    /// Handle the requests sent to the control socket
+    fn poll_control_requests(&mut self) {
+        let request = match self.control.as_ref().and_then(|x| x.poll()) {
+            Some(request) => request,
+            None => return,
+        };
+        info!("handling control request: {}", request);
+        match request {
+            Request::Refresh => self.fetch_all_sources(false),
+            Request::OpenUrl(url) => {
+                self.mount_add_feed_popup(url_helpers::strip_feed_scheme(&url))
             }
         }
+        self.model.force_redraw();
     }
 
     /// ### check_force_redraw
@@ -173,69 +449,829 @@ impl Ui {
         }
     }
 
+    /// ### check_status_message
+    ///
+    /// Clear status message once it has been displayed for long enough
+    fn check_status_message(&mut self) {
+        if self.model.status_message_expired() {
+            self.model.set_status_message(None);
+            self.update_status_bar();
+            self.model.force_redraw();
+        }
+    }
+
+    /// ### archive_article
+    ///
+    /// Archive the page of the article at `url` in background; the result is reported once it's done
+    fn archive_article(&mut self, url: String, title: Option<String>) {
+        let dir = match self
+            .config
+            .archive
+            .dir
+            .clone()
+            .or_else(|| self.archive_dir.clone())
+        {
+            Some(dir) => dir,
+            None => {
+                self.mount_error_popup(
+                    "Could not find a data directory to archive articles into; set `archive.dir`",
+                );
+                return;
+            }
+        };
+        let sender = self.archives.0.clone();
+        thread::spawn(move || {
+            let _ = sender.send(feed::archive_article(
+                url.as_str(),
+                title.as_deref(),
+                dir.as_path(),
+            ));
+        });
+        self.archiving += 1;
+        self.model
+            .set_status_message(Some(String::from("Archiving article...")));
+        self.update_status_bar();
+    }
+
+    /// ### search_podcasts
+    ///
+    /// Search the podcasts matching `query` in background
+    fn search_podcasts(&mut self, query: String) {
+        let sender = self.podcast_searches.0.clone();
+        thread::spawn(move || {
+            let _ = sender.send(feed::search_podcasts(query.as_str()));
+        });
+        self.searching += 1;
+        self.model
+            .set_status_message(Some(String::from("Searching podcasts...")));
+        self.update_status_bar();
+    }
+
+    /// ### subscribe_from_clipboard
+    ///
+    /// Discover in background the feeds of the url in the clipboard, to subscribe to one of them
+    fn subscribe_from_clipboard(&mut self) {
+        let url = match self.clipboard.paste() {
+            Ok(text) => url_helpers::strip_feed_scheme(text.trim()),
+            Err(err) => {
+                self.mount_error_popup(err);
+                return;
+            }
+        };
+        let is_web_url = Url::parse(url.as_str())
+            .map(|x| matches!(x.scheme(), "http" | "https"))
+            .unwrap_or(false);
+        if !is_web_url {
+            self.mount_error_popup(format!("The clipboard doesn't contain a url: {}", url));
+            return;
+        }
+        let sender = self.discoveries.0.clone();
+        thread::spawn(move || {
+            let result = feed::discover_feeds(url.as_str());
+            let _ = sender.send((url, result));
+        });
+        self.discovering += 1;
+        self.model
+            .set_status_message(Some(String::from("Looking for feeds...")));
+        self.update_status_bar();
+    }
+
+    /// ### sync_read_state
+    ///
+    /// Synchronize the read state in background, if the sync interval has elapsed since the last one.
+    /// The synchronization uses its own connection to the store
+    fn sync_read_state(&mut self) {
+        let due = self
+            .last_sync
+            .map(|x| x.elapsed() >= self.config.sync.interval())
+            .unwrap_or(true);
+        let store_file = match self.store_file.clone() {
+            Some(p) if due && !self.syncing && self.config.sync.url.is_some() => p,
+            _ => return,
+        };
+        let config = self.config.sync.clone();
+        let key = self.config.cache.key();
+        let sender = self.syncs.0.clone();
+        thread::spawn(move || {
+            let result = key
+                .and_then(|key| {
+                    match key {
+                        Some(key) => Store::open_encrypted(store_file.as_path(), key.as_str()),
+                        None => Store::open(store_file.as_path()),
+                    }
+                    .map_err(|e| e.to_string())
+                })
+                .and_then(|mut store| sync::sync_read_state(&mut store, &config));
+            let _ = sender.send(result);
+        });
+        self.last_sync = Some(Instant::now());
+        self.syncing = true;
+    }
+
+    /// ### poll_syncs
+    ///
+    /// Get the result of the read state synchronization, reloading the read articles if they've changed
+    fn poll_syncs(&mut self) {
+        while let Ok(result) = self.syncs.1.try_recv() {
+            self.syncing = false;
+            match result {
+                Ok(0) => {}
+                Ok(changes) => {
+                    info!("read state synchronized; {} articles changed", changes);
+                    self.model.reload_read_state();
+                    self.remount_article_list();
+                }
+                Err(err) => {
+                    warn!("could not synchronize read state: {}", err);
+                    self.model.set_status_message(Some(format!(
+                        "Could not synchronize read state: {}",
+                        err
+                    )));
+                    self.update_status_bar();
+                }
+            }
+        }
+    }
+
+    /// ### poll_podcast_searches
+    ///
+    /// Get the results of the podcast searches, listing the podcasts found to subscribe to
+    fn poll_podcast_searches(&mut self) {
+        while let Ok(result) = self.podcast_searches.1.try_recv() {
+            self.searching = self.searching.saturating_sub(1);
+            match result {
+                Ok(podcasts) if podcasts.is_empty() => {
+                    self.model
+                        .set_status_message(Some(String::from("No podcast found")));
+                    self.update_status_bar();
+                }
+                Ok(podcasts) => {
+                    self.model.set_status_message(None);
+                    self.update_status_bar();
+                    self.model.set_podcasts(podcasts);
+                    assert!(self
+                        .app
+                        .remount(
+                            Id::PodcastResultsPopup,
+                            Box::new(self.model.get_podcast_results_popup()),
+                            vec![]
+                        )
+                        .is_ok());
+                    assert!(self.app.active(&Id::PodcastResultsPopup).is_ok());
+                    assert!(self
+                        .app
+                        .attr(
+                            &Id::GlobalListener,
+                            Attribute::Custom(GLOBAL_LISTENER_LOCKED),
+                            AttrValue::Flag(true)
+                        )
+                        .is_ok());
+                }
+                Err(err) => self.mount_error_popup(format!("Could not search podcasts: {}", err)),
+            }
+            self.model.force_redraw();
+        }
+    }
+
+    /// ### poll_discoveries
+    ///
+    /// Get the feeds discovered from the clipboard, opening the popup to subscribe to the first one.
+    /// If no feed is found, the popup is opened with the url anyway, so that it can be fixed
+    fn poll_discoveries(&mut self) {
+        while let Ok((url, result)) = self.discoveries.1.try_recv() {
+            self.discovering = self.discovering.saturating_sub(1);
+            let (message, url) = match result {
+                Ok(feeds) if feeds.len() > 1 => (
+                    Some(format!(
+                        "Found {} feeds; subscribing to {}",
+                        feeds.len(),
+                        feeds[0]
+                    )),
+                    feeds[0].clone(),
+                ),
+                Ok(mut feeds) if !feeds.is_empty() => (None, feeds.remove(0)),
+                Ok(_) => (None, url),
+                Err(err) => (Some(format!("Could not discover feeds: {}", err)), url),
+            };
+            self.model.set_status_message(message);
+            self.update_status_bar();
+            self.mount_add_feed_popup(url);
+            self.model.force_redraw();
+        }
+    }
+
+    /// ### post_webhook
+    ///
+    /// Post the new `article` of `source` to the webhook at `url` in background
+    fn post_webhook(url: String, source: String, article: Box<Article>) {
+        thread::spawn(move || {
+            if let Err(err) = notify_helpers::post_webhook(url.as_str(), source.as_str(), &article)
+            {
+                warn!("could not post to webhook: {}", err);
+            }
+        });
+    }
+
+    /// ### push_article
+    ///
+    /// Push the new `article` of `source` to the service of `target` in background
+    fn push_article(target: PushTarget, source: String, article: Box<Article>) {
+        thread::spawn(move || {
+            let title = article.title.as_deref().unwrap_or(article.url.as_str());
+            let url = Some(article.url.as_str()).filter(|x| !x.is_empty());
+            if let Err(err) = notify_helpers::notify_push(
+                &target.service,
+                target.priority,
+                source.as_str(),
+                title,
+                url,
+            ) {
+                warn!("could not send push notification: {}", err);
+            }
+        });
+    }
+
+    /// ### poll_archived_articles
+    ///
+    /// Report the articles which have been archived
+    fn poll_archived_articles(&mut self) {
+        while let Ok(result) = self.archives.1.try_recv() {
+            self.archiving = self.archiving.saturating_sub(1);
+            match result {
+                Ok(p) => {
+                    self.model
+                        .set_status_message(Some(format!("Article archived to {}", p.display())));
+                    self.update_status_bar();
+                }
+                Err(err) => self.mount_error_popup(format!("Could not archive article: {}", err)),
+            }
+            self.model.force_redraw();
+        }
+    }
+
+    /// ### copy_to_clipboard
+    ///
+    /// Copy `text` to clipboard and report it in the status bar
+    fn copy_to_clipboard(&mut self, text: &str) {
+        match self.clipboard.copy(text) {
+            Ok(()) => {
+                self.model
+                    .set_status_message(Some(String::from("Copied to clipboard")));
+                self.update_status_bar();
+            }
+            Err(err) => self.mount_error_popup(err),
+        }
+    }
+
+    /// ### speak
+    ///
+    /// Read `text` aloud with the configured text-to-speech command and report it in the status bar
+    fn speak(&mut self, text: &str) {
+        let command = match self.config.tts.command.clone() {
+            Some(command) => command,
+            None => {
+                self.mount_error_popup(
+                    "Set `tts.command` in the configuration to read articles aloud",
+                );
+                return;
+            }
+        };
+        match self.speaker.speak(command.as_str(), text) {
+            Ok(()) => {
+                self.model
+                    .set_status_message(Some(String::from("Reading article aloud...")));
+                self.update_status_bar();
+            }
+            Err(err) => self.mount_error_popup(err),
+        }
+    }
+
+    /// ### stop_speaking
+    ///
+    /// Stop reading the article aloud
+    fn stop_speaking(&mut self) {
+        if self.speaker.stop() {
+            self.model
+                .set_status_message(Some(String::from("Stopped reading article")));
+            self.update_status_bar();
+        }
+    }
+
+    // -- config
+
+    /// ### edit_config
+    ///
+    /// Suspend the terminal, open the configuration file in the user's editor
+    /// and reload the configuration once the editor exits
+    fn edit_config(&mut self) {
+        let config_file = match self.config_file.clone() {
+            Some(p) => p,
+            None => {
+                self.mount_error_popup(
+                    "Could not find a configuration path on your operating system...",
+                );
+                return;
+            }
+        };
+        if let Err(err) = self.edit_text_file(config_file.as_path()) {
+            self.mount_error_popup(err);
+            return;
+        }
+        // Reload configuration
+        match Config::load(config_file.as_path()) {
+            Ok(config) => {
+                self.reload_config(config);
+                self.report_source_errors();
+            }
+            Err(err) => self.mount_error_popup(format!("Could not reload configuration: {}", err)),
+        }
+    }
+
+    /// ### edit_text_file
+    ///
+    /// Suspend the terminal and open the file at `p` in the user's editor, until the editor exits
+    fn edit_text_file(&mut self, p: &Path) -> Result<(), String> {
+        // Stop input listener and restore terminal, otherwise the editor won't receive any input
+        self.app
+            .restart_listener(EventListenerCfg::default())
+            .map_err(|err| format!("Could not stop input listener: {}", err))?;
+        self.model.finalize_terminal();
+        let result = open_helpers::edit_text_file(p);
+        // Restore ui
+        self.model.init_terminal();
+        if let Err(err) = self
+            .app
+            .restart_listener(Self::listener_cfg(self.tick, &self.busy))
+        {
+            self.mount_error_popup(format!("Could not restart input listener: {}", err));
+        }
+        self.model.force_redraw();
+        result
+    }
+
+    /// ### edit_note
+    ///
+    /// Edit the note attached to the article with `url` in the user's editor,
+    /// through a temporary file which is removed once the editor exits
+    fn edit_note(&mut self, url: &str) {
+        let p = env::temp_dir().join(format!("tuifeed-note-{}.txt", process::id()));
+        let note = self
+            .model
+            .kiosk()
+            .get_note(url)
+            .unwrap_or_default()
+            .to_string();
+        if let Err(err) = fs::write(p.as_path(), note) {
+            self.mount_error_popup(format!("Could not write note: {}", err));
+            return;
+        }
+        let result = self.edit_text_file(p.as_path()).and_then(|_| {
+            fs::read_to_string(p.as_path()).map_err(|e| format!("Could not read note: {}", e))
+        });
+        let _ = fs::remove_file(p.as_path());
+        match result {
+            Ok(note) => {
+                self.model.set_note(url, note.as_str());
+                self.remount_article_list();
+                self.update_status_bar();
+            }
+            Err(err) => self.mount_error_popup(err),
+        }
+    }
+
+    /// ### reload_config
+    ///
+    /// Replace the current configuration with `config`.
+    /// Removed sources are dropped from the kiosk, while new or changed sources are fetched again
+    fn reload_config(&mut self, config: Config) {
+        let old_config = std::mem::replace(&mut self.config, config);
+        // Remove sources which don't exist anymore
+        for name in old_config.sources.keys() {
+            if !self.config.sources.contains_key(name) {
+                self.model.remove_source(name.as_str());
+            }
+        }
+        // Collect new or changed sources; if the articles limits changed, all sources must be fetched again
+        let limits_changed = old_config.articles.max_articles != self.config.articles.max_articles
+            || old_config.articles.max_age_days != self.config.articles.max_age_days
+            || old_config.reddit != self.config.reddit
+            || old_config.mute != self.config.mute
+            || old_config.scoring != self.config.scoring
+            || old_config.scripting != self.config.scripting;
+        self.client.set_client(Self::feed_client(&self.config));
+        let changed: Vec<(String, Source)> = self
+            .config
+            .sources
+            .iter()
+            .filter(|(name, source)| {
+                limits_changed || old_config.sources.get(*name) != Some(*source)
+            })
+            .map(|(name, source)| (name.clone(), source.clone()))
+            .collect();
+        for (name, _) in changed.iter() {
+            self.model.update_source(name.as_str(), FeedState::Loading);
+        }
+        self.model.set_dates(self.config.dates.clone());
+        self.model.set_layout(self.config.layout.clone());
+        self.model.set_highlights(self.config.highlights.clone());
+        self.model.set_hooks(self.config.hooks.clone());
+        self.model.set_styles(self.config.styles.clone());
+        self.model.set_mute(self.config.mute.clone());
+        self.model.set_openers(self.config.openers.clone());
+        self.model.set_share(self.config.share.clone());
+        self.model.set_scoring(self.config.scoring.clone());
+        self.model.set_terminal(&self.config.terminal);
+        self.model.set_theme(&self.config.theme);
+        self.model.set_urls(self.config.urls.clone());
+        self.model.set_video(self.config.video.clone());
+        self.model.set_webhooks(self.config.webhooks.clone());
+        self.model.set_push(self.config.push.clone());
+        self.model.set_maildir(self.config.maildir.clone());
+        self.model
+            .set_tabs(self.config.tabs.clone(), &self.config.sources);
+        self.model.set_feed_sort(
+            self.config.feeds.sort,
+            self.config.source_ranks(),
+            self.config.pinned_sources(),
+        );
+        assert!(self
+            .app
+            .remount(Id::TabBar, Box::new(self.model.get_tab_bar()), vec![])
+            .is_ok());
+        self.model.retries().set_config(self.config.retry.clone());
+        self.load_script();
+        // Keep the visibility toggled in this session, unless it has been changed in the configuration
+        if self.config.articles.hide_read != old_config.articles.hide_read {
+            self.model.set_hide_read(self.config.articles.hide_read);
+        }
+        if self.config.articles.sort_by_score != old_config.articles.sort_by_score {
+            self.model
+                .set_sort(ArticleSort::by_score(self.config.articles.sort_by_score));
+        }
+        // Update key bindings
+        if self.config.keys != old_config.keys
+            || self.config.scripting.actions != old_config.scripting.actions
+        {
+            self.model.set_keys(self.config.keys.clone());
+            assert!(self
+                .app
+                .remount(
+                    Id::GlobalListener,
+                    Box::new(GlobalListener::new(
+                        self.config.keys.clone(),
+                        self.config.scripting.actions.clone()
+                    )),
+                    Self::subs()
+                )
+                .is_ok());
+        }
+        // Remount lists, since sources or keys may have changed
+        self.remount_lists();
+        // Fetch changed sources
+        for (name, source) in changed.into_iter() {
+            self.fetch_source(name.as_str(), &source);
+        }
+    }
+
+    /// ### subscribe_feed
+    ///
+    /// Add a new source to the configuration file and fetch it
+    fn subscribe_feed(&mut self, name: String, uri: String) {
+        if let Err(err) =
+            self.write_config(|config_file| config_writer::add_source(config_file, &name, &uri))
+        {
+            self.mount_error_popup(err);
+            return;
+        }
+        let source = Source::from(uri);
+        self.config.sources.insert(name.clone(), source.clone());
+        // New sources are written at the end of the configuration
+        if !self.config.source_order.contains(&name) {
+            self.config.source_order.push(name.clone());
+        }
+        self.model.set_feed_sort(
+            self.config.feeds.sort,
+            self.config.source_ranks(),
+            self.config.pinned_sources(),
+        );
+        self.model.update_source(name.as_str(), FeedState::Loading);
+        self.remount_lists();
+        self.fetch_source(name.as_str(), &source);
+    }
+
+    /// ### unsubscribe_feed
+    ///
+    /// Remove source from configuration file and from kiosk
+    fn unsubscribe_feed(&mut self, name: String) {
+        if let Err(err) =
+            self.write_config(|config_file| config_writer::remove_source(config_file, &name))
+        {
+            self.mount_error_popup(err);
+            return;
+        }
+        self.config.sources.remove(&name);
+        self.model.remove_source(name.as_str());
+        self.remount_lists();
+    }
+
+    /// ### rename_feed
+    ///
+    /// Rename source both in configuration file and in kiosk
+    fn rename_feed(&mut self, name: String, new_name: String) {
+        if let Err(err) = self
+            .write_config(|config_file| config_writer::rename_source(config_file, &name, &new_name))
+        {
+            self.mount_error_popup(err);
+            return;
+        }
+        if let Some(source) = self.config.sources.remove(&name) {
+            self.config.sources.insert(new_name.clone(), source);
+        }
+        for x in self.config.source_order.iter_mut().filter(|x| **x == name) {
+            *x = new_name.clone();
+        }
+        self.model
+            .set_tabs(self.config.tabs.clone(), &self.config.sources);
+        self.model.set_feed_sort(
+            self.config.feeds.sort,
+            self.config.source_ranks(),
+            self.config.pinned_sources(),
+        );
+        self.model.rename_source(name.as_str(), new_name.as_str());
+        self.remount_lists();
+        // If source was loading, the fetch result will be discarded, since it refers to the old name
+        if let Some(source) = self.config.sources.get(&new_name).cloned() {
+            if matches!(
+                self.model.kiosk().get_feed_state(new_name.as_str()),
+                Some(FeedState::Loading)
+            ) {
+                self.fetch_source(new_name.as_str(), &source);
+            }
+        }
+    }
+
+    /// ### write_config
+    ///
+    /// Edit configuration file with `write`
+    fn write_config<F>(&self, write: F) -> Result<(), String>
+    where
+        F: FnOnce(&Path) -> Result<(), SerializerError>,
+    {
+        match self.config_file.as_deref() {
+            Some(config_file) => {
+                write(config_file).map_err(|e| format!("Could not save configuration: {}", e))
+            }
+            None => Err(String::from(
+                "Could not find a configuration path on your operating system...",
+            )),
+        }
+    }
+
     // -- source fetch
 
     /// ### fetch_all_sources
     ///
-    /// Fetch all sources and update Ui
+    /// Fetch all sources and update Ui.
+    /// Unless `force` is set, sources fetched more recently than their refresh interval are not
+    /// fetched again: they're loaded from the store if they aren't displayed yet
     #[allow(clippy::needless_collect)]
-    fn fetch_all_sources(&mut self) {
+    fn fetch_all_sources(&mut self, force: bool) {
         // Fetch sources
-        let sources: Vec<(String, String)> = self
+        let sources: Vec<(String, Source)> = self
+            .config
+            .sources
+            .iter()
+            .map(|(name, source)| (name.clone(), source.clone()))
+            .collect();
+        // While the daemon is running, it keeps the store up to date: sources are loaded from there,
+        // unless fetching is forced
+        let from_store = self.model.daemon_running(self.config.daemon.interval());
+        let mut restore: Vec<String> = Vec::new();
+        let all_feed_article = self.selected_all_feed_article();
+        for (name, source) in sources.into_iter() {
+            self.model.retries().cancel(name.as_str());
+            let fresh = !force && self.model.source_is_fresh(name.as_str(), &source);
+            if fresh && self.model.kiosk().get_feed(name.as_str()).is_some() {
+                debug!("{} has been fetched recently, skipping it", name);
+            } else if (fresh || (from_store && !force)) && self.model.load_source(name.as_str()) {
+                self.update_feed_list(name.as_str(), FlatFeedState::Success);
+                restore.push(name);
+            } else {
+                self.fetch_source(name.as_str(), &source);
+            }
+        }
+        if !restore.is_empty() {
+            self.update_status_bar();
+            if self.is_article_list_empty() && !self.model.filtering() {
+                self.init_article();
+            } else if let Some(url) = all_feed_article.filter(|_| !self.model.filtering()) {
+                self.remount_article_list_at(url.as_deref());
+            }
+            for name in restore.iter() {
+                self.restore_session(name.as_str());
+            }
+            self.model.force_redraw();
+        }
+    }
+
+    /// ### fetch_category
+    ///
+    /// Fetch all the sources in the category of the source `name`
+    fn fetch_category(&mut self, name: &str) {
+        let category = match self.config.sources.get(name) {
+            Some(source) => source.category.clone(),
+            None => return,
+        };
+        let category = match category {
+            Some(category) => category,
+            None => {
+                self.model.set_status_message(Some(format!(
+                    r#""{}" doesn't belong to any category"#,
+                    name
+                )));
+                return;
+            }
+        };
+        let sources: Vec<(String, Source)> = self
             .config
             .sources
             .iter()
-            .map(|(name, uri)| (name.clone(), uri.clone()))
+            .filter(|(_, source)| source.category.as_deref() == Some(category.as_str()))
+            .map(|(name, source)| (name.clone(), source.clone()))
             .collect();
-        for (name, uri) in sources.into_iter() {
-            self.fetch_source(name.as_str(), uri.as_str());
+        self.model.set_status_message(Some(format!(
+            r#"Reloading {} sources in "{}""#,
+            sources.len(),
+            category
+        )));
+        for (name, source) in sources.into_iter() {
+            self.model.retries().cancel(name.as_str());
+            self.fetch_source(name.as_str(), &source);
         }
     }
 
+    /// ### feed_client
+    ///
+    /// Make the client to fetch sources with, according to `config`
+    fn feed_client(config: &Config) -> Client {
+        Client::default()
+            .reddit_metadata(config.reddit.metadata)
+            .ca_file(config.tls.ca_file.clone())
+    }
+
     /// ### fetch_source
     ///
-    /// Start a worker to fetch sources
-    fn fetch_source(&mut self, name: &str, uri: &str) {
-        self.client.fetch(name, uri);
+    /// Start a worker to fetch sources; while offline, the source is loaded from the store instead
+    fn fetch_source(&mut self, name: &str, source: &Source) {
+        if self.model.offline() {
+            self.load_offline_source(name);
+            return;
+        }
+        self.client.fetch(
+            name,
+            source.url.as_str(),
+            self.config.articles.limits(source),
+            source.fetch_options(),
+        );
         // Mark source as Loading
         self.model.update_source(name, FeedState::Loading);
         self.update_feed_list(name, FlatFeedState::Loading);
+        self.update_status_bar();
         // Force redraw
         self.model.force_redraw();
     }
 
+    /// ### load_offline_source
+    ///
+    /// Load source `name` from the store, as if it were fetched.
+    /// Sources which have never been fetched are reported as not available offline
+    fn load_offline_source(&mut self, name: &str) {
+        self.model.retries().cancel(name);
+        let state = match self.model.load_source(name) {
+            true => FlatFeedState::Success,
+            false => {
+                self.model
+                    .update_source(name, FeedState::Error(FeedError::Offline));
+                FlatFeedState::Error
+            }
+        };
+        self.update_feed_list(name, state);
+        self.update_status_bar();
+        if self.is_article_list_empty() && !self.model.filtering() {
+            self.init_article();
+        }
+        self.restore_session(name);
+        self.model.force_redraw();
+    }
+
     /// ### poll_fetched_sources
     ///
     /// Get result for all fetched sources
     fn poll_fetched_sources(&mut self) {
         if let Some((name, result)) = self.client.poll() {
+            // Source may have been removed in the meanwhile
+            if !self.config.sources.contains_key(&name) {
+                return;
+            }
             // Adapt state
             let state = match result {
-                Ok(feed) => FeedState::Success(feed),
+                Ok(feed) => {
+                    self.model.retries().cancel(name.as_str());
+                    if let Some(url) = feed.moved_to() {
+                        self.model.set_status_message(Some(format!(
+                            r#""{}" moved to {}: run `tuifeed check --fix` to update the configuration"#,
+                            name, url
+                        )));
+                    }
+                    FeedState::Success(feed)
+                }
                 Err(err) => {
-                    // Mount error and return err
-                    self.mount_error_popup(format!(r#"Could not fetch feed "{}": {}"#, name, err));
+                    self.report_fetch_error(name.as_str(), &err);
                     FeedState::Error(err)
                 }
             };
             // Update source
-            let flat_state = FlatFeedState::from(&state);
+            let flat_state = match FlatFeedState::from(&state) {
+                FlatFeedState::Error if self.model.retries().is_scheduled(name.as_str()) => {
+                    FlatFeedState::Retrying
+                }
+                flat_state => flat_state,
+            };
+            let all_feed_article = self.selected_all_feed_article();
             self.model.update_source(name.as_str(), state);
+            let (fetched, total) = self.model.kiosk().fetch_progress();
+            if fetched == total {
+                self.model.run_refresh_hook(total);
+                self.reorder_feed_list();
+            }
             // Update feed list and initialize article
             self.update_feed_list(name.as_str(), flat_state);
-            if self.is_article_list_empty() {
+            self.update_status_bar();
+            // NOTE: article list may be empty because no article matches the filter
+            if self.is_article_list_empty() && !self.model.filtering() {
                 self.init_article();
+            } else if let Some(url) = all_feed_article.filter(|_| !self.model.filtering()) {
+                self.remount_article_list_at(url.as_deref());
             }
+            self.restore_session(name.as_str());
             // Force redraw
             self.model.force_redraw();
         }
     }
 
+    /// ### report_fetch_error
+    ///
+    /// Schedule a retry for the source which couldn't be fetched and report the error.
+    /// Failed retries are only logged until the source is given up
+    fn report_fetch_error(&mut self, name: &str, err: &FeedError) {
+        let attempts = self.model.retries().attempts(name);
+        let message = match self.model.retries().schedule(name) {
+            Some(delay) => format!(
+                r#"Could not fetch feed "{}": {}; retrying in {}s"#,
+                name,
+                err,
+                delay.as_secs()
+            ),
+            None if attempts > 0 => format!(
+                r#"Could not fetch feed "{}": {}; giving up after {} retries"#,
+                name, err, attempts
+            ),
+            None => format!(r#"Could not fetch feed "{}": {}"#, name, err),
+        };
+        if attempts > 0 && self.model.retries().is_scheduled(name) {
+            warn!("{}", message);
+            return;
+        }
+        match self.config.errors.fetch_errors {
+            ErrorOutput::Popup => self.mount_error_popup(message),
+            ErrorOutput::Status => {
+                warn!("{}", message);
+                self.model.set_status_message(Some(message));
+            }
+        }
+    }
+
+    /// ### retry_sources
+    ///
+    /// Fetch again the failed sources whose retry is due
+    fn retry_sources(&mut self) {
+        for name in self.model.retries().due().into_iter() {
+            match self.config.sources.get(&name).cloned() {
+                Some(source) => {
+                    info!(r#"retrying to fetch "{}""#, name);
+                    self.fetch_source(name.as_str(), &source);
+                }
+                None => self.model.retries().cancel(name.as_str()),
+            }
+        }
+    }
+
     fn update_feed_list(&mut self, name: &str, state: FlatFeedState) {
         // Update item
         let state = match state {
             FlatFeedState::Error => components::lists::FEED_STATE_ERROR,
             FlatFeedState::Loading => components::lists::FEED_STATE_LOADING,
+            FlatFeedState::Retrying => components::lists::FEED_STATE_RETRYING,
             FlatFeedState::Success => components::lists::FEED_STATE_SUCCESS,
         };
         let prop_value = AttrValue::Payload(PropPayload::Tup2((
@@ -252,6 +1288,174 @@ impl Ui {
             .is_ok());
     }
 
+    /// ### update_status_bar
+    ///
+    /// Update status bar with the current fetch progress
+    fn update_status_bar(&mut self) {
+        assert!(self
+            .app
+            .remount(Id::StatusBar, Box::new(self.model.get_status_bar()), vec![])
+            .is_ok());
+    }
+
+    /// ### remount_article_list
+    ///
+    /// Remount the article list of the selected feed, keeping the selected article and the focus
+    fn remount_article_list(&mut self) {
+        self.remount_article_list_at(None);
+    }
+
+    /// ### remount_article_list_at
+    ///
+    /// Remount the article list of the selected feed, as `remount_article_list` does,
+    /// selecting the article with `url` if it's still in the feed
+    fn remount_article_list_at(&mut self, url: Option<&str>) {
+        let name = self.selected_feed_name();
+        let selected = match self.app.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => Some(index),
+            _ => None,
+        };
+        let focus = matches!(
+            self.app.query(&Id::ArticleList, Attribute::Focus),
+            Ok(Some(AttrValue::Flag(true)))
+        );
+        let feed = match name.as_deref().and_then(|x| self.model.kiosk().get_feed(x)) {
+            Some(feed) => feed,
+            None => return,
+        };
+        let selected = url
+            .and_then(|url| feed.articles().position(|x| x.url == url))
+            .or(selected);
+        let articles = self.model.get_article_list(
+            name.as_deref().unwrap_or_default(),
+            feed,
+            self.model.max_article_name_len(),
+            selected,
+        );
+        assert!(self
+            .app
+            .remount(Id::ArticleList, Box::new(articles), vec![])
+            .is_ok());
+        // Remounting the article list makes it lose focus
+        if focus {
+            assert!(self.app.active(&Id::ArticleList).is_ok());
+        }
+    }
+
+    /// ### selected_feed_name
+    ///
+    /// Get the name of the feed selected in the feed list
+    fn selected_feed_name(&self) -> Option<String> {
+        match self.app.state(&Id::FeedList) {
+            Ok(State::One(StateValue::Usize(index))) => self
+                .model
+                .sorted_sources()
+                .get(index)
+                .map(|x| x.to_string()),
+            _ => None,
+        }
+    }
+
+    /// ### selected_all_feed_article
+    ///
+    /// If the aggregated feed is selected, get the url of its selected article, which moves as the sources
+    /// are fetched. Returns `None` if another feed is selected
+    fn selected_all_feed_article(&self) -> Option<Option<String>> {
+        let name = self.selected_feed_name()?;
+        if !self.model.kiosk().is_all_feed(name.as_str()) {
+            return None;
+        }
+        let feed = self.model.kiosk().get_feed(name.as_str())?;
+        Some(match self.app.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => feed.article(index).map(|x| x.url.clone()),
+            _ => None,
+        })
+    }
+
+    /// ### reorder_feed_list
+    ///
+    /// Sort the feed list again, if its order depends on the unread articles, keeping the selected feed and the focus
+    fn reorder_feed_list(&mut self) {
+        let name = match self.app.state(&Id::FeedList) {
+            Ok(State::One(StateValue::Usize(index))) => self
+                .model
+                .sorted_sources()
+                .get(index)
+                .map(|x| x.to_string()),
+            _ => None,
+        };
+        if !self.model.sort_unread_sources() {
+            return;
+        }
+        let index = name
+            .and_then(|name| self.model.sorted_sources().iter().position(|x| **x == name))
+            .unwrap_or(0);
+        let focus = matches!(
+            self.app.query(&Id::FeedList, Attribute::Focus),
+            Ok(Some(AttrValue::Flag(true)))
+        );
+        assert!(self
+            .app
+            .remount(
+                Id::FeedList,
+                Box::new(self.model.get_feed_list().selected(index)),
+                vec![]
+            )
+            .is_ok());
+        // Remounting the feed list makes it lose focus
+        if focus {
+            assert!(self.app.active(&Id::FeedList).is_ok());
+        }
+    }
+
+    /// ### remount_lists
+    ///
+    /// Remount feed list and article list after the sources have changed
+    fn remount_lists(&mut self) {
+        assert!(self
+            .app
+            .remount(Id::FeedList, Box::new(self.model.get_feed_list()), vec![])
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::ArticleList,
+                Box::new(self.model.get_empty_article_list()),
+                vec![]
+            )
+            .is_ok());
+        self.update_status_bar();
+        self.focus_feed_list();
+        self.init_article();
+    }
+
+    /// ### focus_feed_list
+    ///
+    /// Give focus to feed list, then give it back to popups, if any
+    fn focus_feed_list(&mut self) {
+        assert!(self.app.active(&Id::FeedList).is_ok());
+        for popup in [
+            Id::AddFeedPopup,
+            Id::CommandLine,
+            Id::FeedSwitcherPopup,
+            Id::RenameFeedPopup,
+            Id::SearchPopup,
+            Id::TagArticlePopup,
+            Id::TagFilterPopup,
+            Id::DeleteFeedPopup,
+            Id::FeedInfoPopup,
+            Id::HelpPopup,
+            Id::OpenWithPopup,
+            Id::SharePopup,
+            Id::QuitPopup,
+            Id::ErrorPopup,
+        ] {
+            if self.app.mounted(&popup) {
+                assert!(self.app.active(&popup).is_ok());
+            }
+        }
+    }
+
     // -- init
 
     /// ### init_article
@@ -259,72 +1463,200 @@ impl Ui {
     /// Initialize article list entries and article.
     /// This function should be called only if article list is empty
     fn init_article(&mut self) {
-        if let Some(source) = self.model.sorted_sources().get(0) {
-            if let Some(feed) = self.model.kiosk().get_feed(source.as_str()) {
-                assert!(self
-                    .app
-                    .remount(
-                        Id::ArticleList,
-                        Box::new(Model::get_article_list(
-                            feed,
-                            self.model.max_article_name_len()
-                        )),
-                        vec![]
-                    )
-                    .is_ok());
-                // Mount first article
-                if let Some(article) = feed.articles().next() {
-                    let (authors, date, link, summary, title) = Model::get_article_view(article);
-                    assert!(self
-                        .app
-                        .remount(Id::ArticleAuthors, Box::new(authors), vec![])
-                        .is_ok());
-                    assert!(self
-                        .app
-                        .remount(Id::ArticleDate, Box::new(date), vec![])
-                        .is_ok());
-                    assert!(self
-                        .app
-                        .remount(Id::ArticleLink, Box::new(link), vec![])
-                        .is_ok());
-                    assert!(self
-                        .app
-                        .remount(Id::ArticleSummary, Box::new(summary), vec![])
-                        .is_ok());
-                    assert!(self
-                        .app
-                        .remount(Id::ArticleTitle, Box::new(title), vec![])
-                        .is_ok());
-                }
+        let source = match self.model.sorted_sources().first() {
+            Some(source) => source.to_string(),
+            None => return,
+        };
+        if let Some(feed) = self.model.kiosk().get_feed(source.as_str()) {
+            assert!(self
+                .app
+                .remount(
+                    Id::ArticleList,
+                    Box::new(self.model.get_article_list(
+                        source.as_str(),
+                        feed,
+                        self.model.max_article_name_len(),
+                        None
+                    )),
+                    vec![]
+                )
+                .is_ok());
+            // Mount first article
+            if let Some(first) = self
+                .model
+                .visible_articles(source.as_str(), feed, None)
+                .first()
+                .cloned()
+            {
+                self.mount_article(source.as_str(), first, 0);
             }
         }
     }
 
+    /// ### restore_session
+    ///
+    /// Restore the feed, the article and the summary scroll saved on quit, once the feed of name
+    /// `name` has been loaded. The session is dropped if the user has already moved to another feed
+    fn restore_session(&mut self, name: &str) {
+        let session = match self.model.take_session(name) {
+            Some(session) => session,
+            None => return,
+        };
+        let moved = !matches!(
+            self.app.state(&Id::FeedList),
+            Ok(State::One(StateValue::Usize(0)))
+        );
+        let position = self
+            .model
+            .sorted_sources()
+            .iter()
+            .position(|x| x.as_str() == name);
+        let (feed, position) = match (self.model.kiosk().get_feed(name), position) {
+            (Some(feed), Some(position)) if !moved => (feed, position),
+            _ => return,
+        };
+        let article = session
+            .article
+            .as_deref()
+            .and_then(|url| feed.articles().position(|x| x.url == url))
+            .or_else(|| {
+                self.model
+                    .visible_articles(name, feed, None)
+                    .first()
+                    .cloned()
+            });
+        info!("restoring session on {}", name);
+        let articles =
+            self.model
+                .get_article_list(name, feed, self.model.max_article_name_len(), article);
+        assert!(self
+            .app
+            .remount(
+                Id::FeedList,
+                Box::new(self.model.get_feed_list().selected(position)),
+                vec![]
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(Id::ArticleList, Box::new(articles), vec![])
+            .is_ok());
+        if let Some(article) = article {
+            self.mount_article(name, article, session.scroll);
+        }
+        self.focus_feed_list();
+    }
+
+    /// ### mount_article
+    ///
+    /// Mount the article at `index` in the feed of `source`, scrolling its summary to `scroll`
+    fn mount_article(&mut self, source: &str, index: usize, scroll: usize) {
+        let article = self
+            .model
+            .kiosk()
+            .get_feed(source)
+            .and_then(|x| x.article(index));
+        let (authors, comments, date, link, summary, tags, title) = match article {
+            Some(article) => self.model.get_article_view(article),
+            None => return,
+        };
+        assert!(self
+            .app
+            .remount(Id::ArticleAuthors, Box::new(authors), vec![])
+            .is_ok());
+        assert!(self
+            .app
+            .remount(Id::ArticleDate, Box::new(date), vec![])
+            .is_ok());
+        assert!(self
+            .app
+            .remount(Id::ArticleLink, Box::new(link), vec![])
+            .is_ok());
+        assert!(self
+            .app
+            .remount(Id::ArticleComments, Box::new(comments), vec![])
+            .is_ok());
+        assert!(self
+            .app
+            .remount(Id::ArticleTags, Box::new(tags), vec![])
+            .is_ok());
+        assert!(self
+            .app
+            .remount(Id::ArticleSummary, Box::new(summary.scroll(scroll)), vec![])
+            .is_ok());
+        assert!(self
+            .app
+            .remount(Id::ArticleTitle, Box::new(title), vec![])
+            .is_ok());
+    }
+
     /// ### is_article_list_empty
     ///
     /// Returns whether article list is empty
     fn is_article_list_empty(&self) -> bool {
-        self.app
-            .query(&Id::ArticleList, Attribute::Content)
-            .ok()
-            .flatten()
-            .map(|x| x.unwrap_table().is_empty())
-            .unwrap_or(true)
+        // NOTE: the article list has no content attribute; its state is none when it has no rows
+        !matches!(self.app.state(&Id::ArticleList), Ok(State::One(_)))
+    }
+
+    /// ### load_script
+    ///
+    /// Load the script set in the configuration, reporting the errors
+    fn load_script(&mut self) {
+        let script = match self.config.scripting.file.as_deref().map(Script::load) {
+            Some(Ok(script)) => Some(script),
+            Some(Err(err)) => {
+                self.mount_error_popup(err);
+                None
+            }
+            None => None,
+        };
+        self.model.set_script(script);
+    }
+
+    /// ### report_source_errors
+    ///
+    /// Show the errors of the sources which couldn't be loaded from the configuration
+    fn report_source_errors(&mut self) {
+        for err in std::mem::take(&mut self.config.source_errors) {
+            self.mount_error_popup(err);
+        }
     }
 
     /// ### mount_error_popup
     ///
-    /// Mount error and give focus to it
+    /// Push error to the error queue and mount the error popup, giving focus to it
     fn mount_error_popup(&mut self, err: impl ToString) {
+        self.model.push_error(err);
+        if let Some(popup) = self.model.get_error_popup() {
+            assert!(self
+                .app
+                .remount(Id::ErrorPopup, Box::new(popup), vec![])
+                .is_ok());
+            assert!(self.app.active(&Id::ErrorPopup).is_ok());
+        }
+    }
+
+    /// ### mount_add_feed_popup
+    ///
+    /// Mount the popup to subscribe to the feed at `url`, so that the user can name it
+    fn mount_add_feed_popup(&mut self, url: String) {
         assert!(self
             .app
             .remount(
-                Id::ErrorPopup,
-                Box::new(ErrorPopup::new(err.to_string())),
+                Id::AddFeedPopup,
+                Box::new(AddFeedPopup::new(self.model.theme()).url(url.as_str())),
                 vec![]
             )
             .is_ok());
-        assert!(self.app.active(&Id::ErrorPopup).is_ok());
+        assert!(self.app.active(&Id::AddFeedPopup).is_ok());
+        assert!(self
+            .app
+            .attr(
+                &Id::GlobalListener,
+                Attribute::Custom(GLOBAL_LISTENER_LOCKED),
+                AttrValue::Flag(true)
+            )
+            .is_ok());
     }
 
     /// ### init_terminal
@@ -339,26 +1671,36 @@ impl Ui {
     ///
     /// Initialize application.
     /// Panics if it fails
-    fn init_application(model: &Model, tick: u64) -> Application<Id, Msg, NoUserEvent> {
-        let mut app = Application::init(
-            EventListenerCfg::default()
-                .default_input_listener(Duration::from_millis(tick))
-                .poll_timeout(Duration::from_millis(tick)),
-        );
+    fn init_application(
+        model: &Model,
+        config: &Config,
+        tick: u64,
+        busy: &Arc<AtomicBool>,
+    ) -> Application<Id, Msg, NoUserEvent> {
+        let mut app = Application::init(Self::listener_cfg(tick, busy));
         assert!(app
             .mount(Id::FeedList, Box::new(model.get_feed_list()), vec![])
             .is_ok());
         assert!(app
             .mount(
                 Id::ArticleList,
-                Box::new(Model::get_empty_article_list()),
+                Box::new(model.get_empty_article_list()),
                 vec![]
             )
             .is_ok());
+        assert!(app
+            .mount(Id::StatusBar, Box::new(model.get_status_bar()), vec![])
+            .is_ok());
+        assert!(app
+            .mount(Id::TabBar, Box::new(model.get_tab_bar()), vec![])
+            .is_ok());
         assert!(app
             .mount(
                 Id::GlobalListener,
-                Box::new(GlobalListener::default()),
+                Box::new(GlobalListener::new(
+                    model.keys().clone(),
+                    config.scripting.actions.clone()
+                )),
                 Self::subs(),
             )
             .is_ok());
@@ -366,32 +1708,38 @@ impl Ui {
         app
     }
 
+    /// ### listener_cfg
+    ///
+    /// Get the event listener configuration.
+    /// Input events are polled every `tick` ms, while the main loop waits for events up to
+    /// `IDLE_POLL_TIMEOUT`, unless `busy` is set
+    fn listener_cfg(tick: u64, busy: &Arc<AtomicBool>) -> EventListenerCfg<NoUserEvent> {
+        let tick = Duration::from_millis(tick);
+        EventListenerCfg::default()
+            .default_input_listener(tick)
+            .port(
+                Box::new(WakePort::new(Arc::clone(busy))),
+                FORCED_REDRAW_INTERVAL,
+            )
+            .poll_timeout(tick.max(IDLE_POLL_TIMEOUT))
+            .tick_interval(DATES_REFRESH_INTERVAL)
+    }
+
+    /// ### unlocked
+    ///
+    /// Subscription clause which forwards events to the global listener only if it is not locked
+    fn unlocked() -> SubClause {
+        SubClause::not(SubClause::HasAttrValue(
+            Attribute::Custom(GLOBAL_LISTENER_LOCKED),
+            AttrValue::Flag(true),
+        ))
+    }
+
     /// ### subs
     ///
-    /// global listener subscriptions
+    /// global listener subscriptions.
+    /// The global listener receives all the keyboard events, since keys are configurable
     fn subs() -> Vec<Sub<NoUserEvent>> {
-        vec![
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent {
-                    code: Key::Esc,
-                    modifiers: KeyModifiers::NONE,
-                }),
-                SubClause::Always,
-            ),
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent {
-                    code: Key::Char('r'),
-                    modifiers: KeyModifiers::CONTROL,
-                }),
-                SubClause::Always,
-            ),
-            Sub::new(
-                SubEventClause::Keyboard(KeyEvent {
-                    code: Key::Char('r'),
-                    modifiers: KeyModifiers::NONE,
-                }),
-                SubClause::Always,
-            ),
-        ]
+        vec![Sub::new(SubEventClause::Any, Self::unlocked())]
     }
 }
@@ -26,50 +26,205 @@
  * SOFTWARE.
  */
 use super::components::{
-    ArticleAuthors, ArticleDate, ArticleLink, ArticleList, ArticleSummary, ArticleTitle, FeedList,
-    QuitPopup,
+    AddFeedPopup, ArticleAuthors, ArticleComments, ArticleDate, ArticleEntry, ArticleLink,
+    ArticleList, ArticleSummary, ArticleTags, ArticleTitle, CommandLine, DeleteFeedPopup,
+    ErrorPopup, FeedInfoPopup, FeedList, FeedSwitcherPopup, FilterPopup, HelpPopup, OpenWithPopup,
+    PodcastResultsPopup, PodcastSearchPopup, QuitPopup, RenameFeedPopup, SearchPopup, SharePopup,
+    StatusBar, TabBar, TagArticlePopup, TagFilterPopup, GLOBAL_LISTENER_LOCKED, SUMMARY_PROP_LINKS,
 };
-use super::lib::FeedState;
-use super::{Id, Kiosk, Msg, Task};
+use super::lib::{
+    opener_entries, ArticleSort, Command, ErrorQueue, FeedState, FlatFeedState, OpenTarget,
+    RetryScheduler, Setting, TAG_FEED_PREFIX,
+};
+use super::{Id, Kiosk, Msg, Task, STATUS_MESSAGE_DURATION};
 
-use crate::feed::{Article, Feed};
+use crate::config::{
+    mute_feed, score_feed, AgeStyles, DatesConfig, FeedSort, FeedStyle, Highlights, HookEvent,
+    HooksConfig, KeyBindings, Keys, LayoutConfig, MaildirConfig, MuteRule, Opener, PushTarget,
+    ScoringRule, ShareTarget, Source, Tab, TerminalConfig, Theme, ThemeConfig, ThemePreset,
+    UrlsConfig, VideoConfig, Webhook,
+};
+use crate::feed::{Article, Feed, Podcast};
+use crate::helpers::file as file_helpers;
+use crate::helpers::fmt as fmt_helpers;
+use crate::helpers::hooks as hook_helpers;
+use crate::helpers::maildir as maildir_helpers;
 use crate::helpers::open as open_helpers;
 use crate::helpers::strings as str_helpers;
 use crate::helpers::ui as ui_helpers;
+use crate::helpers::urls as url_helpers;
+use crate::script::Script;
+use crate::store::{Session, StoreResult};
 use crate::Config;
 
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{Attribute as TermAttribute, Print, SetAttribute},
+};
+use log::warn;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, LinkedList};
+use std::io::{stdout, Write};
+use std::path::Path;
+use std::str::FromStr;
 use std::time::{Duration, Instant};
+use tuirealm::props::{PropPayload, PropValue};
 use tuirealm::terminal::TerminalBridge;
-use tuirealm::tui::layout::{Constraint, Direction, Layout};
+use tuirealm::tui::layout::{Constraint, Direction, Layout, Rect};
+use tuirealm::tui::style::Style;
 use tuirealm::tui::widgets::Clear;
 use tuirealm::{Application, AttrValue, Attribute, NoUserEvent, State, StateValue, Update, View};
 
+/// Amount of read-state changes which can be undone
+const UNDO_STACK_SIZE: usize = 32;
+/// How long to wait for the terminal to report its background color
+const BACKGROUND_QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
 pub struct Model {
+    article_filter: Option<ArticleFilter>,
+    /// Text searched in the article summary, kept when moving to another article
+    article_search: Option<String>,
+    /// Association between source name and its category
+    categories: HashMap<String, String>,
+    dates: DatesConfig,
+    errors: ErrorQueue,
+    /// Order of the sources in the feed list
+    feed_sort: FeedSort,
+    /// Insert day headers in the article list, when articles are sorted by date
+    group_by_day: bool,
+    hide_read: bool,
+    highlights: Highlights,
+    hooks: HooksConfig,
+    hyperlinks: bool,
+    keys: KeyBindings,
     kiosk: Kiosk,
+    layout: LayoutConfig,
+    maildir: MaildirConfig,
+    mute: Vec<MuteRule>,
+    /// While offline, sources are loaded from the store instead of being fetched
+    offline: bool,
+    openers: Vec<Opener>,
+    /// Sources pinned to the top of the feed list
+    pinned: HashSet<String>,
+    /// Podcasts found by the last search
+    podcasts: Vec<Podcast>,
+    push: Vec<PushTarget>,
+    reading_mode: bool,
+    retries: RetryScheduler,
     quit: bool,
+    scoring: Vec<ScoringRule>,
+    script: Option<Script>,
+    /// Session saved on the last quit, to restore once its feed is loaded
+    session: Option<Session>,
+    share: Vec<ShareTarget>,
+    sort: ArticleSort,
+    /// Position of each source in the configuration order
+    source_ranks: HashMap<String, usize>,
+    status_message: Option<(String, Instant)>,
+    /// Styles of the sources in the feed list, by source or category name
+    styles: HashMap<String, FeedStyle>,
+    /// Index of the selected tab
+    tab: usize,
+    /// Workspaces; the feed list shows only the sources of the selected one
+    tabs: Vec<Tab>,
+    /// Only the articles with this tag are displayed
+    tag_filter: Option<String>,
     last_redraw: Instant,
     redraw: bool,
     tasks: Vec<Task>,
     terminal: TerminalBridge,
+    /// Whether the terminal background is light; detected on startup only when the theme is `auto`
+    light_background: bool,
+    theme: Theme,
+    /// Styles of the unread articles by their age, if enabled
+    age_styles: Option<AgeStyles>,
+    /// Urls of the articles marked as read by each action, from the oldest
+    undo: Vec<Vec<String>>,
+    /// Urls of the articles marked as unread by undo, which mute rules don't mark as read again
+    undone: HashSet<String>,
+    /// Sources which had unread articles when the feed list has been sorted last
+    unread_sources: HashSet<String>,
+    urls: UrlsConfig,
+    video: VideoConfig,
+    webhooks: Vec<Webhook>,
+}
+
+/// ## ArticleFilter
+///
+/// Describes the filter applied to the article list
+struct ArticleFilter {
+    /// Article selected before filtering
+    previous: usize,
 }
 
 impl Model {
     /// ### new
     ///
     /// Instantiates a new `Model`
-    pub fn new(config: &Config, terminal: TerminalBridge) -> Self {
+    pub fn new(config: &Config, mut kiosk: Kiosk, terminal: TerminalBridge) -> Self {
         // Initialize kiosk
-        let mut kiosk = Kiosk::default();
         for name in config.sources.keys() {
-            kiosk.insert_feed(name, FeedState::Loading);
+            // NOTE: loading state is never saved into the store
+            let _ = kiosk.insert_feed(name, FeedState::Loading);
         }
+        let session = kiosk.session().unwrap_or_else(|err| {
+            warn!("could not load the saved session: {}", err);
+            None
+        });
+        // NOTE: the terminal must be queried before the input listener is started
+        let light_background =
+            config.theme.preset == ThemePreset::Auto && Self::detect_light_background();
         Self {
+            article_filter: None,
+            article_search: None,
+            categories: Self::source_categories(&config.sources),
+            dates: config.dates.clone(),
+            errors: ErrorQueue::default(),
+            feed_sort: config.feeds.sort,
+            group_by_day: config.articles.group_by_day,
+            hide_read: config.articles.hide_read,
+            highlights: config.highlights.clone(),
+            hooks: config.hooks.clone(),
+            hyperlinks: Self::hyperlinks_enabled(&config.terminal),
+            keys: config.keys.clone(),
             kiosk,
+            layout: config.layout.clone(),
+            maildir: config.maildir.clone(),
+            mute: config.mute.clone(),
+            offline: false,
+            openers: config.openers.clone(),
+            pinned: config.pinned_sources(),
+            podcasts: Vec::new(),
+            push: config.push.clone(),
+            reading_mode: false,
+            retries: RetryScheduler::new(config.retry.clone()),
             last_redraw: Instant::now(),
             quit: false,
             redraw: true,
+            scoring: config.scoring.clone(),
+            script: None,
+            session,
+            share: config.share.clone(),
+            sort: ArticleSort::by_score(config.articles.sort_by_score),
+            source_ranks: config.source_ranks(),
+            status_message: None,
+            styles: config.styles.clone(),
+            tab: 0,
+            tabs: config.tabs.clone(),
+            tag_filter: None,
             tasks: Vec::new(),
             terminal,
+            light_background,
+            theme: Theme::new(&config.theme, light_background),
+            age_styles: config.theme.age.clone(),
+            undo: Vec::new(),
+            undone: HashSet::new(),
+            unread_sources: HashSet::new(),
+            urls: config.urls.clone(),
+            video: config.video.clone(),
+            webhooks: config.webhooks.clone(),
         }
     }
 
@@ -95,7 +250,12 @@ impl Model {
     ///
     /// Get max article name length for the article list
     pub fn max_article_name_len(&self) -> usize {
-        (self.terminal_width() / 2) - 9 // 50 % - margin - 1
+        let width = self.terminal_width();
+        let lists_pane = match self.layout.is_vertical(width as u16) {
+            true => width,
+            false => width * self.layout.feeds_pane as usize / 100,
+        };
+        lists_pane.saturating_sub(9) // lists pane - margin - 1
     }
 
     /// ### force_redraw
@@ -112,6 +272,252 @@ impl Model {
         self.quit
     }
 
+    /// ### keys
+    ///
+    /// Returns reference to key bindings
+    pub fn keys(&self) -> &KeyBindings {
+        &self.keys
+    }
+
+    /// ### set_keys
+    ///
+    /// Replace key bindings
+    pub fn set_keys(&mut self, keys: KeyBindings) {
+        self.keys = keys;
+    }
+
+    /// ### set_layout
+    ///
+    /// Replace layout configuration
+    pub fn set_layout(&mut self, layout: LayoutConfig) {
+        self.layout = layout;
+    }
+
+    /// ### set_dates
+    ///
+    /// Replace dates configuration
+    pub fn set_dates(&mut self, dates: DatesConfig) {
+        self.dates = dates;
+    }
+
+    /// ### set_mute
+    ///
+    /// Replace mute rules; they're applied to the next fetched feeds
+    pub fn set_mute(&mut self, mute: Vec<MuteRule>) {
+        self.mute = mute;
+    }
+
+    /// ### set_openers
+    ///
+    /// Replace the commands listed in the "open with" menu
+    pub fn set_openers(&mut self, openers: Vec<Opener>) {
+        self.openers = openers;
+    }
+
+    /// ### set_share
+    ///
+    /// Replace the commands listed in the share menu
+    pub fn set_share(&mut self, share: Vec<ShareTarget>) {
+        self.share = share;
+    }
+
+    /// ### set_terminal
+    ///
+    /// Replace terminal configuration
+    pub fn set_terminal(&mut self, terminal: &TerminalConfig) {
+        self.hyperlinks = Self::hyperlinks_enabled(terminal);
+    }
+
+    /// ### theme
+    ///
+    /// Returns the colors of the interface
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// ### set_theme
+    ///
+    /// Replace the theme; components are colored with it once they're remounted.
+    /// The terminal background can't be detected anymore, so `auto` uses the one detected on startup
+    pub fn set_theme(&mut self, theme: &ThemeConfig) {
+        self.theme = Theme::new(theme, self.light_background);
+        self.age_styles = theme.age.clone();
+    }
+
+    /// ### set_highlights
+    ///
+    /// Replace the highlighted words
+    pub fn set_highlights(&mut self, highlights: Highlights) {
+        self.highlights = highlights;
+    }
+
+    /// ### set_styles
+    ///
+    /// Replace the styles of the sources in the feed list
+    pub fn set_styles(&mut self, styles: HashMap<String, FeedStyle>) {
+        self.styles = styles;
+    }
+
+    /// ### set_hooks
+    ///
+    /// Replace the commands run on lifecycle events
+    pub fn set_hooks(&mut self, hooks: HooksConfig) {
+        self.hooks = hooks;
+    }
+
+    /// ### set_script
+    ///
+    /// Replace the script filtering and scoring articles
+    pub fn set_script(&mut self, script: Option<Script>) {
+        self.script = script;
+    }
+
+    /// ### set_urls
+    ///
+    /// Replace urls configuration
+    pub fn set_urls(&mut self, urls: UrlsConfig) {
+        self.urls = urls;
+    }
+
+    /// ### set_video
+    ///
+    /// Replace video configuration
+    pub fn set_video(&mut self, video: VideoConfig) {
+        self.video = video;
+    }
+
+    /// ### set_webhooks
+    ///
+    /// Replace the webhooks the new articles are posted to
+    pub fn set_webhooks(&mut self, webhooks: Vec<Webhook>) {
+        self.webhooks = webhooks;
+    }
+
+    /// ### set_maildir
+    ///
+    /// Replace the Maildir the new articles are delivered into
+    pub fn set_maildir(&mut self, maildir: MaildirConfig) {
+        self.maildir = maildir;
+    }
+
+    /// ### set_push
+    ///
+    /// Replace the push notification services the new articles are sent to
+    pub fn set_push(&mut self, push: Vec<PushTarget>) {
+        self.push = push;
+    }
+
+    /// ### set_hide_read
+    ///
+    /// Set whether to hide read articles
+    pub fn set_hide_read(&mut self, hide_read: bool) {
+        self.hide_read = hide_read;
+    }
+
+    /// ### offline
+    ///
+    /// Returns whether tuifeed is offline
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// ### set_offline
+    ///
+    /// Set whether tuifeed is offline
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// ### set_tabs
+    ///
+    /// Replace the tabs and the categories of the `sources`; the selected tab is kept if it still exists
+    pub fn set_tabs(&mut self, tabs: Vec<Tab>, sources: &HashMap<String, Source>) {
+        if self.tab >= tabs.len() {
+            self.tab = 0;
+        }
+        self.tabs = tabs;
+        self.categories = Self::source_categories(sources);
+    }
+
+    /// ### set_feed_sort
+    ///
+    /// Replace the order of the sources in the feed list, the position of each source in the configuration
+    /// and the sources pinned to the top
+    pub fn set_feed_sort(
+        &mut self,
+        sort: FeedSort,
+        ranks: HashMap<String, usize>,
+        pinned: HashSet<String>,
+    ) {
+        self.feed_sort = sort;
+        self.source_ranks = ranks;
+        self.pinned = pinned;
+    }
+
+    /// ### sort_unread_sources
+    ///
+    /// Update the sources with unread articles, which come first in the feed list when sorted by unread.
+    /// They're not updated as articles are read, so that the feed list doesn't move under the cursor.
+    /// Returns whether the order of the feed list has changed
+    pub fn sort_unread_sources(&mut self) -> bool {
+        if self.feed_sort != FeedSort::UnreadFirst {
+            return false;
+        }
+        let unread: HashSet<String> = self
+            .kiosk
+            .sources()
+            .into_iter()
+            .filter(|name| {
+                self.kiosk
+                    .get_feed(name.as_str())
+                    .map(|feed| feed.articles().any(|x| !self.kiosk.is_read(x.url.as_str())))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+        let changed = unread != self.unread_sources;
+        self.unread_sources = unread;
+        changed
+    }
+
+    /// ### set_scoring
+    ///
+    /// Replace scoring rules; they're applied to the next fetched feeds
+    pub fn set_scoring(&mut self, scoring: Vec<ScoringRule>) {
+        self.scoring = scoring;
+    }
+
+    /// ### set_sort
+    ///
+    /// Set the order of the articles in the article list
+    pub fn set_sort(&mut self, sort: ArticleSort) {
+        self.sort = sort;
+    }
+
+    /// ### set_status_message
+    ///
+    /// Set the message to display in the status bar for a few seconds
+    pub fn set_status_message(&mut self, message: Option<String>) {
+        self.status_message = message.map(|x| (x, Instant::now()));
+    }
+
+    /// ### status_message_expired
+    ///
+    /// Returns whether there is a status message which has been displayed for long enough
+    pub fn status_message_expired(&self) -> bool {
+        self.status_message
+            .as_ref()
+            .map(|(_, since)| since.elapsed() >= STATUS_MESSAGE_DURATION)
+            .unwrap_or(false)
+    }
+
+    /// ### filtering
+    ///
+    /// Returns whether the article list is being filtered
+    pub fn filtering(&self) -> bool {
+        self.article_filter.is_some()
+    }
+
     /// ### kiosk
     ///
     /// Returns reference to kiosk
@@ -119,20 +525,304 @@ impl Model {
         &self.kiosk
     }
 
+    /// ### reload_read_state
+    ///
+    /// Reload the read articles, after they've been changed by sync
+    pub fn reload_read_state(&mut self) {
+        let result = self.kiosk.reload_read();
+        self.report_store_error(result);
+    }
+
     /// ### update_source
     ///
-    /// Update source in kiosk
+    /// Update source in kiosk. Articles matching a mute rule are discarded or marked as read,
+    /// then the remaining ones are scored and passed to the script.
+    /// Articles marked as read by mute rules can be undone, as any other read-state change
     pub fn update_source(&mut self, name: &str, state: FeedState) {
-        self.kiosk.insert_feed(name.to_string(), state);
+        let mut read: Vec<String> = Vec::new();
+        let state = match state {
+            FeedState::Success(mut feed) => {
+                read = mute_feed(self.mute.as_slice(), name, &mut feed);
+                read.retain(|x| !self.kiosk.is_read(x) && !self.undone.contains(x));
+                score_feed(self.scoring.as_slice(), name, &mut feed);
+                if let Some(script) = self.script.as_ref() {
+                    script.process_feed(name, &mut feed);
+                }
+                self.notify_new_articles(name, &feed, read.as_slice());
+                FeedState::Success(feed)
+            }
+            state => state,
+        };
+        let result = self.kiosk.insert_feed(name, state);
+        self.report_store_error(result);
+        // NOTE: articles can be marked as read only once they're in the store
+        for url in read.iter() {
+            let result = self.kiosk.mark_read(url);
+            self.report_store_error(result);
+        }
+        if !read.is_empty() {
+            self.push_undo(read);
+        }
+    }
+
+    /// ### notify_new_articles
+    ///
+    /// Post the new articles of `feed`, fetched for source `name`, to the webhooks and push them to the
+    /// services they match, then run the new article hook on them and deliver them into the Maildir.
+    /// Muted articles, marked as `read`, are skipped. Nothing is notified on the first fetch of a source
+    fn notify_new_articles(&mut self, name: &str, feed: &Feed, read: &[String]) {
+        let hook = self.hooks.command(HookEvent::NewArticle).is_some();
+        if self.webhooks.is_empty() && self.push.is_empty() && !hook && self.maildir.dir.is_none() {
+            return;
+        }
+        let known = match self.kiosk.article_urls(name) {
+            Ok(known) => known,
+            Err(err) => {
+                self.report_store_error(Err(err));
+                return;
+            }
+        };
+        if known.is_empty() {
+            return;
+        }
+        let new: Vec<&Article> = feed
+            .articles()
+            .filter(|x| !known.contains(&x.url) && !read.contains(&x.url))
+            .collect();
+        if hook {
+            for article in new.iter() {
+                self.run_article_hook(HookEvent::NewArticle, name, article);
+            }
+        }
+        if let Some(dir) = self.maildir.dir.as_deref() {
+            for article in new.iter() {
+                if let Err(err) = maildir_helpers::deliver_article(dir, name, article) {
+                    warn!("could not deliver article into maildir: {}", err);
+                }
+            }
+        }
+        let tasks: Vec<Task> = new
+            .into_iter()
+            .flat_map(|article| {
+                self.webhooks
+                    .iter()
+                    .filter(|x| x.matches(name, article))
+                    .map(|x| {
+                        Task::PostWebhook(
+                            x.url.clone(),
+                            name.to_string(),
+                            Box::new(article.clone()),
+                        )
+                    })
+                    .chain(
+                        self.push
+                            .iter()
+                            .filter(|x| x.matches(name, article))
+                            .map(|x| {
+                                Task::Push(x.clone(), name.to_string(), Box::new(article.clone()))
+                            }),
+                    )
+            })
+            .collect();
+        for task in tasks.into_iter() {
+            self.task(task);
+        }
+    }
+
+    /// ### run_article_hook
+    ///
+    /// Run the hook for `event` on the `article` of source `name`, if configured
+    fn run_article_hook(&self, event: HookEvent, name: &str, article: &Article) {
+        if let Some(command) = self.hooks.command(event) {
+            if let Err(err) = hook_helpers::run_article_hook(command, event.name(), name, article) {
+                warn!("could not run {} hook: {}", event.name(), err);
+            }
+        }
+    }
+
+    /// ### run_refresh_hook
+    ///
+    /// Run the refresh complete hook, once `sources` sources have been fetched
+    pub fn run_refresh_hook(&self, sources: usize) {
+        let event = HookEvent::RefreshComplete;
+        if let Some(command) = self.hooks.command(event) {
+            if let Err(err) = hook_helpers::run_refresh_hook(command, event.name(), sources) {
+                warn!("could not run {} hook: {}", event.name(), err);
+            }
+        }
+    }
+
+    /// ### run_selected_article_hook
+    ///
+    /// Run the hook for `event` on the selected article
+    fn run_selected_article_hook(&self, view: &mut View<Id, Msg, NoUserEvent>, event: HookEvent) {
+        if self.hooks.command(event).is_none() {
+            return;
+        }
+        if let (Some(name), Some(article)) = (
+            self.get_selected_source(view),
+            self.get_selected_article(view),
+        ) {
+            self.run_article_hook(event, name.as_str(), article);
+        }
+    }
+
+    /// ### load_source
+    ///
+    /// Load the feed of source `name` from the store, muting and scoring its articles as if it were fetched.
+    /// Returns whether the feed has been loaded; sources which have never been fetched are not
+    pub fn load_source(&mut self, name: &str) -> bool {
+        match self.kiosk.load_feed(name) {
+            Ok(Some(mut feed)) => {
+                // NOTE: articles to mark as read have already been marked by whoever fetched them
+                mute_feed(self.mute.as_slice(), name, &mut feed);
+                score_feed(self.scoring.as_slice(), name, &mut feed);
+                if let Some(script) = self.script.as_ref() {
+                    script.process_feed(name, &mut feed);
+                }
+                self.kiosk.insert_stored_feed(name, feed);
+                true
+            }
+            Ok(None) => false,
+            Err(err) => {
+                self.report_store_error(Err(err));
+                false
+            }
+        }
+    }
+
+    /// ### source_is_fresh
+    ///
+    /// Returns whether `source` named `name` has been fetched more recently than its refresh interval
+    pub fn source_is_fresh(&mut self, name: &str, source: &Source) -> bool {
+        match self.kiosk.last_fetch(name) {
+            Ok(last_fetch) => source.is_fresh(last_fetch, Local::now()),
+            Err(err) => {
+                self.report_store_error(Err(err));
+                false
+            }
+        }
+    }
+
+    /// ### daemon_running
+    ///
+    /// Returns whether the daemon, which fetches sources every `interval`, has recently reported to be running
+    pub fn daemon_running(&self, interval: Duration) -> bool {
+        match self.kiosk.daemon_heartbeat() {
+            Ok(Some(heartbeat)) => Local::now()
+                .signed_duration_since(heartbeat)
+                .to_std()
+                .map(|x| x < interval * 2)
+                .unwrap_or(true),
+            _ => false,
+        }
+    }
+
+    /// ### take_session
+    ///
+    /// Take the session saved on the last quit, if it was on source `name`
+    pub fn take_session(&mut self, name: &str) -> Option<Session> {
+        match self.session.as_ref() {
+            Some(session) if session.feed == name => self.session.take(),
+            _ => None,
+        }
+    }
+
+    /// ### retries
+    ///
+    /// Get a mutable reference to the retry scheduler
+    pub fn retries(&mut self) -> &mut RetryScheduler {
+        &mut self.retries
+    }
+
+    /// ### remove_source
+    ///
+    /// Remove source from kiosk
+    pub fn remove_source(&mut self, name: &str) {
+        self.retries.cancel(name);
+        let result = self.kiosk.remove_feed(name);
+        self.report_store_error(result);
+    }
+
+    /// ### rename_source
+    ///
+    /// Rename source in kiosk
+    pub fn rename_source(&mut self, name: &str, new_name: &str) {
+        self.retries.rename(name, new_name);
+        let result = self.kiosk.rename_feed(name, new_name);
+        self.report_store_error(result);
+    }
+
+    /// ### set_note
+    ///
+    /// Replace the note the user attached to the article with `url` and report it in the status bar
+    pub fn set_note(&mut self, url: &str, note: &str) {
+        let result = self.kiosk.set_note(url, note);
+        self.report_store_error(result);
+        self.set_status_message(Some(String::from(match note.trim().is_empty() {
+            true => "Note removed",
+            false => "Note saved",
+        })));
     }
 
     /// ### sorted_sources
     ///
-    /// Get sorted sources from kiosk
+    /// Get sorted sources from kiosk, in the selected tab
     pub fn sorted_sources(&self) -> Vec<&String> {
-        let mut sources = self.kiosk().sources();
-        sources.sort();
+        let mut sources: Vec<&String> = self
+            .kiosk()
+            .sources()
+            .into_iter()
+            .filter(|x| self.in_tab(x.as_str()))
+            .collect();
+        sources.sort_by(|a, b| self.compare_sources(a, b));
+        sources
+    }
+
+    /// ### compare_sources
+    ///
+    /// Compare the sources `a` and `b` by their order in the feed list: the aggregated feed comes first,
+    /// then pinned sources, then sources are sorted by the feed sort; ties are broken by name
+    fn compare_sources(&self, a: &str, b: &str) -> Ordering {
+        let all = self.kiosk.is_all_feed(b).cmp(&self.kiosk.is_all_feed(a));
+        let pinned = self.pinned.contains(b).cmp(&self.pinned.contains(a));
+        let order = match self.feed_sort {
+            FeedSort::Alphabetical => Ordering::Equal,
+            FeedSort::Config => {
+                let rank = |x: &str| self.source_ranks.get(x).copied().unwrap_or(usize::MAX);
+                rank(a).cmp(&rank(b))
+            }
+            FeedSort::UnreadFirst => self
+                .unread_sources
+                .contains(b)
+                .cmp(&self.unread_sources.contains(a)),
+        };
+        all.then(pinned).then(order).then_with(|| a.cmp(b))
+    }
+
+    /// ### in_tab
+    ///
+    /// Returns whether the source `name` is displayed in the selected tab; without tabs all sources are
+    fn in_tab(&self, name: &str) -> bool {
+        self.tabs
+            .get(self.tab)
+            .map(|tab| tab.contains(self.categories.get(name).map(|x| x.as_str())))
+            .unwrap_or(true)
+    }
+
+    /// ### source_categories
+    ///
+    /// Get the association between the name of each source and its category
+    fn source_categories(sources: &HashMap<String, Source>) -> HashMap<String, String> {
         sources
+            .iter()
+            .filter_map(|(name, source)| {
+                source
+                    .category
+                    .as_ref()
+                    .map(|category| (name.clone(), category.clone()))
+            })
+            .collect()
     }
 
     /// ### get_tasks
@@ -158,46 +848,109 @@ impl Model {
         if self.redraw {
             self.redraw = false;
             self.last_redraw = Instant::now();
+            let mut link_area = None;
             assert!(self
                 .terminal
                 .raw_mut()
                 .draw(|f| {
-                    let chunks = Layout::default()
-                        .direction(Direction::Horizontal)
-                        .margin(1)
+                    // Tab bar is hidden in reading mode
+                    let tabs_len = match self.tabs.is_empty() || self.reading_mode {
+                        true => 0,
+                        false => 1,
+                    };
+                    let main_chunks = Layout::default()
+                        .direction(Direction::Vertical)
                         .constraints(
                             [
-                                Constraint::Percentage(50), // Lists
-                                Constraint::Percentage(50), // Article
+                                Constraint::Length(tabs_len), // Tab bar
+                                Constraint::Min(3),           // Body
+                                Constraint::Length(1),        // Status bar
                             ]
                             .as_ref(),
                         )
                         .split(f.size());
+                    if tabs_len > 0 {
+                        let tab_bar = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .horizontal_margin(3)
+                            .constraints([Constraint::Percentage(100)].as_ref())
+                            .split(main_chunks[0]);
+                        app.view(&Id::TabBar, f, tab_bar[0]);
+                    }
+                    let article_area = if self.reading_mode {
+                        // Article takes the whole terminal in reading mode
+                        Layout::default()
+                            .direction(Direction::Horizontal)
+                            .margin(1)
+                            .horizontal_margin(4)
+                            .constraints([Constraint::Percentage(100)].as_ref())
+                            .split(main_chunks[1])[0]
+                    } else {
+                        let feeds_pane = self.layout.feeds_pane;
+                        // Lists on top of article in vertical layout; on the left otherwise
+                        let direction = match self.layout.is_vertical(f.size().width) {
+                            true => Direction::Vertical,
+                            false => Direction::Horizontal,
+                        };
+                        let chunks = Layout::default()
+                            .direction(direction)
+                            .margin(1)
+                            .constraints(
+                                [
+                                    Constraint::Percentage(feeds_pane),       // Lists
+                                    Constraint::Percentage(100 - feeds_pane), // Article
+                                ]
+                                .as_ref(),
+                            )
+                            .split(main_chunks[1]);
 
-                    // Render layout only if kiosk has been initialized
-                    // -- list
-                    let list_chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .horizontal_margin(2)
-                        .constraints(
-                            [Constraint::Percentage(30), Constraint::Percentage(70)].as_ref(),
-                        )
-                        .split(chunks[0]);
-                    app.view(&Id::FeedList, f, list_chunks[0]);
-                    app.view(&Id::ArticleList, f, list_chunks[1]);
+                        // Render layout only if kiosk has been initialized
+                        // -- list
+                        let list_chunks = Layout::default()
+                            .direction(Direction::Vertical)
+                            .horizontal_margin(2)
+                            .constraints(
+                                [
+                                    Constraint::Percentage(self.layout.list_ratio),
+                                    Constraint::Percentage(100 - self.layout.list_ratio),
+                                ]
+                                .as_ref(),
+                            )
+                            .split(chunks[0]);
+                        app.view(&Id::FeedList, f, list_chunks[0]);
+                        app.view(&Id::ArticleList, f, list_chunks[1]);
+                        // -- filter input at the bottom of the article list
+                        if app.mounted(&Id::FilterPopup) {
+                            let mut popup = list_chunks[1];
+                            popup.y = popup.bottom().saturating_sub(3);
+                            popup.height = popup.height.min(3);
+                            f.render_widget(Clear, popup);
+                            app.view(&Id::FilterPopup, f, popup);
+                        }
+                        chunks[1]
+                    };
                     // -- article
+                    // Optional rows are displayed only if they have a text
+                    let row_len = |id: &Id| match app.query(id, Attribute::Text) {
+                        Ok(Some(AttrValue::String(x))) if !x.is_empty() => 1,
+                        _ => 0,
+                    };
+                    let comments_len = row_len(&Id::ArticleComments);
+                    let tags_len = row_len(&Id::ArticleTags);
                     let article_chunks = Layout::default()
                         .direction(Direction::Vertical)
                         .constraints(
                             [
-                                Constraint::Length(3), // Title
-                                Constraint::Length(1), // Authors + date
-                                Constraint::Min(6),    // Summary
-                                Constraint::Length(1), // Link
+                                Constraint::Length(3),            // Title
+                                Constraint::Length(1),            // Authors + date
+                                Constraint::Length(tags_len),     // Tags
+                                Constraint::Min(6),               // Summary
+                                Constraint::Length(1),            // Link
+                                Constraint::Length(comments_len), // Comments
                             ]
                             .as_ref(),
                         )
-                        .split(chunks[1]);
+                        .split(article_area);
                     let second_article_row = Layout::default()
                         .direction(Direction::Horizontal)
                         .constraints(
@@ -207,9 +960,99 @@ impl Model {
                     app.view(&Id::ArticleTitle, f, article_chunks[0]);
                     app.view(&Id::ArticleAuthors, f, second_article_row[0]);
                     app.view(&Id::ArticleDate, f, second_article_row[1]);
-                    app.view(&Id::ArticleSummary, f, article_chunks[2]);
-                    app.view(&Id::ArticleLink, f, article_chunks[3]);
+                    if tags_len > 0 {
+                        app.view(&Id::ArticleTags, f, article_chunks[2]);
+                    }
+                    app.view(&Id::ArticleSummary, f, article_chunks[3]);
+                    // -- search input at the bottom of the summary
+                    if app.mounted(&Id::SearchPopup) {
+                        let mut popup = article_chunks[3];
+                        popup.y = popup.bottom().saturating_sub(3);
+                        popup.height = popup.height.min(3);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::SearchPopup, f, popup);
+                    }
+                    app.view(&Id::ArticleLink, f, article_chunks[4]);
+                    if comments_len > 0 {
+                        app.view(&Id::ArticleComments, f, article_chunks[5]);
+                    }
+                    link_area = Some(article_chunks[4]);
+                    // -- status bar
+                    let status_bar = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .horizontal_margin(3)
+                        .constraints([Constraint::Percentage(100)].as_ref())
+                        .split(main_chunks[2]);
+                    app.view(&Id::StatusBar, f, status_bar[0]);
                     // -- popups
+                    if app.mounted(&Id::AddFeedPopup) {
+                        let popup = ui_helpers::draw_area_in_rows(f.size(), 50, 6);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::AddFeedPopup, f, popup);
+                    }
+                    if app.mounted(&Id::CommandLine) {
+                        let size = f.size();
+                        let height = size.height.min(3);
+                        let popup =
+                            Rect::new(size.x, size.y + size.height - height, size.width, height);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::CommandLine, f, popup);
+                    }
+                    if app.mounted(&Id::FeedSwitcherPopup) {
+                        let popup = ui_helpers::draw_area_in_rows(f.size(), 50, 14);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::FeedSwitcherPopup, f, popup);
+                    }
+                    if app.mounted(&Id::TagFilterPopup) {
+                        let popup = ui_helpers::draw_area_in_rows(f.size(), 50, 3);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::TagFilterPopup, f, popup);
+                    }
+                    if app.mounted(&Id::TagArticlePopup) {
+                        let popup = ui_helpers::draw_area_in_rows(f.size(), 50, 3);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::TagArticlePopup, f, popup);
+                    }
+                    if app.mounted(&Id::RenameFeedPopup) {
+                        let popup = ui_helpers::draw_area_in_rows(f.size(), 50, 3);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::RenameFeedPopup, f, popup);
+                    }
+                    if app.mounted(&Id::DeleteFeedPopup) {
+                        let popup = ui_helpers::draw_area_in(f.size(), 30, 10);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::DeleteFeedPopup, f, popup);
+                    }
+                    if app.mounted(&Id::OpenWithPopup) {
+                        let popup = ui_helpers::draw_area_in(f.size(), 70, 50);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::OpenWithPopup, f, popup);
+                    }
+                    if app.mounted(&Id::PodcastSearchPopup) {
+                        let popup = ui_helpers::draw_area_in_rows(f.size(), 50, 3);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::PodcastSearchPopup, f, popup);
+                    }
+                    if app.mounted(&Id::PodcastResultsPopup) {
+                        let popup = ui_helpers::draw_area_in(f.size(), 60, 50);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::PodcastResultsPopup, f, popup);
+                    }
+                    if app.mounted(&Id::SharePopup) {
+                        let popup = ui_helpers::draw_area_in(f.size(), 60, 40);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::SharePopup, f, popup);
+                    }
+                    if app.mounted(&Id::FeedInfoPopup) {
+                        let popup = ui_helpers::draw_area_in(f.size(), 60, 40);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::FeedInfoPopup, f, popup);
+                    }
+                    if app.mounted(&Id::HelpPopup) {
+                        let popup = ui_helpers::draw_area_in(f.size(), 60, 80);
+                        f.render_widget(Clear, popup);
+                        app.view(&Id::HelpPopup, f, popup);
+                    }
                     if app.mounted(&Id::QuitPopup) {
                         let popup = ui_helpers::draw_area_in(f.size(), 30, 10);
                         f.render_widget(Clear, popup);
@@ -221,60 +1064,498 @@ impl Model {
                     }
                 })
                 .is_ok());
+            if let Some(area) = link_area {
+                self.draw_hyperlinks(app, area);
+            }
+        }
+    }
+
+    /// ### draw_hyperlinks
+    ///
+    /// Draw the article link, over `area`, and the links displayed in the summary again as OSC 8 hyperlinks,
+    /// so that they can be clicked; where hyperlinks are disabled, links are opened by their number.
+    /// Tui can't render escape sequences, so links are written straight to stdout after the frame has been drawn
+    fn draw_hyperlinks(&self, app: &Application<Id, Msg, NoUserEvent>, area: Rect) {
+        let popups = [
+            Id::AddFeedPopup,
+            Id::CommandLine,
+            Id::DeleteFeedPopup,
+            Id::ErrorPopup,
+            Id::FeedInfoPopup,
+            Id::FeedSwitcherPopup,
+            Id::FilterPopup,
+            Id::HelpPopup,
+            Id::OpenWithPopup,
+            Id::PodcastResultsPopup,
+            Id::PodcastSearchPopup,
+            Id::QuitPopup,
+            Id::RenameFeedPopup,
+            Id::SearchPopup,
+            Id::SharePopup,
+            Id::TagArticlePopup,
+            Id::TagFilterPopup,
+        ];
+        if !self.hyperlinks || popups.iter().any(|x| app.mounted(x)) {
+            return;
+        }
+        let mut out = stdout();
+        if let Ok(Some(AttrValue::String(url))) = app.query(&Id::ArticleLink, Attribute::Text) {
+            if !url.is_empty() && area.height > 0 {
+                let text = str_helpers::truncate_string_at(url.as_str(), area.width as usize);
+                let _ = queue!(
+                    out,
+                    MoveTo(area.x, area.y),
+                    SetAttribute(TermAttribute::Bold),
+                    Print(ui_helpers::hyperlink(url.as_str(), text)),
+                    SetAttribute(TermAttribute::Reset)
+                );
+            }
         }
+        let links = match app.query(&Id::ArticleSummary, Attribute::Custom(SUMMARY_PROP_LINKS)) {
+            Ok(Some(AttrValue::Payload(PropPayload::Linked(links)))) => links,
+            _ => LinkedList::new(),
+        };
+        for link in links {
+            if let PropPayload::Tup4((
+                PropValue::U16(x),
+                PropValue::U16(y),
+                PropValue::Str(marker),
+                PropValue::Str(url),
+            )) = link
+            {
+                let _ = queue!(
+                    out,
+                    MoveTo(x, y),
+                    Print(ui_helpers::hyperlink(url.as_str(), marker.as_str()))
+                );
+            }
+        }
+        let _ = out.flush();
+    }
+
+    /// ### hyperlinks_enabled
+    ///
+    /// Returns whether hyperlinks are enabled in configuration and supported by the current terminal
+    fn hyperlinks_enabled(terminal: &TerminalConfig) -> bool {
+        terminal.hyperlinks
+            && ui_helpers::term_supports_hyperlinks(
+                std::env::var("TERM").unwrap_or_default().as_str(),
+            )
+    }
+
+    /// ### detect_light_background
+    ///
+    /// Returns whether the terminal background is light, asking the terminal for its color
+    /// or, if it doesn't answer, reading the COLORFGBG environment variable.
+    /// The background is assumed to be dark if unknown
+    fn detect_light_background() -> bool {
+        ui_helpers::query_background_color(BACKGROUND_QUERY_TIMEOUT)
+            .map(ui_helpers::is_light_color)
+            .or_else(|| {
+                std::env::var("COLORFGBG")
+                    .ok()
+                    .and_then(|x| ui_helpers::colorfgbg_is_light(x.as_str()))
+            })
+            .unwrap_or(false)
     }
 
     /// ### update_article_view
     ///
     /// Update article into the view
     pub fn get_article_view(
+        &self,
         article: &Article,
     ) -> (
         ArticleAuthors,
+        ArticleComments,
         ArticleDate,
         ArticleLink,
         ArticleSummary,
+        ArticleTags,
         ArticleTitle,
     ) {
         (
-            ArticleAuthors::new(article.authors.as_ref()),
-            ArticleDate::new(article.date),
-            ArticleLink::new(article.url.as_str()),
-            ArticleSummary::new(article.summary.as_str()),
-            ArticleTitle::new(article.title.as_deref().unwrap_or("")),
+            ArticleAuthors::new(article.authors.as_ref(), &self.theme),
+            ArticleComments::new(
+                article
+                    .comments
+                    .as_deref()
+                    .map(|x| self.urls.clean(x))
+                    .as_deref(),
+                &self.theme,
+            ),
+            ArticleDate::new(
+                article
+                    .date
+                    .map(|x| self.format_date(x, self.dates.date_format.as_str(), false))
+                    .into_iter()
+                    .chain(std::iter::once(fmt_helpers::format_reading_time(
+                        article.summary.as_str(),
+                    )))
+                    .collect::<Vec<String>>()
+                    .join(" · "),
+                &self.theme,
+            ),
+            ArticleLink::new(self.urls.clean(article.url.as_str()).as_str()),
+            ArticleSummary::new(
+                article.summary.as_str(),
+                article
+                    .links
+                    .iter()
+                    .map(|x| self.urls.clean(x))
+                    .collect::<Vec<String>>()
+                    .as_slice(),
+                &self.highlights,
+                self.keys.clone(),
+                &self.theme,
+            )
+            .search(self.article_search.as_deref()),
+            ArticleTags::new(
+                article.categories.as_slice(),
+                self.kiosk.get_tags(article.url.as_str()),
+                &self.theme,
+            ),
+            ArticleTitle::new(article.title.as_deref().unwrap_or(""), &self.theme),
         )
     }
 
     /// ### update_article_list
     ///
-    /// Update the current article list
-    pub fn get_article_list(feed: &Feed, max_title_len: usize) -> ArticleList {
-        let articles: Vec<String> = feed
-            .articles()
-            .map(|x| {
-                x.title
-                    .as_ref()
-                    .map(|x| str_helpers::elide_string_at(x.as_str(), max_title_len))
-            })
-            .flatten()
-            .collect();
-        ArticleList::new(articles.as_slice())
+    /// Update the current article list; each row is `width` columns wide.
+    /// The `selected` article is selected and always displayed, even if it should be hidden
+    pub fn get_article_list(
+        &self,
+        name: &str,
+        feed: &Feed,
+        width: usize,
+        selected: Option<usize>,
+    ) -> ArticleList {
+        let indexes = self.visible_articles(name, feed, selected);
+        let articles = self.make_article_rows(name, feed, indexes.as_slice(), width);
+        // Sources are listed only in the aggregated feed
+        let styles = match self.kiosk.is_all_feed(name) {
+            true => self.source_styles(self.kiosk.sources().into_iter()),
+            false => HashMap::new(),
+        };
+        let list = ArticleList::new(
+            articles,
+            width,
+            &self.highlights,
+            self.keys.clone(),
+            &self.theme,
+        )
+        .styles(styles)
+        .title(self.article_list_title(name, feed));
+        match selected {
+            Some(index) => list.selected(index),
+            None => list,
+        }
     }
 
-    /// ### get_empty_article_list
+    /// ### article_list_title
     ///
-    /// Returns an empty article list component
-    pub fn get_empty_article_list() -> ArticleList {
-        ArticleList::new(&[])
+    /// Get the title of the article list of source `name`, telling how many of its articles are unread,
+    /// when it has been fetched and the date of its latest article, if known
+    fn article_list_title(&self, name: &str, feed: &Feed) -> String {
+        let (mut total, mut unread, mut latest) = (0, 0, None);
+        for article in feed.articles() {
+            latest = latest.max(article.date);
+            // Articles without title are never listed, so they can't be read
+            if article.title.is_some() {
+                total += 1;
+                if !self.kiosk.is_read(article.url.as_str()) {
+                    unread += 1;
+                }
+            }
+        }
+        let fetched = self.kiosk.last_fetch(name).ok().flatten().map(|x| {
+            let elapsed = Local::now()
+                .signed_duration_since(x)
+                .max(ChronoDuration::zero());
+            format!("fetched {} ago", fmt_helpers::format_elapsed(elapsed))
+        });
+        let latest = latest.map(|x| {
+            format!(
+                "latest {}",
+                self.format_date(x, self.dates.list_date_format.as_str(), false)
+            )
+        });
+        fetched.into_iter().chain(latest).fold(
+            format!("Articles — {}/{} unread", unread, total),
+            |title, x| format!("{} · {}", title, x),
+        )
     }
 
-    /// ### get_feed_list
+    /// ### visible_articles
     ///
-    /// Get feed list component
-    pub fn get_feed_list(&self) -> FeedList {
+    /// Get the indexes of the articles to display in the article list.
+    /// Read articles are hidden if `hide_read` is enabled, except for the `selected` article.
+    /// If a tag filter is set, only the articles with the tag are displayed.
+    /// Articles are sorted according to the sort of the feed of name `name`
+    pub fn visible_articles(&self, name: &str, feed: &Feed, selected: Option<usize>) -> Vec<usize> {
+        let mut articles: Vec<(usize, &Article)> = feed
+            .articles()
+            .enumerate()
+            .filter(|(i, article)| {
+                article.title.is_some()
+                    && self
+                        .tag_filter
+                        .as_deref()
+                        .map(|tag| {
+                            article.categories.iter().any(|x| x.to_lowercase() == tag)
+                                || self
+                                    .kiosk
+                                    .get_tags(article.url.as_str())
+                                    .iter()
+                                    .any(|x| x == tag)
+                        })
+                        .unwrap_or(true)
+                    && (!self.hide_read
+                        || Some(*i) == selected
+                        || !self.kiosk.is_read(article.url.as_str()))
+            })
+            .collect();
+        // NOTE: sort is stable, so articles with the same score or date keep the feed order
+        match self.article_sort(name) {
+            ArticleSort::Feed => {}
+            ArticleSort::Date => {
+                articles.sort_by_key(|(_, article)| std::cmp::Reverse(article.date))
+            }
+            ArticleSort::Score => articles
+                .sort_by_key(|(_, article)| std::cmp::Reverse(article.interest.unwrap_or(0))),
+            ArticleSort::Unread => articles.sort_by_key(|(_, article)| {
+                (
+                    self.kiosk.is_read(article.url.as_str()),
+                    std::cmp::Reverse(article.date),
+                )
+            }),
+        }
+        articles.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// ### article_sort
+    ///
+    /// Get the order of the articles of the feed of name `name`: the one chosen for the feed, if any,
+    /// or the default one
+    pub fn article_sort(&self, name: &str) -> ArticleSort {
+        self.kiosk.get_article_sort(name).unwrap_or(self.sort)
+    }
+
+    /// ### get_filtered_article_list
+    ///
+    /// Get an article list component, containing only the articles of the feed of name `name` at `indexes`
+    fn get_filtered_article_list(
+        &self,
+        name: &str,
+        feed: &Feed,
+        indexes: &[usize],
+        width: usize,
+    ) -> ArticleList {
+        let articles = self.make_article_rows(name, feed, indexes, width);
+        ArticleList::new(
+            articles,
+            width,
+            &self.highlights,
+            self.keys.clone(),
+            &self.theme,
+        )
+    }
+
+    /// ### make_article_rows
+    ///
+    /// Make the article list rows, made up of the publication date and of the title.
+    /// The date format depends on the available `width`, and it's omitted if the pane is too narrow.
+    /// Rows are grouped by day if `group_by_day` is enabled and the articles of `name` are sorted by date
+    fn make_article_rows(
+        &self,
+        name: &str,
+        feed: &Feed,
+        indexes: &[usize],
+        width: usize,
+    ) -> Vec<ArticleEntry> {
+        let today = match self.group_by_day && self.article_sort(name) == ArticleSort::Date {
+            true => Some(Local::now().naive_local().date()),
+            false => None,
+        };
+        // format and whether to use the short relative format
+        let date_fmt = match width {
+            w if w >= 40 => Some((self.dates.list_date_format.as_str(), false)),
+            w if w >= 20 => Some(("%d/%m", true)),
+            _ => None,
+        };
+        let now = Local::now();
+        // Articles of the aggregated feed are listed with their source
+        let all_feed = self.kiosk.is_all_feed(name);
+        indexes
+            .iter()
+            .filter_map(|i| feed.article(*i).map(|x| (*i, x)))
+            .map(|(index, article)| {
+                let date = date_fmt.and_then(|(fmt, short)| {
+                    article.date.map(|date| self.format_date(date, fmt, short))
+                });
+                // The flair precedes the title, while star and note markers, score and media duration follow it, when known
+                let title = match article.flair.as_deref() {
+                    Some(flair) => {
+                        format!("[{}] {}", flair, article.title.as_deref().unwrap_or(""))
+                    }
+                    None => article.title.clone().unwrap_or_default(),
+                };
+                let suffix: Vec<String> = self
+                    .kiosk
+                    .is_starred(article.url.as_str())
+                    .then(|| String::from("⚑"))
+                    .into_iter()
+                    .chain(
+                        self.kiosk
+                            .get_note(article.url.as_str())
+                            .map(|_| String::from("✎")),
+                    )
+                    .chain(article.interest.map(|x| format!("★{}", x)))
+                    .chain(article.score.map(|x| format!("▲{}", x)))
+                    .chain(article.duration.map(fmt_helpers::format_duration))
+                    .collect();
+                ArticleEntry {
+                    index,
+                    date,
+                    source: all_feed.then(|| self.article_source(name, index).to_string()),
+                    title,
+                    suffix: suffix.join(" "),
+                    read: self.kiosk.is_read(article.url.as_str()),
+                    new: self.kiosk.is_new(article.url.as_str()),
+                    age_style: self
+                        .age_styles
+                        .as_ref()
+                        .zip(article.date)
+                        .map(|(styles, date)| styles.style(now.signed_duration_since(date))),
+                    day: today.map(|today| match article.date {
+                        Some(date) => fmt_helpers::format_day(date, today),
+                        None => String::from("Undated"),
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// ### format_date
+    ///
+    /// Format date according to dates configuration: if relative dates are enabled,
+    /// dates more recent than the threshold are formatted as elapsed time (e.g. `3h ago` or `3h` if `short`),
+    /// otherwise they're formatted with `fmt`
+    fn format_date(&self, date: DateTime<Local>, fmt: &str, short: bool) -> String {
+        let elapsed = Local::now().signed_duration_since(date);
+        if self.dates.relative
+            && elapsed >= ChronoDuration::zero()
+            && elapsed < ChronoDuration::hours(self.dates.relative_threshold)
+        {
+            let elapsed = fmt_helpers::format_elapsed(elapsed);
+            match short {
+                true => elapsed,
+                false => format!("{} ago", elapsed),
+            }
+        } else {
+            fmt_helpers::format_datetime(date, fmt)
+        }
+    }
+
+    /// ### get_empty_article_list
+    ///
+    /// Returns an empty article list component
+    pub fn get_empty_article_list(&self) -> ArticleList {
+        ArticleList::new(vec![], 0, &self.highlights, self.keys.clone(), &self.theme)
+    }
+
+    /// ### get_feed_list
+    ///
+    /// Get feed list component
+    pub fn get_feed_list(&self) -> FeedList {
         let mut sources = self.kiosk.get_state();
-        sources.sort_by(|a, b| a.0.cmp(&b.0));
-        FeedList::new(sources)
+        sources.retain(|(name, _)| self.in_tab(name.as_str()));
+        for (name, state) in sources.iter_mut() {
+            if *state == FlatFeedState::Error && self.retries.is_scheduled(name) {
+                *state = FlatFeedState::Retrying;
+            }
+        }
+        sources.sort_by(|a, b| self.compare_sources(a.0.as_str(), b.0.as_str()));
+        let styles = self.source_styles(sources.iter().map(|(name, _)| name));
+        FeedList::new(sources, self.keys.clone(), &self.theme)
+            .styles(styles)
+            .pinned(self.pinned.clone())
+    }
+
+    /// ### source_styles
+    ///
+    /// Get the style of each of the sources `names` with a feed style
+    fn source_styles<'a>(&self, names: impl Iterator<Item = &'a String>) -> HashMap<String, Style> {
+        names
+            .filter_map(|name| {
+                self.feed_style(name.as_str())
+                    .map(|style| (name.clone(), style.style()))
+            })
+            .collect()
+    }
+
+    /// ### feed_style
+    ///
+    /// Get the style of the source `name`: the one set for the source, otherwise the one of its category
+    fn feed_style(&self, name: &str) -> Option<&FeedStyle> {
+        self.styles.get(name).or_else(|| {
+            self.categories
+                .get(name)
+                .and_then(|category| self.styles.get(category))
+        })
+    }
+
+    /// ### get_tab_bar
+    ///
+    /// Get tab bar component
+    pub fn get_tab_bar(&self) -> TabBar {
+        TabBar::new(
+            self.tabs.iter().map(|x| x.name.clone()).collect(),
+            self.tab,
+            &self.theme,
+        )
+    }
+
+    /// ### get_status_bar
+    ///
+    /// Get status bar component
+    pub fn get_status_bar(&self) -> StatusBar {
+        let (fetched, total) = self.kiosk.fetch_progress();
+        StatusBar::new(fetched, total, self.keys.help.to_string(), &self.theme)
+            .message(self.status_message.as_ref().map(|(x, _)| x.clone()))
+            .offline(self.offline)
+    }
+
+    /// ### push_error
+    ///
+    /// Push error to the error queue
+    pub fn push_error(&mut self, err: impl ToString) {
+        self.errors.push(err);
+    }
+
+    /// ### get_error_popup
+    ///
+    /// Get error popup for the current error in the queue, if any
+    pub fn get_error_popup(&self) -> Option<ErrorPopup> {
+        let (position, errors) = self.errors.position();
+        self.errors
+            .current()
+            .map(|err| ErrorPopup::new(err, position, errors, &self.theme))
+    }
+
+    /// ### mount_error
+    ///
+    /// Remount error popup with the current error, or umount it if the queue is empty
+    fn mount_error(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        match self.get_error_popup() {
+            Some(popup) => {
+                assert!(view.remount(Id::ErrorPopup, Box::new(popup)).is_ok());
+                assert!(view.active(&Id::ErrorPopup).is_ok());
+            }
+            None => {
+                let _ = view.umount(&Id::ErrorPopup);
+            }
+        }
     }
 
     /// ### view_quit
@@ -282,11 +1563,471 @@ impl Model {
     /// Mount quit popup
     fn mount_quit(&self, view: &mut View<Id, Msg, NoUserEvent>) {
         assert!(view
-            .remount(Id::QuitPopup, Box::new(QuitPopup::default()))
+            .remount(Id::QuitPopup, Box::new(QuitPopup::new(&self.theme)))
             .is_ok());
         assert!(view.active(&Id::QuitPopup).is_ok());
     }
 
+    /// ### mount_help
+    ///
+    /// Mount help popup
+    fn mount_help(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        assert!(view
+            .remount(
+                Id::HelpPopup,
+                Box::new(HelpPopup::new(self.keys.clone(), &self.theme))
+            )
+            .is_ok());
+        assert!(view.active(&Id::HelpPopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### mount_feed_info
+    ///
+    /// Mount the popup describing the selected source
+    fn mount_feed_info(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        if let Some(name) = self.get_selected_feed_name(view) {
+            let channel = self
+                .kiosk
+                .get_feed(name.as_str())
+                .map(|x| x.channel().clone())
+                .unwrap_or_default();
+            assert!(view
+                .remount(
+                    Id::FeedInfoPopup,
+                    Box::new(FeedInfoPopup::new(
+                        name.as_str(),
+                        &channel,
+                        self.keys.clone(),
+                        &self.theme
+                    ))
+                )
+                .is_ok());
+            assert!(view.active(&Id::FeedInfoPopup).is_ok());
+            Self::lock_global_listener(view, true);
+        }
+    }
+
+    /// ### mount_filter
+    ///
+    /// Mount filter popup for the article list
+    fn mount_filter(&mut self, view: &mut View<Id, Msg, NoUserEvent>) {
+        if self.get_selected_feed(view).is_none() {
+            return;
+        }
+        let previous = match view.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => index,
+            _ => 0,
+        };
+        self.article_filter = Some(ArticleFilter { previous });
+        assert!(view
+            .remount(
+                Id::FilterPopup,
+                Box::new(FilterPopup::new("Filter articles", &self.theme))
+            )
+            .is_ok());
+        assert!(view.active(&Id::FilterPopup).is_ok());
+        Self::lock_global_listener(view, true);
+        self.filter_articles(view, "");
+    }
+
+    /// ### umount_filter
+    ///
+    /// Umount filter popup and restore the full article list, selecting the article at `index`
+    fn umount_filter(&mut self, view: &mut View<Id, Msg, NoUserEvent>, index: usize) {
+        let _ = view.umount(&Id::FilterPopup);
+        Self::lock_global_listener(view, false);
+        self.article_filter = None;
+        if let Some(name) = self.get_selected_feed_name(view) {
+            let feed = self.kiosk.get_feed(name.as_str()).unwrap();
+            let articles = self.get_article_list(
+                name.as_str(),
+                feed,
+                self.max_article_name_len(),
+                Some(index),
+            );
+            assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
+            self.update_article(view, index);
+        }
+        assert!(view.active(&Id::ArticleList).is_ok());
+    }
+
+    /// ### filter_articles
+    ///
+    /// Show in the article list only the articles fuzzy matching `query`, sorted by score.
+    /// Queries with the regex prefix are regular expressions: the matching articles keep the order of the list
+    fn filter_articles(&mut self, view: &mut View<Id, Msg, NoUserEvent>, query: &str) {
+        let (name, feed) = match self.get_selected_feed_name(view) {
+            Some(name) => {
+                let feed = self.kiosk.get_feed(name.as_str()).unwrap();
+                (name, feed)
+            }
+            None => return,
+        };
+        // NOTE: the regex being typed may be invalid, then it matches nothing
+        let regex =
+            str_helpers::is_regex_query(query).then(|| str_helpers::search_regex(query).ok());
+        let mut matches: Vec<(usize, i64)> = feed
+            .articles()
+            .enumerate()
+            .filter_map(|(i, article)| {
+                article
+                    .title
+                    .as_deref()
+                    .and_then(|title| match &regex {
+                        Some(regex) => regex.as_ref().filter(|x| x.is_match(title)).map(|_| 0),
+                        None => str_helpers::fuzzy_match(query, title),
+                    })
+                    .map(|score| (i, score))
+            })
+            .collect();
+        matches.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        let matches: Vec<usize> = matches.into_iter().map(|(i, _)| i).collect();
+        let articles = self.get_filtered_article_list(
+            name.as_str(),
+            feed,
+            matches.as_slice(),
+            self.max_article_name_len(),
+        );
+        assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
+        if let Some(article) = matches.first() {
+            self.update_article(view, *article);
+        }
+    }
+
+    /// ### mount_add_feed
+    ///
+    /// Mount add feed popup, with the feed url input filled with `url`
+    fn mount_add_feed(&self, view: &mut View<Id, Msg, NoUserEvent>, url: Option<&str>) {
+        let popup = match url {
+            Some(url) => AddFeedPopup::new(&self.theme).url(url),
+            None => AddFeedPopup::new(&self.theme),
+        };
+        assert!(view.remount(Id::AddFeedPopup, Box::new(popup)).is_ok());
+        assert!(view.active(&Id::AddFeedPopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### mount_podcast_search
+    ///
+    /// Mount the popup to type the name of the podcast to search
+    fn mount_podcast_search(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        assert!(view
+            .remount(
+                Id::PodcastSearchPopup,
+                Box::new(PodcastSearchPopup::new(&self.theme))
+            )
+            .is_ok());
+        assert!(view.active(&Id::PodcastSearchPopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### set_podcasts
+    ///
+    /// Set the podcasts found by the last search
+    pub fn set_podcasts(&mut self, podcasts: Vec<Podcast>) {
+        self.podcasts = podcasts;
+    }
+
+    /// ### get_podcast_results_popup
+    ///
+    /// Get the list of the podcasts found by the last search
+    pub fn get_podcast_results_popup(&self) -> PodcastResultsPopup {
+        PodcastResultsPopup::new(self.podcasts.as_slice(), self.keys.clone(), &self.theme)
+    }
+
+    /// ### mount_rename_feed
+    ///
+    /// Mount rename feed popup for the selected source
+    fn mount_rename_feed(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        if let Some(name) = self.get_selected_feed_name(view) {
+            assert!(view
+                .remount(
+                    Id::RenameFeedPopup,
+                    Box::new(RenameFeedPopup::new(name.as_str(), &self.theme))
+                )
+                .is_ok());
+            assert!(view.active(&Id::RenameFeedPopup).is_ok());
+            Self::lock_global_listener(view, true);
+        }
+    }
+
+    /// ### mount_search
+    ///
+    /// Mount the popup to search in the article summary, filled with the last search
+    fn mount_search(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        assert!(view
+            .remount(
+                Id::SearchPopup,
+                Box::new(SearchPopup::new(
+                    self.article_search.as_deref().unwrap_or(""),
+                    &self.theme
+                ))
+            )
+            .is_ok());
+        assert!(view.active(&Id::SearchPopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### search_article
+    ///
+    /// Search `query` in the summary of the selected article and scroll to its first match;
+    /// the search is kept for the next articles. An empty query ends the search
+    fn search_article(&mut self, view: &mut View<Id, Msg, NoUserEvent>, query: &str) {
+        if let Err(err) = str_helpers::search_regex(query) {
+            self.show_status_message(view, err);
+            return;
+        }
+        self.article_search = Some(query).filter(|x| !x.is_empty()).map(String::from);
+        let scroll = match view.state(&Id::ArticleSummary) {
+            Ok(State::One(StateValue::Usize(scroll))) => scroll,
+            _ => 0,
+        };
+        if let Some(article) = self.get_selected_article(view) {
+            let (_, _, _, _, summary, _, _) = self.get_article_view(article);
+            let summary = summary.scroll(scroll).first_match();
+            assert!(view.remount(Id::ArticleSummary, Box::new(summary)).is_ok());
+            assert!(view.active(&Id::ArticleSummary).is_ok());
+        }
+    }
+
+    /// ### search_keys_taken
+    ///
+    /// Returns whether `keys` are taken by the search in the article summary, since the summary is focused
+    /// while searching and they're bound to the next or the previous match too
+    fn search_keys_taken(&self, view: &mut View<Id, Msg, NoUserEvent>, keys: &Keys) -> bool {
+        self.article_search.is_some()
+            && (keys.overlaps(&self.keys.next_match) || keys.overlaps(&self.keys.previous_match))
+            && matches!(
+                view.query(&Id::ArticleSummary, Attribute::Focus),
+                Ok(Some(AttrValue::Flag(true)))
+            )
+    }
+
+    /// ### mount_tag_filter
+    ///
+    /// Mount the tag filter popup, filled with the current tag or with the first tag of the selected article
+    fn mount_tag_filter(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        let tag = self.tag_filter.clone().or_else(|| {
+            self.get_selected_article(view)
+                .and_then(|x| x.categories.first().cloned())
+        });
+        assert!(view
+            .remount(
+                Id::TagFilterPopup,
+                Box::new(TagFilterPopup::new(
+                    tag.as_deref().unwrap_or(""),
+                    &self.theme
+                ))
+            )
+            .is_ok());
+        assert!(view.active(&Id::TagFilterPopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### mount_feed_switcher
+    ///
+    /// Mount the popup to go to a feed by name
+    fn mount_feed_switcher(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        let feeds = self.sorted_sources().into_iter().cloned().collect();
+        assert!(view
+            .remount(
+                Id::FeedSwitcherPopup,
+                Box::new(FeedSwitcherPopup::new(feeds, &self.theme))
+            )
+            .is_ok());
+        assert!(view.active(&Id::FeedSwitcherPopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### switch_feed
+    ///
+    /// Select the feed named `name` in the feed list and move to its articles
+    fn switch_feed(&mut self, view: &mut View<Id, Msg, NoUserEvent>, name: &str) {
+        let _ = view.umount(&Id::FeedSwitcherPopup);
+        Self::lock_global_listener(view, false);
+        let index = match self
+            .sorted_sources()
+            .iter()
+            .position(|x| x.as_str() == name)
+        {
+            Some(index) => index,
+            None => return,
+        };
+        assert!(view
+            .remount(Id::FeedList, Box::new(self.get_feed_list().selected(index)))
+            .is_ok());
+        self.show_feed_articles(view, index);
+        assert!(view.active(&Id::ArticleList).is_ok());
+    }
+
+    /// ### switch_tab
+    ///
+    /// Select the tab at `index`, showing the first feed in it
+    fn switch_tab(&mut self, view: &mut View<Id, Msg, NoUserEvent>, index: usize) {
+        let name = match self.tabs.get(index) {
+            Some(tab) if index != self.tab => tab.name.clone(),
+            _ => return,
+        };
+        self.tab = index;
+        assert!(view
+            .remount(Id::TabBar, Box::new(self.get_tab_bar()))
+            .is_ok());
+        assert!(view
+            .remount(Id::FeedList, Box::new(self.get_feed_list()))
+            .is_ok());
+        match self.sorted_sources().is_empty() {
+            true => assert!(view
+                .remount(Id::ArticleList, Box::new(self.get_empty_article_list()))
+                .is_ok()),
+            false => self.show_feed_articles(view, 0),
+        }
+        assert!(view.active(&Id::FeedList).is_ok());
+        self.show_status_message(view, format!(r#"Showing "{}""#, name));
+    }
+
+    /// ### show_feed_articles
+    ///
+    /// Load the articles of the feed at `index` in the article list and show the first one
+    fn show_feed_articles(&mut self, view: &mut View<Id, Msg, NoUserEvent>, index: usize) {
+        let name = &(*self.sorted_sources().get(index).unwrap()).clone();
+        if let Some(feed) = self.kiosk.get_feed(name.as_str()) {
+            let articles = self.get_article_list(name, feed, self.max_article_name_len(), None);
+            let first = self.visible_articles(name, feed, None).first().cloned();
+            assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
+            // Then load the first article of feed
+            if let Some(first) = first {
+                self.update_article(view, first);
+            }
+        }
+    }
+
+    /// ### filter_tag
+    ///
+    /// Show only the articles with `tag`, or all of them if `tag` is empty
+    fn filter_tag(&mut self, view: &mut View<Id, Msg, NoUserEvent>, tag: &str) {
+        let _ = view.umount(&Id::TagFilterPopup);
+        Self::lock_global_listener(view, false);
+        let tag = tag.trim().to_lowercase();
+        self.set_status_message(Some(match tag.is_empty() {
+            true => String::from("Showing all articles"),
+            false => format!(r#"Showing articles tagged "{}""#, tag),
+        }));
+        self.tag_filter = Some(tag).filter(|x| !x.is_empty());
+        if let Some(name) = self.get_selected_feed_name(view) {
+            let feed = self.kiosk.get_feed(name.as_str()).unwrap();
+            let articles =
+                self.get_article_list(name.as_str(), feed, self.max_article_name_len(), None);
+            assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
+            if let Some(article) = self.visible_articles(name.as_str(), feed, None).first() {
+                self.update_article(view, *article);
+            }
+        }
+        assert!(view.active(&Id::ArticleList).is_ok());
+        assert!(view
+            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+            .is_ok());
+    }
+
+    /// ### mount_tag_article
+    ///
+    /// Mount the popup to edit the user tags of the selected article
+    fn mount_tag_article(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        if let Some(article) = self.get_selected_article(view) {
+            let popup =
+                TagArticlePopup::new(self.kiosk.get_tags(article.url.as_str()), &self.theme);
+            assert!(view.remount(Id::TagArticlePopup, Box::new(popup)).is_ok());
+            assert!(view.active(&Id::TagArticlePopup).is_ok());
+            Self::lock_global_listener(view, true);
+        }
+    }
+
+    /// ### tag_article
+    ///
+    /// Replace the user tags of the selected article with the comma-separated `tags`.
+    /// Tag feeds are updated in the feed list
+    fn tag_article(&mut self, view: &mut View<Id, Msg, NoUserEvent>, tags: &str) {
+        let _ = view.umount(&Id::TagArticlePopup);
+        Self::lock_global_listener(view, false);
+        let url = match self.get_selected_article(view) {
+            Some(article) => article.url.clone(),
+            None => return,
+        };
+        let source = self.get_selected_feed_name(view);
+        let tags = str_helpers::split_tags(tags);
+        let result = self.kiosk.set_tags(url.as_str(), tags.as_slice());
+        self.report_store_error(result);
+        // Keep the selected source, which is moved by added or removed tag feeds
+        let index = source
+            .and_then(|x| self.sorted_sources().iter().position(|s| **s == x))
+            .unwrap_or_default();
+        assert!(view
+            .remount(Id::FeedList, Box::new(self.get_feed_list().selected(index)))
+            .is_ok());
+        self.remount_article_list(view);
+        if let Ok(State::One(StateValue::Usize(article))) = view.state(&Id::ArticleList) {
+            self.update_article(view, article);
+        }
+        self.set_status_message(Some(match tags.is_empty() {
+            true => String::from("Article untagged"),
+            false => format!("Article tagged {}", tags.join(", ")),
+        }));
+        assert!(view
+            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+            .is_ok());
+    }
+
+    /// ### reject_tag_feed
+    ///
+    /// Returns whether `name` is a tag feed or the aggregated feed, which can't be `action`;
+    /// if so the user is told in the status bar
+    fn reject_tag_feed(
+        &mut self,
+        view: &mut View<Id, Msg, NoUserEvent>,
+        name: &str,
+        action: &str,
+    ) -> bool {
+        let message = match (self.kiosk.is_tag_feed(name), self.kiosk.is_all_feed(name)) {
+            (true, _) => format!("Tag feeds can't be {}", action),
+            (_, true) => format!("The {} feed can't be {}", name, action),
+            (false, false) => return false,
+        };
+        self.set_status_message(Some(message));
+        assert!(view
+            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+            .is_ok());
+        true
+    }
+
+    /// ### mount_delete_feed
+    ///
+    /// Mount delete feed popup for the selected source
+    fn mount_delete_feed(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        if let Some(name) = self.get_selected_feed_name(view) {
+            assert!(view
+                .remount(
+                    Id::DeleteFeedPopup,
+                    Box::new(DeleteFeedPopup::new(name.as_str(), &self.theme))
+                )
+                .is_ok());
+            assert!(view.active(&Id::DeleteFeedPopup).is_ok());
+        }
+    }
+
+    /// ### lock_global_listener
+    ///
+    /// Lock or unlock the global listener. It must be locked while the user is typing
+    /// or while a popup which handles global keys is displayed
+    fn lock_global_listener(view: &mut View<Id, Msg, NoUserEvent>, locked: bool) {
+        assert!(view
+            .attr(
+                &Id::GlobalListener,
+                Attribute::Custom(GLOBAL_LISTENER_LOCKED),
+                AttrValue::Flag(locked)
+            )
+            .is_ok());
+    }
+
     /// ### terminal_width
     ///
     /// Get terminal width. If it fails to collect width, returns 65535
@@ -305,14 +2046,621 @@ impl Model {
         self.tasks.push(task);
     }
 
+    /// ### report_store_error
+    ///
+    /// Show an error if the article store couldn't be updated
+    fn report_store_error(&mut self, result: StoreResult<()>) {
+        if let Err(err) = result {
+            self.task(Task::ShowError(format!(
+                "Could not update article store: {}",
+                err
+            )));
+        }
+    }
+
+    /// ### star_article
+    ///
+    /// Star the selected article, or unstar it if it's starred
+    fn star_article(&mut self, view: &mut View<Id, Msg, NoUserEvent>) {
+        let url = match self.get_selected_article(view) {
+            Some(article) => article.url.clone(),
+            None => return,
+        };
+        let starred = !self.kiosk.is_starred(url.as_str());
+        let result = self.kiosk.set_starred(url.as_str(), starred);
+        self.report_store_error(result);
+        self.remount_article_list(view);
+        self.set_status_message(Some(String::from(match starred {
+            true => "Article starred",
+            false => "Article unstarred",
+        })));
+        assert!(view
+            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+            .is_ok());
+    }
+
+    /// ### remount_article_list
+    ///
+    /// Remount article list for the selected feed, keeping the current selection.
+    /// Titles are elided again, since the pane size may have changed
+    fn remount_article_list(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        let selected = match view.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => index,
+            _ => 0,
+        };
+        let focus = matches!(
+            view.query(&Id::ArticleList, Attribute::Focus),
+            Ok(Some(AttrValue::Flag(true)))
+        );
+        if let Some(name) = self.get_selected_feed_name(view) {
+            let feed = self.kiosk.get_feed(name.as_str()).unwrap();
+            let articles = self.get_article_list(
+                name.as_str(),
+                feed,
+                self.max_article_name_len(),
+                Some(selected),
+            );
+            assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
+            // Remounting the article list makes it lose focus
+            if focus {
+                assert!(view.active(&Id::ArticleList).is_ok());
+            }
+        }
+    }
+
+    /// ### refresh_dates
+    ///
+    /// Render dates again, so that relative dates and the time elapsed since the last fetch
+    /// in the article list title are kept up to date
+    fn refresh_dates(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        // NOTE: filtered list is remounted on the next filter change
+        if !self.filtering() {
+            self.remount_article_list(view);
+        }
+        if !self.dates.relative {
+            return;
+        }
+        if let Some(article) = self.get_selected_article(view) {
+            let (_, _, date, _, _, _, _) = self.get_article_view(article);
+            assert!(view.remount(Id::ArticleDate, Box::new(date)).is_ok());
+        }
+    }
+
+    /// ### move_article
+    ///
+    /// Select the next (or previous if `forward` is `false`) article in the article list
+    /// and load it into the view, keeping focus on the article summary
+    fn move_article(&self, view: &mut View<Id, Msg, NoUserEvent>, forward: bool) {
+        let (name, selected) = match (
+            self.get_selected_feed_name(view),
+            view.state(&Id::ArticleList),
+        ) {
+            (Some(name), Ok(State::One(StateValue::Usize(index)))) => (name, index),
+            _ => return,
+        };
+        let feed = self.kiosk.get_feed(name.as_str()).unwrap();
+        let articles = self.visible_articles(name.as_str(), feed, Some(selected));
+        let position = articles.iter().position(|x| *x == selected).unwrap_or(0);
+        let index = match forward {
+            true => articles.get(position + 1),
+            false => position.checked_sub(1).and_then(|x| articles.get(x)),
+        };
+        let index = match index {
+            Some(index) => *index,
+            None => return,
+        };
+        let articles = self.get_article_list(
+            name.as_str(),
+            feed,
+            self.max_article_name_len(),
+            Some(index),
+        );
+        assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
+        self.update_article(view, index);
+        assert!(view.active(&Id::ArticleSummary).is_ok());
+    }
+
+    /// ### move_unread
+    ///
+    /// Select the next (or previous if `forward` is `false`) unread article and load it into the view.
+    /// If there are no more unread articles in the selected feed, the following feeds are searched,
+    /// skipping tag feeds and the aggregated feed, where the articles of all the sources are visited in a row.
+    /// The article is marked as read
+    fn move_unread(&mut self, view: &mut View<Id, Msg, NoUserEvent>, forward: bool) {
+        if self.filtering() {
+            return;
+        }
+        let feed = match view.state(&Id::FeedList) {
+            Ok(State::One(StateValue::Usize(index))) => index,
+            _ => return,
+        };
+        let selected = match view.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => Some(index),
+            _ => None,
+        };
+        let sources = self.sorted_sources();
+        let feeds: Vec<usize> = match forward {
+            true => (feed..sources.len()).collect(),
+            false => (0..=feed.min(sources.len().saturating_sub(1)))
+                .rev()
+                .collect(),
+        };
+        let found = feeds.into_iter().find_map(|index| {
+            let name = sources.get(index)?;
+            if index != feed
+                && (self.kiosk.is_tag_feed(name.as_str()) || self.kiosk.is_all_feed(name.as_str()))
+            {
+                return None;
+            }
+            let articles = self.kiosk.get_feed(name.as_str())?;
+            let selected = selected.filter(|_| index == feed);
+            let mut visible = self.visible_articles(name.as_str(), articles, selected);
+            if !forward {
+                visible.reverse();
+            }
+            // Articles before (or after, moving backward) the selected one are skipped
+            let start = selected
+                .and_then(|x| visible.iter().position(|i| *i == x))
+                .map(|x| x + 1)
+                .unwrap_or(0);
+            visible[start..]
+                .iter()
+                .find(|i| {
+                    articles
+                        .article(**i)
+                        .map(|x| !self.kiosk.is_read(x.url.as_str()))
+                        .unwrap_or(false)
+                })
+                .map(|article| (index, *article))
+        });
+        let (index, article) = match found {
+            Some(found) => found,
+            None => {
+                self.set_status_message(Some(String::from("No more unread articles")));
+                assert!(view
+                    .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+                    .is_ok());
+                return;
+            }
+        };
+        if index != feed {
+            assert!(view
+                .remount(Id::FeedList, Box::new(self.get_feed_list().selected(index)))
+                .is_ok());
+        }
+        if let Some(name) = self.get_selected_feed_name(view) {
+            let feed = self.kiosk.get_feed(name.as_str()).unwrap();
+            let articles = self.get_article_list(
+                name.as_str(),
+                feed,
+                self.max_article_name_len(),
+                Some(article),
+            );
+            assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
+        }
+        self.update_article(view, article);
+        assert!(view.active(&Id::ArticleSummary).is_ok());
+        self.mark_selected_read(view);
+    }
+
+    /// ### mark_selected_read
+    ///
+    /// Mark the selected article as read. The article list is rendered again to dim it
+    fn mark_selected_read(&mut self, view: &mut View<Id, Msg, NoUserEvent>) {
+        let url = match self.get_selected_article(view) {
+            Some(article) if !self.kiosk.is_read(article.url.as_str()) => article.url.clone(),
+            _ => return,
+        };
+        let result = self.kiosk.mark_read(url.as_str());
+        self.report_store_error(result);
+        self.run_selected_article_hook(view, HookEvent::MarkRead);
+        self.push_undo(vec![url]);
+        if !self.filtering() {
+            self.remount_article_list(view);
+        }
+    }
+
+    /// ### mark_all_read
+    ///
+    /// Mark all the unread articles displayed in the article list as read
+    fn mark_all_read(&mut self, view: &mut View<Id, Msg, NoUserEvent>) {
+        if self.filtering() {
+            return;
+        }
+        let selected = match view.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => Some(index),
+            _ => None,
+        };
+        let urls: Vec<String> = match self.get_selected_feed_name(view) {
+            Some(name) => {
+                let feed = self.kiosk.get_feed(name.as_str()).unwrap();
+                self.visible_articles(name.as_str(), feed, selected)
+                    .into_iter()
+                    .filter_map(|x| feed.article(x))
+                    .filter(|x| !self.kiosk.is_read(x.url.as_str()))
+                    .map(|x| x.url.clone())
+                    .collect()
+            }
+            None => return,
+        };
+        for url in urls.iter() {
+            let result = self.kiosk.mark_read(url.as_str());
+            self.report_store_error(result);
+        }
+        if let (Some(name), Some(feed)) = (
+            self.get_selected_feed_name(view),
+            self.get_selected_feed(view),
+        ) {
+            for (index, article) in feed
+                .articles()
+                .enumerate()
+                .filter(|(_, x)| urls.contains(&x.url))
+            {
+                let source = self.article_source(name.as_str(), index);
+                self.run_article_hook(HookEvent::MarkRead, source, article);
+            }
+        }
+        self.set_status_message(Some(format!("{} articles marked as read", urls.len())));
+        if !urls.is_empty() {
+            self.push_undo(urls);
+        }
+        self.remount_article_list(view);
+        assert!(view
+            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+            .is_ok());
+    }
+
+    /// ### push_undo
+    ///
+    /// Remember that the articles with `urls` have been marked as read, by the user or by mute rules,
+    /// so that it can be undone.
+    /// The oldest action is forgotten once the stack is full
+    fn push_undo(&mut self, urls: Vec<String>) {
+        if self.undo.len() >= UNDO_STACK_SIZE {
+            self.undo.remove(0);
+        }
+        self.undo.push(urls);
+    }
+
+    /// ### undo
+    ///
+    /// Mark the articles marked as read by the last action as not read again;
+    /// mute rules won't mark them as read again in this session
+    fn undo(&mut self, view: &mut View<Id, Msg, NoUserEvent>) {
+        let message = match self.undo.pop() {
+            Some(urls) => {
+                for url in urls.iter() {
+                    let result = self.kiosk.mark_unread(url.as_str());
+                    self.report_store_error(result);
+                }
+                self.undone.extend(urls.iter().cloned());
+                if !self.filtering() {
+                    self.remount_article_list(view);
+                }
+                match urls.len() {
+                    1 => String::from("Article marked as unread"),
+                    n => format!("{} articles marked as unread", n),
+                }
+            }
+            None => String::from("Nothing to undo"),
+        };
+        self.set_status_message(Some(message));
+        assert!(view
+            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+            .is_ok());
+    }
+
+    /// ### show_status_message
+    ///
+    /// Display `message` in the status bar for a few seconds
+    fn show_status_message(&mut self, view: &mut View<Id, Msg, NoUserEvent>, message: String) {
+        self.set_status_message(Some(message));
+        assert!(view
+            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+            .is_ok());
+    }
+
+    /// ### hide_read_articles
+    ///
+    /// Set whether read articles are hidden from the article list
+    fn hide_read_articles(&mut self, view: &mut View<Id, Msg, NoUserEvent>, hide_read: bool) {
+        self.hide_read = hide_read;
+        self.remount_article_list(view);
+        let message = match self.hide_read {
+            true => "Read articles hidden",
+            false => "Read articles shown",
+        };
+        self.show_status_message(view, String::from(message));
+    }
+
+    /// ### go_offline
+    ///
+    /// Go offline or back online; once online, the sources are fetched again
+    fn go_offline(&mut self, view: &mut View<Id, Msg, NoUserEvent>, offline: bool) {
+        if offline == self.offline {
+            return;
+        }
+        self.offline = offline;
+        let message = match self.offline {
+            true => "Offline: sources are loaded from the store",
+            false => "Online",
+        };
+        self.show_status_message(view, String::from(message));
+        if !self.offline {
+            self.task(Task::FetchSources(false));
+        }
+    }
+
+    /// ### sort_articles
+    ///
+    /// Change the order of the articles in the article list
+    fn sort_articles(&mut self, view: &mut View<Id, Msg, NoUserEvent>, sort: ArticleSort) {
+        self.sort = sort;
+        self.remount_article_list(view);
+        self.show_status_message(view, Self::sort_message(sort));
+    }
+
+    /// ### sort_feed_articles
+    ///
+    /// Change the order of the articles of the selected feed, remembering it across sessions.
+    /// `None` restores the default order
+    fn sort_feed_articles(
+        &mut self,
+        view: &mut View<Id, Msg, NoUserEvent>,
+        sort: Option<ArticleSort>,
+    ) {
+        let name = match self.get_selected_feed_name(view) {
+            Some(name) => name,
+            None => return,
+        };
+        let result = self.kiosk.set_article_sort(name.as_str(), sort);
+        self.report_store_error(result);
+        self.remount_article_list(view);
+        let message = Self::sort_message(self.article_sort(name.as_str()));
+        self.show_status_message(view, format!("{} in {}", message, name));
+    }
+
+    /// ### sort_message
+    ///
+    /// Get the status message telling how articles are sorted
+    fn sort_message(sort: ArticleSort) -> String {
+        String::from(match sort {
+            ArticleSort::Feed => "Articles sorted by feed order",
+            ArticleSort::Date => "Articles sorted by date",
+            ArticleSort::Score => "Articles sorted by score",
+            ArticleSort::Unread => "Unread articles first",
+        })
+    }
+
+    /// ### set_reading_mode
+    ///
+    /// Enter or leave the distraction-free reading mode; the article is marked as read when entering it
+    fn set_reading_mode(&mut self, view: &mut View<Id, Msg, NoUserEvent>, reading_mode: bool) {
+        if reading_mode && !self.reading_mode {
+            self.mark_selected_read(view);
+        }
+        self.reading_mode = reading_mode;
+        let focus = match self.reading_mode {
+            true => Id::ArticleSummary,
+            false => Id::ArticleList,
+        };
+        assert!(view.active(&focus).is_ok());
+    }
+
+    /// ### mount_open_with
+    ///
+    /// Mount the menu to choose the command to open a link of the selected article with
+    fn mount_open_with(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        let targets = match self.get_selected_article(view) {
+            Some(article) => OpenTarget::of_article(article, &self.urls),
+            None => return,
+        };
+        let entries = opener_entries(
+            self.openers.as_slice(),
+            self.video.player.as_deref(),
+            targets.as_slice(),
+        );
+        assert!(view
+            .remount(
+                Id::OpenWithPopup,
+                Box::new(OpenWithPopup::new(entries, self.keys.clone(), &self.theme))
+            )
+            .is_ok());
+        assert!(view.active(&Id::OpenWithPopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### mount_share
+    ///
+    /// Mount the menu of the commands the selected article can be shared with.
+    /// If there are no share commands, the user is told in the status bar
+    fn mount_share(&mut self, view: &mut View<Id, Msg, NoUserEvent>) {
+        if self.share.is_empty() {
+            self.show_status_message(
+                view,
+                String::from("Add the commands to share articles with to `[[share]]`"),
+            );
+            return;
+        }
+        assert!(view
+            .remount(
+                Id::SharePopup,
+                Box::new(SharePopup::new(
+                    self.share.as_slice(),
+                    self.keys.clone(),
+                    &self.theme
+                ))
+            )
+            .is_ok());
+        assert!(view.active(&Id::SharePopup).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### mount_command_line
+    ///
+    /// Mount the command line
+    fn mount_command_line(&self, view: &mut View<Id, Msg, NoUserEvent>) {
+        assert!(view
+            .remount(Id::CommandLine, Box::new(CommandLine::new(&self.theme)))
+            .is_ok());
+        assert!(view.active(&Id::CommandLine).is_ok());
+        Self::lock_global_listener(view, true);
+    }
+
+    /// ### run_command
+    ///
+    /// Run the command typed in the command line.
+    /// Commands which are bound to a key return the same message as the key, so that the action is shared
+    fn run_command(&mut self, view: &mut View<Id, Msg, NoUserEvent>, line: &str) -> Option<Msg> {
+        let command = match Command::from_str(line) {
+            Ok(command) => command,
+            Err(err) => {
+                self.show_status_message(view, err);
+                return None;
+            }
+        };
+        match command {
+            Command::Add(url) => self.mount_add_feed(view, url.as_deref()),
+            Command::Comments => return Some(Msg::OpenComments),
+            Command::EditConfig => return Some(Msg::EditConfig),
+            Command::Go(query) => match self.find_feed(query.as_str()) {
+                Some(name) => return Some(Msg::SwitchFeed(name)),
+                None => self.show_status_message(view, format!(r#"No feed matches "{}""#, query)),
+            },
+            Command::Help => return Some(Msg::ShowHelpPopup),
+            Command::MarkAllRead => return Some(Msg::MarkAllRead),
+            Command::Open => return Some(Msg::OpenArticle),
+            Command::Podcast(None) => return Some(Msg::ShowPodcastSearchPopup),
+            Command::Podcast(Some(query)) => return Some(Msg::SearchPodcasts(query)),
+            Command::Quit => return Some(Msg::CloseApp),
+            Command::Refresh(None) => return Some(Msg::FetchSource),
+            Command::Refresh(Some(query)) => match self.find_feed(query.as_str()) {
+                Some(name) if !self.reject_tag_feed(view, name.as_str(), "reloaded") => {
+                    self.task(Task::FetchSource(name))
+                }
+                Some(_) => {}
+                None => self.show_status_message(view, format!(r#"No feed matches "{}""#, query)),
+            },
+            Command::RefreshAll => return Some(Msg::FetchAllSources),
+            Command::ForceRefreshAll => return Some(Msg::ForceFetchAllSources),
+            Command::Save(path) => self.save_article(view, path.as_path()),
+            Command::Script(function) => return Some(Msg::RunScript(function)),
+            Command::Set(Setting::HideRead(hide_read)) => self.hide_read_articles(view, hide_read),
+            Command::Set(Setting::Offline(offline)) => self.go_offline(view, offline),
+            Command::Set(Setting::ReadingMode(reading_mode)) => {
+                self.set_reading_mode(view, reading_mode)
+            }
+            Command::Set(Setting::Sort(sort)) => self.sort_articles(view, sort),
+            Command::Sort(sort) => self.sort_feed_articles(view, sort),
+            Command::Tag(tag) => return Some(Msg::FilterTag(tag.unwrap_or_default())),
+            Command::Undo => return Some(Msg::Undo),
+        }
+        None
+    }
+
+    /// ### find_feed
+    ///
+    /// Find the name of the feed matching `query`: an exact name is preferred, otherwise the best fuzzy match
+    fn find_feed(&self, query: &str) -> Option<String> {
+        let sources = self.sorted_sources();
+        if let Some(name) = sources.iter().find(|x| x.as_str() == query) {
+            return Some(name.to_string());
+        }
+        sources
+            .into_iter()
+            .filter_map(|x| str_helpers::fuzzy_match(query, x).map(|score| (score, x)))
+            .min_by_key(|(score, _)| std::cmp::Reverse(*score))
+            .map(|(_, name)| name.clone())
+    }
+
+    /// ### save_article
+    ///
+    /// Save the selected article as text to the file at `path`
+    fn save_article(&mut self, view: &mut View<Id, Msg, NoUserEvent>, path: &Path) {
+        let text = match self.get_selected_article(view) {
+            Some(article) => self.article_text(article),
+            None => {
+                self.show_status_message(view, String::from("No article selected"));
+                return;
+            }
+        };
+        let message = match file_helpers::write_file(path, text.as_str()) {
+            Ok(()) => format!("Article saved to {}", path.display()),
+            Err(err) => format!("Could not save article to {}: {}", path.display(), err),
+        };
+        self.show_status_message(view, message);
+    }
+
+    /// ### run_script_action
+    ///
+    /// Call the action `function` of the script on the selected article, showing the message it returns
+    fn run_script_action(&mut self, view: &mut View<Id, Msg, NoUserEvent>, function: &str) {
+        let script = match self.script.as_ref() {
+            Some(script) => script,
+            None => {
+                self.show_status_message(view, String::from("No script loaded"));
+                return;
+            }
+        };
+        let result = match (
+            self.get_selected_source(view),
+            self.get_selected_article(view),
+        ) {
+            (Some(name), Some(article)) => script.run_action(function, name.as_str(), article),
+            _ => {
+                self.show_status_message(view, String::from("No article selected"));
+                return;
+            }
+        };
+        match result {
+            Ok(Some(message)) => self.show_status_message(view, message),
+            Ok(None) => {}
+            Err(err) => self.task(Task::ShowError(format!(
+                "Script action `{}` failed: {}",
+                function, err
+            ))),
+        }
+    }
+
+    /// ### article_text
+    ///
+    /// Render `article` as plain text: title, link, date and authors, then the summary followed by its numbered links
+    fn article_text(&self, article: &Article) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        if let Some(title) = article.title.as_deref() {
+            lines.push(title.to_string());
+        }
+        lines.push(self.urls.clean(article.url.as_str()));
+        if let Some(date) = article.date {
+            lines.push(date.format(self.dates.date_format.as_str()).to_string());
+        }
+        if !article.authors.is_empty() {
+            lines.push(article.authors.join(", "));
+        }
+        lines.push(String::new());
+        lines.push(article.summary.clone());
+        if !article.links.is_empty() {
+            lines.push(String::new());
+            for (i, link) in article.links.iter().enumerate() {
+                lines.push(format!("[{}] {}", i + 1, self.urls.clean(link)));
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
     /// ### update_article
     ///
     /// Update article into view by index
     fn update_article(&self, view: &mut View<Id, Msg, NoUserEvent>, article: usize) {
         if let Some(feed) = self.get_selected_feed(view) {
-            if let Some(article) = feed.articles().nth(article) {
-                let (authors, date, link, summary, title) = Self::get_article_view(article);
+            if let Some(article) = feed.article(article) {
+                let (authors, comments, date, link, summary, tags, title) =
+                    self.get_article_view(article);
                 assert!(view.remount(Id::ArticleAuthors, Box::new(authors)).is_ok());
+                assert!(view
+                    .remount(Id::ArticleComments, Box::new(comments))
+                    .is_ok());
+                assert!(view.remount(Id::ArticleTags, Box::new(tags)).is_ok());
                 assert!(view.remount(Id::ArticleDate, Box::new(date)).is_ok());
                 assert!(view.remount(Id::ArticleLink, Box::new(link)).is_ok());
                 assert!(view.remount(Id::ArticleSummary, Box::new(summary)).is_ok());
@@ -321,6 +2669,50 @@ impl Model {
         }
     }
 
+    /// ### save_session
+    ///
+    /// Save the selected feed, article and summary scroll, to restore them on the next startup
+    fn save_session(&mut self, view: &mut View<Id, Msg, NoUserEvent>) {
+        // NOTE: a session which has not been restored yet is kept, since the user never got there
+        if self.session.is_some() {
+            return;
+        }
+        let article = match view.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => Some(index),
+            _ => None,
+        };
+        let scroll = match view.state(&Id::ArticleSummary) {
+            Ok(State::One(StateValue::Usize(scroll))) => scroll,
+            _ => 0,
+        };
+        // NOTE: the selected feed may be still loading
+        let session = self.get_selected_feed_name(view).map(|feed| Session {
+            article: self
+                .kiosk
+                .get_feed(feed.as_str())
+                .zip(article)
+                .and_then(|(x, index)| x.article(index))
+                .map(|x| x.url.clone()),
+            feed,
+            scroll,
+        });
+        let result = self.kiosk.save_session(session.as_ref());
+        if let Err(err) = result {
+            warn!("could not save session: {}", err);
+        }
+    }
+
+    /// ### get_selected_article
+    ///
+    /// Get currently selected article
+    fn get_selected_article(&self, view: &mut View<Id, Msg, NoUserEvent>) -> Option<&Article> {
+        let index = match view.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => index,
+            _ => return None,
+        };
+        self.get_selected_feed(view)?.article(index)
+    }
+
     /// ### get_selected_feed
     ///
     /// Get currently selected feed
@@ -342,13 +2734,47 @@ impl Model {
             None
         }
     }
+
+    /// ### get_selected_source
+    ///
+    /// Get the source of the selected article, which is the selected feed unless it's the aggregated one
+    fn get_selected_source(&self, view: &mut View<Id, Msg, NoUserEvent>) -> Option<String> {
+        let name = self.get_selected_feed_name(view)?;
+        match view.state(&Id::ArticleList) {
+            Ok(State::One(StateValue::Usize(index))) => {
+                Some(self.article_source(name.as_str(), index).to_string())
+            }
+            _ => Some(name),
+        }
+    }
+
+    /// ### article_source
+    ///
+    /// Get the source of the article at `index` in the feed `name`: articles of the aggregated feed
+    /// come from any source
+    fn article_source<'a>(&'a self, name: &'a str, index: usize) -> &'a str {
+        match self.kiosk.is_all_feed(name) {
+            true => self.kiosk.get_article_source(index).unwrap_or(name),
+            false => name,
+        }
+    }
 }
 
 impl Update<Id, Msg, NoUserEvent> for Model {
     fn update(&mut self, view: &mut View<Id, Msg, NoUserEvent>, msg: Option<Msg>) -> Option<Msg> {
         self.redraw = true;
         match msg.unwrap_or(Msg::None) {
+            Msg::ArchiveArticle => {
+                if let Some(article) = self.get_selected_article(view) {
+                    self.task(Task::ArchiveArticle(
+                        self.urls.clean(article.url.as_str()),
+                        article.title.clone(),
+                    ));
+                }
+            }
             Msg::ArticleBlur => {
+                // Article list is hidden in reading mode
+                self.reading_mode = false;
                 assert!(view.active(&Id::ArticleList).is_ok());
             }
             Msg::ArticleChanged(article) => {
@@ -357,50 +2783,497 @@ impl Update<Id, Msg, NoUserEvent> for Model {
             Msg::ArticleListBlur => {
                 assert!(view.active(&Id::FeedList).is_ok());
             }
+            Msg::CloseAddFeedPopup => {
+                let _ = view.umount(&Id::AddFeedPopup);
+                Self::lock_global_listener(view, false);
+            }
             Msg::CloseApp => {
+                self.save_session(view);
+                if let Err(err) = self.kiosk.save_quit() {
+                    warn!("could not save quit time: {}", err);
+                }
                 self.quit = true;
             }
+            Msg::CloseDeleteFeedPopup => {
+                let _ = view.umount(&Id::DeleteFeedPopup);
+            }
             Msg::CloseErrorPopup => {
-                let _ = view.umount(&Id::ErrorPopup);
+                self.errors.clear();
+                self.mount_error(view);
             }
             Msg::CloseQuitPopup => {
                 let _ = view.umount(&Id::QuitPopup);
             }
-            Msg::FeedChanged(feed) => {
-                let feed = &(*self.sorted_sources().get(feed).unwrap()).clone();
-                if let Some(feed) = self.kiosk.get_feed(feed.as_str()) {
-                    let articles = Self::get_article_list(feed, self.max_article_name_len());
-                    assert!(view.remount(Id::ArticleList, Box::new(articles)).is_ok());
-                    // Then load the first article of feed
-                    self.update_article(view, 0);
+            Msg::CloseFilterPopup => {
+                let previous = self
+                    .article_filter
+                    .as_ref()
+                    .map(|x| x.previous)
+                    .unwrap_or_default();
+                self.umount_filter(view, previous);
+            }
+            Msg::CloseHelpPopup => {
+                let _ = view.umount(&Id::HelpPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseFeedInfoPopup => {
+                let _ = view.umount(&Id::FeedInfoPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseTagArticlePopup => {
+                let _ = view.umount(&Id::TagArticlePopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseCommandLine => {
+                let _ = view.umount(&Id::CommandLine);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::ClosePodcastSearchPopup => {
+                let _ = view.umount(&Id::PodcastSearchPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::ClosePodcastResultsPopup => {
+                let _ = view.umount(&Id::PodcastResultsPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseSharePopup => {
+                let _ = view.umount(&Id::SharePopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseOpenWithPopup => {
+                let _ = view.umount(&Id::OpenWithPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseFeedSwitcherPopup => {
+                let _ = view.umount(&Id::FeedSwitcherPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseTagFilterPopup => {
+                let _ = view.umount(&Id::TagFilterPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseRenameFeedPopup => {
+                let _ = view.umount(&Id::RenameFeedPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::CloseSearchPopup => {
+                let _ = view.umount(&Id::SearchPopup);
+                Self::lock_global_listener(view, false);
+            }
+            Msg::DismissError => {
+                self.errors.dismiss();
+                self.mount_error(view);
+            }
+            Msg::EditConfig => {
+                self.task(Task::EditConfig);
+            }
+            Msg::EditNote => {
+                if let Some(article) = self.get_selected_article(view) {
+                    self.task(Task::EditNote(article.url.clone()));
                 }
             }
+            Msg::FeedChanged(feed) => {
+                self.show_feed_articles(view, feed);
+            }
             Msg::FeedListBlur => {
                 assert!(view.active(&Id::ArticleList).is_ok());
             }
+            Msg::FilterTag(tag) => {
+                self.filter_tag(view, tag.as_str());
+            }
+            Msg::FilterChanged(query) => {
+                self.filter_articles(view, query.as_str());
+            }
             Msg::FetchSource => {
                 if let Some(name) = self.get_selected_feed_name(view) {
-                    self.task(Task::FetchSource(name))
+                    if !self.reject_tag_feed(view, name.as_str(), "reloaded") {
+                        self.task(Task::FetchSource(name))
+                    }
+                }
+            }
+            Msg::FetchCategory => {
+                if let Some(name) = self.get_selected_feed_name(view) {
+                    if !self.reject_tag_feed(view, name.as_str(), "reloaded") {
+                        self.task(Task::FetchCategory(name))
+                    }
                 }
             }
             Msg::FetchAllSources => {
-                self.task(Task::FetchSources);
+                self.task(Task::FetchSources(false));
+            }
+            Msg::ForceFetchAllSources => {
+                self.task(Task::FetchSources(true));
             }
             Msg::GoReadArticle => {
+                self.mark_selected_read(view);
                 let _ = view.active(&Id::ArticleSummary);
             }
+            Msg::NextArticle => {
+                self.move_article(view, true);
+                self.mark_selected_read(view);
+            }
+            Msg::NextError => {
+                self.errors.next();
+                self.mount_error(view);
+            }
+            Msg::MarkAllRead => {
+                self.mark_all_read(view);
+            }
+            Msg::NextUnread => {
+                // NOTE: the key may be bound to a match of the search too
+                if !self.search_keys_taken(view, &self.keys.next_unread) {
+                    self.move_unread(view, true);
+                }
+            }
             Msg::OpenArticle => {
                 if let Ok(Some(AttrValue::String(url))) =
                     view.query(&Id::ArticleLink, Attribute::Text)
                 {
+                    // Videos are played with the configured player
+                    let player = self.video.player.as_deref().filter(|_| {
+                        self.get_selected_article(view)
+                            .map(|x| url_helpers::is_video_url(x.url.as_str()))
+                            .unwrap_or(false)
+                    });
+                    let result = match player {
+                        Some(player) => open_helpers::open_link_with(player, url.as_str()),
+                        None => open_helpers::open_link(url.as_str()),
+                    };
+                    if let Err(err) = result {
+                        self.task(Task::ShowError(err));
+                    }
+                    self.run_selected_article_hook(view, HookEvent::ArticleOpen);
+                }
+                self.mark_selected_read(view);
+            }
+            Msg::OpenFeedHomepage => {
+                if view.mounted(&Id::FeedInfoPopup) {
+                    let _ = view.umount(&Id::FeedInfoPopup);
+                    Self::lock_global_listener(view, false);
+                }
+                let link = self
+                    .get_selected_feed_name(view)
+                    .and_then(|name| self.kiosk.get_feed(name.as_str()))
+                    .and_then(|feed| feed.channel().link.clone());
+                match link {
+                    Some(link) => {
+                        if let Err(err) = open_helpers::open_link(link.as_str()) {
+                            self.task(Task::ShowError(err));
+                        }
+                    }
+                    None => self.set_status_message(Some(String::from(
+                        "The feed doesn't link its website",
+                    ))),
+                }
+            }
+            Msg::OpenWith(command, url) => {
+                let _ = view.umount(&Id::OpenWithPopup);
+                Self::lock_global_listener(view, false);
+                let result = match command {
+                    Some(command) => open_helpers::open_link_with(command.as_str(), url.as_str()),
+                    None => open_helpers::open_link(url.as_str()),
+                };
+                if let Err(err) = result {
+                    self.task(Task::ShowError(err));
+                }
+                self.run_selected_article_hook(view, HookEvent::ArticleOpen);
+                self.mark_selected_read(view);
+            }
+            Msg::OpenComments => {
+                let url = self
+                    .get_selected_article(view)
+                    .and_then(|x| x.comments.as_deref())
+                    .map(|x| self.urls.clean(x));
+                match url {
+                    Some(url) => {
+                        if let Err(err) = open_helpers::open_link(url.as_str()) {
+                            self.task(Task::ShowError(err));
+                        }
+                        self.mark_selected_read(view);
+                    }
+                    None => {
+                        self.set_status_message(Some(String::from(
+                            "This article has no comments page",
+                        )));
+                        assert!(view
+                            .remount(Id::StatusBar, Box::new(self.get_status_bar()))
+                            .is_ok());
+                    }
+                }
+            }
+            Msg::OpenLink(link) => {
+                let url = self
+                    .get_selected_article(view)
+                    .and_then(|x| x.links.get(link.saturating_sub(1)))
+                    .map(|x| self.urls.clean(x.as_str()));
+                if let Some(url) = url {
                     if let Err(err) = open_helpers::open_link(url.as_str()) {
                         self.task(Task::ShowError(err));
                     }
                 }
             }
+            Msg::PreviousArticle => {
+                self.move_article(view, false);
+                self.mark_selected_read(view);
+            }
+            Msg::PreviousError => {
+                self.errors.previous();
+                self.mount_error(view);
+            }
+            Msg::PreviousUnread => {
+                if !self.search_keys_taken(view, &self.keys.previous_unread) {
+                    self.move_unread(view, false);
+                }
+            }
+            Msg::RefreshDates => {
+                self.refresh_dates(view);
+            }
+            Msg::RenameFeed(name, new_name) => {
+                let new_name = new_name.trim().to_string();
+                if new_name.is_empty() {
+                    self.task(Task::ShowError(String::from("Source name can't be empty")));
+                } else if new_name.starts_with(TAG_FEED_PREFIX) {
+                    self.task(Task::ShowError(format!(
+                        "Source name can't start with {}",
+                        TAG_FEED_PREFIX
+                    )));
+                } else if new_name != name && self.kiosk.get_feed_state(new_name.as_str()).is_some()
+                {
+                    self.task(Task::ShowError(format!(
+                        r#"A source named "{}" already exists"#,
+                        new_name
+                    )));
+                } else {
+                    let _ = view.umount(&Id::RenameFeedPopup);
+                    Self::lock_global_listener(view, false);
+                    if new_name != name {
+                        self.task(Task::RenameFeed(name, new_name));
+                    }
+                }
+            }
+            Msg::ShowPodcastSearchPopup => {
+                self.mount_podcast_search(view);
+            }
+            Msg::SearchArticle(query) => {
+                let _ = view.umount(&Id::SearchPopup);
+                Self::lock_global_listener(view, false);
+                self.search_article(view, query.as_str());
+            }
+            Msg::SearchPodcasts(query) => {
+                let _ = view.umount(&Id::PodcastSearchPopup);
+                Self::lock_global_listener(view, false);
+                self.task(Task::SearchPodcasts(query));
+            }
+            Msg::SubscribePodcast(index) => {
+                let _ = view.umount(&Id::PodcastResultsPopup);
+                Self::lock_global_listener(view, false);
+                if let Some(podcast) = self.podcasts.get(index) {
+                    return Some(Msg::SubscribeFeed(
+                        podcast.name.clone(),
+                        podcast.url.clone(),
+                    ));
+                }
+            }
+            Msg::ShowAddFeedPopup => {
+                self.mount_add_feed(view, None);
+            }
+            Msg::SubscribeFromClipboard => {
+                self.task(Task::SubscribeFromClipboard);
+            }
+            Msg::ShowDeleteFeedPopup => {
+                if let Some(name) = self.get_selected_feed_name(view) {
+                    if !self.reject_tag_feed(view, name.as_str(), "deleted") {
+                        self.mount_delete_feed(view);
+                    }
+                }
+            }
+            Msg::ShowFilterPopup => {
+                self.mount_filter(view);
+            }
+            Msg::ShowHelpPopup => {
+                self.mount_help(view);
+            }
+            Msg::ShowFeedInfoPopup => {
+                self.mount_feed_info(view);
+            }
             Msg::ShowQuitPopup => {
                 self.mount_quit(view);
             }
+            Msg::ShowTagArticlePopup => {
+                self.mount_tag_article(view);
+            }
+            Msg::ShowCommandLine => {
+                self.mount_command_line(view);
+            }
+            Msg::ShowOpenWithPopup => {
+                self.mount_open_with(view);
+            }
+            Msg::ShowFeedSwitcherPopup => {
+                self.mount_feed_switcher(view);
+            }
+            Msg::ShowTagFilterPopup => {
+                self.mount_tag_filter(view);
+            }
+            Msg::ShowSearchPopup => {
+                self.mount_search(view);
+            }
+            Msg::ShowRenameFeedPopup => {
+                if let Some(name) = self.get_selected_feed_name(view) {
+                    if !self.reject_tag_feed(view, name.as_str(), "renamed") {
+                        self.mount_rename_feed(view);
+                    }
+                }
+            }
+            Msg::Share(index) => {
+                let _ = view.umount(&Id::SharePopup);
+                Self::lock_global_listener(view, false);
+                let target = match self.share.get(index) {
+                    Some(target) => target.clone(),
+                    None => return None,
+                };
+                if let Some(article) = self.get_selected_article(view) {
+                    let args = target.args(
+                        self.urls.clean(article.url.as_str()).as_str(),
+                        article.title.as_deref().unwrap_or_default(),
+                    );
+                    match open_helpers::run_command(args.as_slice()) {
+                        Ok(()) => {
+                            self.show_status_message(view, format!("Shared with {}", target.name))
+                        }
+                        Err(err) => self.task(Task::ShowError(err)),
+                    }
+                }
+            }
+            Msg::ShowSharePopup => {
+                self.mount_share(view);
+            }
+            Msg::SpeakArticle => {
+                if let Some(article) = self.get_selected_article(view) {
+                    let text = match article.title.as_deref() {
+                        Some(title) => format!("{}.\n\n{}", title, article.summary),
+                        None => article.summary.clone(),
+                    };
+                    self.task(Task::Speak(text));
+                }
+            }
+            Msg::StopSpeaking => {
+                self.task(Task::StopSpeaking);
+            }
+            Msg::SubmitFilter => {
+                // Jump to the selected match in the full list
+                let index = match view.state(&Id::ArticleList) {
+                    Ok(State::One(StateValue::Usize(index))) => index,
+                    _ => self
+                        .article_filter
+                        .as_ref()
+                        .map(|x| x.previous)
+                        .unwrap_or_default(),
+                };
+                self.umount_filter(view, index);
+            }
+            Msg::SubscribeFeed(name, uri) => {
+                let name = name.trim().to_string();
+                let uri = uri.trim().to_string();
+                if name.is_empty() || uri.is_empty() {
+                    self.task(Task::ShowError(String::from(
+                        "Both name and URL are required to subscribe to a feed",
+                    )));
+                } else if name.starts_with(TAG_FEED_PREFIX) {
+                    self.task(Task::ShowError(format!(
+                        "Source name can't start with {}",
+                        TAG_FEED_PREFIX
+                    )));
+                } else if self.kiosk.get_feed_state(name.as_str()).is_some() {
+                    self.task(Task::ShowError(format!(
+                        r#"A source named "{}" already exists"#,
+                        name
+                    )));
+                } else {
+                    let _ = view.umount(&Id::AddFeedPopup);
+                    Self::lock_global_listener(view, false);
+                    self.task(Task::SubscribeFeed(name, uri));
+                }
+            }
+            Msg::SwitchFeed(name) => {
+                self.switch_feed(view, name.as_str());
+            }
+            Msg::SwitchTab(index) => {
+                self.switch_tab(view, index);
+            }
+            Msg::TagArticle(tags) => {
+                self.tag_article(view, tags.as_str());
+            }
+            Msg::WindowResized => {
+                // NOTE: filtered list is remounted on the next filter change
+                if !self.filtering() {
+                    self.remount_article_list(view);
+                }
+            }
+            Msg::StarArticle => {
+                self.star_article(view);
+            }
+            Msg::ToggleHideRead => {
+                self.hide_read_articles(view, !self.hide_read);
+            }
+            Msg::ToggleOffline => {
+                self.go_offline(view, !self.offline);
+            }
+            Msg::ToggleSortByScore => {
+                // The order chosen for the selected feed is toggled, if any
+                let name = self.get_selected_feed_name(view).unwrap_or_default();
+                let sort = match self.article_sort(name.as_str()) {
+                    ArticleSort::Score => ArticleSort::Feed,
+                    _ => ArticleSort::Score,
+                };
+                match self.kiosk.get_article_sort(name.as_str()) {
+                    Some(_) => self.sort_feed_articles(view, Some(sort)),
+                    None => self.sort_articles(view, sort),
+                }
+            }
+            Msg::ToggleSortByUnread => {
+                let name = self.get_selected_feed_name(view).unwrap_or_default();
+                let sort = match self.article_sort(name.as_str()) {
+                    ArticleSort::Unread if self.sort == ArticleSort::Unread => {
+                        Some(ArticleSort::Feed)
+                    }
+                    ArticleSort::Unread => None,
+                    _ => Some(ArticleSort::Unread),
+                };
+                self.sort_feed_articles(view, sort);
+            }
+            Msg::RunCommand(line) => {
+                let _ = view.umount(&Id::CommandLine);
+                Self::lock_global_listener(view, false);
+                return self.run_command(view, line.as_str());
+            }
+            Msg::RunScript(function) => {
+                self.run_script_action(view, function.as_str());
+            }
+            Msg::ToggleReadingMode => {
+                self.set_reading_mode(view, !self.reading_mode);
+            }
+            Msg::Undo => {
+                self.undo(view);
+            }
+            Msg::UnsubscribeFeed(name) => {
+                let _ = view.umount(&Id::DeleteFeedPopup);
+                self.task(Task::UnsubscribeFeed(name));
+            }
+            Msg::YankArticle => {
+                if let Some(article) = self.get_selected_article(view) {
+                    let url = self.urls.clean(article.url.as_str());
+                    let text = match article.title.as_deref() {
+                        Some(title) => format!("{}\n{}", title, url),
+                        None => url,
+                    };
+                    self.task(Task::CopyToClipboard(text));
+                }
+            }
+            Msg::YankLink => {
+                if let Some(article) = self.get_selected_article(view) {
+                    self.task(Task::CopyToClipboard(self.urls.clean(article.url.as_str())));
+                }
+            }
             Msg::None => {}
         }
         None